@@ -0,0 +1,23 @@
+//! Cargo runner for guest programs, configured through `runner = "nexus-run"` in the guest's `.cargo/config.toml`.
+
+use clap::Parser;
+use nexus_cli::{command::run, setup_logger};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "nexus-run",
+    author,
+    version,
+    about = "Execute a Nexus guest program"
+)]
+struct Cli {
+    #[command(flatten)]
+    args: run::RunArgs,
+}
+
+fn main() -> anyhow::Result<()> {
+    let _guard = setup_logger();
+
+    let Cli { args } = Cli::parse();
+    run::handle_command(args)
+}
@@ -0,0 +1,51 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Context;
+use clap::Args;
+
+/// The guest program and the inputs it is executed on.
+///
+/// Input tapes are expected to be encoded as the guest reads them, i.e., as produced by
+/// `postcard::to_stdvec_cobs` and zero-padded to a word boundary.
+#[derive(Debug, Args)]
+pub struct ProgramArgs {
+    /// Path to the guest ELF.
+    #[arg(name = "elf")]
+    pub elf: PathBuf,
+    /// File containing the encoded public input tape.
+    #[arg(long, name = "public-input")]
+    pub public_input: Option<PathBuf>,
+    /// File containing the encoded private input tape.
+    #[arg(long, name = "private-input")]
+    pub private_input: Option<PathBuf>,
+    /// File containing the associated data bound into the proof.
+    #[arg(long, name = "ad")]
+    pub ad: Option<PathBuf>,
+}
+
+impl ProgramArgs {
+    pub fn elf(&self) -> anyhow::Result<nexus_core::nvm::ElfFile> {
+        nexus_core::nvm::ElfFile::from_path(&self.elf)
+            .with_context(|| format!("failed to load ELF from {}", self.elf.display()))
+    }
+
+    pub fn public_input(&self) -> anyhow::Result<Vec<u8>> {
+        read_optional(self.public_input.as_ref())
+    }
+
+    pub fn private_input(&self) -> anyhow::Result<Vec<u8>> {
+        read_optional(self.private_input.as_ref())
+    }
+
+    pub fn ad(&self) -> anyhow::Result<Vec<u8>> {
+        read_optional(self.ad.as_ref())
+    }
+}
+
+/// Reads the file at `path`, or returns an empty buffer if no path is given.
+pub fn read_optional(path: Option<&PathBuf>) -> anyhow::Result<Vec<u8>> {
+    let Some(path) = path else {
+        return Ok(Vec::new());
+    };
+    fs::read(path).with_context(|| format!("failed to read {}", path.display()))
+}
@@ -0,0 +1,33 @@
+use anyhow::Context;
+use clap::Args;
+use nexus_core::nvm::inspect::Inspection;
+
+use super::common::ProgramArgs;
+
+#[derive(Debug, Args)]
+pub struct InspectArgs {
+    #[command(flatten)]
+    pub program: ProgramArgs,
+    /// Do not run the guest, and so do not report the memory layout.
+    #[arg(long, name = "no-run")]
+    pub no_run: bool,
+}
+
+pub fn handle_command(args: InspectArgs) -> anyhow::Result<()> {
+    let program = &args.program;
+    let mut inspection = Inspection::from_path(&program.elf)
+        .with_context(|| format!("failed to load ELF from {}", program.elf.display()))?;
+
+    if !args.no_run {
+        inspection
+            .compute_memory_layout(
+                &program.public_input()?,
+                &program.private_input()?,
+                &program.ad()?,
+            )
+            .context("failed to run the guest to compute its memory layout")?;
+    }
+
+    print!("{inspection}");
+    Ok(())
+}
@@ -2,12 +2,26 @@ use clap::Subcommand;
 
 use super::ENV;
 
+mod common;
+
 pub mod host;
+pub mod inspect;
+pub mod prove;
+pub mod run;
+pub mod verify;
 
 #[derive(Debug, Subcommand)]
 pub enum Command {
     /// Create a new host/guest Nexus package at <path>.
     Host(host::HostArgs),
+    /// Execute a guest program, printing its logs, exit code and cycle count.
+    Run(run::RunArgs),
+    /// Prove the execution of a guest program with Stwo.
+    Prove(prove::ProveArgs),
+    /// Verify a proof of the execution of a guest program.
+    Verify(verify::VerifyArgs),
+    /// Disassemble a guest program and report its segments, memory layout and unsupported instructions.
+    Inspect(inspect::InspectArgs),
 }
 
 pub fn handle_command(cmd: Command) -> anyhow::Result<()> {
@@ -15,5 +29,151 @@ pub fn handle_command(cmd: Command) -> anyhow::Result<()> {
 
     match cmd {
         Command::Host(args) => host::handle_command(args),
+        Command::Run(args) => run::handle_command(args),
+        Command::Prove(args) => prove::handle_command(args),
+        Command::Verify(args) => verify::handle_command(args),
+        Command::Inspect(args) => inspect::handle_command(args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+    use common::ProgramArgs;
+
+    const FIB_ELF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../vm/test/fib_10.elf");
+
+    fn program(ad: &Path) -> ProgramArgs {
+        ProgramArgs {
+            elf: FIB_ELF.into(),
+            public_input: None,
+            private_input: None,
+            ad: Some(ad.to_path_buf()),
+        }
+    }
+
+    #[test]
+    fn run_prove_verify_inspect() {
+        let dir = tempfile::tempdir().unwrap();
+        let ad = dir.path().join("ad");
+        fs::write(&ad, b"associated data").unwrap();
+
+        let profile = dir.path().join("profile");
+        let trace = dir.path().join("trace");
+        run::handle_command(run::RunArgs {
+            program: program(&ad),
+            public_output: None,
+            trace: Some(trace.clone()),
+            chrome_trace: None,
+            profile: Some(profile.clone()),
+            gdb: None,
+            linear: false,
+        })
+        .unwrap();
+        assert!(!fs::read(&profile).unwrap().is_empty());
+        assert!(!fs::read(&trace).unwrap().is_empty());
+
+        let proof = dir.path().join("proof");
+        let public_output = dir.path().join("public-output");
+        prove::handle_command(prove::ProveArgs {
+            program: program(&ad),
+            path: proof.clone(),
+            public_output: public_output.clone(),
+            log_blowup_factor: None,
+            n_queries: None,
+            pow_bits: None,
+            hash: prove::Hash::Blake2s,
+        })
+        .unwrap();
+
+        let verify_args = |ad: &Path| verify::VerifyArgs {
+            program: program(ad),
+            path: proof.clone(),
+            public_output: public_output.clone(),
+            exit_code: 0,
+        };
+        verify::handle_command(verify_args(&ad)).unwrap();
+
+        let other_ad = dir.path().join("other-ad");
+        fs::write(&other_ad, b"other data").unwrap();
+        assert!(verify::handle_command(verify_args(&other_ad)).is_err());
+
+        inspect::handle_command(inspect::InspectArgs {
+            program: program(&ad),
+            no_run: false,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn prove_verify_poseidon2() {
+        let dir = tempfile::tempdir().unwrap();
+        let ad = dir.path().join("ad");
+        fs::write(&ad, b"").unwrap();
+
+        let proof = dir.path().join("proof");
+        let public_output = dir.path().join("public-output");
+        prove::handle_command(prove::ProveArgs {
+            program: program(&ad),
+            path: proof.clone(),
+            public_output: public_output.clone(),
+            log_blowup_factor: None,
+            n_queries: None,
+            pow_bits: None,
+            hash: prove::Hash::Poseidon2,
+        })
+        .unwrap();
+
+        verify::handle_command(verify::VerifyArgs {
+            program: program(&ad),
+            path: proof,
+            public_output,
+            exit_code: 0,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn prove_rejects_unsupported_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = prove::handle_command(prove::ProveArgs {
+            program: ProgramArgs {
+                elf: FIB_ELF.into(),
+                public_input: None,
+                private_input: None,
+                ad: None,
+            },
+            path: dir.path().join("proof"),
+            public_output: dir.path().join("public-output"),
+            log_blowup_factor: None,
+            n_queries: Some(0),
+            pow_bits: None,
+            hash: prove::Hash::Blake2s,
+        });
+        assert!(result.is_err());
+        assert!(!dir.path().join("proof").exists());
+    }
+
+    #[test]
+    fn prove_rejects_insecure_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = prove::handle_command(prove::ProveArgs {
+            program: ProgramArgs {
+                elf: FIB_ELF.into(),
+                public_input: None,
+                private_input: None,
+                ad: None,
+            },
+            path: dir.path().join("proof"),
+            public_output: dir.path().join("public-output"),
+            log_blowup_factor: None,
+            n_queries: Some(10),
+            pow_bits: None,
+            hash: prove::Hash::Blake2s,
+        });
+        assert!(result.is_err());
+        assert!(!dir.path().join("proof").exists());
     }
 }
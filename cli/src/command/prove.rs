@@ -0,0 +1,135 @@
+use std::{fmt::Write, fs, path::PathBuf};
+
+use anyhow::Context;
+use clap::{Args, ValueEnum};
+use nexus_core::stwo::{HashKind, ProverConfig};
+use nexus_sdk::{stwo::seq::Stwo, Local, Prover, Viewable};
+
+use super::common::ProgramArgs;
+
+#[derive(Debug, Args)]
+pub struct ProveArgs {
+    #[command(flatten)]
+    pub program: ProgramArgs,
+    /// File to save the proof.
+    #[arg(short, long, default_value = "nexus-proof")]
+    pub path: PathBuf,
+    /// File to save the encoded public output tape, for use in verification.
+    #[arg(long, name = "public-output", default_value = "nexus-public-output")]
+    pub public_output: PathBuf,
+    /// Log2 of the FRI blowup factor.
+    ///
+    /// Unset parameters are taken from the standard config, and the resulting config must reach the conjectured
+    /// security required by the verifier.
+    #[arg(long, name = "log-blowup-factor")]
+    pub log_blowup_factor: Option<u32>,
+    /// Number of FRI queries.
+    #[arg(long, name = "queries")]
+    pub n_queries: Option<usize>,
+    /// Number of proof-of-work bits.
+    #[arg(long, name = "pow-bits")]
+    pub pow_bits: Option<u32>,
+    /// Hash function for commitments and the Fiat–Shamir channel.
+    #[arg(long, name = "hash", value_enum, default_value_t = Hash::Blake2s)]
+    pub hash: Hash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Hash {
+    Blake2s,
+    /// Poseidon2 over M31, cheaper to verify recursively.
+    Poseidon2,
+}
+
+impl From<Hash> for HashKind {
+    fn from(hash: Hash) -> Self {
+        match hash {
+            Hash::Blake2s => HashKind::Blake2s,
+            Hash::Poseidon2 => HashKind::Poseidon2M31,
+        }
+    }
+}
+
+impl ProveArgs {
+    fn prover_config(&self) -> ProverConfig {
+        let default = ProverConfig::standard();
+        ProverConfig {
+            log_blowup_factor: self.log_blowup_factor.unwrap_or(default.log_blowup_factor),
+            n_queries: self.n_queries.unwrap_or(default.n_queries),
+            pow_bits: self.pow_bits.unwrap_or(default.pow_bits),
+        }
+    }
+}
+
+pub fn handle_command(args: ProveArgs) -> anyhow::Result<()> {
+    let mut prover: Stwo<Local> = Stwo::new(&args.program.elf()?)?;
+    prover.set_associated_data(&args.program.ad()?)?;
+
+    let config = args.prover_config();
+    if !config.is_valid() {
+        anyhow::bail!("unsupported prover config: {config:?}");
+    }
+    if config.security_bits() < ProverConfig::MIN_SECURITY_BITS {
+        anyhow::bail!(
+            "prover config gives {} bits of conjectured security, the verifier requires at least {}",
+            config.security_bits(),
+            ProverConfig::MIN_SECURITY_BITS
+        );
+    }
+    prover.set_prover_config(config);
+    prover.set_hash(args.hash.into());
+
+    let private_input = args.program.private_input()?;
+    let public_input = args.program.public_input()?;
+
+    let mut term = nexus_progress_bar::TerminalHandle::new_enabled();
+    let mut ctx = term
+        .context("Proving")
+        .on_step(|_step| "program".into())
+        .completion_header("Proved")
+        .completion_stats(|elapsed| format!("program in {elapsed}"));
+
+    let (view, proof) = {
+        let guard = ctx.display_step();
+        match prover.prove_with_encoded_input(&private_input, &public_input) {
+            Ok(result) => result,
+            Err(err) => {
+                guard.abort();
+                return Err(err.into());
+            }
+        }
+    };
+
+    for log in view.logs()? {
+        print!("{log}");
+    }
+    println!("Exit code: {}", view.exit_code()?);
+    let image_id = proof
+        .image_id()
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        });
+    println!("Image ID: {image_id}");
+
+    fs::write(&args.path, proof.to_bytes()?)
+        .with_context(|| format!("failed to write proof to {}", args.path.display()))?;
+    fs::write(
+        &args.public_output,
+        view.view_public_output().unwrap_or_default(),
+    )
+    .with_context(|| {
+        format!(
+            "failed to write public output to {}",
+            args.public_output.display()
+        )
+    })?;
+
+    println!(
+        "Proof saved to {} ({} bits of conjectured security)",
+        args.path.display(),
+        proof.security_bits()
+    );
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    net::TcpListener,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use clap::Args;
+use nexus_core::nvm::{
+    gdb::GdbStub,
+    internals::{Emulator, HarvardEmulator, LinearEmulator, Profiler},
+    trace_export, SymbolTable, VMError,
+};
+use nexus_sdk::Viewable;
+
+use super::common::ProgramArgs;
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub program: ProgramArgs,
+    /// Write the encoded public output tape to this file.
+    #[arg(long, name = "public-output")]
+    pub public_output: Option<PathBuf>,
+    /// Write the execution trace to this file, one line per step.
+    #[arg(long, name = "trace")]
+    pub trace: Option<PathBuf>,
+    /// Write the cycle-tracker regions to this file as Chrome trace JSON, for viewing in Perfetto.
+    #[arg(long, name = "chrome-trace")]
+    pub chrome_trace: Option<PathBuf>,
+    /// Write the cycles spent in each call stack to this file as collapsed stacks, for rendering as a flame graph.
+    #[arg(long, name = "profile")]
+    pub profile: Option<PathBuf>,
+    /// Instead of running to completion, wait for a GDB connection on this port and serve the guest to it.
+    #[arg(long, name = "gdb", value_name = "PORT")]
+    pub gdb: Option<u16>,
+    /// With `--gdb`, debug the guest in the memory layout used for proving rather than its ELF layout. The guest is
+    /// first run to completion to compute the layout.
+    #[arg(long, name = "linear", requires = "gdb")]
+    pub linear: bool,
+}
+
+pub fn handle_command(args: RunArgs) -> anyhow::Result<()> {
+    if let Some(port) = args.gdb {
+        return debug(&args.program, port, args.linear);
+    }
+
+    let elf = args.program.elf()?;
+
+    let (view, trace) = nexus_core::nvm::k_trace(
+        elf,
+        &args.program.ad()?,
+        &args.program.public_input()?,
+        &args.program.private_input()?,
+        1,
+    )?;
+
+    for log in view.logs()? {
+        print!("{log}");
+    }
+
+    let cycles: usize = trace.blocks.iter().map(|block| block.steps.len()).sum();
+    println!("Exit code: {}", view.exit_code()?);
+    println!("Cycles: {cycles}");
+
+    if let Some(path) = args.public_output {
+        let output = view.view_public_output().unwrap_or_default();
+        fs::write(&path, output)
+            .with_context(|| format!("failed to write public output to {}", path.display()))?;
+    }
+
+    if let Some(path) = args.trace {
+        let mut out = BufWriter::new(
+            File::create(&path).with_context(|| format!("failed to create {}", path.display()))?,
+        );
+        trace_export::write_text(&trace, &mut out)
+            .with_context(|| format!("failed to write trace to {}", path.display()))?;
+    }
+
+    if let Some(path) = args.chrome_trace {
+        let mut out = BufWriter::new(
+            File::create(&path).with_context(|| format!("failed to create {}", path.display()))?,
+        );
+        trace_export::write_chrome_trace(&trace, &view, &mut out)
+            .with_context(|| format!("failed to write Chrome trace to {}", path.display()))?;
+    }
+
+    if let Some(path) = args.profile {
+        profile(&args.program, &path)?;
+    }
+
+    Ok(())
+}
+
+/// Profiles the guest on the emulator it is proven on, so that the cycles match those reported by the run.
+fn profile(program: &ProgramArgs, path: &Path) -> anyhow::Result<()> {
+    let symbols = SymbolTable::from_path(&program.elf)
+        .with_context(|| format!("failed to load symbols from {}", program.elf.display()))?;
+    let mut emulator = linear(program)?;
+    emulator.get_executor_mut().profiler = Some(Profiler::new(symbols));
+    run_to_exit(&mut emulator)?;
+
+    let mut out = BufWriter::new(
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?,
+    );
+    let profiler = emulator.get_executor().profiler.as_ref().unwrap();
+    profiler
+        .write_collapsed(&mut out)
+        .with_context(|| format!("failed to write profile to {}", path.display()))?;
+    Ok(())
+}
+
+fn debug(program: &ProgramArgs, port: u16, linear_layout: bool) -> anyhow::Result<()> {
+    let symbols = SymbolTable::from_path(&program.elf)
+        .with_context(|| format!("failed to load symbols from {}", program.elf.display()))?;
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .with_context(|| format!("failed to listen on port {port}"))?;
+    println!("Waiting for GDB to attach with `target remote :{port}`");
+    let (stream, _) = listener.accept()?;
+
+    if linear_layout {
+        GdbStub::new(&mut linear(program)?, symbols).run(stream)?;
+    } else {
+        GdbStub::new(&mut harvard(program)?, symbols).run(stream)?;
+    }
+    Ok(())
+}
+
+fn harvard(program: &ProgramArgs) -> anyhow::Result<HarvardEmulator> {
+    Ok(HarvardEmulator::from_elf(
+        &program.elf()?,
+        &program.public_input()?,
+        &program.private_input()?,
+    ))
+}
+
+/// Builds the linear emulator the guest is proven on, with the memory layout of a first run on the harvard emulator.
+fn linear(program: &ProgramArgs) -> anyhow::Result<LinearEmulator> {
+    let mut harvard = harvard(program)?;
+    run_to_exit(&mut harvard)?;
+    Ok(LinearEmulator::from_harvard(
+        &harvard,
+        program.elf()?,
+        &program.ad()?,
+        &program.private_input()?,
+    )?)
+}
+
+fn run_to_exit(emulator: &mut impl Emulator) -> anyhow::Result<()> {
+    match emulator.execute(false) {
+        Err(VMError::VMExited(_)) => Ok(()),
+        Err(e) => Err(e.into()),
+        Ok(_) => anyhow::bail!("the guest stopped without exiting"),
+    }
+}
@@ -0,0 +1,45 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Context;
+use clap::Args;
+use nexus_sdk::{stwo::seq::Proof, Verifiable};
+
+use super::common::{read_optional, ProgramArgs};
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    #[command(flatten)]
+    pub program: ProgramArgs,
+    /// File to read the proof from.
+    #[arg(short, long, default_value = "nexus-proof")]
+    pub path: PathBuf,
+    /// File containing the expected encoded public output tape.
+    #[arg(long, name = "public-output", default_value = "nexus-public-output")]
+    pub public_output: PathBuf,
+    /// The expected exit code.
+    #[arg(long, name = "exit-code", default_value_t = 0)]
+    pub exit_code: u32,
+}
+
+pub fn handle_command(args: VerifyArgs) -> anyhow::Result<()> {
+    let bytes = fs::read(&args.path)
+        .with_context(|| format!("failed to read proof from {}", args.path.display()))?;
+    let proof = Proof::from_bytes(&bytes)
+        .with_context(|| format!("failed to decode proof from {}", args.path.display()))?;
+
+    let result = proof.verify_expected_encoded(
+        &args.program.public_input()?,
+        args.exit_code,
+        &read_optional(Some(&args.public_output))?,
+        &args.program.elf()?,
+        &args.program.ad()?,
+    );
+
+    match result {
+        Ok(()) => {
+            println!("Verification succeeded");
+            Ok(())
+        }
+        Err(err) => anyhow::bail!("verification failed: {err}"),
+    }
+}
@@ -104,21 +104,19 @@ fn encode_j_type(instruction: &Instruction) -> u32 {
 }
 
 /// Encodes an instruction into its binary representation to little-endian format.
+///
+/// Custom (precompile) instructions are encoded by their instruction type, using the raw opcode
+/// and funct3/funct7 values they were declared with.
 pub fn encode_instruction(instruction: &Instruction) -> u32 {
-    if instruction.opcode.is_builtin() {
-        match instruction.ins_type {
-            InstructionType::RType => encode_r_type(instruction).to_le(),
-            InstructionType::IType => encode_i_type(instruction).to_le(),
-            InstructionType::ITypeShamt => encode_i_shamt_type(instruction).to_le(),
-            InstructionType::SType => encode_s_type(instruction).to_le(),
-            InstructionType::BType => encode_b_type(instruction).to_le(),
-            InstructionType::UType => encode_u_type(instruction).to_le(),
-            InstructionType::JType => encode_j_type(instruction).to_le(),
-            InstructionType::Unimpl => 0,
-        }
-    } else {
-        // Don't support for now, panic
-        todo!("Need to support not precompile instructions")
+    match instruction.ins_type {
+        InstructionType::RType => encode_r_type(instruction).to_le(),
+        InstructionType::IType => encode_i_type(instruction).to_le(),
+        InstructionType::ITypeShamt => encode_i_shamt_type(instruction).to_le(),
+        InstructionType::SType => encode_s_type(instruction).to_le(),
+        InstructionType::BType => encode_b_type(instruction).to_le(),
+        InstructionType::UType => encode_u_type(instruction).to_le(),
+        InstructionType::JType => encode_j_type(instruction).to_le(),
+        InstructionType::Unimpl => 0,
     }
 }
 
@@ -208,5 +206,16 @@ mod tests {
         };
         let encoded_i_shamt = i_shamt_instruction.encode();
         assert_eq!(encoded_i_shamt, 0x40A1D113);
+
+        // Test encode of a custom R-type instruction
+        let custom_instruction = Instruction {
+            opcode: Opcode::new(0b0001011, Some(0b001), Some(0b0000010), "custom"),
+            ins_type: InstructionType::RType,
+            op_a: 2.into(),
+            op_b: 3.into(),
+            op_c: 1,
+        };
+        let encoded_custom = custom_instruction.encode();
+        assert_eq!(encoded_custom, 0x0411910B);
     }
 }
@@ -4,21 +4,33 @@
 /// RISC-V processing
 pub mod nvm {
     pub use nexus_vm::{
-        elf::{ElfError, ElfFile},
-        emulator::View,
-        error::VMError,
-        trace::{bb_trace, k_trace, BBTrace, UniformTrace},
+        elf::{ElfError, ElfFile, SymbolTable},
+        emulator::{Oracles, Precompiles, ProgramImage, PublicStatement, View},
+        error::{StatementError, VMError},
+        gdb, inspect,
+        trace::{
+            bb_trace, export as trace_export, k_trace, k_trace_with_hints, k_trace_with_oracles,
+            k_trace_with_precompiles, segment, BBTrace, UniformTrace,
+        },
     };
     pub mod internals {
         pub use nexus_vm::emulator::{
             convert_instruction, elf_into_program_info, io_entries_into_vec, map_into_io_entries,
-            slice_into_io_entries, LinearEmulator, LinearMemoryLayout, MemoryInitializationEntry,
-            ProgramInfo, PublicOutputEntry,
+            slice_into_io_entries, Emulator, HarvardEmulator, InternalView, LinearEmulator,
+            LinearMemoryLayout, MemoryInitializationEntry, Profiler, ProgramInfo,
+            PublicOutputEntry,
         };
     }
 }
 
 /// Stwo proving
 pub mod stwo {
-    pub use nexus_vm_prover::{prove, verify, Proof, ProvingError, VerificationError};
+    pub use nexus_vm_prover::extensions::circuit::CircuitExtension;
+    pub use nexus_vm_prover::{
+        image_id, preflight, preflight_with_extensions, prove, prove_segment_with_extensions,
+        prove_with_config, prove_with_extensions, verify, verify_segment_with_extensions,
+        verify_with_extensions, Blake2sMerkleHasher, ExtensionComponent, HashKind, MachineHasher,
+        Poseidon2M31MerkleHasher, PreflightReport, Proof, ProverConfig, ProvingError, Unsupported,
+        VerificationError,
+    };
 }
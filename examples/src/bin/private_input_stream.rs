@@ -0,0 +1,17 @@
+// This example streams the whole private input tape into memory, rather than decoding a fixed set of inputs.
+
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+#[nexus_rt::main]
+fn main() -> (u32, u32) {
+    let mut bytes = Vec::new();
+    let len = nexus_rt::PrivateInput.read_to_end(&mut bytes);
+
+    let sum = bytes
+        .iter()
+        .fold(0u32, |acc, &byte| acc.wrapping_add(byte as u32));
+    (len as u32, sum)
+}
@@ -0,0 +1,686 @@
+//! Circuit proving calls of the 256-bit modular arithmetic precompile.
+//!
+//! A call takes a single row, which consumes the call from [`PrecompileLookupElements`]. The argument block, the
+//! operands and the result may overlap in RAM, therefore the row accesses every distinct byte touched by the call
+//! exactly once, in a slot, and the reads and the write of the call look the slots up through
+//! [`Bigint256ByteElements`]: a slot provides its previous value once per read of its address, and its new value to
+//! the write of the result if it is written. Slots for the same address cannot coexist, since each of them proves
+//! that the byte was last accessed before the call.
+//!
+//! The arithmetic is checked with gadgets from [`bigint`](nexus_vm_prover::extensions::bigint), with the modulus read
+//! from RAM.
+
+use std::{array, collections::BTreeMap};
+
+use nexus_precompiles::PrecompileCircuit;
+use nexus_vm_prover::{
+    components::AllLookupElements,
+    extensions::{
+        bigint::{assert_lt, assert_zero_mod_var, next_is_zero, next_uint, Poly},
+        circuit::{Row, RowCircuit, RowField},
+        LoadStoreLookupElements, PrecompileLookupElements,
+    },
+    hash::MachineChannel,
+    preflight::Support,
+    trace::sidenote::SideNote,
+};
+use num_bigint::BigUint;
+use stwo_prover::core::fields::m31::BaseField;
+
+use crate::{BIGINT_WORDS, OP_ADD_MOD, OP_MUL_MOD, STATUS_INVALID, STATUS_OK};
+
+/// Number of bytes in a 256-bit integer.
+const BIGINT_BYTES: usize = 4 * BIGINT_WORDS;
+/// Number of bytes in the argument block.
+const ARGUMENT_BYTES: usize = 16;
+/// Maximal number of distinct bytes accessed by a call: the argument block, three operands and the result.
+pub const MAX_SLOTS: usize = ARGUMENT_BYTES + 4 * BIGINT_BYTES;
+
+/// Tag of a byte read by the call, looked up from the previous value of a slot.
+const READ_TAG: u32 = 0;
+/// Tag of a byte written by the call, looked up from the new value of a slot.
+const WRITE_TAG: u32 = 1;
+
+/// The tuple consists of clk, a tag, the address and the byte.
+///
+/// Reads use [`READ_TAG`] and the write of the result [`WRITE_TAG`], so that reads cannot observe the result.
+const BYTE_TUPLE_SIZE: usize = 4 + 1 + 4 + 1;
+stwo_prover::relation!(Bigint256ByteElements, BYTE_TUPLE_SIZE);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Operation {
+    /// Padding row.
+    #[default]
+    None,
+    MulMod,
+    AddMod,
+    /// Known operation with a zero modulus.
+    ZeroModulus,
+    /// Unknown operation with a nonzero byte above the lowest one.
+    InvalidHigh,
+    /// Unknown operation below 256.
+    InvalidLow,
+}
+
+/// A distinct byte accessed in RAM.
+#[derive(Debug, Clone, Copy, Default)]
+struct Slot {
+    address: u32,
+    prev_value: u8,
+    prev_timestamp: u32,
+    value: u8,
+    /// Number of reads of the byte by the call.
+    reads: u32,
+    written: bool,
+}
+
+/// Witness of a row, i.e. of a single call.
+#[derive(Debug, Clone, Default)]
+pub struct Bigint256Row {
+    operation: Operation,
+    clk: u32,
+    args_ptr: u32,
+    op: u32,
+    args: [u8; ARGUMENT_BYTES],
+    /// `a`, `b` and the modulus.
+    operands: [[u8; BIGINT_BYTES]; 3],
+    /// The result, zero unless written.
+    result: [u8; BIGINT_BYTES],
+    slots: Vec<Slot>,
+}
+
+/// Proves calls of [`Bigint256`](crate::Bigint256) with the given precompile index.
+///
+/// Every call takes a single row of the extension.
+#[derive(Debug, Clone)]
+pub struct Bigint256Circuit {
+    index: u16,
+}
+
+impl Bigint256Circuit {
+    /// The index is the position of the precompile in the guest's `use_precompiles!` invocation.
+    pub fn new(index: u16) -> Self {
+        Self { index }
+    }
+}
+
+impl PrecompileCircuit for Bigint256Circuit {}
+
+impl RowCircuit for Bigint256Circuit {
+    type Input = Bigint256Row;
+
+    fn rows(&self, side_note: &SideNote) -> Vec<Bigint256Row> {
+        side_note
+            .precompile_calls()
+            .iter()
+            .filter(|call| call.index == self.index)
+            .map(|call| {
+                let mut slots: BTreeMap<u32, Slot> = call
+                    .memory
+                    .iter()
+                    .map(|access| {
+                        let slot = Slot {
+                            address: access.address,
+                            prev_value: access.prev_value,
+                            prev_timestamp: access.prev_timestamp,
+                            value: access.value,
+                            ..Slot::default()
+                        };
+                        (access.address, slot)
+                    })
+                    .collect();
+                let mut read = |address: u32| {
+                    let slot = slots
+                        .get_mut(&address)
+                        .expect("bigint256 reads an accessed byte");
+                    slot.reads += 1;
+                    slot.prev_value
+                };
+
+                let args: [u8; ARGUMENT_BYTES] =
+                    array::from_fn(|i| read(call.value_b.wrapping_add(i as u32)));
+                let pointers: [u32; 4] =
+                    array::from_fn(|k| u32::from_le_bytes(array::from_fn(|j| args[4 * k + j])));
+                let operands = array::from_fn(|k| {
+                    array::from_fn(|i| read(pointers[k].wrapping_add(i as u32)))
+                });
+
+                let operation = match call.value_c {
+                    OP_MUL_MOD if call.value_a == STATUS_OK => Operation::MulMod,
+                    OP_ADD_MOD if call.value_a == STATUS_OK => Operation::AddMod,
+                    OP_MUL_MOD | OP_ADD_MOD => Operation::ZeroModulus,
+                    op if op >> 8 != 0 => Operation::InvalidHigh,
+                    _ => Operation::InvalidLow,
+                };
+                assert!(
+                    matches!(call.value_a, STATUS_OK | STATUS_INVALID),
+                    "bigint256 status mismatch"
+                );
+
+                let mut result = [0; BIGINT_BYTES];
+                if call.value_a == STATUS_OK {
+                    for (i, byte) in result.iter_mut().enumerate() {
+                        let slot = slots
+                            .get_mut(&pointers[3].wrapping_add(i as u32))
+                            .expect("bigint256 writes an accessed byte");
+                        slot.written = true;
+                        *byte = slot.value;
+                    }
+                }
+                assert!(slots.len() <= MAX_SLOTS);
+
+                Bigint256Row {
+                    operation,
+                    clk: call.clk,
+                    args_ptr: call.value_b,
+                    op: call.value_c,
+                    args,
+                    operands,
+                    result,
+                    slots: slots.into_values().collect(),
+                }
+            })
+            .collect()
+    }
+
+    fn evaluate<R: Row>(
+        &self,
+        row: &mut R,
+        input: &Bigint256Row,
+        lookup_elements: &AllLookupElements,
+    ) {
+        let one = constant::<R::F>(1);
+
+        let [is_mul, is_add, is_zero_modulus, is_invalid_high, is_invalid_low] = [
+            Operation::MulMod,
+            Operation::AddMod,
+            Operation::ZeroModulus,
+            Operation::InvalidHigh,
+            Operation::InvalidLow,
+        ]
+        .map(|operation| next_bool(row, input.operation == operation));
+        let is_ok = is_mul.clone() + is_add.clone();
+        let is_valid = is_ok.clone() + is_zero_modulus.clone();
+        let is_invalid = is_invalid_high.clone() + is_invalid_low.clone();
+        let is_real = is_valid.clone() + is_invalid.clone();
+        row.add_constraint(is_real.clone() * (is_real.clone() - one.clone()));
+
+        let clk = next_bytes(row, input.clk);
+        let args_ptr = next_bytes(row, input.args_ptr);
+        let op = next_bytes(row, input.op);
+
+        // Decode the operation, unknown ones either have a nonzero high byte or the lowest byte at least 2.
+        for byte in &op[1..] {
+            row.add_constraint(is_valid.clone() * byte.clone());
+        }
+        row.add_constraint(is_mul.clone() * op[0].clone());
+        row.add_constraint(is_add.clone() * (op[0].clone() - one.clone()));
+        row.add_constraint(is_zero_modulus.clone() * op[0].clone() * (op[0].clone() - one.clone()));
+        let is_high_zero = next_is_zero(row, &op[1..]);
+        row.add_constraint(is_invalid_high.clone() * is_high_zero);
+        row.range_check_u8(
+            is_invalid_low.clone(),
+            op[0].clone() - constant(2),
+            lookup_elements,
+        );
+
+        let precompile: &PrecompileLookupElements = lookup_elements.as_ref();
+        let mut tuple = clk.to_vec();
+        tuple.push(constant(self.index as u32));
+        tuple.extend(args_ptr.iter().cloned());
+        tuple.extend(op.iter().cloned());
+        tuple.extend([
+            is_zero_modulus.clone() + is_invalid,
+            constant(0),
+            constant(0),
+            constant(0),
+        ]);
+        row.add_to_relation(precompile, -is_real.clone(), &tuple);
+
+        // Read the argument block and the operands, all of them are read regardless of the operation.
+        let bytes = row_bytes(row, input);
+        let args = &bytes[..ARGUMENT_BYTES];
+        evaluate_reads(
+            row,
+            lookup_elements,
+            &is_real,
+            &clk,
+            input.args_ptr,
+            &args_ptr,
+            args,
+        );
+        let pointer_values: [u32; 4] =
+            array::from_fn(|k| u32::from_le_bytes(array::from_fn(|j| input.args[4 * k + j])));
+        let pointers: [[R::F; 4]; 4] =
+            array::from_fn(|k| array::from_fn(|j| args[4 * k + j].clone()));
+        let operands: [&[R::F]; 3] = array::from_fn(|k| {
+            let start = ARGUMENT_BYTES + k * BIGINT_BYTES;
+            &bytes[start..start + BIGINT_BYTES]
+        });
+        for k in 0..3 {
+            evaluate_reads(
+                row,
+                lookup_elements,
+                &is_real,
+                &clk,
+                pointer_values[k],
+                &pointers[k],
+                operands[k],
+            );
+        }
+        let [a, b, modulus] = operands;
+
+        let is_modulus_zero = next_is_zero(row, modulus);
+        row.add_constraint(is_ok.clone() * is_modulus_zero.clone());
+        row.add_constraint(is_zero_modulus.clone() * (one.clone() - is_modulus_zero));
+
+        // a・b ≡ r and a + b ≡ r modulo a nonzero modulus, with r < modulus.
+        let result = next_uint(
+            row,
+            &BigUint::from_bytes_le(&input.result),
+            BIGINT_BYTES,
+            &is_ok,
+            lookup_elements,
+        );
+        let poly = Poly::from_bytes;
+        assert_zero_mod_var(
+            row,
+            poly(a) * poly(b) - poly(&result),
+            modulus,
+            &is_mul,
+            lookup_elements,
+        );
+        assert_zero_mod_var(
+            row,
+            poly(a) + poly(b) - poly(&result),
+            modulus,
+            &is_add,
+            lookup_elements,
+        );
+        assert_lt(row, &result, poly(modulus), &is_ok, lookup_elements);
+
+        // Write the result.
+        let bytes: &Bigint256ByteElements = lookup_elements.get_custom();
+        for (w, word) in result.chunks_exact(4).enumerate() {
+            let address = next_word_address(
+                row,
+                &is_ok,
+                pointer_values[3],
+                &pointers[3],
+                4 * w as u32,
+                lookup_elements,
+            );
+            for (j, byte) in word.iter().enumerate() {
+                let mut tuple = clk.to_vec();
+                tuple.push(constant(WRITE_TAG));
+                tuple.extend(byte_address(&address, j));
+                tuple.push(byte.clone());
+                row.add_to_relation(bytes, -is_ok.clone(), &tuple);
+            }
+        }
+
+        self.evaluate_slots(row, input, lookup_elements, &clk);
+    }
+
+    fn draw_lookup_elements(
+        &self,
+        lookup_elements: &mut AllLookupElements,
+        channel: &mut MachineChannel,
+    ) {
+        lookup_elements.insert_custom(Bigint256ByteElements::draw(channel));
+    }
+
+    fn declare_support(&self, support: &mut Support) {
+        support.precompiles.insert(self.index);
+    }
+}
+
+impl Bigint256Circuit {
+    /// Accesses the bytes of the call in RAM, and provides their values to reads and the write.
+    fn evaluate_slots<R: Row>(
+        &self,
+        row: &mut R,
+        input: &Bigint256Row,
+        lookup_elements: &AllLookupElements,
+        clk: &[R::F; 4],
+    ) {
+        let one = constant::<R::F>(1);
+        let load_store: &LoadStoreLookupElements = lookup_elements.as_ref();
+        let bytes: &Bigint256ByteElements = lookup_elements.get_custom();
+
+        for s in 0..MAX_SLOTS {
+            let slot = input.slots.get(s);
+            let is_active = next_bool(row, slot.is_some());
+            let slot = slot.copied().unwrap_or_default();
+            let reads = row.next(BaseField::from(slot.reads));
+            let is_written = next_bool(row, slot.written);
+            row.add_constraint(reads.clone() * (one.clone() - is_active.clone()));
+            row.add_constraint(is_written.clone() * (one.clone() - is_active.clone()));
+
+            let address = next_bytes(row, slot.address);
+            let prev_value = row.next(BaseField::from(slot.prev_value as u32));
+            let value = row.next(BaseField::from(slot.value as u32));
+            row.add_constraint(
+                (one.clone() - is_written.clone()) * (value.clone() - prev_value.clone()),
+            );
+
+            // prev_timestamp < clk, i.e. clk - 1 - prev_timestamp fits into 32 bits.
+            let prev_timestamp = next_bytes(row, slot.prev_timestamp);
+            let aux = next_lt(
+                row,
+                &is_active,
+                slot.prev_timestamp,
+                input.clk,
+                &prev_timestamp,
+                clk,
+            );
+            for byte in &aux {
+                row.range_check_u8(is_active.clone(), byte.clone(), lookup_elements);
+            }
+
+            let mut tuple = address.to_vec();
+            tuple.push(prev_value.clone());
+            tuple.extend(prev_timestamp);
+            row.add_to_relation(load_store, -is_active.clone(), &tuple);
+            let mut tuple = address.to_vec();
+            tuple.push(value.clone());
+            tuple.extend(clk.iter().cloned());
+            row.add_to_relation(load_store, is_active, &tuple);
+
+            for (multiplicity, tag, value) in [
+                (reads, READ_TAG, prev_value),
+                (is_written, WRITE_TAG, value),
+            ] {
+                let mut tuple = clk.to_vec();
+                tuple.push(constant(tag));
+                tuple.extend(address.iter().cloned());
+                tuple.push(value);
+                row.add_to_relation(bytes, multiplicity, &tuple);
+            }
+        }
+    }
+}
+
+/// Reads `values` from consecutive bytes at the word-aligned `ptr`, looking them up from the slots.
+fn evaluate_reads<R: Row>(
+    row: &mut R,
+    lookup_elements: &AllLookupElements,
+    is_real: &R::F,
+    clk: &[R::F; 4],
+    ptr_value: u32,
+    ptr: &[R::F; 4],
+    values: &[R::F],
+) {
+    let alignment = row.next(BaseField::from((ptr_value & 0xFF) >> 2));
+    row.add_constraint(is_real.clone() * (ptr[0].clone() - alignment.clone() * BaseField::from(4)));
+    row.range_check_u8(is_real.clone(), alignment, lookup_elements);
+
+    let bytes: &Bigint256ByteElements = lookup_elements.get_custom();
+    for (w, word) in values.chunks_exact(4).enumerate() {
+        let address =
+            next_word_address(row, is_real, ptr_value, ptr, 4 * w as u32, lookup_elements);
+        for (j, value) in word.iter().enumerate() {
+            let mut tuple = clk.to_vec();
+            tuple.push(constant(READ_TAG));
+            tuple.extend(byte_address(&address, j));
+            tuple.push(value.clone());
+            row.add_to_relation(bytes, -is_real.clone(), &tuple);
+        }
+    }
+}
+
+/// Allocates the bytes read by the call: the argument block followed by the operands.
+fn row_bytes<R: Row>(row: &mut R, input: &Bigint256Row) -> Vec<R::F> {
+    input
+        .args
+        .iter()
+        .chain(input.operands.iter().flatten())
+        .map(|byte| row.next(BaseField::from(*byte as u32)))
+        .collect()
+}
+
+fn byte_address<F: RowField>(word: &[F; 4], j: usize) -> [F; 4] {
+    [
+        word[0].clone() + constant(j as u32),
+        word[1].clone(),
+        word[2].clone(),
+        word[3].clone(),
+    ]
+}
+
+fn constant<F: RowField>(value: u32) -> F {
+    F::from(BaseField::from(value))
+}
+
+fn next_bool<R: Row>(row: &mut R, value: bool) -> R::F {
+    let bit = row.next(BaseField::from(value as u32));
+    row.add_constraint(bit.clone() * (bit.clone() - constant(1)));
+    bit
+}
+
+/// Allocates bytes of a word, the caller is responsible for checking their range.
+fn next_bytes<R: Row>(row: &mut R, value: u32) -> [R::F; 4] {
+    value
+        .to_le_bytes()
+        .map(|byte| row.next(BaseField::from(byte as u32)))
+}
+
+fn byte_limbs<F: RowField>(bytes: &[F; 4]) -> [F; 2] {
+    array::from_fn(|j| bytes[2 * j].clone() + bytes[2 * j + 1].clone() * BaseField::from(1 << 8))
+}
+
+/// Allocates the bytes of `ptr + offset` for a word-aligned `ptr` and a small multiple of four `offset`.
+fn next_word_address<R: Row>(
+    row: &mut R,
+    flag: &R::F,
+    ptr_value: u32,
+    ptr: &[R::F; 4],
+    offset: u32,
+    lookup_elements: &AllLookupElements,
+) -> [R::F; 4] {
+    let address = next_bytes(row, ptr_value.wrapping_add(offset));
+    for byte in &address {
+        row.range_check_u8(flag.clone(), byte.clone(), lookup_elements);
+    }
+    let carry = next_bool(row, (ptr_value & 0xFFFF) + offset > 0xFFFF);
+    let [ptr_lo, ptr_hi] = byte_limbs(ptr);
+    let [lo, hi] = byte_limbs(&address);
+    row.add_constraint(
+        flag.clone() * (ptr_lo + constant(offset) - lo - carry.clone() * BaseField::from(1 << 16)),
+    );
+    row.add_constraint(flag.clone() * (ptr_hi + carry - hi));
+    address
+}
+
+/// Constrains `lhs < rhs` when `flag` is set, returns the bytes of `rhs - 1 - lhs` which the caller must range check.
+fn next_lt<R: Row>(
+    row: &mut R,
+    flag: &R::F,
+    lhs_value: u32,
+    rhs_value: u32,
+    lhs: &[R::F; 4],
+    rhs: &[R::F; 4],
+) -> [R::F; 4] {
+    let aux_value = rhs_value.wrapping_sub(1).wrapping_sub(lhs_value);
+    let aux = next_bytes(row, aux_value);
+    let borrow = next_bool(
+        row,
+        (lhs_value & 0xFFFF) + 1 + (aux_value & 0xFFFF) > 0xFFFF,
+    );
+    let [lhs_lo, lhs_hi] = byte_limbs(lhs);
+    let [aux_lo, aux_hi] = byte_limbs(&aux);
+    let [rhs_lo, rhs_hi] = byte_limbs(rhs);
+    row.add_constraint(
+        flag.clone()
+            * (lhs_lo + constant::<R::F>(1) + aux_lo
+                - rhs_lo
+                - borrow.clone() * BaseField::from(1 << 16)),
+    );
+    row.add_constraint(flag.clone() * (lhs_hi + aux_hi + borrow - rhs_hi));
+    aux
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use nexus_vm::{
+        emulator::{Emulator, HarvardEmulator, InternalView},
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, InstructionType, Opcode, Register},
+        trace::k_trace_harvard,
+    };
+    use nexus_vm_prover::{
+        extensions::{circuit::CircuitExtension, ExtensionComponent},
+        machine::{BaseComponent, Machine},
+        Blake2sMerkleHasher, ProverConfig,
+    };
+
+    use num_traits::Zero;
+
+    use crate::Bigint256;
+
+    // Offsets from x1 = 0x8FF00 of the argument block and the integers.
+    const ARGS: u32 = 0xC0;
+    const A: u32 = 0x00;
+    const B: u32 = 0x20;
+    const MODULUS: u32 = 0x40;
+    const ZERO: u32 = 0x60;
+    const RESULT: u32 = 0xF0;
+
+    /// Stores the little-endian bytes of `value` at `offset`.
+    fn store(instructions: &mut Vec<Instruction>, offset: u32, value: &BigUint) {
+        let mut bytes = value.to_bytes_le();
+        bytes.resize(BIGINT_BYTES, 0);
+        for (i, byte) in bytes.into_iter().enumerate() {
+            instructions.push(Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                6,
+                0,
+                byte as u32,
+            ));
+            instructions.push(Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::SB),
+                1,
+                6,
+                offset + i as u32,
+            ));
+        }
+    }
+
+    /// Points the argument block to the integers at the given offsets.
+    fn store_args(instructions: &mut Vec<Instruction>, offsets: [u32; 4]) {
+        for (k, offset) in offsets.into_iter().enumerate() {
+            instructions.push(Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                7,
+                1,
+                offset,
+            ));
+            instructions.push(Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::SW),
+                10,
+                7,
+                4 * k as u32,
+            ));
+        }
+    }
+
+    fn call(instructions: &mut Vec<Instruction>, opcode: &Opcode, op: u32) {
+        instructions.push(Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            11,
+            0,
+            op,
+        ));
+        instructions.push(Instruction::new(
+            opcode.clone(),
+            Register::X12,
+            Register::X10,
+            11,
+            InstructionType::RType,
+        ));
+    }
+
+    #[test]
+    fn test_prove_bigint256() {
+        let opcode = Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "dynamic");
+        let max = (BigUint::from(1u32) << 256) - 1u32;
+        let p = BigUint::parse_bytes(
+            b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F",
+            16,
+        )
+        .unwrap();
+        let b = BigUint::parse_bytes(
+            b"483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8",
+            16,
+        )
+        .unwrap();
+
+        let mut instructions = vec![
+            // x1 = 0x8FF00 and x10 = 0x8FFC0, so that the addresses of the result carry into the third byte.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0x7FF),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 0x100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 1, 1, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 1, ARGS),
+        ];
+        store(&mut instructions, A, &max);
+        store(&mut instructions, B, &b);
+        store(&mut instructions, MODULUS, &p);
+        store(&mut instructions, ZERO, &BigUint::zero());
+        store_args(&mut instructions, [A, B, MODULUS, RESULT]);
+        call(&mut instructions, &opcode, OP_MUL_MOD);
+        call(&mut instructions, &opcode, OP_ADD_MOD);
+        // The result overwrites an operand.
+        store_args(&mut instructions, [A, B, MODULUS, A]);
+        call(&mut instructions, &opcode, OP_MUL_MOD);
+        call(&mut instructions, &opcode, OP_ADD_MOD);
+        // All operands are the same integer.
+        store_args(&mut instructions, [MODULUS, MODULUS, MODULUS, RESULT]);
+        call(&mut instructions, &opcode, OP_ADD_MOD);
+        // An operand and the result overlap the argument block.
+        store_args(&mut instructions, [ARGS, B, MODULUS, ARGS]);
+        call(&mut instructions, &opcode, OP_MUL_MOD);
+        // A zero modulus and unknown operations.
+        store_args(&mut instructions, [A, B, ZERO, RESULT]);
+        call(&mut instructions, &opcode, OP_MUL_MOD);
+        call(&mut instructions, &opcode, OP_ADD_MOD);
+        store_args(&mut instructions, [A, B, MODULUS, RESULT]);
+        call(&mut instructions, &opcode, 2);
+        call(&mut instructions, &opcode, 0x100);
+        let basic_blocks = vec![BasicBlock::new(instructions)];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator.add_opcode::<Bigint256>(&opcode).unwrap();
+        let (view, program_trace) = k_trace_harvard(emulator, 1).expect("error generating trace");
+
+        let prove_verify = |extensions: &[ExtensionComponent]| {
+            let proof = Machine::<BaseComponent>::prove_with_extensions::<Blake2sMerkleHasher>(
+                extensions,
+                &program_trace,
+                &view,
+                &ProverConfig::default(),
+            )
+            .unwrap();
+            Machine::<BaseComponent>::verify_with_extensions(
+                extensions,
+                proof,
+                ProverConfig::default().security_bits(),
+                view.get_program_memory(),
+                view.view_memory_layout().as_ref(),
+                &[],
+                view.get_initial_memory(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+        prove_verify(&[ExtensionComponent::custom(Arc::new(CircuitExtension::new(
+            Bigint256Circuit::new(0),
+            4,
+        )))])
+        .unwrap();
+
+        // Without the extension nothing consumes the calls added by the CPU.
+        assert!(prove_verify(&[]).is_err());
+    }
+}
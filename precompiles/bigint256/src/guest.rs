@@ -0,0 +1,60 @@
+/// In the guest context, there is nothing actually associated with the precompile other than the
+/// convenience wrappers for emitting the instruction call.
+pub struct Bigint256;
+
+#[macro_export]
+macro_rules! generate_instruction_caller {
+    ($path:path) => {
+        trait Bigint256Caller {
+            /// Computes `a・b mod modulus`.
+            fn mul_mod(
+                a: &$crate::U256,
+                b: &$crate::U256,
+                modulus: &$crate::U256,
+            ) -> Option<$crate::U256>;
+
+            /// Computes `a + b mod modulus`.
+            fn add_mod(
+                a: &$crate::U256,
+                b: &$crate::U256,
+                modulus: &$crate::U256,
+            ) -> Option<$crate::U256>;
+        }
+
+        impl Bigint256Caller for $path {
+            fn mul_mod(
+                a: &$crate::U256,
+                b: &$crate::U256,
+                modulus: &$crate::U256,
+            ) -> Option<$crate::U256> {
+                let mut result = [0u32; $crate::BIGINT_WORDS];
+                let args = [
+                    a.as_ptr() as u32,
+                    b.as_ptr() as u32,
+                    modulus.as_ptr() as u32,
+                    result.as_mut_ptr() as u32,
+                ];
+                let status = Self::emit_instruction(args.as_ptr() as u32, $crate::OP_MUL_MOD, 0);
+
+                (status == $crate::STATUS_OK).then_some(result)
+            }
+
+            fn add_mod(
+                a: &$crate::U256,
+                b: &$crate::U256,
+                modulus: &$crate::U256,
+            ) -> Option<$crate::U256> {
+                let mut result = [0u32; $crate::BIGINT_WORDS];
+                let args = [
+                    a.as_ptr() as u32,
+                    b.as_ptr() as u32,
+                    modulus.as_ptr() as u32,
+                    result.as_mut_ptr() as u32,
+                ];
+                let status = Self::emit_instruction(args.as_ptr() as u32, $crate::OP_ADD_MOD, 0);
+
+                (status == $crate::STATUS_OK).then_some(result)
+            }
+        }
+    };
+}
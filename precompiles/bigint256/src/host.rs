@@ -0,0 +1,446 @@
+use nexus_common::{
+    cpu::{InstructionExecutor, InstructionResult, InstructionState, Processor, Registers},
+    error::MemoryError,
+    memory::{LoadOp, LoadOps, MemAccessSize, MemoryProcessor, StoreOps},
+    riscv::{instruction::Instruction, register::Register},
+};
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use nexus_precompiles::{PrecompileCircuit, PrecompileInstruction, PrecompileMetadata};
+
+use crate::{
+    Bigint256Circuit, BIGINT_WORDS, OP_ADD_MOD, OP_MUL_MOD, STATUS_INVALID, STATUS_OK, U256,
+};
+
+/// Number of addresses in the argument block: `a`, `b`, `modulus` and `result`.
+const ARGUMENT_WORDS: usize = 4;
+
+fn to_biguint(words: &[u32]) -> BigUint {
+    BigUint::from_slice(words)
+}
+
+fn from_biguint(value: &BigUint) -> U256 {
+    let mut words = [0u32; BIGINT_WORDS];
+    for (word, digit) in words.iter_mut().zip(value.iter_u32_digits()) {
+        *word = digit;
+    }
+    words
+}
+
+/// Applies `op` to `a` and `b` modulo `modulus`, returning `None` if the operation is unknown or the modulus is zero.
+fn apply(op: u32, a: &[u32], b: &[u32], modulus: &[u32]) -> Option<U256> {
+    let modulus = to_biguint(modulus);
+    if modulus.is_zero() {
+        return None;
+    }
+
+    let (a, b) = (to_biguint(a), to_biguint(b));
+    let result = match op {
+        OP_MUL_MOD => (a * b) % modulus,
+        OP_ADD_MOD => (a + b) % modulus,
+        _ => return None,
+    };
+    Some(from_biguint(&result))
+}
+
+/// Computes `a・b mod modulus`, executed on the native host.
+///
+/// Returns `None` if the modulus is zero, like the wrapper generated for the guest.
+pub fn mul_mod(a: &U256, b: &U256, modulus: &U256) -> Option<U256> {
+    apply(OP_MUL_MOD, a, b, modulus)
+}
+
+/// Computes `a + b mod modulus`, executed on the native host.
+///
+/// Returns `None` if the modulus is zero, like the wrapper generated for the guest.
+pub fn add_mod(a: &U256, b: &U256, modulus: &U256) -> Option<U256> {
+    apply(OP_ADD_MOD, a, b, modulus)
+}
+
+/// Defines the caller trait of the guest for the native host, backed by [`mul_mod`] and [`add_mod`], so that guest
+/// programs calling the precompile also build natively.
+#[macro_export]
+macro_rules! generate_instruction_caller {
+    ($path:path) => {
+        trait Bigint256Caller {
+            /// Computes `a・b mod modulus`.
+            fn mul_mod(
+                a: &$crate::U256,
+                b: &$crate::U256,
+                modulus: &$crate::U256,
+            ) -> Option<$crate::U256>;
+
+            /// Computes `a + b mod modulus`.
+            fn add_mod(
+                a: &$crate::U256,
+                b: &$crate::U256,
+                modulus: &$crate::U256,
+            ) -> Option<$crate::U256>;
+        }
+
+        impl Bigint256Caller for $path {
+            fn mul_mod(
+                a: &$crate::U256,
+                b: &$crate::U256,
+                modulus: &$crate::U256,
+            ) -> Option<$crate::U256> {
+                $crate::mul_mod(a, b, modulus)
+            }
+
+            fn add_mod(
+                a: &$crate::U256,
+                b: &$crate::U256,
+                modulus: &$crate::U256,
+            ) -> Option<$crate::U256> {
+                $crate::add_mod(a, b, modulus)
+            }
+        }
+    };
+}
+
+/// Returns the address of the `index`-th word at `ptr`, failing instead of wrapping around the address space.
+fn word_address(ptr: u32, index: usize) -> Result<u32, MemoryError> {
+    ptr.checked_add((index * 4) as u32)
+        .ok_or(MemoryError::AddressCalculationOverflow)
+}
+
+#[derive(Default)]
+pub struct Bigint256 {
+    rd: (Register, u32),
+    args_ptr: u32,
+    op: u32,
+    result_ptr: u32,
+    operands: Vec<u32>,
+    result: Option<U256>,
+}
+
+impl InstructionState for Bigint256 {
+    fn execute(&mut self) {
+        self.result = apply(
+            self.op,
+            &self.operands[..BIGINT_WORDS],
+            &self.operands[BIGINT_WORDS..2 * BIGINT_WORDS],
+            &self.operands[2 * BIGINT_WORDS..],
+        );
+        self.rd.1 = if self.result.is_some() {
+            STATUS_OK
+        } else {
+            STATUS_INVALID
+        };
+    }
+
+    fn memory_read(&mut self, memory: &impl MemoryProcessor) -> Result<LoadOps, MemoryError> {
+        let mut load_ops = LoadOps::default();
+        let mut read_word = |address: u32| -> Result<u32, MemoryError> {
+            let load_op = memory.read(address, MemAccessSize::Word)?;
+            load_ops.insert(load_op);
+
+            let LoadOp::Op(_, _, value) = load_op;
+            Ok(value)
+        };
+
+        let mut args = [0u32; ARGUMENT_WORDS];
+        for (i, arg) in args.iter_mut().enumerate() {
+            *arg = read_word(word_address(self.args_ptr, i)?)?;
+        }
+        let [a_ptr, b_ptr, modulus_ptr, result_ptr] = args;
+        self.result_ptr = result_ptr;
+
+        self.operands = Vec::with_capacity(3 * BIGINT_WORDS);
+        for ptr in [a_ptr, b_ptr, modulus_ptr] {
+            for i in 0..BIGINT_WORDS {
+                self.operands.push(read_word(word_address(ptr, i)?)?);
+            }
+        }
+
+        Ok(load_ops)
+    }
+
+    fn memory_write(&self, memory: &mut impl MemoryProcessor) -> Result<StoreOps, MemoryError> {
+        let mut store_ops = StoreOps::default();
+        let Some(result) = self.result else {
+            return Ok(store_ops);
+        };
+
+        for (i, word) in result.iter().enumerate() {
+            store_ops.insert(memory.write(
+                word_address(self.result_ptr, i)?,
+                MemAccessSize::Word,
+                *word,
+            )?);
+        }
+
+        Ok(store_ops)
+    }
+
+    fn write_back(&self, cpu: &mut impl Processor) -> InstructionResult {
+        cpu.registers_mut().write(self.rd.0, self.rd.1);
+        Some(self.rd.1)
+    }
+}
+
+impl InstructionExecutor for Bigint256 {
+    type InstructionState = Self;
+
+    fn decode(ins: &Instruction, registers: &impl Registers) -> Self {
+        Self {
+            rd: (ins.op_a, registers[ins.op_a]),
+            args_ptr: registers[ins.op_b],
+            op: registers[Register::from(ins.op_c as u8)],
+            ..Default::default()
+        }
+    }
+}
+
+impl PrecompileInstruction for Bigint256 {
+    fn metadata() -> PrecompileMetadata {
+        PrecompileMetadata {
+            author: "The Nexus Team",
+            name: "Bigint256",
+            description: "256-bit modular multiplication and addition",
+            version_major: 1,
+            version_minor: 0,
+            version_patch: 0,
+        }
+    }
+
+    fn circuit() -> impl PrecompileCircuit {
+        Bigint256Circuit::new(0)
+    }
+
+    fn native_call(_rs1: u32, _rs2: u32) -> u32 {
+        // The argument block cannot be dereferenced on the native host, the wrappers generated for the host call
+        // [`mul_mod`] and [`add_mod`] instead of emitting the instruction.
+        STATUS_INVALID
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nexus_vm::{
+        emulator::{Emulator, HarvardEmulator, LinearEmulator},
+        error::VMError,
+        memory::{UnifiedMemory, VariableMemory, RW},
+        riscv::{InstructionType, Opcode},
+    };
+
+    const ARGS_ADDRESS: u32 = 0x1000;
+    const A_ADDRESS: u32 = 0x1100;
+    const B_ADDRESS: u32 = 0x1200;
+    const MODULUS_ADDRESS: u32 = 0x1300;
+    const RESULT_ADDRESS: u32 = 0x1400;
+
+    fn hex(value: &str) -> U256 {
+        from_biguint(&BigUint::parse_bytes(value.as_bytes(), 16).unwrap())
+    }
+
+    /// The secp256k1 base field modulus.
+    fn secp256k1_p() -> U256 {
+        hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+    }
+
+    fn minus(modulus: &U256, value: u32) -> U256 {
+        from_biguint(&(to_biguint(modulus) - value))
+    }
+
+    #[test]
+    fn test_native_mul_mod() {
+        let p = secp256k1_p();
+        let minus_one = minus(&p, 1);
+        let one = from_biguint(&BigUint::from(1u32));
+
+        assert_eq!(mul_mod(&minus_one, &minus_one, &p), Some(one));
+
+        // 2^128・2^128 mod (2^256 - 1) = 1.
+        let mut a = [0u32; BIGINT_WORDS];
+        a[4] = 1;
+        assert_eq!(mul_mod(&a, &a, &[u32::MAX; BIGINT_WORDS]), Some(one));
+    }
+
+    #[test]
+    fn test_native_add_mod() {
+        let p = secp256k1_p();
+
+        assert_eq!(
+            add_mod(&minus(&p, 1), &minus(&p, 2), &p),
+            Some(minus(&p, 3))
+        );
+
+        // The sum overflows 256 bits before being reduced.
+        let max = [u32::MAX; BIGINT_WORDS];
+        assert_eq!(
+            add_mod(&max, &max, &p).map(|result| to_biguint(&result)),
+            Some((to_biguint(&max) << 1) % to_biguint(&p))
+        );
+    }
+
+    #[test]
+    fn test_native_zero_modulus() {
+        let one = [1; BIGINT_WORDS];
+        let zero = [0; BIGINT_WORDS];
+
+        assert_eq!(mul_mod(&one, &one, &zero), None);
+        assert_eq!(add_mod(&one, &one, &zero), None);
+    }
+
+    #[test]
+    fn test_native_caller() {
+        // The wrappers a guest program calls, as generated by `use_precompiles!` on the native host.
+        struct Precompile;
+        crate::generate_instruction_caller!(Precompile);
+
+        let p = secp256k1_p();
+        let (a, b) = (minus(&p, 1), minus(&p, 2));
+        assert_eq!(
+            <Precompile as Bigint256Caller>::mul_mod(&a, &b, &p),
+            mul_mod(&a, &b, &p)
+        );
+        assert_eq!(
+            <Precompile as Bigint256Caller>::add_mod(&a, &b, &p),
+            Some(minus(&p, 3))
+        );
+        assert_eq!(
+            <Precompile as Bigint256Caller>::mul_mod(&a, &b, &[0; BIGINT_WORDS]),
+            None
+        );
+    }
+
+    /// Runs the precompile on the operands through the given emulator and returns the status and the result buffer.
+    fn run<E: Emulator>(
+        emulator: &mut E,
+        memory: impl Fn(&mut E) -> &mut UnifiedMemory,
+        op: u32,
+        (a, b, modulus): (&U256, &U256, &U256),
+    ) -> (u32, U256) {
+        let opcode = Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "dynamic");
+        emulator.add_opcode::<Bigint256>(&opcode).unwrap();
+
+        let args = [A_ADDRESS, B_ADDRESS, MODULUS_ADDRESS, RESULT_ADDRESS];
+        for (address, words) in [
+            (ARGS_ADDRESS, &args[..]),
+            (A_ADDRESS, &a[..]),
+            (B_ADDRESS, &b[..]),
+            (MODULUS_ADDRESS, &modulus[..]),
+        ] {
+            for (i, word) in words.iter().enumerate() {
+                memory(emulator)
+                    .write(address + (i * 4) as u32, MemAccessSize::Word, *word)
+                    .unwrap();
+            }
+        }
+        emulator
+            .get_executor_mut()
+            .cpu
+            .registers
+            .write(Register::X1, ARGS_ADDRESS);
+        emulator
+            .get_executor_mut()
+            .cpu
+            .registers
+            .write(Register::X2, op);
+
+        let instruction = Instruction::new(
+            opcode,
+            Register::X3,
+            Register::X1,
+            2,
+            InstructionType::RType,
+        );
+        let (res, _) = emulator.execute_instruction(&instruction, false).unwrap();
+
+        let mut result = [0u32; BIGINT_WORDS];
+        for (i, word) in result.iter_mut().enumerate() {
+            let LoadOp::Op(_, _, value) = memory(emulator)
+                .read(RESULT_ADDRESS + (i * 4) as u32, MemAccessSize::Word)
+                .unwrap();
+            *word = value;
+        }
+        (res.unwrap(), result)
+    }
+
+    fn run_all_emulators(op: u32, operands: (&U256, &U256, &U256)) -> (u32, U256) {
+        let mut harvard = HarvardEmulator::from_basic_blocks(&Vec::new());
+        let harvard_result = run(&mut harvard, |e| &mut e.data_memory, op, operands);
+
+        let mut linear = LinearEmulator::default();
+        linear.memory = UnifiedMemory::from(VariableMemory::<RW>::default());
+        let linear_result = run(&mut linear, |e| &mut e.memory, op, operands);
+
+        assert_eq!(harvard_result, linear_result);
+        harvard_result
+    }
+
+    #[test]
+    fn test_mul_mod_instruction() {
+        let p = secp256k1_p();
+        let a = hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798");
+        let b = hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8");
+        let expected = mul_mod(&a, &b, &p).unwrap();
+
+        assert_eq!(
+            run_all_emulators(OP_MUL_MOD, (&a, &b, &p)),
+            (STATUS_OK, expected)
+        );
+    }
+
+    #[test]
+    fn test_add_mod_instruction() {
+        let p = secp256k1_p();
+
+        assert_eq!(
+            run_all_emulators(OP_ADD_MOD, (&minus(&p, 1), &minus(&p, 2), &p)),
+            (STATUS_OK, minus(&p, 3))
+        );
+    }
+
+    #[test]
+    fn test_invalid_instruction() {
+        let p = secp256k1_p();
+        let one = from_biguint(&BigUint::from(1u32));
+
+        assert_eq!(
+            run_all_emulators(2, (&one, &one, &p)),
+            (STATUS_INVALID, [0u32; BIGINT_WORDS])
+        );
+        assert_eq!(
+            run_all_emulators(OP_MUL_MOD, (&one, &one, &[0u32; BIGINT_WORDS])),
+            (STATUS_INVALID, [0u32; BIGINT_WORDS])
+        );
+    }
+
+    #[test]
+    fn test_pointer_overflow() {
+        let opcode = Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "dynamic");
+        let mut emulator = HarvardEmulator::from_basic_blocks(&Vec::new());
+        emulator.add_opcode::<Bigint256>(&opcode).unwrap();
+
+        // The operand `a` would extend past the end of the address space.
+        let args = [u32::MAX - 15, B_ADDRESS, MODULUS_ADDRESS, RESULT_ADDRESS];
+        for (i, word) in args.iter().enumerate() {
+            emulator
+                .data_memory
+                .write(ARGS_ADDRESS + (i * 4) as u32, MemAccessSize::Word, *word)
+                .unwrap();
+        }
+        let registers = &mut emulator.get_executor_mut().cpu.registers;
+        registers.write(Register::X1, ARGS_ADDRESS);
+        registers.write(Register::X2, OP_MUL_MOD);
+
+        let instruction = Instruction::new(
+            opcode,
+            Register::X3,
+            Register::X1,
+            2,
+            InstructionType::RType,
+        );
+        assert!(matches!(
+            emulator.execute_instruction(&instruction, false),
+            Err(VMError::MemoryError(
+                MemoryError::AddressCalculationOverflow
+            ))
+        ));
+    }
+}
@@ -0,0 +1,40 @@
+//! 256-bit modular arithmetic precompile.
+//!
+//! The first operand register points to a word-aligned argument block of four addresses `[a, b, modulus, result]`,
+//! each pointing to a word-aligned 256-bit integer stored as 8 little-endian 32-bit words. The second operand
+//! register selects the operation, [`OP_MUL_MOD`] or [`OP_ADD_MOD`]. On success, `result` is overwritten with the
+//! reduced result and [`STATUS_OK`] is returned. Otherwise [`STATUS_INVALID`] is returned and nothing is written,
+//! which happens if the operation is unknown or the modulus is zero.
+//!
+//! In the guest, `use_precompiles!` generates `mul_mod` and `add_mod` wrappers around the instruction. On the native
+//! host, it generates the same wrappers around [`mul_mod`](host::mul_mod) and [`add_mod`](host::add_mod), which
+//! implement the operations natively, so that guest programs can also be run and tested natively.
+//!
+//! Calls are proven by adding [`Bigint256Circuit`] as an extension of the prover, see [`circuit`].
+#![cfg_attr(target_arch = "riscv32", no_std)]
+
+#[cfg(target_arch = "riscv32")]
+pub mod guest;
+#[cfg(target_arch = "riscv32")]
+pub use guest::*;
+
+#[cfg(not(target_arch = "riscv32"))]
+pub mod circuit;
+#[cfg(not(target_arch = "riscv32"))]
+pub mod host;
+#[cfg(not(target_arch = "riscv32"))]
+pub use circuit::Bigint256Circuit;
+#[cfg(not(target_arch = "riscv32"))]
+pub use host::*;
+
+pub const OP_MUL_MOD: u32 = 0;
+pub const OP_ADD_MOD: u32 = 1;
+
+pub const STATUS_OK: u32 = 0;
+pub const STATUS_INVALID: u32 = 1;
+
+/// Number of 32-bit words in a 256-bit integer.
+pub const BIGINT_WORDS: usize = 8;
+
+/// A 256-bit integer, stored as little-endian words.
+pub type U256 = [u32; BIGINT_WORDS];
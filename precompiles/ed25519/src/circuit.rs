@@ -0,0 +1,721 @@
+//! Circuit proving calls of the ed25519 precompile.
+//!
+//! A call takes a single row, which consumes the call from [`PrecompileLookupElements`] and reads and writes the
+//! buffer in RAM. Field arithmetic is checked modulo p with gadgets from
+//! [`bigint`](nexus_vm_prover::extensions::bigint) over the bytes of the buffer. The addition law is complete, so a
+//! point addition follows [`point_add`](crate::point_add) without special cases, except that each denominator is
+//! either inverted or zero, in which case the host's inverse and the coordinate are zero as well.
+//!
+//! Results are always canonical, i.e. less than p.
+
+use std::array;
+
+use nexus_precompiles::PrecompileCircuit;
+use nexus_vm_prover::{
+    components::AllLookupElements,
+    extensions::{
+        bigint::{assert_lt, assert_zero_mod, next_is_zero, next_uint, Poly},
+        circuit::{Row, RowCircuit, RowField},
+        LoadStoreLookupElements, PrecompileLookupElements,
+    },
+    hash::MachineChannel,
+    preflight::Support,
+    trace::sidenote::SideNote,
+};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use stwo_prover::core::fields::m31::BaseField;
+
+use crate::{
+    curve_d, field_inv, modulus, FIELD_WORDS, OP_FIELD_MUL, OP_POINT_ADD, OP_POINT_DOUBLE,
+    STATUS_INVALID_OP, STATUS_OK,
+};
+
+/// Number of bytes in a field element.
+const ELEMENT_BYTES: usize = 4 * FIELD_WORDS;
+/// Number of bytes in the largest buffer, i.e. the operands of a point addition.
+const BUFFER_BYTES: usize = 4 * ELEMENT_BYTES;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Operation {
+    /// Padding row.
+    #[default]
+    None,
+    FieldMul,
+    PointAdd,
+    PointDouble,
+    /// Unknown operation with a nonzero byte above the lowest one.
+    InvalidHigh,
+    /// Unknown operation below 256.
+    InvalidLow,
+}
+
+impl Operation {
+    fn new(op: u32) -> Self {
+        match op {
+            OP_FIELD_MUL => Self::FieldMul,
+            OP_POINT_ADD => Self::PointAdd,
+            OP_POINT_DOUBLE => Self::PointDouble,
+            _ if op >> 8 != 0 => Self::InvalidHigh,
+            _ => Self::InvalidLow,
+        }
+    }
+
+    /// Number of bytes read from the buffer.
+    fn buffer_bytes(self) -> usize {
+        match self {
+            Self::FieldMul | Self::PointDouble => 2 * ELEMENT_BYTES,
+            Self::PointAdd => 4 * ELEMENT_BYTES,
+            _ => 0,
+        }
+    }
+}
+
+/// A byte of the buffer accessed in RAM.
+#[derive(Debug, Clone, Copy, Default)]
+struct BufferByte {
+    prev_value: u8,
+    prev_timestamp: u32,
+    value: u8,
+}
+
+/// Witness of a row, i.e. of a single call.
+#[derive(Debug, Clone, Default)]
+pub struct Ed25519Row {
+    operation: Operation,
+    clk: u32,
+    buffer_ptr: u32,
+    op: u32,
+    /// Bytes of the buffer, as many as read by the operation.
+    bytes: Vec<BufferByte>,
+}
+
+/// Values of a row derived from the operands, see [`point_add`](crate::point_add).
+#[derive(Debug, Default)]
+struct PointWitness {
+    /// `x1・x2`, `y1・y2`, their product and `d・x1・x2・y1・y2`.
+    xx: BigUint,
+    yy: BigUint,
+    xxyy: BigUint,
+    dxxyy: BigUint,
+    /// Inverses of `1 + d・x1・x2・y1・y2` and `1 - d・x1・x2・y1・y2`, zero if there are none.
+    x_inverse: BigUint,
+    y_inverse: BigUint,
+}
+
+impl PointWitness {
+    fn new(x1: &BigUint, y1: &BigUint, x2: &BigUint, y2: &BigUint) -> Self {
+        let p = modulus();
+        let xx = x1 * x2 % &p;
+        let yy = y1 * y2 % &p;
+        let xxyy = &xx * &yy % &p;
+        let dxxyy = curve_d() * &xxyy % &p;
+        Self {
+            x_inverse: field_inv(&((BigUint::one() + &dxxyy) % &p)),
+            y_inverse: field_inv(&((&p + 1u32 - &dxxyy) % &p)),
+            xx,
+            yy,
+            xxyy,
+            dxxyy,
+        }
+    }
+}
+
+/// Proves calls of [`Ed25519`](crate::Ed25519) with the given precompile index.
+///
+/// Every call takes a single row of the extension.
+#[derive(Debug, Clone)]
+pub struct Ed25519Circuit {
+    index: u16,
+}
+
+impl Ed25519Circuit {
+    /// The index is the position of the precompile in the guest's `use_precompiles!` invocation.
+    pub fn new(index: u16) -> Self {
+        Self { index }
+    }
+}
+
+impl PrecompileCircuit for Ed25519Circuit {}
+
+impl RowCircuit for Ed25519Circuit {
+    type Input = Ed25519Row;
+
+    fn rows(&self, side_note: &SideNote) -> Vec<Ed25519Row> {
+        side_note
+            .precompile_calls()
+            .iter()
+            .filter(|call| call.index == self.index)
+            .map(|call| {
+                let operation = Operation::new(call.value_c);
+                let num_bytes = operation.buffer_bytes();
+                assert_eq!(
+                    call.value_a,
+                    if num_bytes == 0 {
+                        STATUS_INVALID_OP
+                    } else {
+                        STATUS_OK
+                    },
+                    "ed25519 status mismatch"
+                );
+                assert_eq!(
+                    call.memory.len(),
+                    num_bytes,
+                    "ed25519 accesses the operands of the operation"
+                );
+                for (i, access) in call.memory.iter().enumerate() {
+                    assert_eq!(
+                        access.address,
+                        call.value_b.wrapping_add(i as u32),
+                        "ed25519 accesses the buffer at rs1"
+                    );
+                }
+                Ed25519Row {
+                    operation,
+                    clk: call.clk,
+                    buffer_ptr: call.value_b,
+                    op: call.value_c,
+                    bytes: call
+                        .memory
+                        .iter()
+                        .map(|access| BufferByte {
+                            prev_value: access.prev_value,
+                            prev_timestamp: access.prev_timestamp,
+                            value: access.value,
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    fn evaluate<R: Row>(
+        &self,
+        row: &mut R,
+        input: &Ed25519Row,
+        lookup_elements: &AllLookupElements,
+    ) {
+        let one = constant::<R::F>(1);
+        let p = modulus();
+
+        let [is_mul, is_add, is_double, is_invalid_high, is_invalid_low] = [
+            Operation::FieldMul,
+            Operation::PointAdd,
+            Operation::PointDouble,
+            Operation::InvalidHigh,
+            Operation::InvalidLow,
+        ]
+        .map(|operation| next_bool(row, input.operation == operation));
+        let is_point = is_add.clone() + is_double.clone();
+        let is_valid = is_mul.clone() + is_point.clone();
+        let is_invalid = is_invalid_high.clone() + is_invalid_low.clone();
+        let is_real = is_valid.clone() + is_invalid.clone();
+        row.add_constraint(is_real.clone() * (is_real.clone() - one.clone()));
+
+        let clk = next_bytes(row, input.clk);
+        let buffer_ptr = next_bytes(row, input.buffer_ptr);
+        let op = next_bytes(row, input.op);
+
+        // Decode the operation, unknown ones either have a nonzero high byte or the lowest byte at least 3.
+        for byte in &op[1..] {
+            row.add_constraint(is_valid.clone() * byte.clone());
+        }
+        row.add_constraint(
+            is_valid.clone()
+                * (op[0].clone() - is_add.clone() - is_double.clone() * BaseField::from(2)),
+        );
+        let is_high_zero = next_is_zero(row, &op[1..]);
+        row.add_constraint(is_invalid_high.clone() * is_high_zero);
+        row.range_check_u8(
+            is_invalid_low.clone(),
+            op[0].clone() - constant(3),
+            lookup_elements,
+        );
+
+        let precompile: &PrecompileLookupElements = lookup_elements.as_ref();
+        let mut tuple = clk.to_vec();
+        tuple.push(constant(self.index as u32));
+        tuple.extend(buffer_ptr.iter().cloned());
+        tuple.extend(op.iter().cloned());
+        tuple.extend([is_invalid, constant(0), constant(0), constant(0)]);
+        row.add_to_relation(precompile, -is_real, &tuple);
+
+        // The buffer must be word-aligned, so that the bytes of a word only differ in the lowest address byte.
+        let alignment = row.next(BaseField::from((input.buffer_ptr & 0xFF) >> 2));
+        row.add_constraint(buffer_ptr[0].clone() - alignment.clone() * BaseField::from(4));
+        row.range_check_u8(is_valid.clone(), alignment, lookup_elements);
+
+        let bytes: [BufferByte; BUFFER_BYTES] =
+            array::from_fn(|i| input.bytes.get(i).copied().unwrap_or_default());
+        let prev_values: [R::F; BUFFER_BYTES] =
+            array::from_fn(|i| row.next(BaseField::from(bytes[i].prev_value as u32)));
+        let element = |k: usize| &prev_values[k * ELEMENT_BYTES..(k + 1) * ELEMENT_BYTES];
+        let element_value = |k: usize| {
+            BigUint::from_bytes_le(
+                &bytes[k * ELEMENT_BYTES..(k + 1) * ELEMENT_BYTES]
+                    .iter()
+                    .map(|byte| byte.prev_value)
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let (x1, y1) = (element(0).to_vec(), element(1).to_vec());
+
+        // The right-hand side of a point operation, the left-hand side itself for doubling.
+        let (x2_value, y2_value) = if input.operation == Operation::PointAdd {
+            (element_value(2), element_value(3))
+        } else {
+            (element_value(0), element_value(1))
+        };
+        let x2 = next_element(row, &x2_value);
+        let y2 = next_element(row, &y2_value);
+        for (rhs, (added, doubled)) in x2.iter().chain(&y2).zip(
+            element(2)
+                .iter()
+                .chain(element(3))
+                .zip(x1.iter().chain(&y1)),
+        ) {
+            row.add_constraint(is_add.clone() * (rhs.clone() - added.clone()));
+            row.add_constraint(is_double.clone() * (rhs.clone() - doubled.clone()));
+        }
+
+        let witness = match input.operation {
+            Operation::PointAdd | Operation::PointDouble => {
+                PointWitness::new(&element_value(0), &element_value(1), &x2_value, &y2_value)
+            }
+            _ => PointWitness::default(),
+        };
+        // Whether the denominators of x3 and y3 are invertible or zero.
+        let is_point_value = matches!(
+            input.operation,
+            Operation::PointAdd | Operation::PointDouble
+        );
+        let is_x_invertible = next_bool(row, is_point_value && !witness.x_inverse.is_zero());
+        let is_x_singular = next_bool(row, is_point_value && witness.x_inverse.is_zero());
+        let is_y_invertible = next_bool(row, is_point_value && !witness.y_inverse.is_zero());
+        let is_y_singular = next_bool(row, is_point_value && witness.y_inverse.is_zero());
+        row.add_constraint(is_x_invertible.clone() + is_x_singular.clone() - is_point.clone());
+        row.add_constraint(is_y_invertible.clone() + is_y_singular.clone() - is_point.clone());
+
+        // The result, written to the first one or two elements of the buffer.
+        let result_value = |k: usize| {
+            BigUint::from_bytes_le(
+                &bytes[k * ELEMENT_BYTES..(k + 1) * ELEMENT_BYTES]
+                    .iter()
+                    .map(|byte| byte.value)
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let x3 = next_uint(
+            row,
+            &result_value(0),
+            ELEMENT_BYTES,
+            &is_valid,
+            lookup_elements,
+        );
+        let y3_value = if input.operation == Operation::FieldMul {
+            BigUint::zero()
+        } else {
+            result_value(1)
+        };
+        let y3 = next_uint(row, &y3_value, ELEMENT_BYTES, &is_point, lookup_elements);
+
+        let [xx, yy, xxyy, dxxyy] = [&witness.xx, &witness.yy, &witness.xxyy, &witness.dxxyy]
+            .map(|value| next_uint(row, value, ELEMENT_BYTES, &is_point, lookup_elements));
+        let x_inverse = next_uint(
+            row,
+            &witness.x_inverse,
+            ELEMENT_BYTES,
+            &is_x_invertible,
+            lookup_elements,
+        );
+        let y_inverse = next_uint(
+            row,
+            &witness.y_inverse,
+            ELEMENT_BYTES,
+            &is_y_invertible,
+            lookup_elements,
+        );
+
+        let poly = Poly::from_bytes;
+        let int = |value: u32| Poly::<R::F>::constant(&BigUint::from(value));
+
+        // a・b ≡ r
+        assert_zero_mod(
+            row,
+            poly(&x1) * poly(&y1) - poly(&x3),
+            &p,
+            &is_mul,
+            lookup_elements,
+        );
+        // d・x1・x2・y1・y2, one product at a time.
+        assert_zero_mod(
+            row,
+            poly(&x1) * poly(&x2) - poly(&xx),
+            &p,
+            &is_point,
+            lookup_elements,
+        );
+        assert_zero_mod(
+            row,
+            poly(&y1) * poly(&y2) - poly(&yy),
+            &p,
+            &is_point,
+            lookup_elements,
+        );
+        assert_zero_mod(
+            row,
+            poly(&xx) * poly(&yy) - poly(&xxyy),
+            &p,
+            &is_point,
+            lookup_elements,
+        );
+        assert_zero_mod(
+            row,
+            Poly::constant(&curve_d()) * poly(&xxyy) - poly(&dxxyy),
+            &p,
+            &is_point,
+            lookup_elements,
+        );
+        // x3・(1 + dxxyy) ≡ x1・y2 + y1・x2 with an invertible denominator, otherwise both are zero.
+        let x_denominator = int(1) + poly(&dxxyy);
+        assert_zero_mod(
+            row,
+            x_denominator.clone() * poly(&x_inverse) - int(1),
+            &p,
+            &is_x_invertible,
+            lookup_elements,
+        );
+        assert_zero_mod(
+            row,
+            poly(&x3) * x_denominator.clone() - poly(&x1) * poly(&y2) - poly(&y1) * poly(&x2),
+            &p,
+            &is_x_invertible,
+            lookup_elements,
+        );
+        assert_zero_mod(row, x_denominator, &p, &is_x_singular, lookup_elements);
+        // y3・(1 - dxxyy) ≡ y1・y2 + x1・x2 with an invertible denominator, otherwise both are zero.
+        let y_denominator = int(1) - poly(&dxxyy);
+        assert_zero_mod(
+            row,
+            y_denominator.clone() * poly(&y_inverse) - int(1),
+            &p,
+            &is_y_invertible,
+            lookup_elements,
+        );
+        assert_zero_mod(
+            row,
+            poly(&y3) * y_denominator.clone() - poly(&yy) - poly(&xx),
+            &p,
+            &is_y_invertible,
+            lookup_elements,
+        );
+        assert_zero_mod(row, y_denominator, &p, &is_y_singular, lookup_elements);
+        for (x, y) in x3.iter().zip(&y3) {
+            row.add_constraint(is_x_singular.clone() * x.clone());
+            row.add_constraint(is_y_singular.clone() * y.clone());
+        }
+        assert_lt(row, &x3, Poly::constant(&p), &is_valid, lookup_elements);
+        assert_lt(row, &y3, Poly::constant(&p), &is_point, lookup_elements);
+
+        // Read the operands and write the result.
+        let load_store: &LoadStoreLookupElements = lookup_elements.as_ref();
+        for (w, word_bytes) in bytes.chunks_exact(4).enumerate() {
+            // Words of the first two elements are accessed by all operations, the others only by additions.
+            let is_accessed = if w < 2 * FIELD_WORDS {
+                is_valid.clone()
+            } else {
+                is_add.clone()
+            };
+            let address = next_word_address(
+                row,
+                &is_accessed,
+                input.buffer_ptr,
+                &buffer_ptr,
+                4 * w as u32,
+                lookup_elements,
+            );
+
+            for (j, byte) in word_bytes.iter().enumerate() {
+                let i = 4 * w + j;
+                let prev_value = prev_values[i].clone();
+                // The first element is written by all operations, the second one by point operations.
+                let value = match i / ELEMENT_BYTES {
+                    0 | 1 => {
+                        let (is_written, result) = if i < ELEMENT_BYTES {
+                            (is_valid.clone(), x3[i].clone())
+                        } else {
+                            (is_point.clone(), y3[i - ELEMENT_BYTES].clone())
+                        };
+                        let value = row.next(BaseField::from(byte.value as u32));
+                        row.add_constraint(
+                            value.clone()
+                                - prev_value.clone()
+                                - is_written * (result - prev_value.clone()),
+                        );
+                        value
+                    }
+                    _ => prev_value.clone(),
+                };
+
+                // prev_timestamp < clk, i.e. clk - 1 - prev_timestamp fits into 32 bits.
+                let prev_timestamp = next_bytes(row, byte.prev_timestamp);
+                let aux = next_lt(
+                    row,
+                    &is_accessed,
+                    byte.prev_timestamp,
+                    input.clk,
+                    &prev_timestamp,
+                    &clk,
+                );
+                for byte in &aux {
+                    row.range_check_u8(is_accessed.clone(), byte.clone(), lookup_elements);
+                }
+
+                let address = [
+                    address[0].clone() + constant(j as u32),
+                    address[1].clone(),
+                    address[2].clone(),
+                    address[3].clone(),
+                ];
+                let mut tuple = address.to_vec();
+                tuple.push(prev_value);
+                tuple.extend(prev_timestamp);
+                row.add_to_relation(load_store, -is_accessed.clone(), &tuple);
+                let mut tuple = address.to_vec();
+                tuple.push(value);
+                tuple.extend(clk.iter().cloned());
+                row.add_to_relation(load_store, is_accessed.clone(), &tuple);
+            }
+        }
+    }
+
+    fn draw_lookup_elements(
+        &self,
+        _lookup_elements: &mut AllLookupElements,
+        _channel: &mut MachineChannel,
+    ) {
+    }
+
+    fn declare_support(&self, support: &mut Support) {
+        support.precompiles.insert(self.index);
+    }
+}
+
+fn constant<F: RowField>(value: u32) -> F {
+    F::from(BaseField::from(value))
+}
+
+fn next_bool<R: Row>(row: &mut R, value: bool) -> R::F {
+    let bit = row.next(BaseField::from(value as u32));
+    row.add_constraint(bit.clone() * (bit.clone() - constant(1)));
+    bit
+}
+
+/// Allocates bytes of a word, the caller is responsible for checking their range.
+fn next_bytes<R: Row>(row: &mut R, value: u32) -> [R::F; 4] {
+    value
+        .to_le_bytes()
+        .map(|byte| row.next(BaseField::from(byte as u32)))
+}
+
+/// Allocates bytes of a field element, the caller is responsible for checking their range.
+fn next_element<R: Row>(row: &mut R, value: &BigUint) -> Vec<R::F> {
+    let mut bytes = value.to_bytes_le();
+    bytes.resize(ELEMENT_BYTES, 0);
+    bytes
+        .into_iter()
+        .map(|byte| row.next(BaseField::from(byte as u32)))
+        .collect()
+}
+
+fn byte_limbs<F: RowField>(bytes: &[F; 4]) -> [F; 2] {
+    array::from_fn(|j| bytes[2 * j].clone() + bytes[2 * j + 1].clone() * BaseField::from(1 << 8))
+}
+
+/// Allocates the bytes of `ptr + offset` for a word-aligned `ptr` and a small multiple of four `offset`.
+fn next_word_address<R: Row>(
+    row: &mut R,
+    flag: &R::F,
+    ptr_value: u32,
+    ptr: &[R::F; 4],
+    offset: u32,
+    lookup_elements: &AllLookupElements,
+) -> [R::F; 4] {
+    let address = next_bytes(row, ptr_value.wrapping_add(offset));
+    for byte in &address {
+        row.range_check_u8(flag.clone(), byte.clone(), lookup_elements);
+    }
+    let carry = next_bool(row, (ptr_value & 0xFFFF) + offset > 0xFFFF);
+    let [ptr_lo, ptr_hi] = byte_limbs(ptr);
+    let [lo, hi] = byte_limbs(&address);
+    row.add_constraint(
+        flag.clone() * (ptr_lo + constant(offset) - lo - carry.clone() * BaseField::from(1 << 16)),
+    );
+    row.add_constraint(flag.clone() * (ptr_hi + carry - hi));
+    address
+}
+
+/// Constrains `lhs < rhs` when `flag` is set, returns the bytes of `rhs - 1 - lhs` which the caller must range check.
+fn next_lt<R: Row>(
+    row: &mut R,
+    flag: &R::F,
+    lhs_value: u32,
+    rhs_value: u32,
+    lhs: &[R::F; 4],
+    rhs: &[R::F; 4],
+) -> [R::F; 4] {
+    let aux_value = rhs_value.wrapping_sub(1).wrapping_sub(lhs_value);
+    let aux = next_bytes(row, aux_value);
+    let borrow = next_bool(
+        row,
+        (lhs_value & 0xFFFF) + 1 + (aux_value & 0xFFFF) > 0xFFFF,
+    );
+    let [lhs_lo, lhs_hi] = byte_limbs(lhs);
+    let [aux_lo, aux_hi] = byte_limbs(&aux);
+    let [rhs_lo, rhs_hi] = byte_limbs(rhs);
+    row.add_constraint(
+        flag.clone()
+            * (lhs_lo + constant::<R::F>(1) + aux_lo
+                - rhs_lo
+                - borrow.clone() * BaseField::from(1 << 16)),
+    );
+    row.add_constraint(flag.clone() * (lhs_hi + aux_hi + borrow - rhs_hi));
+    aux
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use nexus_vm::{
+        emulator::{Emulator, HarvardEmulator, InternalView},
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, InstructionType, Opcode, Register},
+        trace::k_trace_harvard,
+    };
+    use nexus_vm_prover::{
+        extensions::{circuit::CircuitExtension, ExtensionComponent},
+        machine::{BaseComponent, Machine},
+        Blake2sMerkleHasher, ProverConfig,
+    };
+
+    use crate::{point_double, Ed25519};
+
+    fn hex(value: &str) -> BigUint {
+        BigUint::parse_bytes(value.as_bytes(), 16).unwrap()
+    }
+
+    /// Stores the little-endian bytes of `elements` into the buffer, starting at element `first`.
+    fn store(instructions: &mut Vec<Instruction>, first: usize, elements: &[BigUint]) {
+        for (k, element) in elements.iter().enumerate() {
+            let mut bytes = element.to_bytes_le();
+            bytes.resize(ELEMENT_BYTES, 0);
+            for (i, byte) in bytes.into_iter().enumerate() {
+                instructions.push(Instruction::new_ir(
+                    Opcode::from(BuiltinOpcode::ADDI),
+                    6,
+                    0,
+                    byte as u32,
+                ));
+                instructions.push(Instruction::new_ir(
+                    Opcode::from(BuiltinOpcode::SB),
+                    10,
+                    6,
+                    ((first + k) * ELEMENT_BYTES + i) as u32,
+                ));
+            }
+        }
+    }
+
+    fn call(instructions: &mut Vec<Instruction>, opcode: &Opcode, op: u32) {
+        instructions.push(Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            11,
+            0,
+            op,
+        ));
+        instructions.push(Instruction::new(
+            opcode.clone(),
+            Register::X12,
+            Register::X10,
+            11,
+            InstructionType::RType,
+        ));
+    }
+
+    #[test]
+    fn test_prove_ed25519() {
+        let opcode = Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "dynamic");
+        let p = modulus();
+        let b = (
+            hex("216936D3CD6E53FEC0A4E231FDD6DC5C692CC7609525A7B2C9562D608F25D51A"),
+            hex("6666666666666666666666666666666666666666666666666666666666666658"),
+        );
+        let b2 = point_double(&b);
+        let one = BigUint::one();
+        let d_inverse = field_inv(&curve_d());
+
+        let mut instructions = vec![
+            // x10 = 0x8FFC0, so that the addresses of the buffer carry into the third byte.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0x7FF),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 0x100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 1, 1, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 1, 0xC0),
+        ];
+        // B + 2B, doubled, then x・y of the result.
+        store(&mut instructions, 0, &[b.0, b.1, b2.0, b2.1]);
+        call(&mut instructions, &opcode, OP_POINT_ADD);
+        call(&mut instructions, &opcode, OP_POINT_DOUBLE);
+        call(&mut instructions, &opcode, OP_FIELD_MUL);
+        // Points off the curve with 1 + d・x1・x2・y1・y2 ≡ 0, then with 1 - d・x1・x2・y1・y2 ≡ 0.
+        store(
+            &mut instructions,
+            0,
+            &[one.clone(), one.clone(), one.clone(), &p - &d_inverse],
+        );
+        call(&mut instructions, &opcode, OP_POINT_ADD);
+        store(
+            &mut instructions,
+            0,
+            &[one.clone(), one.clone(), one.clone(), d_inverse],
+        );
+        call(&mut instructions, &opcode, OP_POINT_ADD);
+        // Unknown operations.
+        call(&mut instructions, &opcode, 3);
+        call(&mut instructions, &opcode, 0x100);
+        let basic_blocks = vec![BasicBlock::new(instructions)];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator.add_opcode::<Ed25519>(&opcode).unwrap();
+        let (view, program_trace) = k_trace_harvard(emulator, 1).expect("error generating trace");
+
+        let prove_verify = |extensions: &[ExtensionComponent]| {
+            let proof = Machine::<BaseComponent>::prove_with_extensions::<Blake2sMerkleHasher>(
+                extensions,
+                &program_trace,
+                &view,
+                &ProverConfig::default(),
+            )
+            .unwrap();
+            Machine::<BaseComponent>::verify_with_extensions(
+                extensions,
+                proof,
+                ProverConfig::default().security_bits(),
+                view.get_program_memory(),
+                view.view_memory_layout().as_ref(),
+                &[],
+                view.get_initial_memory(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+        prove_verify(&[ExtensionComponent::custom(Arc::new(CircuitExtension::new(
+            Ed25519Circuit::new(0),
+            4,
+        )))])
+        .unwrap();
+
+        // Without the extension nothing consumes the calls added by the CPU.
+        assert!(prove_verify(&[]).is_err());
+    }
+}
@@ -0,0 +1,21 @@
+/// In the guest context, there is nothing actually associated with the precompile other than the
+/// convenience wrappers for emitting the instruction call.
+pub struct Ed25519;
+
+#[macro_export]
+macro_rules! generate_instruction_caller {
+    ($path:path) => {
+        trait Ed25519Caller {
+            /// Runs operation `op` on the operand buffer in place, returning the status.
+            fn ed25519_call(op: u32, buffer: &mut [u32]) -> u32;
+        }
+
+        impl Ed25519Caller for $path {
+            fn ed25519_call(op: u32, buffer: &mut [u32]) -> u32 {
+                Self::emit_instruction(buffer.as_mut_ptr() as u32, op, 0)
+            }
+        }
+
+        $crate::generate_types!($path);
+    };
+}
@@ -0,0 +1,561 @@
+use nexus_common::{
+    cpu::{InstructionExecutor, InstructionResult, InstructionState, Processor, Registers},
+    error::MemoryError,
+    memory::{LoadOp, LoadOps, MemAccessSize, MemoryProcessor, StoreOps},
+    riscv::{instruction::Instruction, register::Register},
+};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use nexus_precompiles::{PrecompileCircuit, PrecompileInstruction, PrecompileMetadata};
+
+use crate::{
+    Ed25519Circuit, FIELD_WORDS, OP_FIELD_MUL, OP_POINT_ADD, OP_POINT_DOUBLE, STATUS_INVALID_OP,
+    STATUS_OK,
+};
+
+/// An affine point `(x, y)`.
+pub type AffinePoint = (BigUint, BigUint);
+
+/// The field modulus, p = 2^255 - 19.
+pub fn modulus() -> BigUint {
+    (BigUint::one() << 255) - BigUint::from(19u32)
+}
+
+/// Computes `a・b mod p`, executed on the native host.
+pub fn field_mul(a: &BigUint, b: &BigUint) -> BigUint {
+    (a * b) % modulus()
+}
+
+fn field_add(a: &BigUint, b: &BigUint) -> BigUint {
+    (a + b) % modulus()
+}
+
+fn field_sub(a: &BigUint, b: &BigUint) -> BigUint {
+    let p = modulus();
+    ((a % &p) + &p - (b % &p)) % p
+}
+
+pub(crate) fn field_inv(a: &BigUint) -> BigUint {
+    let p = modulus();
+    a.modpow(&(&p - 2u32), &p)
+}
+
+/// The curve constant d = -121665 / 121666.
+pub(crate) fn curve_d() -> BigUint {
+    field_mul(
+        &field_sub(&BigUint::zero(), &BigUint::from(121665u32)),
+        &field_inv(&BigUint::from(121666u32)),
+    )
+}
+
+/// Adds two points on -x^2 + y^2 = 1 + d・x^2・y^2, executed on the native host.
+///
+/// The addition law is complete, so no special cases are needed.
+pub fn point_add(lhs: &AffinePoint, rhs: &AffinePoint) -> AffinePoint {
+    let ((x1, y1), (x2, y2)) = (lhs, rhs);
+    let one = BigUint::one();
+
+    let dxxyy = field_mul(
+        &curve_d(),
+        &field_mul(&field_mul(x1, x2), &field_mul(y1, y2)),
+    );
+    // x3 = (x1・y2 + y1・x2) / (1 + d・x1・x2・y1・y2)
+    let x3 = field_mul(
+        &field_add(&field_mul(x1, y2), &field_mul(y1, x2)),
+        &field_inv(&field_add(&one, &dxxyy)),
+    );
+    // y3 = (y1・y2 + x1・x2) / (1 - d・x1・x2・y1・y2)
+    let y3 = field_mul(
+        &field_add(&field_mul(y1, y2), &field_mul(x1, x2)),
+        &field_inv(&field_sub(&one, &dxxyy)),
+    );
+    (x3, y3)
+}
+
+/// Doubles a point on -x^2 + y^2 = 1 + d・x^2・y^2, executed on the native host.
+pub fn point_double(point: &AffinePoint) -> AffinePoint {
+    point_add(point, point)
+}
+
+fn to_field(words: &[u32]) -> BigUint {
+    BigUint::from_slice(words)
+}
+
+fn from_field(value: &BigUint) -> [u32; FIELD_WORDS] {
+    let mut words = [0u32; FIELD_WORDS];
+    for (word, digit) in words.iter_mut().zip(value.iter_u32_digits()) {
+        *word = digit;
+    }
+    words
+}
+
+fn to_point(words: &[u32]) -> AffinePoint {
+    (
+        to_field(&words[..FIELD_WORDS]),
+        to_field(&words[FIELD_WORDS..]),
+    )
+}
+
+fn from_point((x, y): &AffinePoint) -> [u32; 2 * FIELD_WORDS] {
+    let mut words = [0u32; 2 * FIELD_WORDS];
+    words[..FIELD_WORDS].copy_from_slice(&from_field(x));
+    words[FIELD_WORDS..].copy_from_slice(&from_field(y));
+    words
+}
+
+/// Returns the number of words read and written by the operation, or `None` if the operation is unknown.
+fn buffer_words(op: u32) -> Option<(usize, usize)> {
+    match op {
+        OP_FIELD_MUL => Some((2 * FIELD_WORDS, FIELD_WORDS)),
+        OP_POINT_ADD => Some((4 * FIELD_WORDS, 2 * FIELD_WORDS)),
+        OP_POINT_DOUBLE => Some((2 * FIELD_WORDS, 2 * FIELD_WORDS)),
+        _ => None,
+    }
+}
+
+/// Applies operation `op` to the operand buffer and returns the words to write back, or `None` if the operation is
+/// unknown.
+fn apply(op: u32, buffer: &[u32]) -> Option<Vec<u32>> {
+    let result = match op {
+        OP_FIELD_MUL => from_field(&field_mul(
+            &to_field(&buffer[..FIELD_WORDS]),
+            &to_field(&buffer[FIELD_WORDS..2 * FIELD_WORDS]),
+        ))
+        .to_vec(),
+        OP_POINT_ADD => from_point(&point_add(
+            &to_point(&buffer[..2 * FIELD_WORDS]),
+            &to_point(&buffer[2 * FIELD_WORDS..4 * FIELD_WORDS]),
+        ))
+        .to_vec(),
+        OP_POINT_DOUBLE => {
+            from_point(&point_double(&to_point(&buffer[..2 * FIELD_WORDS]))).to_vec()
+        }
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Runs operation `op` on the operand buffer in place, executed on the native host.
+///
+/// Returns the status of the instruction, the buffer is left unchanged if the operation is unknown.
+pub fn buffer_call(op: u32, buffer: &mut [u32]) -> u32 {
+    match apply(op, buffer) {
+        Some(result) => {
+            buffer[..result.len()].copy_from_slice(&result);
+            STATUS_OK
+        }
+        None => STATUS_INVALID_OP,
+    }
+}
+
+/// Defines the caller trait and the field and curve types of the guest for the native host, backed by
+/// [`buffer_call`], so that guest programs calling the precompile also build and run natively.
+#[macro_export]
+macro_rules! generate_instruction_caller {
+    ($path:path) => {
+        trait Ed25519Caller {
+            /// Runs operation `op` on the operand buffer in place, returning the status.
+            fn ed25519_call(op: u32, buffer: &mut [u32]) -> u32;
+        }
+
+        impl Ed25519Caller for $path {
+            fn ed25519_call(op: u32, buffer: &mut [u32]) -> u32 {
+                $crate::buffer_call(op, buffer)
+            }
+        }
+
+        $crate::generate_types!($path);
+    };
+}
+
+#[derive(Default)]
+pub struct Ed25519 {
+    rd: (Register, u32),
+    buffer_ptr: u32,
+    op: u32,
+    buffer: Vec<u32>,
+}
+
+impl InstructionState for Ed25519 {
+    fn execute(&mut self) {
+        match apply(self.op, &self.buffer) {
+            Some(result) => {
+                self.buffer = result;
+                self.rd.1 = STATUS_OK;
+            }
+            None => self.rd.1 = STATUS_INVALID_OP,
+        }
+    }
+
+    fn memory_read(&mut self, memory: &impl MemoryProcessor) -> Result<LoadOps, MemoryError> {
+        let mut load_ops = LoadOps::default();
+        let Some((num_words, _)) = buffer_words(self.op) else {
+            return Ok(load_ops);
+        };
+
+        self.buffer = Vec::with_capacity(num_words);
+        for i in 0..num_words {
+            let load_op = memory.read(self.buffer_ptr + (i * 4) as u32, MemAccessSize::Word)?;
+            load_ops.insert(load_op);
+
+            let LoadOp::Op(_, _, value) = load_op;
+            self.buffer.push(value);
+        }
+
+        Ok(load_ops)
+    }
+
+    fn memory_write(&self, memory: &mut impl MemoryProcessor) -> Result<StoreOps, MemoryError> {
+        let mut store_ops = StoreOps::default();
+        let Some((_, num_words)) = buffer_words(self.op) else {
+            return Ok(store_ops);
+        };
+
+        for (i, word) in self.buffer.iter().take(num_words).enumerate() {
+            store_ops.insert(memory.write(
+                self.buffer_ptr + (i * 4) as u32,
+                MemAccessSize::Word,
+                *word,
+            )?);
+        }
+
+        Ok(store_ops)
+    }
+
+    fn write_back(&self, cpu: &mut impl Processor) -> InstructionResult {
+        cpu.registers_mut().write(self.rd.0, self.rd.1);
+        Some(self.rd.1)
+    }
+}
+
+impl InstructionExecutor for Ed25519 {
+    type InstructionState = Self;
+
+    fn decode(ins: &Instruction, registers: &impl Registers) -> Self {
+        Self {
+            rd: (ins.op_a, registers[ins.op_a]),
+            buffer_ptr: registers[ins.op_b],
+            op: registers[Register::from(ins.op_c as u8)],
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl PrecompileInstruction for Ed25519 {
+    fn metadata() -> PrecompileMetadata {
+        PrecompileMetadata {
+            author: "The Nexus Team",
+            name: "Ed25519",
+            description: "ed25519 field multiplication and affine point addition and doubling",
+            version_major: 1,
+            version_minor: 0,
+            version_patch: 0,
+        }
+    }
+
+    fn circuit() -> impl PrecompileCircuit {
+        Ed25519Circuit::new(0)
+    }
+
+    fn native_call(_rs1: u32, _rs2: u32) -> u32 {
+        // The buffer pointer cannot be dereferenced on the native host, use [`buffer_call`] instead.
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nexus_vm::{
+        emulator::{Emulator, HarvardEmulator, LinearEmulator},
+        memory::{UnifiedMemory, VariableMemory, RW},
+        riscv::{InstructionType, Opcode},
+    };
+
+    const BUFFER_ADDRESS: u32 = 0x1000;
+
+    fn hex(value: &str) -> BigUint {
+        BigUint::parse_bytes(value.as_bytes(), 16).unwrap()
+    }
+
+    fn base_point() -> AffinePoint {
+        (
+            hex("216936D3CD6E53FEC0A4E231FDD6DC5C692CC7609525A7B2C9562D608F25D51A"),
+            hex("6666666666666666666666666666666666666666666666666666666666666658"),
+        )
+    }
+
+    fn identity() -> AffinePoint {
+        (BigUint::zero(), BigUint::one())
+    }
+
+    fn negate((x, y): &AffinePoint) -> AffinePoint {
+        (field_sub(&BigUint::zero(), x), y.clone())
+    }
+
+    fn is_on_curve((x, y): &AffinePoint) -> bool {
+        let xx = field_mul(x, x);
+        let yy = field_mul(y, y);
+        field_sub(&yy, &xx)
+            == field_add(
+                &BigUint::one(),
+                &field_mul(&curve_d(), &field_mul(&xx, &yy)),
+            )
+    }
+
+    #[test]
+    fn test_native_point_arithmetic() {
+        let b = base_point();
+        let b2 = point_double(&b);
+        let b3 = point_add(&b2, &b);
+
+        assert!(is_on_curve(&b));
+        assert!(is_on_curve(&b2));
+        assert!(is_on_curve(&b3));
+        assert_ne!(b2, b);
+        assert_eq!(point_add(&b, &b2), b3);
+        assert_eq!(point_double(&b2), point_add(&b3, &b));
+        assert_eq!(point_add(&b, &negate(&b)), identity());
+        assert_eq!(point_add(&b, &identity()), b);
+    }
+
+    #[test]
+    fn test_native_field_mul() {
+        let minus_one = modulus() - 1u32;
+
+        assert_eq!(field_mul(&minus_one, &minus_one), BigUint::one());
+        assert_eq!(field_mul(&minus_one, &BigUint::zero()), BigUint::zero());
+    }
+
+    /// Runs the precompile on `buffer` through the given emulator and returns the status and the updated buffer.
+    fn run<E: Emulator>(
+        emulator: &mut E,
+        memory: impl Fn(&mut E) -> &mut UnifiedMemory,
+        op: u32,
+        buffer: &[u32],
+    ) -> (u32, Vec<u32>) {
+        let opcode = Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "dynamic");
+        emulator.add_opcode::<Ed25519>(&opcode).unwrap();
+
+        for (i, word) in buffer.iter().enumerate() {
+            memory(emulator)
+                .write(BUFFER_ADDRESS + (i * 4) as u32, MemAccessSize::Word, *word)
+                .unwrap();
+        }
+        emulator
+            .get_executor_mut()
+            .cpu
+            .registers
+            .write(Register::X1, BUFFER_ADDRESS);
+        emulator
+            .get_executor_mut()
+            .cpu
+            .registers
+            .write(Register::X2, op);
+
+        let instruction = Instruction::new(
+            opcode,
+            Register::X3,
+            Register::X1,
+            2,
+            InstructionType::RType,
+        );
+        let (res, _) = emulator.execute_instruction(&instruction, false).unwrap();
+
+        let updated = (0..buffer.len())
+            .map(|i| {
+                let LoadOp::Op(_, _, value) = memory(emulator)
+                    .read(BUFFER_ADDRESS + (i * 4) as u32, MemAccessSize::Word)
+                    .unwrap();
+                value
+            })
+            .collect();
+        (res.unwrap(), updated)
+    }
+
+    fn run_all_emulators(op: u32, buffer: &[u32]) -> (u32, Vec<u32>) {
+        let mut harvard = HarvardEmulator::from_basic_blocks(&Vec::new());
+        let harvard_result = run(&mut harvard, |e| &mut e.data_memory, op, buffer);
+
+        let mut linear = LinearEmulator::default();
+        linear.memory = UnifiedMemory::from(VariableMemory::<RW>::default());
+        let linear_result = run(&mut linear, |e| &mut e.memory, op, buffer);
+
+        assert_eq!(harvard_result, linear_result);
+        harvard_result
+    }
+
+    #[test]
+    fn test_field_mul_instruction() {
+        let minus_one = from_field(&(modulus() - 1u32));
+        let buffer = [minus_one, minus_one].concat();
+
+        let (status, updated) = run_all_emulators(OP_FIELD_MUL, &buffer);
+
+        assert_eq!(status, STATUS_OK);
+        assert_eq!(to_field(&updated[..FIELD_WORDS]), BigUint::one());
+        assert_eq!(updated[FIELD_WORDS..], buffer[FIELD_WORDS..]);
+    }
+
+    #[test]
+    fn test_point_add_instruction() {
+        let b = base_point();
+        let b2 = point_double(&b);
+        let buffer = [from_point(&b), from_point(&b2)].concat();
+
+        let (status, updated) = run_all_emulators(OP_POINT_ADD, &buffer);
+
+        assert_eq!(status, STATUS_OK);
+        assert_eq!(to_point(&updated[..2 * FIELD_WORDS]), point_add(&b, &b2));
+    }
+
+    #[test]
+    fn test_point_double_instruction() {
+        let b = base_point();
+        let (status, updated) = run_all_emulators(OP_POINT_DOUBLE, &from_point(&b));
+
+        assert_eq!(status, STATUS_OK);
+        assert_eq!(to_point(&updated), point_add(&b, &b));
+    }
+
+    #[test]
+    fn test_invalid_op_instruction() {
+        let buffer = from_point(&base_point());
+
+        let (status, updated) = run_all_emulators(3, &buffer);
+
+        assert_eq!(status, STATUS_INVALID_OP);
+        assert_eq!(updated, buffer);
+    }
+
+    /// The types a guest program uses, as generated by `use_precompiles!` on the native host.
+    mod native {
+        pub struct Precompile;
+        crate::generate_instruction_caller!(crate::host::tests::native::Precompile);
+    }
+
+    /// The types generated for a caller whose precompile always fails.
+    #[allow(dead_code)]
+    mod failing {
+        use crate::STATUS_INVALID_OP;
+
+        trait Ed25519Caller {
+            fn ed25519_call(op: u32, buffer: &mut [u32]) -> u32;
+        }
+
+        pub struct Precompile;
+
+        impl Ed25519Caller for Precompile {
+            fn ed25519_call(_op: u32, _buffer: &mut [u32]) -> u32 {
+                STATUS_INVALID_OP
+            }
+        }
+
+        crate::generate_types!(crate::host::tests::failing::Precompile);
+    }
+
+    use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, edwards::CompressedEdwardsY};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    use crate::{ff::Field, group::Group};
+    use native::ed25519::{AffinePoint as GuestPoint, FieldElement, Scalar};
+
+    #[test]
+    fn test_native_field_element() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let p = modulus();
+
+        for _ in 0..16 {
+            let (a, b) = (
+                FieldElement::random(&mut rng),
+                FieldElement::random(&mut rng),
+            );
+            let (x, y) = (to_field(&a.0), to_field(&b.0));
+
+            assert_eq!(to_field(&(a * b).0), field_mul(&x, &y));
+            assert_eq!(to_field(&(a + b).0), (&x + &y) % &p);
+            assert_eq!(to_field(&(a - b).0), field_sub(&x, &y));
+            assert_eq!(to_field(&(-a).0), field_sub(&BigUint::zero(), &x));
+            assert_eq!(to_field(&a.invert().unwrap().0), field_inv(&x));
+
+            let square = a.square();
+            let root = square.sqrt().unwrap();
+            assert!(root == a || root == -a);
+            // 2 is a non-square, so twice a square is not a square, and the root of 2・2・a^2 is returned instead.
+            let double = square.double();
+            let (is_square, root) = FieldElement::sqrt_ratio(&double, &FieldElement::ONE);
+            assert!(!bool::from(is_square));
+            assert_eq!(root.square(), double.double());
+        }
+
+        assert!(bool::from(FieldElement::ZERO.invert().is_none()));
+        let (is_square, root) = FieldElement::sqrt_ratio(&FieldElement::ZERO, &FieldElement::ZERO);
+        assert!(bool::from(is_square));
+        assert_eq!(root, FieldElement::ZERO);
+        let (is_square, root) = FieldElement::sqrt_ratio(&FieldElement::ONE, &FieldElement::ZERO);
+        assert!(!bool::from(is_square));
+        assert_eq!(root, FieldElement::ZERO);
+    }
+
+    #[test]
+    fn test_native_group() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let g = GuestPoint::generator();
+
+        assert_eq!(
+            g.to_compressed(),
+            ED25519_BASEPOINT_POINT.compress().to_bytes()
+        );
+        assert_eq!(g * Scalar::ZERO, GuestPoint::identity());
+        assert_eq!(g - g, GuestPoint::identity());
+        assert_eq!(g + GuestPoint::identity(), g);
+        assert_eq!(Group::double(&g), g + g);
+        assert!(bool::from(Group::is_identity(&(g - g))));
+        assert!((g - g).is_identity());
+
+        for _ in 0..4 {
+            let (s, t) = (Scalar::random(&mut rng), Scalar::random(&mut rng));
+            let expected = (ED25519_BASEPOINT_POINT * s).compress().to_bytes();
+
+            assert_eq!((g * s).to_compressed(), expected);
+            assert_eq!(GuestPoint::from_compressed(&expected), Some(g * s));
+            assert_eq!(g * s + g * t, g * (s + t));
+            assert_eq!(-(g * s), g * -s);
+            assert_eq!([g * s, g * t].iter().sum::<GuestPoint>(), g * (s + t));
+        }
+
+        let point = GuestPoint::random(&mut rng);
+        assert!(is_on_curve(&(to_field(&point.x.0), to_field(&point.y.0))));
+    }
+
+    #[test]
+    fn test_native_decompression() {
+        // Small values of y include points with both signs of x, and encodings of no point at all.
+        for y in 0..16u8 {
+            for sign in [0, 0x80] {
+                let mut bytes = [0u8; 32];
+                bytes[0] = y;
+                bytes[31] = sign;
+                let expected = CompressedEdwardsY(bytes)
+                    .decompress()
+                    // `curve25519-dalek` ignores the sign bit of x = 0, RFC 8032 rejects it.
+                    .filter(|point| sign == 0 || point.compress().to_bytes()[31] & 0x80 != 0);
+
+                let decoded = GuestPoint::from_compressed(&bytes);
+                assert_eq!(
+                    decoded.map(|point| point.to_compressed()),
+                    expected.map(|point| point.compress().to_bytes())
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ed25519 precompile failed")]
+    fn test_native_caller_failure() {
+        let one = failing::ed25519::FieldElement::ONE;
+        let _ = one * one;
+    }
+}
@@ -0,0 +1,261 @@
+/// Defines the field and curve types on top of the `Ed25519Caller` trait implemented for `$path`, shared by the
+/// guest and the native host.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! generate_types {
+    ($path:path) => {
+        /// Field and curve types backed by the ed25519 precompile.
+        ///
+        /// [`FieldElement`] implements `ff::Field` and [`AffinePoint`] implements `group::Group` with the scalars of
+        /// `curve25519-dalek`, so that both can be used by code generic over these traits. Points are exchanged with
+        /// `curve25519-dalek` through compressed encodings, see [`AffinePoint::from_compressed`].
+        ///
+        /// The operations panic if the precompile fails, which only happens if the instruction is not executed by
+        /// the ed25519 precompile.
+        pub mod ed25519 {
+            use super::Ed25519Caller;
+            use $crate::nexus_precompiles::{
+                ff::{Field, PrimeField},
+                field,
+                subtle::{ConditionallySelectable, ConstantTimeEq},
+            };
+
+            pub use $crate::curve25519_dalek::Scalar;
+
+            const FIELD_WORDS: usize = $crate::FIELD_WORDS;
+
+            /// The field modulus, p = 2^255 - 19.
+            const MODULUS: [u32; FIELD_WORDS] = [
+                0xffffffed, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff,
+                0x7fffffff,
+            ];
+            /// p - 2, inverses are computed as a^(p - 2).
+            const INVERSE_EXPONENT: [u32; FIELD_WORDS] = [
+                0xffffffeb, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff,
+                0x7fffffff,
+            ];
+            /// (p + 3) / 8, square roots are computed from a^((p + 3) / 8) since p = 5 mod 8.
+            const SQRT_EXPONENT: [u32; FIELD_WORDS] = [
+                0xfffffffe, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff,
+                0x0fffffff,
+            ];
+            /// A square root of -1, 2^((p - 1) / 4).
+            const SQRT_MINUS_ONE: FieldElement = FieldElement([
+                0x4a0ea0b0, 0xc4ee1b27, 0xad2fe478, 0x2f431806, 0x3dfbd7a7, 0x2b4d0099, 0x4fc1df0b,
+                0x2b832480,
+            ]);
+            /// The curve constant d = -121665 / 121666.
+            const D: FieldElement = FieldElement([
+                0x135978a3, 0x75eb4dca, 0x4141d8ab, 0x00700a4d, 0x7779e898, 0x8cc74079, 0x2b6ffe73,
+                0x52036cee,
+            ]);
+
+            /// Runs operation `op` on the operand buffer in place, panicking if the precompile fails.
+            fn call(op: u32, buffer: &mut [u32]) {
+                let status = <$path as Ed25519Caller>::ed25519_call(op, buffer);
+                assert_eq!(status, $crate::STATUS_OK, "ed25519 precompile failed");
+            }
+
+            /// An element of the base field, stored as reduced little-endian words.
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+            pub struct FieldElement(pub [u32; FIELD_WORDS]);
+
+            impl FieldElement {
+                pub const ZERO: Self = Self([0; FIELD_WORDS]);
+                pub const ONE: Self = Self([1, 0, 0, 0, 0, 0, 0, 0]);
+
+                /// Decodes a little-endian encoded field element, as in RFC 8032.
+                ///
+                /// The encoded integer must be below the modulus, which is not checked.
+                pub fn from_le_bytes(bytes: &[u8; 32]) -> Self {
+                    let mut words = [0u32; FIELD_WORDS];
+                    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+                        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                    }
+                    Self(words)
+                }
+
+                /// Encodes the field element as little-endian bytes, as in RFC 8032.
+                pub fn to_le_bytes(self) -> [u8; 32] {
+                    let mut bytes = [0u8; 32];
+                    for (chunk, word) in bytes.chunks_exact_mut(4).zip(self.0.iter()) {
+                        chunk.copy_from_slice(&word.to_le_bytes());
+                    }
+                    bytes
+                }
+
+                /// Returns whether the field element is odd, which RFC 8032 calls negative.
+                fn is_odd(&self) -> $crate::nexus_precompiles::subtle::Choice {
+                    ((self.0[0] & 1) as u8).into()
+                }
+            }
+
+            fn mul_words(a: &[u32; FIELD_WORDS], b: &[u32; FIELD_WORDS]) -> [u32; FIELD_WORDS] {
+                let mut buffer = [0u32; 2 * FIELD_WORDS];
+                buffer[..FIELD_WORDS].copy_from_slice(a);
+                buffer[FIELD_WORDS..].copy_from_slice(b);
+                call($crate::OP_FIELD_MUL, &mut buffer);
+
+                let mut words = [0u32; FIELD_WORDS];
+                words.copy_from_slice(&buffer[..FIELD_WORDS]);
+                words
+            }
+
+            /// Returns whether `a` is a square and, if so, one of its roots, see RFC 8032, section 5.1.3.
+            fn sqrt(a: &FieldElement) -> ($crate::nexus_precompiles::subtle::Choice, FieldElement) {
+                let root = FieldElement(field::pow(
+                    &a.0,
+                    &SQRT_EXPONENT,
+                    &FieldElement::ONE.0,
+                    mul_words,
+                ));
+                // The candidate is a root of either `a` or `-a`.
+                let root = FieldElement::conditional_select(
+                    &root,
+                    &(root * SQRT_MINUS_ONE),
+                    (root * root).ct_eq(&-*a),
+                );
+                ((root * root).ct_eq(a), root)
+            }
+
+            // 2 is a non-square since p = 5 mod 8.
+            $crate::nexus_precompiles::impl_field!(
+                FieldElement,
+                modulus: MODULUS,
+                inverse_exponent: INVERSE_EXPONENT,
+                non_square: FieldElement([2, 0, 0, 0, 0, 0, 0, 0]),
+                mul: mul_words,
+                sqrt: sqrt,
+            );
+
+            /// A curve point in affine coordinates, `(0, 1)` is the identity.
+            #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+            pub struct AffinePoint {
+                pub x: FieldElement,
+                pub y: FieldElement,
+            }
+
+            impl AffinePoint {
+                pub const IDENTITY: Self = Self {
+                    x: FieldElement::ZERO,
+                    y: FieldElement::ONE,
+                };
+
+                /// The base point, as specified in RFC 8032.
+                pub const GENERATOR: Self = Self {
+                    x: FieldElement([
+                        0x8f25d51a, 0xc9562d60, 0x9525a7b2, 0x692cc760, 0xfdd6dc5c, 0xc0a4e231,
+                        0xcd6e53fe, 0x216936d3,
+                    ]),
+                    y: FieldElement([
+                        0x66666658, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666,
+                        0x66666666, 0x66666666,
+                    ]),
+                };
+
+                pub fn is_identity(&self) -> bool {
+                    *self == Self::IDENTITY
+                }
+
+                /// Decodes a compressed point, as in RFC 8032 and `curve25519_dalek::edwards::CompressedEdwardsY`.
+                ///
+                /// Returns `None` if the encoding is not a point on the curve. Unlike `CompressedEdwardsY`, and as
+                /// required by RFC 8032, non-canonical encodings are rejected: `y` must be reduced and `x = 0` must
+                /// have a clear sign bit.
+                pub fn from_compressed(bytes: &[u8; 32]) -> Option<Self> {
+                    let mut y = *bytes;
+                    y[31] &= 0x7f;
+                    let y = FieldElement::from_le_bytes(&y);
+                    if field::lt(&y.0, &MODULUS) == 0 {
+                        return None;
+                    }
+
+                    // x^2 = (y^2 - 1) / (d・y^2 + 1)
+                    let yy = y.square();
+                    let (is_square, x) = FieldElement::sqrt_ratio(
+                        &(yy - FieldElement::ONE),
+                        &(D * yy + FieldElement::ONE),
+                    );
+                    let sign = (bytes[31] >> 7).into();
+                    if !bool::from(is_square) || bool::from(x.is_zero() & sign) {
+                        return None;
+                    }
+                    let x = FieldElement::conditional_select(&x, &-x, x.is_odd() ^ sign);
+                    Some(Self { x, y })
+                }
+
+                /// Encodes the point as a compressed point, as in RFC 8032 and
+                /// `curve25519_dalek::edwards::CompressedEdwardsY`.
+                pub fn to_compressed(self) -> [u8; 32] {
+                    let mut bytes = self.y.to_le_bytes();
+                    bytes[31] |= self.x.is_odd().unwrap_u8() << 7;
+                    bytes
+                }
+
+                pub fn double(&self) -> Self {
+                    let mut buffer = self.to_words();
+                    call($crate::OP_POINT_DOUBLE, &mut buffer);
+                    Self::from_words(&buffer)
+                }
+
+                fn to_words(self) -> [u32; 2 * FIELD_WORDS] {
+                    let mut words = [0u32; 2 * FIELD_WORDS];
+                    words[..FIELD_WORDS].copy_from_slice(&self.x.0);
+                    words[FIELD_WORDS..].copy_from_slice(&self.y.0);
+                    words
+                }
+
+                fn from_words(words: &[u32]) -> Self {
+                    let mut x = [0u32; FIELD_WORDS];
+                    let mut y = [0u32; FIELD_WORDS];
+                    x.copy_from_slice(&words[..FIELD_WORDS]);
+                    y.copy_from_slice(&words[FIELD_WORDS..2 * FIELD_WORDS]);
+                    Self {
+                        x: FieldElement(x),
+                        y: FieldElement(y),
+                    }
+                }
+            }
+
+            impl Default for AffinePoint {
+                fn default() -> Self {
+                    Self::IDENTITY
+                }
+            }
+
+            impl core::ops::Neg for AffinePoint {
+                type Output = Self;
+
+                fn neg(self) -> Self {
+                    Self {
+                        x: -self.x,
+                        y: self.y,
+                    }
+                }
+            }
+
+            fn add_points(lhs: &AffinePoint, rhs: &AffinePoint) -> AffinePoint {
+                let mut buffer = [0u32; 4 * FIELD_WORDS];
+                buffer[..2 * FIELD_WORDS].copy_from_slice(&lhs.to_words());
+                buffer[2 * FIELD_WORDS..].copy_from_slice(&rhs.to_words());
+                call($crate::OP_POINT_ADD, &mut buffer);
+                AffinePoint::from_words(&buffer)
+            }
+
+            fn scalar_be_bytes(scalar: &Scalar) -> [u8; 32] {
+                let mut bytes = scalar.to_repr();
+                bytes.reverse();
+                bytes
+            }
+
+            $crate::nexus_precompiles::impl_group!(
+                AffinePoint,
+                scalar: Scalar,
+                identity: AffinePoint::IDENTITY,
+                generator: AffinePoint::GENERATOR,
+                add: add_points,
+                scalar_be_bytes: scalar_be_bytes,
+            );
+        }
+    };
+}
@@ -0,0 +1,692 @@
+//! Circuit proving calls of the dummy hash precompile, i.e. BLAKE2s with a 4-byte digest.
+//!
+//! Every 64-byte block of a hashed input takes one row. The row reads the bytes of its block from RAM and computes
+//! the compression function over bits. Consecutive blocks of a call are linked with [`DummyHashChainElements`], and
+//! the last block consumes the call itself from [`PrecompileLookupElements`].
+
+use std::array;
+
+use nexus_vm_prover::{
+    components::AllLookupElements,
+    extensions::{
+        circuit::{Row, RowCircuit, RowField},
+        LoadStoreLookupElements, PrecompileLookupElements,
+    },
+    hash::MachineChannel,
+    preflight::Support,
+    trace::sidenote::SideNote,
+};
+use stwo_prover::core::fields::m31::BaseField;
+
+const BLOCK_SIZE: usize = 64;
+const WORD_BITS: usize = 32;
+
+const IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// The columns of G, see RFC 7693, section 3.2.
+const G_INDICES: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// The first word of the parameter block: 4-byte digest, no key, fanout and depth of one.
+const PARAM_WORD: u32 = 0x0101_0004;
+
+/// The tuple consists of clk, rs1 and rs2 bytes, the number of hashed bytes in two 16-bit limbs and the chaining
+/// value in 16-bit limbs.
+const CHAIN_TUPLE_SIZE: usize = 3 * 4 + 2 + 16;
+stwo_prover::relation!(DummyHashChainElements, CHAIN_TUPLE_SIZE);
+
+fn initial_chaining_value() -> [u32; 8] {
+    let mut h = IV;
+    h[0] ^= PARAM_WORD;
+    h
+}
+
+/// The compression function F computed natively.
+fn compress(h: &[u32; 8], m: &[u32; 16], t: u32, is_last: bool) -> [u32; 8] {
+    let mut v = [0u32; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&IV);
+    v[12] ^= t;
+    if is_last {
+        v[14] = !v[14];
+    }
+    for s in SIGMA {
+        for (j, [a, b, c, d]) in G_INDICES.into_iter().enumerate() {
+            let (x, y) = (m[s[2 * j]], m[s[2 * j + 1]]);
+            v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+            v[d] = (v[d] ^ v[a]).rotate_right(16);
+            v[c] = v[c].wrapping_add(v[d]);
+            v[b] = (v[b] ^ v[c]).rotate_right(12);
+            v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+            v[d] = (v[d] ^ v[a]).rotate_right(8);
+            v[c] = v[c].wrapping_add(v[d]);
+            v[b] = (v[b] ^ v[c]).rotate_right(7);
+        }
+    }
+    array::from_fn(|i| h[i] ^ v[i] ^ v[i + 8])
+}
+
+/// A byte of the block read from RAM.
+#[derive(Debug, Clone, Copy, Default)]
+struct BlockByte {
+    value: u8,
+    prev_timestamp: u32,
+}
+
+/// Witness of a row, i.e. of a single compressed block.
+#[derive(Debug, Clone, Default)]
+pub struct BlockInput {
+    is_real: bool,
+    is_first: bool,
+    is_last: bool,
+    clk: u32,
+    rs1: u32,
+    rs2: u32,
+    /// The number of bytes hashed before this block.
+    offset: u32,
+    /// The chaining value before this block.
+    h: [u32; 8],
+    /// At most [`BLOCK_SIZE`] bytes, only the last block of a call may be shorter.
+    bytes: Vec<BlockByte>,
+}
+
+/// Proves calls of [`DummyHash`](crate::DummyHash) with the given precompile index.
+#[derive(Debug, Clone)]
+pub struct DummyHashCircuit {
+    index: u16,
+}
+
+impl DummyHashCircuit {
+    /// The index is the position of the precompile in the guest's `use_precompiles!` invocation.
+    pub fn new(index: u16) -> Self {
+        Self { index }
+    }
+}
+
+impl RowCircuit for DummyHashCircuit {
+    type Input = BlockInput;
+
+    fn rows(&self, side_note: &SideNote) -> Vec<BlockInput> {
+        let mut rows = Vec::new();
+        for call in side_note
+            .precompile_calls()
+            .iter()
+            .filter(|call| call.index == self.index)
+        {
+            let (rs1, rs2) = (call.value_b, call.value_c);
+            assert_eq!(
+                call.memory.len(),
+                rs2 as usize,
+                "dummy hash reads rs2 bytes"
+            );
+            let bytes: Vec<BlockByte> = call
+                .memory
+                .iter()
+                .enumerate()
+                .map(|(i, access)| {
+                    assert_eq!(
+                        access.address,
+                        rs1.wrapping_add(i as u32),
+                        "dummy hash reads from rs1"
+                    );
+                    assert_eq!(access.value, access.prev_value, "dummy hash doesn't write");
+                    BlockByte {
+                        value: access.value,
+                        prev_timestamp: access.prev_timestamp,
+                    }
+                })
+                .collect();
+
+            let num_blocks = bytes.len().div_ceil(BLOCK_SIZE).max(1);
+            let mut h = initial_chaining_value();
+            for block in 0..num_blocks {
+                let offset = block * BLOCK_SIZE;
+                let block_bytes = &bytes[offset..bytes.len().min(offset + BLOCK_SIZE)];
+                let is_last = block + 1 == num_blocks;
+                rows.push(BlockInput {
+                    is_real: true,
+                    is_first: block == 0,
+                    is_last,
+                    clk: call.clk,
+                    rs1,
+                    rs2,
+                    offset: offset as u32,
+                    h,
+                    bytes: block_bytes.to_vec(),
+                });
+
+                let mut padded = [0u8; BLOCK_SIZE];
+                for (byte, b) in padded.iter_mut().zip(block_bytes) {
+                    *byte = b.value;
+                }
+                let m = array::from_fn(|i| {
+                    u32::from_le_bytes(padded[4 * i..4 * i + 4].try_into().expect("4 bytes"))
+                });
+                h = compress(&h, &m, (offset + block_bytes.len()) as u32, is_last);
+            }
+            assert_eq!(h[0], call.value_a, "dummy hash result mismatch");
+        }
+        rows
+    }
+
+    fn evaluate<R: Row>(
+        &self,
+        row: &mut R,
+        input: &BlockInput,
+        lookup_elements: &AllLookupElements,
+    ) {
+        let one = constant::<R::F>(1);
+
+        let is_real = next_bool(row, input.is_real);
+        let is_first = next_bool(row, input.is_first);
+        let is_last = next_bool(row, input.is_last);
+        row.add_constraint(is_first.clone() * (one.clone() - is_real.clone()));
+        row.add_constraint(is_last.clone() * (one.clone() - is_real.clone()));
+
+        // Call arguments, their bytes are matched against the main trace through the chain.
+        let clk = next_bytes(row, input.clk);
+        let rs1 = next_bytes(row, input.rs1);
+        let rs2 = next_bytes(row, input.rs2);
+        let offset = [
+            row.next(BaseField::from(input.offset & 0xFFFF)),
+            row.next(BaseField::from(input.offset >> 16)),
+        ];
+        for limb in &offset {
+            row.add_constraint(is_first.clone() * limb.clone());
+        }
+
+        // Base address of the block, rs1 + offset.
+        let base_value = input.rs1.wrapping_add(input.offset);
+        let base = next_bytes(row, base_value);
+        for byte in &base {
+            row.range_check_u8(is_real.clone(), byte.clone(), lookup_elements);
+        }
+        let base_carry = next_bool(row, (input.rs1 & 0xFFFF) + (input.offset & 0xFFFF) > 0xFFFF);
+        let [rs1_lo, rs1_hi] = byte_limbs(&rs1);
+        let [base_lo, base_hi] = byte_limbs(&base);
+        row.add_constraint(
+            rs1_lo + offset[0].clone() - base_lo - base_carry.clone() * BaseField::from(1 << 16),
+        );
+        row.add_constraint(rs1_hi + offset[1].clone() + base_carry - base_hi);
+
+        // Slot `i` holds the byte at base + i. Its address carries into the upper bytes iff k[i] is set, the upper
+        // bytes of base + 256 are precomputed for that case.
+        let actives: [R::F; BLOCK_SIZE] = array::from_fn(|i| next_bool(row, i < input.bytes.len()));
+        let mut carries = [false; BLOCK_SIZE];
+        for i in 0..BLOCK_SIZE {
+            carries[i] = if i < input.bytes.len() {
+                (base_value & 0xFF) as usize + i > 0xFF
+            } else {
+                i > 0 && carries[i - 1]
+            };
+        }
+        let ks: [R::F; BLOCK_SIZE] = array::from_fn(|i| next_bool(row, carries[i]));
+        let has_carry = carries[BLOCK_SIZE - 1];
+        let upper_value = (base_value >> 8) + has_carry as u32;
+        let upper: [R::F; 3] =
+            array::from_fn(|j| row.next(BaseField::from((upper_value >> (8 * j)) & 0xFF)));
+        for byte in &upper {
+            row.range_check_u8(is_real.clone(), byte.clone(), lookup_elements);
+        }
+        let upper_carries: [R::F; 2] = array::from_fn(|j| {
+            next_bool(
+                row,
+                has_carry
+                    && (base_value >> 8) & ((1 << (8 * (j + 1))) - 1) == (1 << (8 * (j + 1))) - 1,
+            )
+        });
+        row.add_constraint(
+            base[1].clone() + ks[BLOCK_SIZE - 1].clone()
+                - upper[0].clone()
+                - upper_carries[0].clone() * BaseField::from(1 << 8),
+        );
+        row.add_constraint(
+            base[2].clone() + upper_carries[0].clone()
+                - upper[1].clone()
+                - upper_carries[1].clone() * BaseField::from(1 << 8),
+        );
+        row.add_constraint(base[3].clone() + upper_carries[1].clone() - upper[2].clone());
+
+        let load_store: &LoadStoreLookupElements = lookup_elements.as_ref();
+        let mut values = Vec::with_capacity(BLOCK_SIZE);
+        for i in 0..BLOCK_SIZE {
+            let byte = input.bytes.get(i).copied().unwrap_or_default();
+            let (active, k) = (actives[i].clone(), ks[i].clone());
+            if i == 0 {
+                row.add_constraint(active.clone() * (one.clone() - is_real.clone()));
+            } else {
+                row.add_constraint(active.clone() * (one.clone() - actives[i - 1].clone()));
+            }
+            if i + 1 < BLOCK_SIZE {
+                row.add_constraint(k.clone() * (one.clone() - ks[i + 1].clone()));
+            }
+
+            let value = row.next(BaseField::from(byte.value as u32));
+            row.add_constraint((one.clone() - active.clone()) * value.clone());
+
+            // Equal to (base + i) >> 8 for active slots.
+            let address_upper = if carries[i] {
+                upper_value
+            } else {
+                base_value >> 8
+            };
+            let address_0 =
+                base[0].clone() + constant::<R::F>(i as u32) - k.clone() * BaseField::from(1 << 8);
+            row.range_check_u8(active.clone(), address_0.clone(), lookup_elements);
+            let mut address = vec![address_0];
+            for j in 0..3 {
+                let byte = row.next(BaseField::from((address_upper >> (8 * j)) & 0xFF));
+                row.add_constraint(
+                    byte.clone()
+                        - base[j + 1].clone()
+                        - k.clone() * (upper[j].clone() - base[j + 1].clone()),
+                );
+                address.push(byte);
+            }
+
+            // prev_timestamp < clk, i.e. clk - 1 - prev_timestamp fits into 32 bits.
+            let prev_timestamp = next_bytes(row, byte.prev_timestamp);
+            let aux_value = input.clk.wrapping_sub(1).wrapping_sub(byte.prev_timestamp);
+            let aux = next_bytes(row, aux_value);
+            for byte in &aux {
+                row.range_check_u8(active.clone(), byte.clone(), lookup_elements);
+            }
+            let borrow = next_bool(
+                row,
+                (byte.prev_timestamp & 0xFFFF) + 1 + (aux_value & 0xFFFF) > 0xFFFF,
+            );
+            let [prev_lo, prev_hi] = byte_limbs(&prev_timestamp);
+            let [aux_lo, aux_hi] = byte_limbs(&aux);
+            let [clk_lo, clk_hi] = byte_limbs(&clk);
+            row.add_constraint(
+                active.clone()
+                    * (prev_lo + one.clone() + aux_lo
+                        - clk_lo
+                        - borrow.clone() * BaseField::from(1 << 16)),
+            );
+            row.add_constraint(active.clone() * (prev_hi + aux_hi + borrow - clk_hi));
+
+            // Read the byte, the value stays the same.
+            let mut tuple = address.clone();
+            tuple.push(value.clone());
+            tuple.extend(prev_timestamp);
+            row.add_to_relation(load_store, -active.clone(), &tuple);
+            let mut tuple = address;
+            tuple.push(value.clone());
+            tuple.extend(clk.iter().cloned());
+            row.add_to_relation(load_store, active.clone(), &tuple);
+
+            values.push((active, value));
+        }
+
+        // All blocks but the last are full, and the last block is only empty for an empty input.
+        let n = values
+            .iter()
+            .fold(constant::<R::F>(0), |acc, (active, _)| acc + active.clone());
+        row.add_constraint(
+            (is_real.clone() - is_last.clone()) * (n.clone() - constant(BLOCK_SIZE as u32)),
+        );
+        row.add_constraint(
+            is_last.clone()
+                * (one.clone() - is_first.clone())
+                * (one.clone() - values[0].0.clone()),
+        );
+
+        // The counter t = offset + n, equal to rs2 on the last block.
+        let t_value = input.offset + input.bytes.len() as u32;
+        let t = next_bits(row, t_value);
+        let t_carry = next_bool(
+            row,
+            (input.offset & 0xFFFF) + input.bytes.len() as u32 > 0xFFFF,
+        );
+        let [t_lo, t_hi] = limbs(&t);
+        row.add_constraint(
+            t_lo.clone() + t_carry.clone() * BaseField::from(1 << 16) - offset[0].clone() - n,
+        );
+        row.add_constraint(t_hi.clone() - offset[1].clone() - t_carry);
+        let [rs2_lo, rs2_hi] = byte_limbs(&rs2);
+        row.add_constraint(is_last.clone() * (t_lo.clone() - rs2_lo));
+        row.add_constraint(is_last.clone() * (t_hi.clone() - rs2_hi));
+
+        // The chaining value, the initial one for the first block.
+        let h: [Word<R::F>; 8] = array::from_fn(|i| next_bits(row, input.h[i]));
+        for (word, init) in h.iter().zip(initial_chaining_value()) {
+            for (bit, init) in word.iter().zip(constant_bits::<R::F>(init)) {
+                row.add_constraint(is_first.clone() * (bit.clone() - init));
+            }
+        }
+
+        let m: [[R::F; 2]; 16] = array::from_fn(|w| {
+            let bytes: [R::F; 4] = array::from_fn(|j| values[4 * w + j].1.clone());
+            byte_limbs(&bytes)
+        });
+
+        let mut v: [Word<R::F>; 16] = array::from_fn(|i| match i {
+            0..=7 => h[i].clone(),
+            12 => xor_constant(&t, IV[4]),
+            14 => xor_constant(&array::from_fn(|_| is_last.clone()), IV[6]),
+            _ => constant_bits(IV[i - 8]),
+        });
+        for s in SIGMA {
+            for (j, [a, b, c, d]) in G_INDICES.into_iter().enumerate() {
+                let (x, y) = (&m[s[2 * j]], &m[s[2 * j + 1]]);
+                v[a] = add(row, &[limbs(&v[a]), limbs(&v[b]), x.clone()]);
+                v[d] = rotate_right(&xor(row, &v[d], &v[a]), 16);
+                v[c] = add(row, &[limbs(&v[c]), limbs(&v[d])]);
+                v[b] = rotate_right(&xor(row, &v[b], &v[c]), 12);
+                v[a] = add(row, &[limbs(&v[a]), limbs(&v[b]), y.clone()]);
+                v[d] = rotate_right(&xor(row, &v[d], &v[a]), 8);
+                v[c] = add(row, &[limbs(&v[c]), limbs(&v[d])]);
+                v[b] = rotate_right(&xor(row, &v[b], &v[c]), 7);
+            }
+        }
+        let h_out: [Word<R::F>; 8] = array::from_fn(|i| xor3(row, &h[i], &v[i], &v[i + 8]));
+
+        // Link blocks of the same call.
+        let chain: &DummyHashChainElements = lookup_elements.get_custom();
+        let mut tuple: Vec<R::F> = clk.iter().chain(&rs1).chain(&rs2).cloned().collect();
+        tuple.extend(offset);
+        tuple.extend(h.iter().flat_map(limbs));
+        row.add_to_relation(chain, is_first.clone() - is_real.clone(), &tuple);
+        let mut tuple: Vec<R::F> = clk.iter().chain(&rs1).chain(&rs2).cloned().collect();
+        tuple.extend([t_lo, t_hi]);
+        tuple.extend(h_out.iter().flat_map(limbs));
+        row.add_to_relation(chain, is_real - is_last.clone(), &tuple);
+
+        // The last block returns the first four bytes of the digest.
+        let precompile: &PrecompileLookupElements = lookup_elements.as_ref();
+        let mut tuple = clk.to_vec();
+        tuple.push(constant(self.index as u32));
+        tuple.extend(rs1);
+        tuple.extend(rs2);
+        tuple.extend(array::from_fn::<_, 4, _>(|j| {
+            (0..8).fold(constant::<R::F>(0), |acc, bit| {
+                acc + h_out[0][8 * j + bit].clone() * BaseField::from(1 << bit)
+            })
+        }));
+        row.add_to_relation(precompile, -is_last, &tuple);
+    }
+
+    fn draw_lookup_elements(
+        &self,
+        lookup_elements: &mut AllLookupElements,
+        channel: &mut MachineChannel,
+    ) {
+        lookup_elements.insert_custom(DummyHashChainElements::draw(channel));
+    }
+
+    fn declare_support(&self, support: &mut Support) {
+        support.precompiles.insert(self.index);
+    }
+}
+
+/// A 32-bit word as little-endian bits.
+type Word<F> = [F; WORD_BITS];
+
+fn constant<F: RowField>(value: u32) -> F {
+    F::from(BaseField::from(value))
+}
+
+fn constant_bits<F: RowField>(value: u32) -> Word<F> {
+    array::from_fn(|i| constant((value >> i) & 1))
+}
+
+fn next_bool<R: Row>(row: &mut R, value: bool) -> R::F {
+    let bit = row.next(BaseField::from(value as u32));
+    row.add_constraint(bit.clone() * (bit.clone() - constant(1)));
+    bit
+}
+
+fn next_bits<R: Row>(row: &mut R, value: u32) -> Word<R::F> {
+    array::from_fn(|i| next_bool(row, (value >> i) & 1 == 1))
+}
+
+/// Allocates bytes of a word, the caller is responsible for checking their range.
+fn next_bytes<R: Row>(row: &mut R, value: u32) -> [R::F; 4] {
+    value
+        .to_le_bytes()
+        .map(|byte| row.next(BaseField::from(byte as u32)))
+}
+
+fn word_value<R: Row>(row: &R, word: &Word<R::F>) -> u32 {
+    word.iter()
+        .enumerate()
+        .fold(0, |acc, (i, bit)| acc | (row.value(bit).0 << i))
+}
+
+/// Splits a word into 16-bit limbs.
+fn limbs<F: RowField>(word: &Word<F>) -> [F; 2] {
+    array::from_fn(|j| {
+        (0..16).fold(constant::<F>(0), |acc, i| {
+            acc + word[16 * j + i].clone() * BaseField::from(1 << i)
+        })
+    })
+}
+
+fn byte_limbs<F: RowField>(bytes: &[F; 4]) -> [F; 2] {
+    array::from_fn(|j| bytes[2 * j].clone() + bytes[2 * j + 1].clone() * BaseField::from(1 << 8))
+}
+
+fn rotate_right<F: Clone>(word: &Word<F>, n: usize) -> Word<F> {
+    array::from_fn(|i| word[(i + n) % WORD_BITS].clone())
+}
+
+fn xor_constant<F: RowField>(word: &Word<F>, value: u32) -> Word<F> {
+    array::from_fn(|i| {
+        if (value >> i) & 1 == 1 {
+            constant::<F>(1) - word[i].clone()
+        } else {
+            word[i].clone()
+        }
+    })
+}
+
+fn xor<R: Row>(row: &mut R, a: &Word<R::F>, b: &Word<R::F>) -> Word<R::F> {
+    let value = word_value(row, a) ^ word_value(row, b);
+    array::from_fn(|i| {
+        let bit = row.next(BaseField::from((value >> i) & 1));
+        row.add_constraint(
+            bit.clone() - a[i].clone() - b[i].clone()
+                + a[i].clone() * b[i].clone() * BaseField::from(2),
+        );
+        bit
+    })
+}
+
+fn xor3<R: Row>(row: &mut R, a: &Word<R::F>, b: &Word<R::F>, c: &Word<R::F>) -> Word<R::F> {
+    let value = word_value(row, a) ^ word_value(row, b) ^ word_value(row, c);
+    array::from_fn(|i| {
+        let (a, b, c) = (a[i].clone(), b[i].clone(), c[i].clone());
+        let bit = row.next(BaseField::from((value >> i) & 1));
+        let pairs = a.clone() * b.clone() + b.clone() * c.clone() + c.clone() * a.clone();
+        let expected = a.clone() + b.clone() + c.clone() - pairs * BaseField::from(2)
+            + a * b * c * BaseField::from(4);
+        row.add_constraint(bit.clone() - expected);
+        bit
+    })
+}
+
+/// Adds words given in 16-bit limbs modulo 2^32, at most three of them.
+fn add<R: Row>(row: &mut R, operands: &[[R::F; 2]]) -> Word<R::F> {
+    assert!(operands.len() <= 3, "carries are at most 2");
+    let [lo, hi] = array::from_fn(|j| {
+        operands
+            .iter()
+            .fold(constant::<R::F>(0), |acc, limbs| acc + limbs[j].clone())
+    });
+    let lo_value = row.value(&lo).0;
+    let hi_value = row.value(&hi).0 + (lo_value >> 16);
+    let sum = next_bits(row, (lo_value & 0xFFFF) | (hi_value << 16));
+    let carries = [lo_value >> 16, hi_value >> 16].map(|carry| {
+        let carry = row.next(BaseField::from(carry));
+        row.add_constraint(
+            carry.clone() * (carry.clone() - constant(1)) * (carry.clone() - constant(2)),
+        );
+        carry
+    });
+    let [sum_lo, sum_hi] = limbs(&sum);
+    row.add_constraint(lo - sum_lo - carries[0].clone() * BaseField::from(1 << 16));
+    row.add_constraint(
+        hi + carries[0].clone() - sum_hi - carries[1].clone() * BaseField::from(1 << 16),
+    );
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use blake2::{digest::consts::U4, Blake2s, Digest};
+    use nexus_vm::{
+        emulator::{Emulator, HarvardEmulator, InternalView},
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, InstructionType, Opcode, Register},
+        trace::k_trace_harvard,
+    };
+    use nexus_vm_prover::{
+        extensions::{circuit::CircuitExtension, ExtensionComponent},
+        machine::{BaseComponent, Machine},
+        Blake2sMerkleHasher, ProverConfig,
+    };
+
+    use crate::DummyHash;
+
+    fn dummy_hash(data: &[u8]) -> u32 {
+        let mut hasher = Blake2s::<U4>::new();
+        hasher.update(data);
+        u32::from_le_bytes(hasher.finalize().into())
+    }
+
+    fn test_input(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 37 + 11) as u8).collect()
+    }
+
+    #[test]
+    fn test_compress() {
+        for len in [0, 1, 63, 64, 65, 130, 256] {
+            let data = test_input(len);
+            let num_blocks = len.div_ceil(BLOCK_SIZE).max(1);
+            let mut h = initial_chaining_value();
+            for block in 0..num_blocks {
+                let chunk = &data[block * BLOCK_SIZE..len.min((block + 1) * BLOCK_SIZE)];
+                let mut padded = [0u8; BLOCK_SIZE];
+                padded[..chunk.len()].copy_from_slice(chunk);
+                let m = array::from_fn(|i| {
+                    u32::from_le_bytes(padded[4 * i..4 * i + 4].try_into().unwrap())
+                });
+                let t = (block * BLOCK_SIZE + chunk.len()) as u32;
+                h = compress(&h, &m, t, block + 1 == num_blocks);
+            }
+            assert_eq!(h[0], dummy_hash(&data), "length {len}");
+        }
+    }
+
+    #[test]
+    fn test_prove_dummy_hash() {
+        let opcode = Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "dynamic");
+        let inputs = [test_input(0), test_input(64), test_input(130)];
+
+        let mut instructions = vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 1, 1, 19),
+            // The buffer starts close to a multiple of 256, so addresses within a block carry into the second byte.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 1, 0xF0),
+        ];
+        for input in &inputs {
+            for (i, byte) in input.iter().enumerate() {
+                instructions.push(Instruction::new_ir(
+                    Opcode::from(BuiltinOpcode::ADDI),
+                    6,
+                    0,
+                    *byte as u32,
+                ));
+                instructions.push(Instruction::new_ir(
+                    Opcode::from(BuiltinOpcode::SB),
+                    10,
+                    6,
+                    i as u32,
+                ));
+            }
+            instructions.push(Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                11,
+                0,
+                input.len() as u32,
+            ));
+            instructions.push(Instruction::new(
+                opcode.clone(),
+                Register::X12,
+                Register::X10,
+                11,
+                InstructionType::RType,
+            ));
+        }
+        let basic_blocks = vec![BasicBlock::new(instructions)];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator.add_opcode::<DummyHash>(&opcode).unwrap();
+        let (view, program_trace) = k_trace_harvard(emulator, 1).expect("error generating trace");
+
+        let results: Vec<u32> = program_trace
+            .blocks
+            .iter()
+            .flat_map(|block| &block.steps)
+            .filter(|step| step.instruction.opcode == opcode)
+            .map(|step| step.result.expect("dummy hash returns a result"))
+            .collect();
+        let expected: Vec<u32> = inputs.iter().map(|input| dummy_hash(input)).collect();
+        assert_eq!(results, expected);
+
+        let prove_verify = |extensions: &[ExtensionComponent]| {
+            let proof = Machine::<BaseComponent>::prove_with_extensions::<Blake2sMerkleHasher>(
+                extensions,
+                &program_trace,
+                &view,
+                &ProverConfig::default(),
+            )
+            .unwrap();
+            Machine::<BaseComponent>::verify_with_extensions(
+                extensions,
+                proof,
+                ProverConfig::default().security_bits(),
+                view.get_program_memory(),
+                view.view_memory_layout().as_ref(),
+                &[],
+                view.get_initial_memory(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+        prove_verify(&[ExtensionComponent::custom(Arc::new(CircuitExtension::new(
+            DummyHashCircuit::new(0),
+            4,
+        )))])
+        .unwrap();
+
+        // Without the extension nothing consumes the calls added by the CPU.
+        assert!(prove_verify(&[]).is_err());
+    }
+}
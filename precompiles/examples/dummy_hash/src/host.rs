@@ -1,4 +1,4 @@
-use blake2::{digest::consts::U4, Blake2s, Blake2sVar, Blake2sVarCore, Digest};
+use blake2::{digest::consts::U4, Blake2s, Digest};
 use nexus_common::{
     cpu::{InstructionExecutor, InstructionResult, InstructionState, Processor, Registers},
     error::MemoryError,
@@ -34,7 +34,7 @@ impl InstructionState for DummyHash {
         // If you wanted to improve prover & VM performance, you could do this word-wise (then
         // half-word-wise, then byte-wise) instead of byte-wise. This is bytewise purely for the
         // sake of simplicity.
-        for addr in self.rs1..(self.rs1 + self.rs2 as u32) {
+        for addr in self.rs1..(self.rs1 + self.rs2) {
             let load_op = memory.read(addr, MemAccessSize::Byte)?;
             load_ops.insert(load_op);
 
@@ -5,7 +5,11 @@ pub mod guest;
 #[cfg(target_arch = "riscv32")]
 pub use guest::*;
 
+#[cfg(not(target_arch = "riscv32"))]
+pub mod circuit;
 #[cfg(not(target_arch = "riscv32"))]
 pub mod host;
 #[cfg(not(target_arch = "riscv32"))]
+pub use circuit::DummyHashCircuit;
+#[cfg(not(target_arch = "riscv32"))]
 pub use host::*;
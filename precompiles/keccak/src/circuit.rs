@@ -0,0 +1,649 @@
+//! Circuit proving calls of the Keccak-f\[1600\] precompile.
+//!
+//! A call takes [`ROWS_PER_CALL`] rows. The first 24 rows compute one round each over the bits of the state, and
+//! consecutive rounds are linked with [`KeccakChainElements`]. Independently, row `i` of a call reads and writes lane
+//! `i` of the state in RAM, and hands the lane over to the first and the last round with [`KeccakLaneElements`]. The
+//! first round also consumes the call itself from [`PrecompileLookupElements`].
+
+use std::array;
+
+use nexus_precompiles::PrecompileCircuit;
+use nexus_vm_prover::{
+    components::AllLookupElements,
+    extensions::{
+        circuit::{Row, RowCircuit, RowField},
+        LoadStoreLookupElements, PrecompileLookupElements,
+    },
+    hash::MachineChannel,
+    preflight::Support,
+    trace::sidenote::{PrecompileMemoryAccess, SideNote},
+};
+use stwo_prover::core::fields::m31::BaseField;
+
+use crate::{keccak_f, STATE_LANES};
+
+/// Number of rows taken by a single call, one per round and one more for the last lane of the state.
+pub const ROWS_PER_CALL: usize = STATE_LANES;
+
+const ROUNDS: usize = 24;
+const LANE_BITS: usize = 64;
+const LANE_BYTES: usize = 8;
+/// Number of 16-bit limbs in a lane.
+const LANE_LIMBS: usize = 4;
+
+const ROUND_CONSTANTS: [u64; ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation offsets of rho, indexed by lane `x + 5y`.
+const ROTATIONS: [usize; STATE_LANES] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// The tuple consists of clk, the index of the next round and the state in 16-bit limbs.
+const CHAIN_TUPLE_SIZE: usize = 4 + 1 + STATE_LANES * LANE_LIMBS;
+stwo_prover::relation!(KeccakChainElements, CHAIN_TUPLE_SIZE);
+
+/// The tuple consists of clk, a tag, the address of the lane in two 16-bit limbs and the lane in 16-bit limbs.
+///
+/// The tag of a lane `i` read from RAM is `i`, and `25 + i` for the same lane written back. The address is only part
+/// of the former.
+const LANE_TUPLE_SIZE: usize = 4 + 1 + 2 + LANE_LIMBS;
+stwo_prover::relation!(KeccakLaneElements, LANE_TUPLE_SIZE);
+
+/// Destination lane of pi for lane `x + 5y`, i.e. `y + 5 (2x + 3y)`.
+fn pi(lane: usize) -> usize {
+    let (x, y) = (lane % 5, lane / 5);
+    y + 5 * ((2 * x + 3 * y) % 5)
+}
+
+/// The theta step computed natively, returns the column parities and the updated state.
+fn theta(state: &[u64; STATE_LANES]) -> ([u64; 5], [u64; STATE_LANES]) {
+    let c: [u64; 5] = array::from_fn(|x| (0..5).fold(0, |acc, y| acc ^ state[x + 5 * y]));
+    let d: [u64; 5] = array::from_fn(|x| c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1));
+    (c, array::from_fn(|i| state[i] ^ d[i % 5]))
+}
+
+/// The steps rho, pi, chi and iota computed natively on the output of theta.
+fn chi_iota(state: &[u64; STATE_LANES], round: usize) -> [u64; STATE_LANES] {
+    let mut b = [0u64; STATE_LANES];
+    for (i, lane) in state.iter().enumerate() {
+        b[pi(i)] = lane.rotate_left(ROTATIONS[i] as u32);
+    }
+    let mut out: [u64; STATE_LANES] = array::from_fn(|i| {
+        let (x, y) = (i % 5, i / 5);
+        b[i] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y])
+    });
+    out[0] ^= ROUND_CONSTANTS[round];
+    out
+}
+
+/// Assembles the lanes of the state from the bytes accessed by a call.
+fn lanes(
+    memory: &[PrecompileMemoryAccess],
+    value: impl Fn(&PrecompileMemoryAccess) -> u8,
+) -> [u64; STATE_LANES] {
+    array::from_fn(|i| u64::from_le_bytes(array::from_fn(|j| value(&memory[LANE_BYTES * i + j]))))
+}
+
+/// A byte of a lane accessed in RAM.
+#[derive(Debug, Clone, Copy, Default)]
+struct LaneByte {
+    prev_value: u8,
+    prev_timestamp: u32,
+    value: u8,
+}
+
+/// Witness of a row, i.e. of a single round and of a single lane accessed in RAM.
+#[derive(Debug, Clone, Default)]
+pub struct KeccakRow {
+    is_real: bool,
+    /// The round computed by the row, the last row of a call computes none.
+    round: Option<usize>,
+    clk: u32,
+    state_ptr: u32,
+    /// The unused second operand, only needed for consuming the call.
+    rs2: u32,
+    /// The state before the round, zero without a round.
+    state: [u64; STATE_LANES],
+    /// The index of the lane accessed in RAM.
+    lane: usize,
+    bytes: [LaneByte; LANE_BYTES],
+}
+
+/// Proves calls of [`Keccak`](crate::Keccak) with the given precompile index.
+///
+/// Every call takes [`ROWS_PER_CALL`] rows of the extension.
+#[derive(Debug, Clone)]
+pub struct KeccakCircuit {
+    index: u16,
+}
+
+impl KeccakCircuit {
+    /// The index is the position of the precompile in the guest's `use_precompiles!` invocation.
+    pub fn new(index: u16) -> Self {
+        Self { index }
+    }
+}
+
+impl PrecompileCircuit for KeccakCircuit {}
+
+impl RowCircuit for KeccakCircuit {
+    type Input = KeccakRow;
+
+    fn rows(&self, side_note: &SideNote) -> Vec<KeccakRow> {
+        let mut rows = Vec::new();
+        for call in side_note
+            .precompile_calls()
+            .iter()
+            .filter(|call| call.index == self.index)
+        {
+            let state_ptr = call.value_b;
+            assert_eq!(state_ptr % 4, 0, "keccak state must be word-aligned");
+            assert_eq!(
+                call.memory.len(),
+                STATE_LANES * LANE_BYTES,
+                "keccak accesses the whole state"
+            );
+            for (i, access) in call.memory.iter().enumerate() {
+                assert_eq!(
+                    access.address,
+                    state_ptr.wrapping_add(i as u32),
+                    "keccak accesses the state at rs1"
+                );
+            }
+            assert_eq!(call.value_a, 0, "keccak returns zero");
+
+            let mut state = lanes(&call.memory, |access| access.prev_value);
+            let mut expected = state;
+            keccak_f(&mut expected);
+            assert_eq!(
+                lanes(&call.memory, |access| access.value),
+                expected,
+                "keccak result mismatch"
+            );
+
+            for i in 0..ROWS_PER_CALL {
+                let round = (i < ROUNDS).then_some(i);
+                rows.push(KeccakRow {
+                    is_real: true,
+                    round,
+                    clk: call.clk,
+                    state_ptr,
+                    rs2: call.value_c,
+                    state: if round.is_some() {
+                        state
+                    } else {
+                        [0; STATE_LANES]
+                    },
+                    lane: i,
+                    bytes: array::from_fn(|j| {
+                        let access = &call.memory[LANE_BYTES * i + j];
+                        LaneByte {
+                            prev_value: access.prev_value,
+                            prev_timestamp: access.prev_timestamp,
+                            value: access.value,
+                        }
+                    }),
+                });
+                if let Some(round) = round {
+                    state = chi_iota(&theta(&state).1, round);
+                }
+            }
+        }
+        rows
+    }
+
+    fn evaluate<R: Row>(
+        &self,
+        row: &mut R,
+        input: &KeccakRow,
+        lookup_elements: &AllLookupElements,
+    ) {
+        let one = constant::<R::F>(1);
+
+        let is_real = next_bool(row, input.is_real);
+        let rounds: [R::F; ROUNDS] = array::from_fn(|r| next_bool(row, input.round == Some(r)));
+        let is_round = rounds
+            .iter()
+            .fold(constant::<R::F>(0), |acc, flag| acc + flag.clone());
+        row.add_constraint(is_round.clone() * (one.clone() - is_round.clone()));
+        row.add_constraint(is_round.clone() * (one.clone() - is_real.clone()));
+        let (is_first, is_last) = (rounds[0].clone(), rounds[ROUNDS - 1].clone());
+
+        let clk = next_bytes(row, input.clk);
+        let state_ptr = next_bytes(row, input.state_ptr);
+        let rs2 = next_bytes(row, input.rs2);
+
+        // The state must be word-aligned, so that the bytes of a word only differ in the lowest address byte.
+        let alignment = row.next(BaseField::from((input.state_ptr & 0xFF) >> 2));
+        row.range_check_u8(is_first.clone(), alignment.clone(), lookup_elements);
+        row.add_constraint(state_ptr[0].clone() - alignment * BaseField::from(4));
+
+        self.evaluate_lane(row, input, lookup_elements, &is_real, &clk);
+
+        // Theta, the parity of every column is c + 2q with q in {0, 1, 2}.
+        let state: [[R::F; LANE_BITS]; STATE_LANES] =
+            array::from_fn(|i| next_lane_bits(row, input.state[i]));
+        let (c_value, theta_value) = theta(&input.state);
+        let c: [[R::F; LANE_BITS]; 5] = array::from_fn(|x| next_lane_bits(row, c_value[x]));
+        for (x, c) in c.iter().enumerate() {
+            for (z, c) in c.iter().enumerate() {
+                let ones = (0..5)
+                    .filter(|y| (input.state[x + 5 * y] >> z) & 1 == 1)
+                    .count() as u32;
+                let q = row.next(BaseField::from(ones >> 1));
+                row.add_constraint(
+                    q.clone() * (q.clone() - one.clone()) * (q.clone() - constant(2)),
+                );
+                let sum = (0..5).fold(constant::<R::F>(0), |acc, y| {
+                    acc + state[x + 5 * y][z].clone()
+                });
+                row.add_constraint(sum - c.clone() - q * BaseField::from(2));
+            }
+        }
+        let theta_out: [[R::F; LANE_BITS]; STATE_LANES] = array::from_fn(|i| {
+            let x = i % 5;
+            array::from_fn(|z| {
+                let d = xor(
+                    &c[(x + 4) % 5][z],
+                    &c[(x + 1) % 5][(z + LANE_BITS - 1) % LANE_BITS],
+                );
+                let bit = row.next(BaseField::from(((theta_value[i] >> z) & 1) as u32));
+                row.add_constraint(bit.clone() - xor(&state[i][z], &d));
+                bit
+            })
+        });
+
+        // Rho and pi only move bits around.
+        let mut b: [[R::F; LANE_BITS]; STATE_LANES] =
+            array::from_fn(|_| array::from_fn(|_| constant(0)));
+        for (i, lane) in theta_out.iter().enumerate() {
+            b[pi(i)] = array::from_fn(|z| lane[(z + LANE_BITS - ROTATIONS[i]) % LANE_BITS].clone());
+        }
+
+        // Chi and iota, the round constant is selected by the round flags.
+        let round_constant: [R::F; LANE_BITS] = array::from_fn(|z| {
+            rounds
+                .iter()
+                .zip(ROUND_CONSTANTS)
+                .filter(|(_, rc)| (rc >> z) & 1 == 1)
+                .fold(constant::<R::F>(0), |acc, (flag, _)| acc + flag.clone())
+        });
+        let out_value = input
+            .round
+            .map(|round| chi_iota(&theta_value, round))
+            .unwrap_or_default();
+        let out: [[R::F; LANE_BITS]; STATE_LANES] = array::from_fn(|i| {
+            let (x, y) = (i % 5, i / 5);
+            array::from_fn(|z| {
+                let (b0, b1, b2) = (
+                    b[i][z].clone(),
+                    b[(x + 1) % 5 + 5 * y][z].clone(),
+                    b[(x + 2) % 5 + 5 * y][z].clone(),
+                );
+                let mut expected = xor(&b0, &((one.clone() - b1) * b2));
+                if i == 0 {
+                    expected = xor(&expected, &round_constant[z]);
+                }
+                let bit = row.next(BaseField::from(((out_value[i] >> z) & 1) as u32));
+                row.add_constraint(bit.clone() - expected);
+                bit
+            })
+        });
+
+        // Link rounds of the same call.
+        let round_index = rounds
+            .iter()
+            .enumerate()
+            .fold(constant::<R::F>(0), |acc, (r, flag)| {
+                acc + flag.clone() * BaseField::from(r as u32)
+            });
+        let chain: &KeccakChainElements = lookup_elements.get_custom();
+        let mut tuple = clk.to_vec();
+        tuple.push(round_index.clone());
+        tuple.extend(state.iter().flat_map(lane_limbs));
+        row.add_to_relation(chain, is_first.clone() - is_round.clone(), &tuple);
+        let mut tuple = clk.to_vec();
+        tuple.push(round_index + one.clone());
+        tuple.extend(out.iter().flat_map(lane_limbs));
+        row.add_to_relation(chain, is_round - is_last.clone(), &tuple);
+
+        // The first round reads the state from RAM and consumes the call, the last one writes it back. Lane i starts
+        // at state_ptr + 8i, the carry into the upper limb is a witness.
+        let lanes: &KeccakLaneElements = lookup_elements.get_custom();
+        let [ptr_lo, ptr_hi] = byte_limbs(&state_ptr);
+        for (i, lane) in state.iter().enumerate() {
+            let address = input.state_ptr.wrapping_add((LANE_BYTES * i) as u32);
+            let carry = next_bool(row, address & 0xFFFF < input.state_ptr & 0xFFFF);
+            let mut tuple = clk.to_vec();
+            tuple.extend([
+                constant(i as u32),
+                ptr_lo.clone() + constant((LANE_BYTES * i) as u32)
+                    - carry.clone() * BaseField::from(1 << 16),
+                ptr_hi.clone() + carry,
+            ]);
+            tuple.extend(lane_limbs(lane));
+            row.add_to_relation(lanes, -is_first.clone(), &tuple);
+        }
+        for (i, lane) in out.iter().enumerate() {
+            let mut tuple = clk.to_vec();
+            tuple.extend([constant((STATE_LANES + i) as u32), constant(0), constant(0)]);
+            tuple.extend(lane_limbs(lane));
+            row.add_to_relation(lanes, -is_last.clone(), &tuple);
+        }
+
+        let precompile: &PrecompileLookupElements = lookup_elements.as_ref();
+        let mut tuple = clk.to_vec();
+        tuple.push(constant(self.index as u32));
+        tuple.extend(state_ptr);
+        tuple.extend(rs2);
+        tuple.extend(array::from_fn::<R::F, 4, _>(|_| constant(0)));
+        row.add_to_relation(precompile, -is_first, &tuple);
+    }
+
+    fn draw_lookup_elements(
+        &self,
+        lookup_elements: &mut AllLookupElements,
+        channel: &mut MachineChannel,
+    ) {
+        lookup_elements.insert_custom(KeccakChainElements::draw(channel));
+        lookup_elements.insert_custom(KeccakLaneElements::draw(channel));
+    }
+
+    fn declare_support(&self, support: &mut Support) {
+        support.precompiles.insert(self.index);
+    }
+}
+
+impl KeccakCircuit {
+    /// Reads and writes the lane of a row in RAM, and adds the lane before and after the call to
+    /// [`KeccakLaneElements`].
+    fn evaluate_lane<R: Row>(
+        &self,
+        row: &mut R,
+        input: &KeccakRow,
+        lookup_elements: &AllLookupElements,
+        is_real: &R::F,
+        clk: &[R::F; 4],
+    ) {
+        let lane = row.next(BaseField::from(input.lane as u32));
+
+        // The lane consists of two words, their lowest address bytes are multiples of four.
+        let address_value = input
+            .state_ptr
+            .wrapping_add((LANE_BYTES * input.lane) as u32);
+        let words: [[R::F; 4]; 2] = array::from_fn(|w| {
+            let word = next_bytes(row, address_value.wrapping_add(4 * w as u32));
+            for byte in &word {
+                row.range_check_u8(is_real.clone(), byte.clone(), lookup_elements);
+            }
+            word
+        });
+        let carry = next_bool(row, address_value & 0xFFFF > 0xFFFF - 4);
+        let [lo0, hi0] = byte_limbs(&words[0]);
+        let [lo1, hi1] = byte_limbs(&words[1]);
+        row.add_constraint(
+            is_real.clone()
+                * (lo0.clone() + constant(4) - lo1 - carry.clone() * BaseField::from(1 << 16)),
+        );
+        row.add_constraint(is_real.clone() * (hi0.clone() + carry - hi1));
+
+        let load_store: &LoadStoreLookupElements = lookup_elements.as_ref();
+        let mut prev_values = Vec::with_capacity(LANE_BYTES);
+        let mut values = Vec::with_capacity(LANE_BYTES);
+        for (j, byte) in input.bytes.iter().enumerate() {
+            let word = &words[j / 4];
+            let address = [
+                word[0].clone() + constant((j % 4) as u32),
+                word[1].clone(),
+                word[2].clone(),
+                word[3].clone(),
+            ];
+            let prev_value = row.next(BaseField::from(byte.prev_value as u32));
+            let value = row.next(BaseField::from(byte.value as u32));
+            row.range_check_u8(is_real.clone(), value.clone(), lookup_elements);
+
+            // prev_timestamp < clk, i.e. clk - 1 - prev_timestamp fits into 32 bits.
+            let prev_timestamp = next_bytes(row, byte.prev_timestamp);
+            let aux = next_lt(
+                row,
+                is_real,
+                byte.prev_timestamp,
+                input.clk,
+                &prev_timestamp,
+                clk,
+            );
+            for byte in &aux {
+                row.range_check_u8(is_real.clone(), byte.clone(), lookup_elements);
+            }
+
+            let mut tuple = address.to_vec();
+            tuple.push(prev_value.clone());
+            tuple.extend(prev_timestamp);
+            row.add_to_relation(load_store, -is_real.clone(), &tuple);
+            let mut tuple = address.to_vec();
+            tuple.push(value.clone());
+            tuple.extend(clk.iter().cloned());
+            row.add_to_relation(load_store, is_real.clone(), &tuple);
+
+            prev_values.push(prev_value);
+            values.push(value);
+        }
+
+        let lanes: &KeccakLaneElements = lookup_elements.get_custom();
+        let mut tuple = clk.to_vec();
+        tuple.extend([lane.clone(), lo0, hi0]);
+        tuple.extend(
+            prev_values
+                .chunks_exact(2)
+                .map(|pair| pair[0].clone() + pair[1].clone() * BaseField::from(1 << 8)),
+        );
+        row.add_to_relation(lanes, is_real.clone(), &tuple);
+        let mut tuple = clk.to_vec();
+        tuple.extend([
+            lane + constant(STATE_LANES as u32),
+            constant(0),
+            constant(0),
+        ]);
+        tuple.extend(
+            values
+                .chunks_exact(2)
+                .map(|pair| pair[0].clone() + pair[1].clone() * BaseField::from(1 << 8)),
+        );
+        row.add_to_relation(lanes, is_real.clone(), &tuple);
+    }
+}
+
+fn constant<F: RowField>(value: u32) -> F {
+    F::from(BaseField::from(value))
+}
+
+fn next_bool<R: Row>(row: &mut R, value: bool) -> R::F {
+    let bit = row.next(BaseField::from(value as u32));
+    row.add_constraint(bit.clone() * (bit.clone() - constant(1)));
+    bit
+}
+
+fn next_lane_bits<R: Row>(row: &mut R, value: u64) -> [R::F; LANE_BITS] {
+    array::from_fn(|z| next_bool(row, (value >> z) & 1 == 1))
+}
+
+/// Allocates bytes of a word, the caller is responsible for checking their range.
+fn next_bytes<R: Row>(row: &mut R, value: u32) -> [R::F; 4] {
+    value
+        .to_le_bytes()
+        .map(|byte| row.next(BaseField::from(byte as u32)))
+}
+
+fn xor<F: RowField>(a: &F, b: &F) -> F {
+    a.clone() + b.clone() - a.clone() * b.clone() * BaseField::from(2)
+}
+
+/// Splits a lane into 16-bit limbs.
+fn lane_limbs<F: RowField>(lane: &[F; LANE_BITS]) -> [F; LANE_LIMBS] {
+    array::from_fn(|j| {
+        (0..16).fold(constant::<F>(0), |acc, i| {
+            acc + lane[16 * j + i].clone() * BaseField::from(1 << i)
+        })
+    })
+}
+
+fn byte_limbs<F: RowField>(bytes: &[F; 4]) -> [F; 2] {
+    array::from_fn(|j| bytes[2 * j].clone() + bytes[2 * j + 1].clone() * BaseField::from(1 << 8))
+}
+
+/// Constrains `lhs < rhs` when `flag` is set, returns the bytes of `rhs - 1 - lhs` which the caller must range check.
+fn next_lt<R: Row>(
+    row: &mut R,
+    flag: &R::F,
+    lhs_value: u32,
+    rhs_value: u32,
+    lhs: &[R::F; 4],
+    rhs: &[R::F; 4],
+) -> [R::F; 4] {
+    let aux_value = rhs_value.wrapping_sub(1).wrapping_sub(lhs_value);
+    let aux = next_bytes(row, aux_value);
+    let borrow = next_bool(
+        row,
+        (lhs_value & 0xFFFF) + 1 + (aux_value & 0xFFFF) > 0xFFFF,
+    );
+    let [lhs_lo, lhs_hi] = byte_limbs(lhs);
+    let [aux_lo, aux_hi] = byte_limbs(&aux);
+    let [rhs_lo, rhs_hi] = byte_limbs(rhs);
+    row.add_constraint(
+        flag.clone()
+            * (lhs_lo + constant::<R::F>(1) + aux_lo
+                - rhs_lo
+                - borrow.clone() * BaseField::from(1 << 16)),
+    );
+    row.add_constraint(flag.clone() * (lhs_hi + aux_hi + borrow - rhs_hi));
+    aux
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use nexus_vm::{
+        emulator::{Emulator, HarvardEmulator, InternalView},
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, InstructionType, Opcode, Register},
+        trace::k_trace_harvard,
+    };
+    use nexus_vm_prover::{
+        extensions::{circuit::CircuitExtension, ExtensionComponent},
+        machine::{BaseComponent, Machine},
+        Blake2sMerkleHasher, ProverConfig,
+    };
+
+    use crate::Keccak;
+
+    #[test]
+    fn test_rounds() {
+        let initial: [u64; STATE_LANES] =
+            array::from_fn(|i| (i as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let mut state = initial;
+        for round in 0..ROUNDS {
+            state = chi_iota(&theta(&state).1, round);
+        }
+
+        let mut expected = initial;
+        keccak_f(&mut expected);
+        assert_eq!(state, expected);
+    }
+
+    #[test]
+    fn test_prove_keccak() {
+        let opcode = Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "dynamic");
+
+        let mut instructions = vec![
+            // x10 = 0x8FFC0, so that the addresses of the state carry into the third byte.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0x7FF),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 0x100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 1, 1, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 1, 0xC0),
+        ];
+        for i in 0..STATE_LANES * LANE_BYTES {
+            instructions.push(Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                6,
+                0,
+                ((i * 37 + 11) % 256) as u32,
+            ));
+            instructions.push(Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::SB),
+                10,
+                6,
+                i as u32,
+            ));
+        }
+        // Permute the state twice, the second call reads the result of the first one.
+        for _ in 0..2 {
+            instructions.push(Instruction::new(
+                opcode.clone(),
+                Register::X12,
+                Register::X10,
+                0,
+                InstructionType::RType,
+            ));
+        }
+        let basic_blocks = vec![BasicBlock::new(instructions)];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator.add_opcode::<Keccak>(&opcode).unwrap();
+        let (view, program_trace) = k_trace_harvard(emulator, 1).expect("error generating trace");
+
+        let prove_verify = |extensions: &[ExtensionComponent]| {
+            let proof = Machine::<BaseComponent>::prove_with_extensions::<Blake2sMerkleHasher>(
+                extensions,
+                &program_trace,
+                &view,
+                &ProverConfig::default(),
+            )
+            .unwrap();
+            Machine::<BaseComponent>::verify_with_extensions(
+                extensions,
+                proof,
+                ProverConfig::default().security_bits(),
+                view.get_program_memory(),
+                view.view_memory_layout().as_ref(),
+                &[],
+                view.get_initial_memory(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+        prove_verify(&[ExtensionComponent::custom(Arc::new(CircuitExtension::new(
+            KeccakCircuit::new(0),
+            6,
+        )))])
+        .unwrap();
+
+        // Without the extension nothing consumes the calls added by the CPU.
+        assert!(prove_verify(&[]).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+/// In the guest context, there is nothing actually associated with the precompile other than the
+/// convenience wrapper for emitting the instruction call.
+pub struct Keccak;
+
+#[macro_export]
+macro_rules! generate_instruction_caller {
+    ($path:path) => {
+        trait KeccakCaller {
+            /// Applies the Keccak-f[1600] permutation to `state` in place.
+            fn keccak_f(state: &mut [u64; 25]);
+
+            /// Computes the Keccak-256 digest of `input`, as used by Ethereum.
+            fn keccak256(input: &[u8]) -> [u8; 32];
+        }
+
+        impl KeccakCaller for $path {
+            fn keccak_f(state: &mut [u64; 25]) {
+                Self::emit_instruction(state.as_mut_ptr() as u32, 0, 0);
+            }
+
+            fn keccak256(input: &[u8]) -> [u8; 32] {
+                // Rate of Keccak-256 in bytes: (1600 - 2 * 256) / 8.
+                const RATE: usize = 136;
+
+                fn absorb(state: &mut [u64; 25], block: &[u8]) {
+                    for (lane, bytes) in state.iter_mut().zip(block.chunks_exact(8)) {
+                        let mut buf = [0u8; 8];
+                        buf.copy_from_slice(bytes);
+                        *lane ^= u64::from_le_bytes(buf);
+                    }
+                }
+
+                let mut state = [0u64; 25];
+                let mut blocks = input.chunks_exact(RATE);
+                for block in &mut blocks {
+                    absorb(&mut state, block);
+                    <$path as KeccakCaller>::keccak_f(&mut state);
+                }
+
+                let remainder = blocks.remainder();
+                let mut last = [0u8; RATE];
+                last[..remainder.len()].copy_from_slice(remainder);
+                last[remainder.len()] ^= 0x01;
+                last[RATE - 1] ^= 0x80;
+                absorb(&mut state, &last);
+                <$path as KeccakCaller>::keccak_f(&mut state);
+
+                let mut digest = [0u8; 32];
+                for (bytes, lane) in digest.chunks_exact_mut(8).zip(state.iter()) {
+                    bytes.copy_from_slice(&lane.to_le_bytes());
+                }
+                digest
+            }
+        }
+    };
+}
@@ -0,0 +1,184 @@
+use nexus_common::{
+    cpu::{InstructionExecutor, InstructionResult, InstructionState, Processor, Registers},
+    error::MemoryError,
+    memory::{LoadOp, LoadOps, MemAccessSize, MemoryProcessor, StoreOps},
+    riscv::{instruction::Instruction, register::Register},
+};
+
+use nexus_precompiles::{PrecompileCircuit, PrecompileInstruction, PrecompileMetadata};
+
+use crate::KeccakCircuit;
+
+/// Number of 64-bit lanes in the Keccak-f[1600] state.
+pub const STATE_LANES: usize = 25;
+
+/// Number of 32-bit words the state occupies in guest memory.
+const STATE_WORDS: usize = STATE_LANES * 2;
+
+#[derive(Default)]
+pub struct Keccak {
+    rd: Register,
+    state_ptr: u32,
+    state: [u64; STATE_LANES],
+}
+
+/// Applies the Keccak-f[1600] permutation to `state` in place, executed on the native host.
+pub fn keccak_f(state: &mut [u64; STATE_LANES]) {
+    keccak::f1600(state);
+}
+
+impl InstructionState for Keccak {
+    fn execute(&mut self) {
+        keccak_f(&mut self.state);
+    }
+
+    fn memory_read(&mut self, memory: &impl MemoryProcessor) -> Result<LoadOps, MemoryError> {
+        let mut load_ops = LoadOps::default();
+        let mut words = [0u32; STATE_WORDS];
+
+        for (i, word) in words.iter_mut().enumerate() {
+            let load_op = memory.read(self.state_ptr + (i * 4) as u32, MemAccessSize::Word)?;
+            load_ops.insert(load_op);
+
+            let LoadOp::Op(_, _, value) = load_op;
+            *word = value;
+        }
+
+        for (lane, halves) in self.state.iter_mut().zip(words.chunks_exact(2)) {
+            *lane = (halves[0] as u64) | ((halves[1] as u64) << 32);
+        }
+
+        Ok(load_ops)
+    }
+
+    fn memory_write(&self, memory: &mut impl MemoryProcessor) -> Result<StoreOps, MemoryError> {
+        let mut store_ops = StoreOps::default();
+
+        for (i, lane) in self.state.iter().enumerate() {
+            let address = self.state_ptr + (i * 8) as u32;
+
+            store_ops.insert(memory.write(address, MemAccessSize::Word, *lane as u32)?);
+            store_ops.insert(memory.write(
+                address + 4,
+                MemAccessSize::Word,
+                (*lane >> 32) as u32,
+            )?);
+        }
+
+        Ok(store_ops)
+    }
+
+    fn write_back(&self, cpu: &mut impl Processor) -> InstructionResult {
+        cpu.registers_mut().write(self.rd, 0);
+        Some(0)
+    }
+}
+
+impl InstructionExecutor for Keccak {
+    type InstructionState = Self;
+
+    fn decode(ins: &Instruction, registers: &impl Registers) -> Self {
+        Self {
+            rd: ins.op_a,
+            state_ptr: registers[ins.op_b],
+            state: [0; STATE_LANES],
+        }
+    }
+}
+
+impl PrecompileInstruction for Keccak {
+    fn metadata() -> PrecompileMetadata {
+        PrecompileMetadata {
+            author: "The Nexus Team",
+            name: "Keccak",
+            description: "Keccak-f[1600] permutation over a state in guest memory",
+            version_major: 1,
+            version_minor: 0,
+            version_patch: 0,
+        }
+    }
+
+    fn circuit() -> impl PrecompileCircuit {
+        KeccakCircuit::new(0)
+    }
+
+    fn native_call(_rs1: u32, _rs2: u32) -> u32 {
+        // The state pointer cannot be dereferenced on the native host, use [`keccak_f`] directly instead.
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nexus_vm::{
+        cpu::Cpu,
+        memory::{VariableMemory, RW},
+        riscv::{InstructionType, Opcode},
+    };
+
+    const STATE_ADDRESS: u32 = 0x1000;
+
+    fn setup_memory(state: &[u64; STATE_LANES]) -> VariableMemory<RW> {
+        let mut memory = VariableMemory::<RW>::default();
+        for (i, lane) in state.iter().enumerate() {
+            let address = STATE_ADDRESS + (i * 8) as u32;
+            memory
+                .write(address, MemAccessSize::Word, *lane as u32)
+                .unwrap();
+            memory
+                .write(address + 4, MemAccessSize::Word, (*lane >> 32) as u32)
+                .unwrap();
+        }
+        memory
+    }
+
+    fn read_state(memory: &VariableMemory<RW>) -> [u64; STATE_LANES] {
+        let mut state = [0u64; STATE_LANES];
+        for (i, lane) in state.iter_mut().enumerate() {
+            let address = STATE_ADDRESS + (i * 8) as u32;
+            let LoadOp::Op(_, _, low) = memory.read(address, MemAccessSize::Word).unwrap();
+            let LoadOp::Op(_, _, high) = memory.read(address + 4, MemAccessSize::Word).unwrap();
+            *lane = (low as u64) | ((high as u64) << 32);
+        }
+        state
+    }
+
+    #[test]
+    fn test_keccak_permutation() {
+        let mut cpu = Cpu::default();
+        let initial: [u64; STATE_LANES] =
+            std::array::from_fn(|i| (i as u64) * 0x0101_0101_0101_0101);
+        let mut memory = setup_memory(&initial);
+
+        cpu.registers.write(Register::X1, STATE_ADDRESS);
+
+        let bare_instruction = Instruction::new(
+            Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "dynamic"),
+            Register::X2,
+            Register::X1,
+            0,
+            InstructionType::RType,
+        );
+        let (res, (load_ops, store_ops)) =
+            Keccak::evaluator(&mut cpu, &mut memory, &bare_instruction).unwrap();
+
+        let mut expected = initial;
+        keccak_f(&mut expected);
+
+        assert_eq!(res, Some(0));
+        assert_eq!(load_ops.len(), STATE_WORDS);
+        assert_eq!(store_ops.len(), STATE_WORDS);
+        assert_eq!(read_state(&memory), expected);
+    }
+
+    #[test]
+    fn test_keccak_f_zero_state() {
+        let mut state = [0u64; STATE_LANES];
+        keccak_f(&mut state);
+
+        assert_eq!(state[0], 0xF1258F7940E1DDE7);
+        assert_eq!(state[24], 0xEAF1FF7B5CECA249);
+    }
+}
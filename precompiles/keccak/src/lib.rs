@@ -0,0 +1,21 @@
+//! Keccak-f\[1600\] permutation precompile.
+//!
+//! The precompile permutes a 200-byte Keccak state in place. The state is laid out in guest memory as 25 little-endian
+//! 64-bit lanes (`[u64; 25]`), and its address must be word-aligned.
+//!
+//! Calls are proven by adding [`KeccakCircuit`] as an extension of the prover, see [`circuit`].
+#![cfg_attr(target_arch = "riscv32", no_std)]
+
+#[cfg(target_arch = "riscv32")]
+pub mod guest;
+#[cfg(target_arch = "riscv32")]
+pub use guest::*;
+
+#[cfg(not(target_arch = "riscv32"))]
+pub mod circuit;
+#[cfg(not(target_arch = "riscv32"))]
+pub mod host;
+#[cfg(not(target_arch = "riscv32"))]
+pub use circuit::KeccakCircuit;
+#[cfg(not(target_arch = "riscv32"))]
+pub use host::*;
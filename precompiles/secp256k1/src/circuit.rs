@@ -0,0 +1,806 @@
+//! Circuit proving calls of the secp256k1 precompile.
+//!
+//! A call takes a single row, which consumes the call from [`PrecompileLookupElements`] and reads and writes the
+//! buffer in RAM. Field arithmetic is checked modulo p with gadgets from
+//! [`bigint`](nexus_vm_prover::extensions::bigint) over the bytes of the buffer. A point addition is split into the
+//! cases of [`point_add`](crate::point_add), each selected by a flag: the points at infinity, opposite points, the
+//! tangent and the chord. The tangent at a point with `y ≡ 0` has a separate case, because the host uses a zero slope
+//! there.
+//!
+//! Results are always canonical, i.e. less than p, except for the raw operand copied when the other one is the point
+//! at infinity.
+
+use std::array;
+
+use nexus_precompiles::PrecompileCircuit;
+use nexus_vm_prover::{
+    components::AllLookupElements,
+    extensions::{
+        bigint::{assert_lt, assert_zero_mod, next_is_zero, next_uint, Poly},
+        circuit::{Row, RowCircuit, RowField},
+        LoadStoreLookupElements, PrecompileLookupElements,
+    },
+    hash::MachineChannel,
+    preflight::Support,
+    trace::sidenote::SideNote,
+};
+use num_bigint::BigUint;
+use num_traits::Zero;
+use stwo_prover::core::fields::m31::BaseField;
+
+use crate::{
+    field_inv, modulus, FIELD_WORDS, OP_FIELD_MUL, OP_POINT_ADD, OP_POINT_DOUBLE,
+    STATUS_INVALID_OP, STATUS_OK,
+};
+
+/// Number of bytes in a field element.
+const ELEMENT_BYTES: usize = 4 * FIELD_WORDS;
+/// Number of bytes in the largest buffer, i.e. the operands of a point addition.
+const BUFFER_BYTES: usize = 4 * ELEMENT_BYTES;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Operation {
+    /// Padding row.
+    #[default]
+    None,
+    FieldMul,
+    PointAdd,
+    PointDouble,
+    /// Unknown operation with a nonzero byte above the lowest one.
+    InvalidHigh,
+    /// Unknown operation below 256.
+    InvalidLow,
+}
+
+impl Operation {
+    fn new(op: u32) -> Self {
+        match op {
+            OP_FIELD_MUL => Self::FieldMul,
+            OP_POINT_ADD => Self::PointAdd,
+            OP_POINT_DOUBLE => Self::PointDouble,
+            _ if op >> 8 != 0 => Self::InvalidHigh,
+            _ => Self::InvalidLow,
+        }
+    }
+
+    /// Number of bytes read from the buffer.
+    fn buffer_bytes(self) -> usize {
+        match self {
+            Self::FieldMul | Self::PointDouble => 2 * ELEMENT_BYTES,
+            Self::PointAdd => 4 * ELEMENT_BYTES,
+            _ => 0,
+        }
+    }
+}
+
+/// Case of a point addition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    /// The left-hand side is the point at infinity, the result is the right-hand side.
+    LhsZero,
+    /// The right-hand side is the point at infinity, the result is the left-hand side.
+    RhsZero,
+    /// `x1 ≡ x2` and `y1 + y2 ≡ 0`, the result is the point at infinity.
+    Opposite,
+    /// `x1 ≡ x2` and `y1 + y2 ≢ 0`, the slope is `3・x1^2 / 2・y1`.
+    Tangent,
+    /// `x1 ≡ x2`, `y1 + y2 ≢ 0` and `y1 ≡ 0`, the slope is zero.
+    ZeroTangent,
+    /// `x1 ≢ x2`, the slope is `(y2 - y1) / (x2 - x1)`.
+    Chord,
+}
+
+const CASES: [Case; 6] = [
+    Case::LhsZero,
+    Case::RhsZero,
+    Case::Opposite,
+    Case::Tangent,
+    Case::ZeroTangent,
+    Case::Chord,
+];
+
+/// A byte of the buffer accessed in RAM.
+#[derive(Debug, Clone, Copy, Default)]
+struct BufferByte {
+    prev_value: u8,
+    prev_timestamp: u32,
+    value: u8,
+}
+
+/// Witness of a row, i.e. of a single call.
+#[derive(Debug, Clone, Default)]
+pub struct Secp256k1Row {
+    operation: Operation,
+    clk: u32,
+    buffer_ptr: u32,
+    op: u32,
+    /// Bytes of the buffer, as many as read by the operation.
+    bytes: Vec<BufferByte>,
+}
+
+/// Values of a row derived from the operands.
+#[derive(Debug, Default)]
+struct PointWitness {
+    case: Option<Case>,
+    slope: BigUint,
+    /// Inverse of `x2 - x1` for the chord, and of `y1 + y2` for tangents.
+    inverse: BigUint,
+    /// Inverse of `2・y1` for the tangent.
+    tangent_inverse: BigUint,
+}
+
+impl PointWitness {
+    fn new(x1: &BigUint, y1: &BigUint, x2: &BigUint, y2: &BigUint) -> Self {
+        let p = modulus();
+        let sub = |a: &BigUint, b: &BigUint| ((a % &p) + &p - (b % &p)) % &p;
+        let case = if x1.is_zero() && y1.is_zero() {
+            Case::LhsZero
+        } else if x2.is_zero() && y2.is_zero() {
+            Case::RhsZero
+        } else if x1 % &p == x2 % &p {
+            if (y1 + y2) % &p == BigUint::zero() {
+                Case::Opposite
+            } else if y1 % &p == BigUint::zero() {
+                Case::ZeroTangent
+            } else {
+                Case::Tangent
+            }
+        } else {
+            Case::Chord
+        };
+
+        let mut witness = Self {
+            case: Some(case),
+            ..Self::default()
+        };
+        match case {
+            Case::Tangent => {
+                witness.inverse = field_inv(&(y1 + y2));
+                witness.tangent_inverse = field_inv(&(y1 << 1));
+                witness.slope =
+                    (BigUint::from(3u32) * x1 * x1 % &p) * &witness.tangent_inverse % &p;
+            }
+            Case::ZeroTangent => {
+                witness.inverse = field_inv(&(y1 + y2));
+            }
+            Case::Chord => {
+                witness.inverse = field_inv(&sub(x2, x1));
+                witness.slope = sub(y2, y1) * &witness.inverse % &p;
+            }
+            _ => {}
+        }
+        witness
+    }
+}
+
+/// Proves calls of [`Secp256k1`](crate::Secp256k1) with the given precompile index.
+///
+/// Every call takes a single row of the extension.
+#[derive(Debug, Clone)]
+pub struct Secp256k1Circuit {
+    index: u16,
+}
+
+impl Secp256k1Circuit {
+    /// The index is the position of the precompile in the guest's `use_precompiles!` invocation.
+    pub fn new(index: u16) -> Self {
+        Self { index }
+    }
+}
+
+impl PrecompileCircuit for Secp256k1Circuit {}
+
+impl RowCircuit for Secp256k1Circuit {
+    type Input = Secp256k1Row;
+
+    fn rows(&self, side_note: &SideNote) -> Vec<Secp256k1Row> {
+        side_note
+            .precompile_calls()
+            .iter()
+            .filter(|call| call.index == self.index)
+            .map(|call| {
+                let operation = Operation::new(call.value_c);
+                let num_bytes = operation.buffer_bytes();
+                assert_eq!(
+                    call.value_a,
+                    if num_bytes == 0 {
+                        STATUS_INVALID_OP
+                    } else {
+                        STATUS_OK
+                    },
+                    "secp256k1 status mismatch"
+                );
+                assert_eq!(
+                    call.memory.len(),
+                    num_bytes,
+                    "secp256k1 accesses the operands of the operation"
+                );
+                for (i, access) in call.memory.iter().enumerate() {
+                    assert_eq!(
+                        access.address,
+                        call.value_b.wrapping_add(i as u32),
+                        "secp256k1 accesses the buffer at rs1"
+                    );
+                }
+                Secp256k1Row {
+                    operation,
+                    clk: call.clk,
+                    buffer_ptr: call.value_b,
+                    op: call.value_c,
+                    bytes: call
+                        .memory
+                        .iter()
+                        .map(|access| BufferByte {
+                            prev_value: access.prev_value,
+                            prev_timestamp: access.prev_timestamp,
+                            value: access.value,
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    fn evaluate<R: Row>(
+        &self,
+        row: &mut R,
+        input: &Secp256k1Row,
+        lookup_elements: &AllLookupElements,
+    ) {
+        let one = constant::<R::F>(1);
+        let p = modulus();
+
+        let [is_mul, is_add, is_double, is_invalid_high, is_invalid_low] = [
+            Operation::FieldMul,
+            Operation::PointAdd,
+            Operation::PointDouble,
+            Operation::InvalidHigh,
+            Operation::InvalidLow,
+        ]
+        .map(|operation| next_bool(row, input.operation == operation));
+        let is_point = is_add.clone() + is_double.clone();
+        let is_valid = is_mul.clone() + is_point.clone();
+        let is_invalid = is_invalid_high.clone() + is_invalid_low.clone();
+        let is_real = is_valid.clone() + is_invalid.clone();
+        row.add_constraint(is_real.clone() * (is_real.clone() - one.clone()));
+
+        let clk = next_bytes(row, input.clk);
+        let buffer_ptr = next_bytes(row, input.buffer_ptr);
+        let op = next_bytes(row, input.op);
+
+        // Decode the operation, unknown ones either have a nonzero high byte or the lowest byte at least 3.
+        for byte in &op[1..] {
+            row.add_constraint(is_valid.clone() * byte.clone());
+        }
+        row.add_constraint(
+            is_valid.clone()
+                * (op[0].clone() - is_add.clone() - is_double.clone() * BaseField::from(2)),
+        );
+        let is_high_zero = next_is_zero(row, &op[1..]);
+        row.add_constraint(is_invalid_high.clone() * is_high_zero);
+        row.range_check_u8(
+            is_invalid_low.clone(),
+            op[0].clone() - constant(3),
+            lookup_elements,
+        );
+
+        let precompile: &PrecompileLookupElements = lookup_elements.as_ref();
+        let mut tuple = clk.to_vec();
+        tuple.push(constant(self.index as u32));
+        tuple.extend(buffer_ptr.iter().cloned());
+        tuple.extend(op.iter().cloned());
+        tuple.extend([is_invalid, constant(0), constant(0), constant(0)]);
+        row.add_to_relation(precompile, -is_real, &tuple);
+
+        // The buffer must be word-aligned, so that the bytes of a word only differ in the lowest address byte.
+        let alignment = row.next(BaseField::from((input.buffer_ptr & 0xFF) >> 2));
+        row.add_constraint(buffer_ptr[0].clone() - alignment.clone() * BaseField::from(4));
+        row.range_check_u8(is_valid.clone(), alignment, lookup_elements);
+
+        let bytes: [BufferByte; BUFFER_BYTES] =
+            array::from_fn(|i| input.bytes.get(i).copied().unwrap_or_default());
+        let prev_values: [R::F; BUFFER_BYTES] =
+            array::from_fn(|i| row.next(BaseField::from(bytes[i].prev_value as u32)));
+        let element = |k: usize| &prev_values[k * ELEMENT_BYTES..(k + 1) * ELEMENT_BYTES];
+        let element_value = |k: usize| {
+            BigUint::from_bytes_le(
+                &bytes[k * ELEMENT_BYTES..(k + 1) * ELEMENT_BYTES]
+                    .iter()
+                    .map(|byte| byte.prev_value)
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let (x1, y1) = (element(0).to_vec(), element(1).to_vec());
+
+        // The right-hand side of a point operation, the left-hand side itself for doubling.
+        let (x2_value, y2_value) = if input.operation == Operation::PointAdd {
+            (element_value(2), element_value(3))
+        } else {
+            (element_value(0), element_value(1))
+        };
+        let x2 = next_element(row, &x2_value);
+        let y2 = next_element(row, &y2_value);
+        for (rhs, (added, doubled)) in x2.iter().chain(&y2).zip(
+            element(2)
+                .iter()
+                .chain(element(3))
+                .zip(x1.iter().chain(&y1)),
+        ) {
+            row.add_constraint(is_add.clone() * (rhs.clone() - added.clone()));
+            row.add_constraint(is_double.clone() * (rhs.clone() - doubled.clone()));
+        }
+
+        let witness = match input.operation {
+            Operation::PointAdd | Operation::PointDouble => {
+                PointWitness::new(&element_value(0), &element_value(1), &x2_value, &y2_value)
+            }
+            _ => PointWitness::default(),
+        };
+        let [is_lhs_zero, is_rhs_zero, is_opposite, is_tangent, is_zero_tangent, is_chord] =
+            CASES.map(|case| next_bool(row, witness.case == Some(case)));
+        row.add_constraint(
+            is_lhs_zero.clone()
+                + is_rhs_zero.clone()
+                + is_opposite.clone()
+                + is_tangent.clone()
+                + is_zero_tangent.clone()
+                + is_chord.clone()
+                - is_point.clone(),
+        );
+        let lhs_zero = next_is_zero(row, &[x1.as_slice(), &y1].concat());
+        let rhs_zero = next_is_zero(row, &[x2.as_slice(), &y2].concat());
+        row.add_constraint(is_lhs_zero.clone() - is_point.clone() * lhs_zero.clone());
+        row.add_constraint(
+            is_rhs_zero.clone() - is_point.clone() * (one.clone() - lhs_zero) * rhs_zero,
+        );
+        let is_equal_x = is_opposite.clone() + is_tangent.clone() + is_zero_tangent.clone();
+        let is_sloped = is_tangent.clone() + is_zero_tangent.clone() + is_chord.clone();
+
+        // The result, written to the first one or two elements of the buffer.
+        let result_value = |k: usize| {
+            BigUint::from_bytes_le(
+                &bytes[k * ELEMENT_BYTES..(k + 1) * ELEMENT_BYTES]
+                    .iter()
+                    .map(|byte| byte.value)
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let x3 = next_uint(
+            row,
+            &result_value(0),
+            ELEMENT_BYTES,
+            &is_valid,
+            lookup_elements,
+        );
+        let y3_value = if input.operation == Operation::FieldMul {
+            BigUint::zero()
+        } else {
+            result_value(1)
+        };
+        let y3 = next_uint(row, &y3_value, ELEMENT_BYTES, &is_point, lookup_elements);
+
+        let slope = next_uint(
+            row,
+            &witness.slope,
+            ELEMENT_BYTES,
+            &is_sloped,
+            lookup_elements,
+        );
+        let inverse = next_uint(
+            row,
+            &witness.inverse,
+            ELEMENT_BYTES,
+            &is_sloped,
+            lookup_elements,
+        );
+        let tangent_inverse = next_uint(
+            row,
+            &witness.tangent_inverse,
+            ELEMENT_BYTES,
+            &is_tangent,
+            lookup_elements,
+        );
+
+        let poly = Poly::from_bytes;
+        let int = |value: u32| Poly::<R::F>::constant(&BigUint::from(value));
+
+        // a・b ≡ r
+        assert_zero_mod(
+            row,
+            poly(&x1) * poly(&y1) - poly(&x3),
+            &p,
+            &is_mul,
+            lookup_elements,
+        );
+        assert_zero_mod(row, poly(&x1) - poly(&x2), &p, &is_equal_x, lookup_elements);
+        assert_zero_mod(
+            row,
+            poly(&y1) + poly(&y2),
+            &p,
+            &is_opposite,
+            lookup_elements,
+        );
+        // (y1 + y2)・w ≡ 1, so that both tangent cases exclude opposite points.
+        assert_zero_mod(
+            row,
+            (poly(&y1) + poly(&y2)) * poly(&inverse) - int(1),
+            &p,
+            &(is_tangent.clone() + is_zero_tangent.clone()),
+            lookup_elements,
+        );
+        // 2・y1・w' ≡ 1 and 2・y1・λ ≡ 3・x1^2
+        assert_zero_mod(
+            row,
+            int(2) * poly(&y1) * poly(&tangent_inverse) - int(1),
+            &p,
+            &is_tangent,
+            lookup_elements,
+        );
+        assert_zero_mod(
+            row,
+            int(2) * poly(&y1) * poly(&slope) - int(3) * poly(&x1) * poly(&x1),
+            &p,
+            &is_tangent,
+            lookup_elements,
+        );
+        // y1 ≡ 0 and λ = 0
+        assert_zero_mod(row, poly(&y1), &p, &is_zero_tangent, lookup_elements);
+        for byte in &slope {
+            row.add_constraint(is_zero_tangent.clone() * byte.clone());
+        }
+        // (x2 - x1)・w ≡ 1 and (x2 - x1)・λ ≡ y2 - y1
+        assert_zero_mod(
+            row,
+            (poly(&x2) - poly(&x1)) * poly(&inverse) - int(1),
+            &p,
+            &is_chord,
+            lookup_elements,
+        );
+        assert_zero_mod(
+            row,
+            (poly(&x2) - poly(&x1)) * poly(&slope) - poly(&y2) + poly(&y1),
+            &p,
+            &is_chord,
+            lookup_elements,
+        );
+        // x3 ≡ λ^2 - x1 - x2 and y3 ≡ λ・(x1 - x3) - y1
+        assert_zero_mod(
+            row,
+            poly(&slope) * poly(&slope) - poly(&x1) - poly(&x2) - poly(&x3),
+            &p,
+            &is_sloped,
+            lookup_elements,
+        );
+        assert_zero_mod(
+            row,
+            poly(&slope) * (poly(&x1) - poly(&x3)) - poly(&y1) - poly(&y3),
+            &p,
+            &is_sloped,
+            lookup_elements,
+        );
+        assert_lt(
+            row,
+            &x3,
+            Poly::constant(&p),
+            &(is_mul.clone() + is_sloped.clone()),
+            lookup_elements,
+        );
+        assert_lt(row, &y3, Poly::constant(&p), &is_sloped, lookup_elements);
+
+        // Remaining cases copy an operand or return the point at infinity.
+        for (result, (lhs, rhs)) in x3
+            .iter()
+            .chain(&y3)
+            .zip(x1.iter().chain(&y1).zip(x2.iter().chain(&y2)))
+        {
+            row.add_constraint(is_lhs_zero.clone() * (result.clone() - rhs.clone()));
+            row.add_constraint(is_rhs_zero.clone() * (result.clone() - lhs.clone()));
+            row.add_constraint(is_opposite.clone() * result.clone());
+        }
+
+        // Read the operands and write the result.
+        let load_store: &LoadStoreLookupElements = lookup_elements.as_ref();
+        for (w, word_bytes) in bytes.chunks_exact(4).enumerate() {
+            // Words of the first two elements are accessed by all operations, the others only by additions.
+            let is_accessed = if w < 2 * FIELD_WORDS {
+                is_valid.clone()
+            } else {
+                is_add.clone()
+            };
+            let address = next_word_address(
+                row,
+                &is_accessed,
+                input.buffer_ptr,
+                &buffer_ptr,
+                4 * w as u32,
+                lookup_elements,
+            );
+
+            for (j, byte) in word_bytes.iter().enumerate() {
+                let i = 4 * w + j;
+                let prev_value = prev_values[i].clone();
+                // The first element is written by all operations, the second one by point operations.
+                let value = match i / ELEMENT_BYTES {
+                    0 | 1 => {
+                        let (is_written, result) = if i < ELEMENT_BYTES {
+                            (is_valid.clone(), x3[i].clone())
+                        } else {
+                            (is_point.clone(), y3[i - ELEMENT_BYTES].clone())
+                        };
+                        let value = row.next(BaseField::from(byte.value as u32));
+                        row.add_constraint(
+                            value.clone()
+                                - prev_value.clone()
+                                - is_written * (result - prev_value.clone()),
+                        );
+                        value
+                    }
+                    _ => prev_value.clone(),
+                };
+
+                // prev_timestamp < clk, i.e. clk - 1 - prev_timestamp fits into 32 bits.
+                let prev_timestamp = next_bytes(row, byte.prev_timestamp);
+                let aux = next_lt(
+                    row,
+                    &is_accessed,
+                    byte.prev_timestamp,
+                    input.clk,
+                    &prev_timestamp,
+                    &clk,
+                );
+                for byte in &aux {
+                    row.range_check_u8(is_accessed.clone(), byte.clone(), lookup_elements);
+                }
+
+                let address = [
+                    address[0].clone() + constant(j as u32),
+                    address[1].clone(),
+                    address[2].clone(),
+                    address[3].clone(),
+                ];
+                let mut tuple = address.to_vec();
+                tuple.push(prev_value);
+                tuple.extend(prev_timestamp);
+                row.add_to_relation(load_store, -is_accessed.clone(), &tuple);
+                let mut tuple = address.to_vec();
+                tuple.push(value);
+                tuple.extend(clk.iter().cloned());
+                row.add_to_relation(load_store, is_accessed.clone(), &tuple);
+            }
+        }
+    }
+
+    fn draw_lookup_elements(
+        &self,
+        _lookup_elements: &mut AllLookupElements,
+        _channel: &mut MachineChannel,
+    ) {
+    }
+
+    fn declare_support(&self, support: &mut Support) {
+        support.precompiles.insert(self.index);
+    }
+}
+
+fn constant<F: RowField>(value: u32) -> F {
+    F::from(BaseField::from(value))
+}
+
+fn next_bool<R: Row>(row: &mut R, value: bool) -> R::F {
+    let bit = row.next(BaseField::from(value as u32));
+    row.add_constraint(bit.clone() * (bit.clone() - constant(1)));
+    bit
+}
+
+/// Allocates bytes of a word, the caller is responsible for checking their range.
+fn next_bytes<R: Row>(row: &mut R, value: u32) -> [R::F; 4] {
+    value
+        .to_le_bytes()
+        .map(|byte| row.next(BaseField::from(byte as u32)))
+}
+
+/// Allocates bytes of a field element, the caller is responsible for checking their range.
+fn next_element<R: Row>(row: &mut R, value: &BigUint) -> Vec<R::F> {
+    let mut bytes = value.to_bytes_le();
+    bytes.resize(ELEMENT_BYTES, 0);
+    bytes
+        .into_iter()
+        .map(|byte| row.next(BaseField::from(byte as u32)))
+        .collect()
+}
+
+fn byte_limbs<F: RowField>(bytes: &[F; 4]) -> [F; 2] {
+    array::from_fn(|j| bytes[2 * j].clone() + bytes[2 * j + 1].clone() * BaseField::from(1 << 8))
+}
+
+/// Allocates the bytes of `ptr + offset` for a word-aligned `ptr` and a small multiple of four `offset`.
+fn next_word_address<R: Row>(
+    row: &mut R,
+    flag: &R::F,
+    ptr_value: u32,
+    ptr: &[R::F; 4],
+    offset: u32,
+    lookup_elements: &AllLookupElements,
+) -> [R::F; 4] {
+    let address = next_bytes(row, ptr_value.wrapping_add(offset));
+    for byte in &address {
+        row.range_check_u8(flag.clone(), byte.clone(), lookup_elements);
+    }
+    let carry = next_bool(row, (ptr_value & 0xFFFF) + offset > 0xFFFF);
+    let [ptr_lo, ptr_hi] = byte_limbs(ptr);
+    let [lo, hi] = byte_limbs(&address);
+    row.add_constraint(
+        flag.clone() * (ptr_lo + constant(offset) - lo - carry.clone() * BaseField::from(1 << 16)),
+    );
+    row.add_constraint(flag.clone() * (ptr_hi + carry - hi));
+    address
+}
+
+/// Constrains `lhs < rhs` when `flag` is set, returns the bytes of `rhs - 1 - lhs` which the caller must range check.
+fn next_lt<R: Row>(
+    row: &mut R,
+    flag: &R::F,
+    lhs_value: u32,
+    rhs_value: u32,
+    lhs: &[R::F; 4],
+    rhs: &[R::F; 4],
+) -> [R::F; 4] {
+    let aux_value = rhs_value.wrapping_sub(1).wrapping_sub(lhs_value);
+    let aux = next_bytes(row, aux_value);
+    let borrow = next_bool(
+        row,
+        (lhs_value & 0xFFFF) + 1 + (aux_value & 0xFFFF) > 0xFFFF,
+    );
+    let [lhs_lo, lhs_hi] = byte_limbs(lhs);
+    let [aux_lo, aux_hi] = byte_limbs(&aux);
+    let [rhs_lo, rhs_hi] = byte_limbs(rhs);
+    row.add_constraint(
+        flag.clone()
+            * (lhs_lo + constant::<R::F>(1) + aux_lo
+                - rhs_lo
+                - borrow.clone() * BaseField::from(1 << 16)),
+    );
+    row.add_constraint(flag.clone() * (lhs_hi + aux_hi + borrow - rhs_hi));
+    aux
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use nexus_vm::{
+        emulator::{Emulator, HarvardEmulator, InternalView},
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, InstructionType, Opcode, Register},
+        trace::k_trace_harvard,
+    };
+    use nexus_vm_prover::{
+        extensions::{circuit::CircuitExtension, ExtensionComponent},
+        machine::{BaseComponent, Machine},
+        Blake2sMerkleHasher, ProverConfig,
+    };
+
+    use crate::Secp256k1;
+
+    fn hex(value: &str) -> BigUint {
+        BigUint::parse_bytes(value.as_bytes(), 16).unwrap()
+    }
+
+    /// Stores the little-endian bytes of `elements` into the buffer, starting at element `first`.
+    fn store(instructions: &mut Vec<Instruction>, first: usize, elements: &[BigUint]) {
+        for (k, element) in elements.iter().enumerate() {
+            let mut bytes = element.to_bytes_le();
+            bytes.resize(ELEMENT_BYTES, 0);
+            for (i, byte) in bytes.into_iter().enumerate() {
+                instructions.push(Instruction::new_ir(
+                    Opcode::from(BuiltinOpcode::ADDI),
+                    6,
+                    0,
+                    byte as u32,
+                ));
+                instructions.push(Instruction::new_ir(
+                    Opcode::from(BuiltinOpcode::SB),
+                    10,
+                    6,
+                    ((first + k) * ELEMENT_BYTES + i) as u32,
+                ));
+            }
+        }
+    }
+
+    fn call(instructions: &mut Vec<Instruction>, opcode: &Opcode, op: u32) {
+        instructions.push(Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            11,
+            0,
+            op,
+        ));
+        instructions.push(Instruction::new(
+            opcode.clone(),
+            Register::X12,
+            Register::X10,
+            11,
+            InstructionType::RType,
+        ));
+    }
+
+    #[test]
+    fn test_prove_secp256k1() {
+        let opcode = Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "dynamic");
+        let p = modulus();
+        let (gx, gy) = (
+            hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"),
+            hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8"),
+        );
+        let (g2x, g2y) = (
+            hex("C6047F9441ED7D6D3045406E95C07CD85C778E4B8CEF3CA7ABAC09B95C709EE5"),
+            hex("1AE168FEA63DC339A3C58419466CEAEEF7F632653266D0E1236431A950CFE52A"),
+        );
+        let zero = BigUint::zero();
+
+        let mut instructions = vec![
+            // x10 = 0x8FFC0, so that the addresses of the buffer carry into the third byte.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0x7FF),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 0x100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 1, 1, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 1, 0xC0),
+        ];
+        // G + 2G, doubled, then x・y of the result.
+        store(
+            &mut instructions,
+            0,
+            &[gx.clone(), gy.clone(), g2x.clone(), g2y.clone()],
+        );
+        call(&mut instructions, &opcode, OP_POINT_ADD);
+        call(&mut instructions, &opcode, OP_POINT_DOUBLE);
+        call(&mut instructions, &opcode, OP_FIELD_MUL);
+        // O + 2G, 2G + O and 2G - 2G.
+        store(&mut instructions, 0, &[zero.clone(), zero.clone()]);
+        call(&mut instructions, &opcode, OP_POINT_ADD);
+        store(&mut instructions, 2, &[zero.clone(), zero.clone()]);
+        call(&mut instructions, &opcode, OP_POINT_ADD);
+        store(&mut instructions, 2, &[g2x.clone(), &p - &g2y]);
+        call(&mut instructions, &opcode, OP_POINT_ADD);
+        // Points with x1 ≡ x2 and a non-canonical y1 ≡ 0, the slope is zero.
+        store(&mut instructions, 0, &[BigUint::from(7u32), p.clone()]);
+        store(&mut instructions, 2, &[&p + 7u32, BigUint::from(5u32)]);
+        call(&mut instructions, &opcode, OP_POINT_ADD);
+        // Unknown operations.
+        call(&mut instructions, &opcode, 3);
+        call(&mut instructions, &opcode, 0x100);
+        let basic_blocks = vec![BasicBlock::new(instructions)];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator.add_opcode::<Secp256k1>(&opcode).unwrap();
+        let (view, program_trace) = k_trace_harvard(emulator, 1).expect("error generating trace");
+
+        let prove_verify = |extensions: &[ExtensionComponent]| {
+            let proof = Machine::<BaseComponent>::prove_with_extensions::<Blake2sMerkleHasher>(
+                extensions,
+                &program_trace,
+                &view,
+                &ProverConfig::default(),
+            )
+            .unwrap();
+            Machine::<BaseComponent>::verify_with_extensions(
+                extensions,
+                proof,
+                ProverConfig::default().security_bits(),
+                view.get_program_memory(),
+                view.view_memory_layout().as_ref(),
+                &[],
+                view.get_initial_memory(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+        prove_verify(&[ExtensionComponent::custom(Arc::new(CircuitExtension::new(
+            Secp256k1Circuit::new(0),
+            4,
+        )))])
+        .unwrap();
+
+        // Without the extension nothing consumes the calls added by the CPU.
+        assert!(prove_verify(&[]).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+//! secp256k1 field and curve arithmetic precompile.
+//!
+//! The first operand register points to a word-aligned buffer of field elements, each stored as 8 little-endian
+//! 32-bit words, and the second selects the operation:
+//!
+//! - [`OP_FIELD_MUL`]: `[a, b]`, `a` is overwritten with `a・b mod p`.
+//! - [`OP_POINT_ADD`]: `[x1, y1, x2, y2]`, `(x1, y1)` is overwritten with the sum of both points.
+//! - [`OP_POINT_DOUBLE`]: `[x, y]`, overwritten with the doubled point.
+//!
+//! Points are in affine coordinates, with `(0, 0)` encoding the point at infinity. The instruction returns
+//! [`STATUS_OK`], or [`STATUS_INVALID_OP`] without accessing memory if the operation is unknown.
+//!
+//! `use_precompiles!` generates a `secp256k1` module with a `FieldElement` type implementing [`ff::Field`] and an
+//! `AffinePoint` type implementing [`group::Group`] over [`k256::Scalar`], backed by the instruction in the guest and
+//! by [`buffer_call`](host::buffer_call) on the native host.
+//!
+//! Calls are proven by adding [`Secp256k1Circuit`] as an extension of the prover, see [`circuit`].
+#![cfg_attr(target_arch = "riscv32", no_std)]
+
+pub use k256;
+pub use nexus_precompiles::{self, ff, group};
+
+mod types;
+
+#[cfg(target_arch = "riscv32")]
+pub mod guest;
+#[cfg(target_arch = "riscv32")]
+pub use guest::*;
+
+#[cfg(not(target_arch = "riscv32"))]
+pub mod circuit;
+#[cfg(not(target_arch = "riscv32"))]
+pub mod host;
+#[cfg(not(target_arch = "riscv32"))]
+pub use circuit::Secp256k1Circuit;
+#[cfg(not(target_arch = "riscv32"))]
+pub use host::*;
+
+pub const OP_FIELD_MUL: u32 = 0;
+pub const OP_POINT_ADD: u32 = 1;
+pub const OP_POINT_DOUBLE: u32 = 2;
+
+pub const STATUS_OK: u32 = 0;
+pub const STATUS_INVALID_OP: u32 = 1;
+
+/// Number of 32-bit words in a field element.
+pub const FIELD_WORDS: usize = 8;
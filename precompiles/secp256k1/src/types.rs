@@ -0,0 +1,225 @@
+/// Defines the field and curve types on top of the `Secp256k1Caller` trait implemented for `$path`, shared by the
+/// guest and the native host.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! generate_types {
+    ($path:path) => {
+        /// Field and curve types backed by the secp256k1 precompile.
+        ///
+        /// [`FieldElement`] implements `ff::Field` and [`AffinePoint`] implements `group::Group` with the scalars of
+        /// `k256`, so that both can be used by code generic over these traits. Points are exchanged with `k256`
+        /// through uncompressed SEC1 encodings, see [`AffinePoint::from_uncompressed_sec1`].
+        ///
+        /// The operations panic if the precompile fails, which only happens if the instruction is not executed by
+        /// the secp256k1 precompile.
+        pub mod secp256k1 {
+            use super::Secp256k1Caller;
+            use $crate::nexus_precompiles::{ff::PrimeField, field, subtle::ConstantTimeEq};
+
+            pub use $crate::k256::Scalar;
+
+            const FIELD_WORDS: usize = $crate::FIELD_WORDS;
+
+            /// The field modulus, p = 2^256 - 2^32 - 977.
+            const MODULUS: [u32; FIELD_WORDS] = [
+                0xfffffc2f, 0xfffffffe, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff,
+                0xffffffff,
+            ];
+            /// p - 2, inverses are computed as a^(p - 2).
+            const INVERSE_EXPONENT: [u32; FIELD_WORDS] = [
+                0xfffffc2d, 0xfffffffe, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff,
+                0xffffffff,
+            ];
+            /// (p + 1) / 4, square roots are computed as a^((p + 1) / 4) since p = 3 mod 4.
+            const SQRT_EXPONENT: [u32; FIELD_WORDS] = [
+                0xbfffff0c, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff,
+                0x3fffffff,
+            ];
+
+            /// Runs operation `op` on the operand buffer in place, panicking if the precompile fails.
+            fn call(op: u32, buffer: &mut [u32]) {
+                let status = <$path as Secp256k1Caller>::secp256k1_call(op, buffer);
+                assert_eq!(status, $crate::STATUS_OK, "secp256k1 precompile failed");
+            }
+
+            /// An element of the base field, stored as reduced little-endian words.
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+            pub struct FieldElement(pub [u32; FIELD_WORDS]);
+
+            impl FieldElement {
+                pub const ZERO: Self = Self([0; FIELD_WORDS]);
+                pub const ONE: Self = Self([1, 0, 0, 0, 0, 0, 0, 0]);
+
+                /// Decodes a big-endian encoded field element, as in SEC1.
+                ///
+                /// The encoded integer must be below the modulus, which is not checked.
+                pub fn from_be_bytes(bytes: &[u8; 32]) -> Self {
+                    let mut words = [0u32; FIELD_WORDS];
+                    for (word, chunk) in words.iter_mut().zip(bytes.rchunks_exact(4)) {
+                        *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                    }
+                    Self(words)
+                }
+
+                /// Encodes the field element as big-endian bytes, as in SEC1.
+                pub fn to_be_bytes(self) -> [u8; 32] {
+                    let mut bytes = [0u8; 32];
+                    for (chunk, word) in bytes.rchunks_exact_mut(4).zip(self.0.iter()) {
+                        chunk.copy_from_slice(&word.to_be_bytes());
+                    }
+                    bytes
+                }
+            }
+
+            fn mul_words(a: &[u32; FIELD_WORDS], b: &[u32; FIELD_WORDS]) -> [u32; FIELD_WORDS] {
+                let mut buffer = [0u32; 2 * FIELD_WORDS];
+                buffer[..FIELD_WORDS].copy_from_slice(a);
+                buffer[FIELD_WORDS..].copy_from_slice(b);
+                call($crate::OP_FIELD_MUL, &mut buffer);
+
+                let mut words = [0u32; FIELD_WORDS];
+                words.copy_from_slice(&buffer[..FIELD_WORDS]);
+                words
+            }
+
+            /// Returns whether `a` is a square and, if so, its root a^((p + 1) / 4).
+            fn sqrt(a: &FieldElement) -> ($crate::nexus_precompiles::subtle::Choice, FieldElement) {
+                let root = FieldElement(field::pow(
+                    &a.0,
+                    &SQRT_EXPONENT,
+                    &FieldElement::ONE.0,
+                    mul_words,
+                ));
+                ((root * root).ct_eq(a), root)
+            }
+
+            // -1 is a non-square since p = 3 mod 4.
+            $crate::nexus_precompiles::impl_field!(
+                FieldElement,
+                modulus: MODULUS,
+                inverse_exponent: INVERSE_EXPONENT,
+                non_square: -FieldElement::ONE,
+                mul: mul_words,
+                sqrt: sqrt,
+            );
+
+            /// A curve point in affine coordinates, `(0, 0)` is the point at infinity.
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+            pub struct AffinePoint {
+                pub x: FieldElement,
+                pub y: FieldElement,
+            }
+
+            impl AffinePoint {
+                pub const IDENTITY: Self = Self {
+                    x: FieldElement::ZERO,
+                    y: FieldElement::ZERO,
+                };
+
+                /// The generator of the group, as specified in SEC 2.
+                pub const GENERATOR: Self = Self {
+                    x: FieldElement([
+                        0x16f81798, 0x59f2815b, 0x2dce28d9, 0x029bfcdb, 0xce870b07, 0x55a06295,
+                        0xf9dcbbac, 0x79be667e,
+                    ]),
+                    y: FieldElement([
+                        0xfb10d4b8, 0x9c47d08f, 0xa6855419, 0xfd17b448, 0x0e1108a8, 0x5da4fbfc,
+                        0x26a3c465, 0x483ada77,
+                    ]),
+                };
+
+                pub fn is_identity(&self) -> bool {
+                    *self == Self::IDENTITY
+                }
+
+                /// Decodes an uncompressed SEC1 point `0x04 || x || y`, as encoded by `k256::EncodedPoint`.
+                ///
+                /// Returns `None` for other tags. The point is not checked to be on the curve.
+                pub fn from_uncompressed_sec1(bytes: &[u8; 65]) -> Option<Self> {
+                    if bytes[0] != 0x04 {
+                        return None;
+                    }
+                    let mut x = [0u8; 32];
+                    let mut y = [0u8; 32];
+                    x.copy_from_slice(&bytes[1..33]);
+                    y.copy_from_slice(&bytes[33..]);
+                    Some(Self {
+                        x: FieldElement::from_be_bytes(&x),
+                        y: FieldElement::from_be_bytes(&y),
+                    })
+                }
+
+                /// Encodes the point as an uncompressed SEC1 point `0x04 || x || y`.
+                ///
+                /// Returns `None` for the identity, which SEC1 encodes as the single byte `0x00`.
+                pub fn to_uncompressed_sec1(self) -> Option<[u8; 65]> {
+                    if self.is_identity() {
+                        return None;
+                    }
+                    let mut bytes = [0u8; 65];
+                    bytes[0] = 0x04;
+                    bytes[1..33].copy_from_slice(&self.x.to_be_bytes());
+                    bytes[33..].copy_from_slice(&self.y.to_be_bytes());
+                    Some(bytes)
+                }
+
+                pub fn double(&self) -> Self {
+                    let mut buffer = self.to_words();
+                    call($crate::OP_POINT_DOUBLE, &mut buffer);
+                    Self::from_words(&buffer)
+                }
+
+                fn to_words(self) -> [u32; 2 * FIELD_WORDS] {
+                    let mut words = [0u32; 2 * FIELD_WORDS];
+                    words[..FIELD_WORDS].copy_from_slice(&self.x.0);
+                    words[FIELD_WORDS..].copy_from_slice(&self.y.0);
+                    words
+                }
+
+                fn from_words(words: &[u32]) -> Self {
+                    let mut x = [0u32; FIELD_WORDS];
+                    let mut y = [0u32; FIELD_WORDS];
+                    x.copy_from_slice(&words[..FIELD_WORDS]);
+                    y.copy_from_slice(&words[FIELD_WORDS..2 * FIELD_WORDS]);
+                    Self {
+                        x: FieldElement(x),
+                        y: FieldElement(y),
+                    }
+                }
+            }
+
+            impl core::ops::Neg for AffinePoint {
+                type Output = Self;
+
+                fn neg(self) -> Self {
+                    // The identity is its own negation, as -0 = 0.
+                    Self {
+                        x: self.x,
+                        y: -self.y,
+                    }
+                }
+            }
+
+            fn add_points(lhs: &AffinePoint, rhs: &AffinePoint) -> AffinePoint {
+                let mut buffer = [0u32; 4 * FIELD_WORDS];
+                buffer[..2 * FIELD_WORDS].copy_from_slice(&lhs.to_words());
+                buffer[2 * FIELD_WORDS..].copy_from_slice(&rhs.to_words());
+                call($crate::OP_POINT_ADD, &mut buffer);
+                AffinePoint::from_words(&buffer)
+            }
+
+            fn scalar_be_bytes(scalar: &Scalar) -> [u8; 32] {
+                scalar.to_repr().into()
+            }
+
+            $crate::nexus_precompiles::impl_group!(
+                AffinePoint,
+                scalar: Scalar,
+                identity: AffinePoint::IDENTITY,
+                generator: AffinePoint::GENERATOR,
+                add: add_points,
+                scalar_be_bytes: scalar_be_bytes,
+            );
+        }
+    };
+}
@@ -0,0 +1,877 @@
+//! Circuit proving calls of the SHA-256 compression precompile.
+//!
+//! A call takes [`ROWS_PER_CALL`] rows, one per round. Every row holds the working variables before its round, the
+//! 16 message words of the schedule starting with the one used by the round, and the state the call started with.
+//! Consecutive rounds are linked with [`Sha256ChainElements`], the last round adds the working variables to the
+//! initial state.
+//!
+//! Independently, each of the first 24 rows of a call accesses one word in RAM, a word of the block or of the state.
+//! The words are handed over to the first and the last round with [`Sha256WordElements`]. The first round also
+//! consumes the call itself from [`PrecompileLookupElements`]. The state and the block must not overlap.
+
+use std::{array, collections::BTreeMap};
+
+use nexus_precompiles::PrecompileCircuit;
+use nexus_vm_prover::{
+    components::AllLookupElements,
+    extensions::{
+        circuit::{Row, RowCircuit, RowField},
+        LoadStoreLookupElements, PrecompileLookupElements,
+    },
+    hash::MachineChannel,
+    preflight::Support,
+    trace::sidenote::SideNote,
+};
+use stwo_prover::core::fields::m31::BaseField;
+
+use crate::sha256_compress;
+
+/// Number of rows taken by a single call, one per round.
+pub const ROWS_PER_CALL: usize = ROUNDS;
+
+const ROUNDS: usize = 64;
+const WORD_BITS: usize = 32;
+const STATE_WORDS: usize = 8;
+const BLOCK_WORDS: usize = 16;
+
+/// Number of words accessed in RAM, the block is read and the state is read and written back.
+const ACCESSED_WORDS: usize = BLOCK_WORDS + STATE_WORDS;
+
+/// Tags of words handed over with [`Sha256WordElements`]: block words, state words read by the call and state
+/// words written back. A word accessed with tag `i` is written back with tag `ACCESSED_WORDS + i`.
+const BLOCK_TAG: u32 = 0;
+const STATE_TAG: u32 = BLOCK_WORDS as u32;
+const OUTPUT_TAG: u32 = (ACCESSED_WORDS + BLOCK_WORDS) as u32;
+
+const K: [u32; ROUNDS] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// The tuple consists of clk, the index of the next round, the working variables, the message schedule and the
+/// initial state, all words in 16-bit limbs.
+const CHAIN_TUPLE_SIZE: usize = 4 + 1 + 2 * (STATE_WORDS + BLOCK_WORDS + STATE_WORDS);
+stwo_prover::relation!(Sha256ChainElements, CHAIN_TUPLE_SIZE);
+
+/// The tuple consists of clk, a tag, the address of the word in two 16-bit limbs and the word in 16-bit limbs.
+///
+/// See [`BLOCK_TAG`], [`STATE_TAG`] and [`OUTPUT_TAG`], the address is zero for state words written back.
+const WORD_TUPLE_SIZE: usize = 4 + 1 + 2 + 2;
+stwo_prover::relation!(Sha256WordElements, WORD_TUPLE_SIZE);
+
+fn big_sigma0(x: u32) -> u32 {
+    x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22)
+}
+
+fn big_sigma1(x: u32) -> u32 {
+    x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25)
+}
+
+fn small_sigma0(x: u32) -> u32 {
+    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+}
+
+fn small_sigma1(x: u32) -> u32 {
+    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+}
+
+fn ch(e: u32, f: u32, g: u32) -> u32 {
+    (e & f) ^ (!e & g)
+}
+
+fn maj(a: u32, b: u32, c: u32) -> u32 {
+    (a & b) ^ (a & c) ^ (b & c)
+}
+
+/// A round computed natively, returns the working variables after the round.
+fn round(v: &[u32; STATE_WORDS], k: u32, w: u32) -> [u32; STATE_WORDS] {
+    let [a, b, c, d, e, f, g, h] = *v;
+    let t1 = h
+        .wrapping_add(big_sigma1(e))
+        .wrapping_add(ch(e, f, g))
+        .wrapping_add(k)
+        .wrapping_add(w);
+    let t2 = big_sigma0(a).wrapping_add(maj(a, b, c));
+    [t1.wrapping_add(t2), a, b, c, d.wrapping_add(t1), e, f, g]
+}
+
+/// The next word of the message schedule computed natively.
+fn next_message_word(w: &[u32; BLOCK_WORDS]) -> u32 {
+    small_sigma1(w[14])
+        .wrapping_add(w[9])
+        .wrapping_add(small_sigma0(w[1]))
+        .wrapping_add(w[0])
+}
+
+/// A byte of a word accessed in RAM.
+#[derive(Debug, Clone, Copy, Default)]
+struct WordByte {
+    prev_value: u8,
+    prev_timestamp: u32,
+    value: u8,
+}
+
+/// A word of the block or the state accessed in RAM.
+#[derive(Debug, Clone, Default)]
+struct WordAccess {
+    tag: u32,
+    address: u32,
+    bytes: [WordByte; 4],
+}
+
+/// Witness of a row, i.e. of a single round and of at most one word accessed in RAM.
+#[derive(Debug, Clone, Default)]
+pub struct Sha256Row {
+    is_real: bool,
+    round: usize,
+    clk: u32,
+    state_ptr: u32,
+    block_ptr: u32,
+    /// The state before the call.
+    initial: [u32; STATE_WORDS],
+    /// The working variables before the round.
+    v: [u32; STATE_WORDS],
+    /// The message schedule, starting with the word used by the round.
+    w: [u32; BLOCK_WORDS],
+    access: Option<WordAccess>,
+}
+
+/// Proves calls of [`Sha256Compress`](crate::Sha256Compress) with the given precompile index.
+///
+/// Every call takes [`ROWS_PER_CALL`] rows of the extension.
+#[derive(Debug, Clone)]
+pub struct Sha256CompressCircuit {
+    index: u16,
+}
+
+impl Sha256CompressCircuit {
+    /// The index is the position of the precompile in the guest's `use_precompiles!` invocation.
+    pub fn new(index: u16) -> Self {
+        Self { index }
+    }
+}
+
+impl PrecompileCircuit for Sha256CompressCircuit {}
+
+impl RowCircuit for Sha256CompressCircuit {
+    type Input = Sha256Row;
+
+    fn rows(&self, side_note: &SideNote) -> Vec<Sha256Row> {
+        let mut rows = Vec::new();
+        for call in side_note
+            .precompile_calls()
+            .iter()
+            .filter(|call| call.index == self.index)
+        {
+            let (state_ptr, block_ptr) = (call.value_b, call.value_c);
+            assert!(
+                state_ptr % 4 == 0 && block_ptr % 4 == 0,
+                "sha256 state and block must be word-aligned"
+            );
+            assert_eq!(
+                call.memory.len(),
+                4 * ACCESSED_WORDS,
+                "sha256 state and block must not overlap"
+            );
+            assert_eq!(call.value_a, 0, "sha256 compression returns zero");
+
+            let memory: BTreeMap<u32, _> = call
+                .memory
+                .iter()
+                .map(|access| (access.address, access))
+                .collect();
+            let accesses: Vec<WordAccess> = (0..ACCESSED_WORDS)
+                .map(|i| {
+                    let address = if i < BLOCK_WORDS {
+                        block_ptr.wrapping_add(4 * i as u32)
+                    } else {
+                        state_ptr.wrapping_add(4 * (i - BLOCK_WORDS) as u32)
+                    };
+                    WordAccess {
+                        tag: i as u32,
+                        address,
+                        bytes: array::from_fn(|j| {
+                            let access = memory
+                                .get(&address.wrapping_add(j as u32))
+                                .expect("sha256 accesses the state at rs1 and the block at rs2");
+                            WordByte {
+                                prev_value: access.prev_value,
+                                prev_timestamp: access.prev_timestamp,
+                                value: access.value,
+                            }
+                        }),
+                    }
+                })
+                .collect();
+            let word = |access: &WordAccess, value: fn(&WordByte) -> u8| {
+                u32::from_le_bytes(access.bytes.map(|byte| value(&byte)))
+            };
+
+            let block: [u32; BLOCK_WORDS] =
+                array::from_fn(|i| word(&accesses[i], |byte| byte.prev_value));
+            let initial: [u32; STATE_WORDS] =
+                array::from_fn(|i| word(&accesses[BLOCK_WORDS + i], |byte| byte.prev_value));
+            for access in &accesses[..BLOCK_WORDS] {
+                assert_eq!(
+                    word(access, |byte| byte.value),
+                    word(access, |byte| byte.prev_value),
+                    "sha256 compression doesn't write the block"
+                );
+            }
+            let mut expected = initial;
+            sha256_compress(&mut expected, &block);
+            for (i, expected) in expected.iter().enumerate() {
+                assert_eq!(
+                    word(&accesses[BLOCK_WORDS + i], |byte| byte.value),
+                    *expected,
+                    "sha256 compression result mismatch"
+                );
+            }
+
+            let (mut v, mut w) = (initial, block);
+            for (t, k) in K.into_iter().enumerate() {
+                rows.push(Sha256Row {
+                    is_real: true,
+                    round: t,
+                    clk: call.clk,
+                    state_ptr,
+                    block_ptr,
+                    initial,
+                    v,
+                    w,
+                    access: accesses.get(t).cloned(),
+                });
+                v = round(&v, k, w[0]);
+                let next = next_message_word(&w);
+                w.rotate_left(1);
+                w[BLOCK_WORDS - 1] = next;
+            }
+        }
+        rows
+    }
+
+    fn evaluate<R: Row>(
+        &self,
+        row: &mut R,
+        input: &Sha256Row,
+        lookup_elements: &AllLookupElements,
+    ) {
+        let one = constant::<R::F>(1);
+
+        let rounds: [R::F; ROUNDS] =
+            array::from_fn(|t| next_bool(row, input.is_real && input.round == t));
+        let is_real = rounds
+            .iter()
+            .fold(constant::<R::F>(0), |acc, flag| acc + flag.clone());
+        row.add_constraint(is_real.clone() * (one.clone() - is_real.clone()));
+        let (is_first, is_last) = (rounds[0].clone(), rounds[ROUNDS - 1].clone());
+
+        let clk = next_bytes(row, input.clk);
+        let state_ptr = next_bytes(row, input.state_ptr);
+        let block_ptr = next_bytes(row, input.block_ptr);
+
+        // Both pointers must be word-aligned, so that the bytes of a word only differ in the lowest address byte.
+        for (ptr, value) in [(&state_ptr, input.state_ptr), (&block_ptr, input.block_ptr)] {
+            let alignment = row.next(BaseField::from((value & 0xFF) >> 2));
+            row.range_check_u8(is_first.clone(), alignment.clone(), lookup_elements);
+            row.add_constraint(ptr[0].clone() - alignment * BaseField::from(4));
+        }
+
+        self.evaluate_access(row, input, lookup_elements, &is_real, &clk);
+
+        // The working variables, only those used by the boolean functions are allocated as bits.
+        let [a, b, c, _, e, f, g, _] = input.v;
+        let v: [Word<R::F>; STATE_WORDS] = array::from_fn(|i| match i {
+            3 | 7 => Word::Limbs(next_limbs(row, input.v[i])),
+            _ => Word::Bits(next_bits(row, input.v[i])),
+        });
+        let initial: [[R::F; 2]; STATE_WORDS] =
+            array::from_fn(|i| next_limbs(row, input.initial[i]));
+        for (initial, v) in initial.iter().zip(&v) {
+            for (initial, v) in initial.iter().zip(v.limbs()) {
+                row.add_constraint(is_first.clone() * (initial.clone() - v));
+            }
+        }
+
+        // The message schedule, the words used by the small sigma functions are allocated as bits.
+        let w: [Word<R::F>; BLOCK_WORDS] = array::from_fn(|i| match i {
+            1 | 14 => Word::Bits(next_bits(row, input.w[i])),
+            _ => Word::Limbs(next_limbs(row, input.w[i])),
+        });
+
+        let sigma1 = next_xor3(
+            row,
+            big_sigma1(e),
+            [
+                rotr(v[4].bits(), 6),
+                rotr(v[4].bits(), 11),
+                rotr(v[4].bits(), 25),
+            ],
+        );
+        let sigma0 = next_xor3(
+            row,
+            big_sigma0(a),
+            [
+                rotr(v[0].bits(), 2),
+                rotr(v[0].bits(), 13),
+                rotr(v[0].bits(), 22),
+            ],
+        );
+        let ch_value = ch(e, f, g);
+        let ch: Bits<R::F> = array::from_fn(|i| {
+            let (e, f, g) = (&v[4].bits()[i], &v[5].bits()[i], &v[6].bits()[i]);
+            // The two halves never overlap, so their xor is a sum.
+            let expected = e.clone() * f.clone() + (one.clone() - e.clone()) * g.clone();
+            let bit = row.next(BaseField::from((ch_value >> i) & 1));
+            row.add_constraint(bit.clone() - expected);
+            bit
+        });
+        let maj_value = maj(a, b, c);
+        let maj: Bits<R::F> = array::from_fn(|i| {
+            let (a, b, c) = (&v[0].bits()[i], &v[1].bits()[i], &v[2].bits()[i]);
+            let expected = a.clone() * b.clone() + a.clone() * c.clone() + b.clone() * c.clone()
+                - a.clone() * b.clone() * c.clone() * BaseField::from(2);
+            let bit = row.next(BaseField::from((maj_value >> i) & 1));
+            row.add_constraint(bit.clone() - expected);
+            bit
+        });
+
+        // The round constant is selected by the round flags.
+        let k: [R::F; 2] = array::from_fn(|j| {
+            rounds
+                .iter()
+                .zip(K)
+                .fold(constant::<R::F>(0), |acc, (flag, k)| {
+                    acc + flag.clone() * BaseField::from((k >> (16 * j)) & 0xFFFF)
+                })
+        });
+
+        // T1 = h + Σ1(e) + Ch(e, f, g) + K + W, a = T1 + Σ0(a) + Maj(a, b, c) and e = d + T1.
+        let t1 = [
+            v[7].limbs(),
+            limbs(&sigma1),
+            limbs(&ch),
+            k.clone(),
+            w[0].limbs(),
+        ];
+        let k_value = if input.is_real { K[input.round] } else { 0 };
+        let t1_value = input.v[7]
+            .wrapping_add(big_sigma1(e))
+            .wrapping_add(ch_value)
+            .wrapping_add(k_value)
+            .wrapping_add(input.w[0]);
+        let mut a_operands = t1.to_vec();
+        a_operands.extend([limbs(&sigma0), limbs(&maj)]);
+        let new_a = next_add(
+            row,
+            &a_operands,
+            t1_value.wrapping_add(big_sigma0(a)).wrapping_add(maj_value),
+            &is_real,
+            lookup_elements,
+        );
+        let mut e_operands = t1.to_vec();
+        e_operands.push(v[3].limbs());
+        let new_e = next_add(
+            row,
+            &e_operands,
+            input.v[3].wrapping_add(t1_value),
+            &is_real,
+            lookup_elements,
+        );
+
+        // The next word of the message schedule, σ1(w[14]) + w[9] + σ0(w[1]) + w[0].
+        let w1 = w[1].bits();
+        let small0 = next_xor3(
+            row,
+            small_sigma0(input.w[1]),
+            [rotr(w1, 7), rotr(w1, 18), shr(w1, 3)],
+        );
+        let w14 = w[14].bits();
+        let small1 = next_xor3(
+            row,
+            small_sigma1(input.w[14]),
+            [rotr(w14, 17), rotr(w14, 19), shr(w14, 10)],
+        );
+        let new_w = next_add(
+            row,
+            &[limbs(&small1), w[9].limbs(), limbs(&small0), w[0].limbs()],
+            next_message_word(&input.w),
+            &is_real,
+            lookup_elements,
+        );
+
+        let out: [[R::F; 2]; STATE_WORDS] = [
+            limbs(&new_a),
+            v[0].limbs(),
+            v[1].limbs(),
+            v[2].limbs(),
+            limbs(&new_e),
+            v[4].limbs(),
+            v[5].limbs(),
+            v[6].limbs(),
+        ];
+        let mut schedule: Vec<[R::F; 2]> = w[1..].iter().map(Word::limbs).collect();
+        schedule.push(limbs(&new_w));
+
+        // Link rounds of the same call.
+        let round_index = rounds
+            .iter()
+            .enumerate()
+            .fold(constant::<R::F>(0), |acc, (t, flag)| {
+                acc + flag.clone() * BaseField::from(t as u32)
+            });
+        let chain: &Sha256ChainElements = lookup_elements.get_custom();
+        let mut tuple = clk.to_vec();
+        tuple.push(round_index.clone());
+        tuple.extend(v.iter().flat_map(Word::limbs));
+        tuple.extend(w.iter().flat_map(Word::limbs));
+        tuple.extend(initial.iter().flatten().cloned());
+        row.add_to_relation(chain, is_first.clone() - is_real.clone(), &tuple);
+        let mut tuple = clk.to_vec();
+        tuple.push(round_index + one.clone());
+        tuple.extend(out.iter().flatten().cloned());
+        tuple.extend(schedule.into_iter().flatten());
+        tuple.extend(initial.iter().flatten().cloned());
+        row.add_to_relation(chain, is_real - is_last.clone(), &tuple);
+
+        // The first round reads the block and the state, word i of either starts at ptr + 4i and the carry into
+        // the upper limb is a witness.
+        let words: &Sha256WordElements = lookup_elements.get_custom();
+        let read_words = w
+            .iter()
+            .map(Word::limbs)
+            .zip(0..BLOCK_WORDS)
+            .map(|(word, i)| (BLOCK_TAG + i as u32, &block_ptr, input.block_ptr, i, word))
+            .chain(
+                v.iter()
+                    .map(Word::limbs)
+                    .zip(0..STATE_WORDS)
+                    .map(|(word, i)| (STATE_TAG + i as u32, &state_ptr, input.state_ptr, i, word)),
+            );
+        for (tag, ptr, ptr_value, i, word) in read_words {
+            let address = ptr_value.wrapping_add(4 * i as u32);
+            let carry = next_bool(row, address & 0xFFFF < ptr_value & 0xFFFF);
+            let [ptr_lo, ptr_hi] = byte_limbs(ptr);
+            let mut tuple = clk.to_vec();
+            tuple.extend([
+                constant(tag),
+                ptr_lo + constant(4 * i as u32) - carry.clone() * BaseField::from(1 << 16),
+                ptr_hi + carry,
+            ]);
+            tuple.extend(word);
+            row.add_to_relation(words, -is_first.clone(), &tuple);
+        }
+
+        // The last round adds the working variables to the initial state and writes it back.
+        let result_value = {
+            let v = round(&input.v, k_value, input.w[0]);
+            array::from_fn::<u32, STATE_WORDS, _>(|i| input.initial[i].wrapping_add(v[i]))
+        };
+        for (i, (initial, out)) in initial.iter().zip(&out).enumerate() {
+            let result = next_limbs(row, result_value[i]);
+            let lo_value = (input.initial[i] & 0xFFFF) + (row.value(&out[0]).0 & 0xFFFF);
+            let hi_value =
+                (input.initial[i] >> 16) + (row.value(&out[1]).0 & 0xFFFF) + (lo_value >> 16);
+            let carries = [lo_value > 0xFFFF, hi_value > 0xFFFF].map(|carry| next_bool(row, carry));
+            row.add_constraint(
+                initial[0].clone() + out[0].clone()
+                    - result[0].clone()
+                    - carries[0].clone() * BaseField::from(1 << 16),
+            );
+            row.add_constraint(
+                initial[1].clone() + out[1].clone() + carries[0].clone()
+                    - result[1].clone()
+                    - carries[1].clone() * BaseField::from(1 << 16),
+            );
+
+            let mut tuple = clk.to_vec();
+            tuple.extend([constant(OUTPUT_TAG + i as u32), constant(0), constant(0)]);
+            tuple.extend(result);
+            row.add_to_relation(words, -is_last.clone(), &tuple);
+        }
+
+        let precompile: &PrecompileLookupElements = lookup_elements.as_ref();
+        let mut tuple = clk.to_vec();
+        tuple.push(constant(self.index as u32));
+        tuple.extend(state_ptr);
+        tuple.extend(block_ptr);
+        tuple.extend(array::from_fn::<R::F, 4, _>(|_| constant(0)));
+        row.add_to_relation(precompile, -is_first, &tuple);
+    }
+
+    fn draw_lookup_elements(
+        &self,
+        lookup_elements: &mut AllLookupElements,
+        channel: &mut MachineChannel,
+    ) {
+        lookup_elements.insert_custom(Sha256ChainElements::draw(channel));
+        lookup_elements.insert_custom(Sha256WordElements::draw(channel));
+    }
+
+    fn declare_support(&self, support: &mut Support) {
+        support.precompiles.insert(self.index);
+    }
+}
+
+impl Sha256CompressCircuit {
+    /// Reads a word of the row in RAM, writing back state words, and adds the word before and after the call to
+    /// [`Sha256WordElements`].
+    fn evaluate_access<R: Row>(
+        &self,
+        row: &mut R,
+        input: &Sha256Row,
+        lookup_elements: &AllLookupElements,
+        is_real: &R::F,
+        clk: &[R::F; 4],
+    ) {
+        let one = constant::<R::F>(1);
+        let access = input.access.clone().unwrap_or_default();
+
+        let is_access = next_bool(row, input.access.is_some());
+        let is_state = next_bool(row, input.access.is_some() && access.tag >= STATE_TAG);
+        row.add_constraint(is_access.clone() * (one.clone() - is_real.clone()));
+        row.add_constraint(is_state.clone() * (one - is_access.clone()));
+        let tag = row.next(BaseField::from(access.tag));
+
+        let address = next_bytes(row, access.address);
+        for byte in &address {
+            row.range_check_u8(is_access.clone(), byte.clone(), lookup_elements);
+        }
+
+        let load_store: &LoadStoreLookupElements = lookup_elements.as_ref();
+        let mut prev_values = Vec::with_capacity(4);
+        let mut values = Vec::with_capacity(4);
+        for (j, byte) in access.bytes.iter().enumerate() {
+            let mut byte_address = address.clone();
+            byte_address[0] = byte_address[0].clone() + constant(j as u32);
+
+            // Only state words are written.
+            let prev_value = row.next(BaseField::from(byte.prev_value as u32));
+            let value = row.next(BaseField::from(byte.value as u32));
+            row.range_check_u8(is_access.clone(), value.clone(), lookup_elements);
+            row.add_constraint(
+                (is_access.clone() - is_state.clone()) * (value.clone() - prev_value.clone()),
+            );
+
+            // prev_timestamp < clk, i.e. clk - 1 - prev_timestamp fits into 32 bits.
+            let prev_timestamp = next_bytes(row, byte.prev_timestamp);
+            let aux = next_lt(
+                row,
+                &is_access,
+                byte.prev_timestamp,
+                input.clk,
+                &prev_timestamp,
+                clk,
+            );
+            for byte in &aux {
+                row.range_check_u8(is_access.clone(), byte.clone(), lookup_elements);
+            }
+
+            let mut tuple = byte_address.to_vec();
+            tuple.push(prev_value.clone());
+            tuple.extend(prev_timestamp);
+            row.add_to_relation(load_store, -is_access.clone(), &tuple);
+            let mut tuple = byte_address.to_vec();
+            tuple.push(value.clone());
+            tuple.extend(clk.iter().cloned());
+            row.add_to_relation(load_store, is_access.clone(), &tuple);
+
+            prev_values.push(prev_value);
+            values.push(value);
+        }
+
+        let words: &Sha256WordElements = lookup_elements.get_custom();
+        let [address_lo, address_hi] = byte_limbs(&address);
+        let mut tuple = clk.to_vec();
+        tuple.extend([tag.clone(), address_lo, address_hi]);
+        tuple.extend(byte_limbs(&array::from_fn(|j| prev_values[j].clone())));
+        row.add_to_relation(words, is_access, &tuple);
+        let mut tuple = clk.to_vec();
+        tuple.extend([
+            tag + constant(ACCESSED_WORDS as u32),
+            constant(0),
+            constant(0),
+        ]);
+        tuple.extend(byte_limbs(&array::from_fn(|j| values[j].clone())));
+        row.add_to_relation(words, is_state, &tuple);
+    }
+}
+
+/// A 32-bit word as little-endian bits.
+type Bits<F> = [F; WORD_BITS];
+
+/// A word allocated either as bits or as 16-bit limbs.
+enum Word<F> {
+    Bits(Bits<F>),
+    Limbs([F; 2]),
+}
+
+impl<F: RowField> Word<F> {
+    fn bits(&self) -> &Bits<F> {
+        match self {
+            Word::Bits(bits) => bits,
+            Word::Limbs(_) => panic!("word is not allocated as bits"),
+        }
+    }
+
+    fn limbs(&self) -> [F; 2] {
+        match self {
+            Word::Bits(bits) => limbs(bits),
+            Word::Limbs(limbs) => limbs.clone(),
+        }
+    }
+}
+
+fn constant<F: RowField>(value: u32) -> F {
+    F::from(BaseField::from(value))
+}
+
+fn next_bool<R: Row>(row: &mut R, value: bool) -> R::F {
+    let bit = row.next(BaseField::from(value as u32));
+    row.add_constraint(bit.clone() * (bit.clone() - constant(1)));
+    bit
+}
+
+fn next_bits<R: Row>(row: &mut R, value: u32) -> Bits<R::F> {
+    array::from_fn(|i| next_bool(row, (value >> i) & 1 == 1))
+}
+
+/// Allocates 16-bit limbs of a word, the caller is responsible for checking their range.
+fn next_limbs<R: Row>(row: &mut R, value: u32) -> [R::F; 2] {
+    [value & 0xFFFF, value >> 16].map(|limb| row.next(BaseField::from(limb)))
+}
+
+/// Allocates bytes of a word, the caller is responsible for checking their range.
+fn next_bytes<R: Row>(row: &mut R, value: u32) -> [R::F; 4] {
+    value
+        .to_le_bytes()
+        .map(|byte| row.next(BaseField::from(byte as u32)))
+}
+
+fn rotr<F: Clone>(bits: &Bits<F>, n: usize) -> Bits<F> {
+    array::from_fn(|i| bits[(i + n) % WORD_BITS].clone())
+}
+
+fn shr<F: RowField>(bits: &Bits<F>, n: usize) -> Bits<F> {
+    array::from_fn(|i| {
+        if i + n < WORD_BITS {
+            bits[i + n].clone()
+        } else {
+            constant(0)
+        }
+    })
+}
+
+/// Allocates the xor of three words, `value` is the xor computed natively.
+fn next_xor3<R: Row>(row: &mut R, value: u32, [a, b, c]: [Bits<R::F>; 3]) -> Bits<R::F> {
+    array::from_fn(|i| {
+        let (a, b, c) = (a[i].clone(), b[i].clone(), c[i].clone());
+        let bit = row.next(BaseField::from((value >> i) & 1));
+        let pairs = a.clone() * b.clone() + b.clone() * c.clone() + c.clone() * a.clone();
+        let expected = a.clone() + b.clone() + c.clone() - pairs * BaseField::from(2)
+            + a * b * c * BaseField::from(4);
+        row.add_constraint(bit.clone() - expected);
+        bit
+    })
+}
+
+/// Adds words given in 16-bit limbs modulo 2^32, `value` is the sum computed natively.
+///
+/// The carries are range checked as bytes, which allows adding up to 256 words.
+fn next_add<R: Row>(
+    row: &mut R,
+    operands: &[[R::F; 2]],
+    value: u32,
+    multiplicity: &R::F,
+    lookup_elements: &AllLookupElements,
+) -> Bits<R::F> {
+    let [lo, hi] = array::from_fn(|j| {
+        operands
+            .iter()
+            .fold(constant::<R::F>(0), |acc, limbs| acc + limbs[j].clone())
+    });
+    let lo_carry = row.value(&lo).0.wrapping_sub(value & 0xFFFF) >> 16;
+    let hi_carry = (row.value(&hi).0 + lo_carry).wrapping_sub(value >> 16) >> 16;
+    let sum = next_bits(row, value);
+    let carries = [lo_carry, hi_carry].map(|carry| {
+        let carry = row.next(BaseField::from(carry));
+        row.range_check_u8(multiplicity.clone(), carry.clone(), lookup_elements);
+        carry
+    });
+    let [sum_lo, sum_hi] = limbs(&sum);
+    row.add_constraint(lo - sum_lo - carries[0].clone() * BaseField::from(1 << 16));
+    row.add_constraint(
+        hi + carries[0].clone() - sum_hi - carries[1].clone() * BaseField::from(1 << 16),
+    );
+    sum
+}
+
+/// Splits a word into 16-bit limbs.
+fn limbs<F: RowField>(bits: &Bits<F>) -> [F; 2] {
+    array::from_fn(|j| {
+        (0..16).fold(constant::<F>(0), |acc, i| {
+            acc + bits[16 * j + i].clone() * BaseField::from(1 << i)
+        })
+    })
+}
+
+fn byte_limbs<F: RowField>(bytes: &[F; 4]) -> [F; 2] {
+    array::from_fn(|j| bytes[2 * j].clone() + bytes[2 * j + 1].clone() * BaseField::from(1 << 8))
+}
+
+/// Constrains `lhs < rhs` when `flag` is set, returns the bytes of `rhs - 1 - lhs` which the caller must range check.
+fn next_lt<R: Row>(
+    row: &mut R,
+    flag: &R::F,
+    lhs_value: u32,
+    rhs_value: u32,
+    lhs: &[R::F; 4],
+    rhs: &[R::F; 4],
+) -> [R::F; 4] {
+    let aux_value = rhs_value.wrapping_sub(1).wrapping_sub(lhs_value);
+    let aux = next_bytes(row, aux_value);
+    let borrow = next_bool(
+        row,
+        (lhs_value & 0xFFFF) + 1 + (aux_value & 0xFFFF) > 0xFFFF,
+    );
+    let [lhs_lo, lhs_hi] = byte_limbs(lhs);
+    let [aux_lo, aux_hi] = byte_limbs(&aux);
+    let [rhs_lo, rhs_hi] = byte_limbs(rhs);
+    row.add_constraint(
+        flag.clone()
+            * (lhs_lo + constant::<R::F>(1) + aux_lo
+                - rhs_lo
+                - borrow.clone() * BaseField::from(1 << 16)),
+    );
+    row.add_constraint(flag.clone() * (lhs_hi + aux_hi + borrow - rhs_hi));
+    aux
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use nexus_vm::{
+        emulator::{Emulator, HarvardEmulator, InternalView},
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, InstructionType, Opcode, Register},
+        trace::k_trace_harvard,
+    };
+    use nexus_vm_prover::{
+        extensions::{circuit::CircuitExtension, ExtensionComponent},
+        machine::{BaseComponent, Machine},
+        Blake2sMerkleHasher, ProverConfig,
+    };
+
+    use crate::{Sha256Compress, SHA256_INITIAL_STATE};
+
+    fn test_block() -> [u32; BLOCK_WORDS] {
+        array::from_fn(|i| (i as u32 + 1).wrapping_mul(0x9E37_79B9))
+    }
+
+    #[test]
+    fn test_rounds() {
+        let (mut v, mut w) = (SHA256_INITIAL_STATE, test_block());
+        for k in K {
+            v = round(&v, k, w[0]);
+            let next = next_message_word(&w);
+            w.rotate_left(1);
+            w[BLOCK_WORDS - 1] = next;
+        }
+        let result: [u32; STATE_WORDS] =
+            array::from_fn(|i| SHA256_INITIAL_STATE[i].wrapping_add(v[i]));
+
+        let mut expected = SHA256_INITIAL_STATE;
+        sha256_compress(&mut expected, &test_block());
+        assert_eq!(result, expected);
+    }
+
+    /// Stores `words` at the address in register x10 with byte stores.
+    fn store_words(instructions: &mut Vec<Instruction>, words: &[u32]) {
+        for (i, byte) in words.iter().flat_map(|word| word.to_le_bytes()).enumerate() {
+            instructions.push(Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                6,
+                0,
+                byte as u32,
+            ));
+            instructions.push(Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::SB),
+                10,
+                6,
+                i as u32,
+            ));
+        }
+    }
+
+    #[test]
+    fn test_prove_sha256_compress() {
+        let opcode = Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "dynamic");
+
+        let mut instructions = vec![
+            // x10 = 0x81000 for the block.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x81),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 10, 10, 12),
+        ];
+        store_words(&mut instructions, &test_block());
+        instructions.extend([
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 11, 10, 0),
+            // x10 = 0x8FFF0 for the state, so that its addresses carry into the third byte.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x7FF),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 10, 0x100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 10, 10, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 10, 0xF0),
+        ]);
+        store_words(&mut instructions, &SHA256_INITIAL_STATE);
+        // Compress the block twice, the second call reads the state written by the first one.
+        for _ in 0..2 {
+            instructions.push(Instruction::new(
+                opcode.clone(),
+                Register::X12,
+                Register::X10,
+                11,
+                InstructionType::RType,
+            ));
+        }
+        let basic_blocks = vec![BasicBlock::new(instructions)];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator.add_opcode::<Sha256Compress>(&opcode).unwrap();
+        let (view, program_trace) = k_trace_harvard(emulator, 1).expect("error generating trace");
+
+        let prove_verify = |extensions: &[ExtensionComponent]| {
+            let proof = Machine::<BaseComponent>::prove_with_extensions::<Blake2sMerkleHasher>(
+                extensions,
+                &program_trace,
+                &view,
+                &ProverConfig::default(),
+            )
+            .unwrap();
+            Machine::<BaseComponent>::verify_with_extensions(
+                extensions,
+                proof,
+                ProverConfig::default().security_bits(),
+                view.get_program_memory(),
+                view.view_memory_layout().as_ref(),
+                &[],
+                view.get_initial_memory(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+        prove_verify(&[ExtensionComponent::custom(Arc::new(CircuitExtension::new(
+            Sha256CompressCircuit::new(0),
+            7,
+        )))])
+        .unwrap();
+
+        // Without the extension nothing consumes the calls added by the CPU.
+        assert!(prove_verify(&[]).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+/// In the guest context, there is nothing actually associated with the precompile other than the
+/// convenience wrappers for emitting the instruction call.
+pub struct Sha256Compress;
+
+#[macro_export]
+macro_rules! generate_instruction_caller {
+    ($path:path) => {
+        trait Sha256CompressCaller {
+            /// Applies the SHA-256 compression function to `state` with the message words of one block.
+            fn compress(state: &mut [u32; 8], block: &[u32; 16]);
+        }
+
+        impl Sha256CompressCaller for $path {
+            fn compress(state: &mut [u32; 8], block: &[u32; 16]) {
+                Self::emit_instruction(state.as_mut_ptr() as u32, block.as_ptr() as u32, 0);
+            }
+        }
+
+        /// SHA-256 hasher core backed by the compression precompile, use through [`Sha256`].
+        #[derive(Clone)]
+        pub struct Sha256Core {
+            state: [u32; 8],
+            block_len: u64,
+        }
+
+        impl Sha256Core {
+            fn compress_bytes(state: &mut [u32; 8], block: &[u8]) {
+                let mut words = [0u32; 16];
+                for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+                    *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                }
+                <$path as Sha256CompressCaller>::compress(state, &words);
+            }
+        }
+
+        impl Default for Sha256Core {
+            fn default() -> Self {
+                Self {
+                    state: $crate::SHA256_INITIAL_STATE,
+                    block_len: 0,
+                }
+            }
+        }
+
+        impl $crate::digest::HashMarker for Sha256Core {}
+
+        impl $crate::digest::core_api::BlockSizeUser for Sha256Core {
+            type BlockSize = $crate::digest::consts::U64;
+        }
+
+        impl $crate::digest::core_api::BufferKindUser for Sha256Core {
+            type BufferKind = $crate::digest::core_api::Eager;
+        }
+
+        impl $crate::digest::OutputSizeUser for Sha256Core {
+            type OutputSize = $crate::digest::consts::U32;
+        }
+
+        impl $crate::digest::core_api::UpdateCore for Sha256Core {
+            fn update_blocks(&mut self, blocks: &[$crate::digest::core_api::Block<Self>]) {
+                self.block_len += blocks.len() as u64;
+                for block in blocks {
+                    Self::compress_bytes(&mut self.state, block);
+                }
+            }
+        }
+
+        impl $crate::digest::core_api::FixedOutputCore for Sha256Core {
+            fn finalize_fixed_core(
+                &mut self,
+                buffer: &mut $crate::digest::core_api::Buffer<Self>,
+                out: &mut $crate::digest::Output<Self>,
+            ) {
+                let bit_len = 8 * (buffer.get_pos() as u64 + self.block_len * 64);
+                let mut state = self.state;
+                buffer.len64_padding_be(bit_len, |block| Self::compress_bytes(&mut state, block));
+
+                for (bytes, word) in out.chunks_exact_mut(4).zip(state.iter()) {
+                    bytes.copy_from_slice(&word.to_be_bytes());
+                }
+            }
+        }
+
+        impl $crate::digest::Reset for Sha256Core {
+            fn reset(&mut self) {
+                *self = Default::default();
+            }
+        }
+
+        /// SHA-256 hasher implementing [`digest::Digest`], backed by the compression precompile.
+        pub type Sha256 = $crate::digest::core_api::CoreWrapper<Sha256Core>;
+    };
+}
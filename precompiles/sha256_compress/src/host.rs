@@ -0,0 +1,195 @@
+use nexus_common::{
+    cpu::{InstructionExecutor, InstructionResult, InstructionState, Processor, Registers},
+    error::MemoryError,
+    memory::{LoadOp, LoadOps, MemAccessSize, MemoryProcessor, StoreOps},
+    riscv::{instruction::Instruction, register::Register},
+};
+use sha2::digest::generic_array::GenericArray;
+
+use nexus_precompiles::{PrecompileCircuit, PrecompileInstruction, PrecompileMetadata};
+
+use crate::Sha256CompressCircuit;
+
+#[derive(Default)]
+pub struct Sha256Compress {
+    rd: Register,
+    state_ptr: u32,
+    block_ptr: u32,
+    state: [u32; 8],
+    block: [u32; 16],
+}
+
+/// Applies the SHA-256 compression function to `state` with the message words of one block, executed on the native
+/// host.
+pub fn sha256_compress(state: &mut [u32; 8], block: &[u32; 16]) {
+    let mut bytes = [0u8; 64];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(block.iter()) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    sha2::compress256(state, &[GenericArray::clone_from_slice(&bytes)]);
+}
+
+fn read_words(
+    memory: &impl MemoryProcessor,
+    address: u32,
+    words: &mut [u32],
+    load_ops: &mut LoadOps,
+) -> Result<(), MemoryError> {
+    for (i, word) in words.iter_mut().enumerate() {
+        let load_op = memory.read(address + (i * 4) as u32, MemAccessSize::Word)?;
+        load_ops.insert(load_op);
+
+        let LoadOp::Op(_, _, value) = load_op;
+        *word = value;
+    }
+    Ok(())
+}
+
+impl InstructionState for Sha256Compress {
+    fn execute(&mut self) {
+        sha256_compress(&mut self.state, &self.block);
+    }
+
+    fn memory_read(&mut self, memory: &impl MemoryProcessor) -> Result<LoadOps, MemoryError> {
+        let mut load_ops = LoadOps::default();
+
+        read_words(memory, self.state_ptr, &mut self.state, &mut load_ops)?;
+        read_words(memory, self.block_ptr, &mut self.block, &mut load_ops)?;
+
+        Ok(load_ops)
+    }
+
+    fn memory_write(&self, memory: &mut impl MemoryProcessor) -> Result<StoreOps, MemoryError> {
+        let mut store_ops = StoreOps::default();
+
+        for (i, word) in self.state.iter().enumerate() {
+            store_ops.insert(memory.write(
+                self.state_ptr + (i * 4) as u32,
+                MemAccessSize::Word,
+                *word,
+            )?);
+        }
+
+        Ok(store_ops)
+    }
+
+    fn write_back(&self, cpu: &mut impl Processor) -> InstructionResult {
+        cpu.registers_mut().write(self.rd, 0);
+        Some(0)
+    }
+}
+
+impl InstructionExecutor for Sha256Compress {
+    type InstructionState = Self;
+
+    fn decode(ins: &Instruction, registers: &impl Registers) -> Self {
+        Self {
+            rd: ins.op_a,
+            state_ptr: registers[ins.op_b],
+            block_ptr: registers[Register::from(ins.op_c as u8)],
+            state: [0; 8],
+            block: [0; 16],
+        }
+    }
+}
+
+impl PrecompileInstruction for Sha256Compress {
+    fn metadata() -> PrecompileMetadata {
+        PrecompileMetadata {
+            author: "The Nexus Team",
+            name: "Sha256Compress",
+            description: "SHA-256 compression function over a state and a block in guest memory",
+            version_major: 1,
+            version_minor: 0,
+            version_patch: 0,
+        }
+    }
+
+    fn circuit() -> impl PrecompileCircuit {
+        Sha256CompressCircuit::new(0)
+    }
+
+    fn native_call(_rs1: u32, _rs2: u32) -> u32 {
+        // The state and block pointers cannot be dereferenced on the native host, use [`sha256_compress`] directly
+        // instead.
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SHA256_INITIAL_STATE;
+
+    use nexus_vm::{
+        cpu::Cpu,
+        memory::{VariableMemory, RW},
+        riscv::{InstructionType, Opcode},
+    };
+
+    const STATE_ADDRESS: u32 = 0x1000;
+    const BLOCK_ADDRESS: u32 = 0x2000;
+
+    // The single padded block of the message "abc".
+    fn abc_block() -> [u32; 16] {
+        let mut block = [0u32; 16];
+        block[0] = 0x61626380;
+        block[15] = 0x18;
+        block
+    }
+
+    // SHA-256("abc"), see FIPS 180-4 examples.
+    const ABC_DIGEST: [u32; 8] = [
+        0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+        0xf20015ad,
+    ];
+
+    #[test]
+    fn test_sha256_compress_native() {
+        let mut state = SHA256_INITIAL_STATE;
+        sha256_compress(&mut state, &abc_block());
+
+        assert_eq!(state, ABC_DIGEST);
+    }
+
+    #[test]
+    fn test_sha256_compress_instruction() {
+        let mut cpu = Cpu::default();
+        let mut memory = VariableMemory::<RW>::default();
+
+        for (i, word) in SHA256_INITIAL_STATE.iter().enumerate() {
+            memory
+                .write(STATE_ADDRESS + (i * 4) as u32, MemAccessSize::Word, *word)
+                .unwrap();
+        }
+        for (i, word) in abc_block().iter().enumerate() {
+            memory
+                .write(BLOCK_ADDRESS + (i * 4) as u32, MemAccessSize::Word, *word)
+                .unwrap();
+        }
+
+        cpu.registers.write(Register::X1, STATE_ADDRESS);
+        cpu.registers.write(Register::X2, BLOCK_ADDRESS);
+
+        let bare_instruction = Instruction::new(
+            Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "dynamic"),
+            Register::X3,
+            Register::X1,
+            2,
+            InstructionType::RType,
+        );
+        let (res, (load_ops, store_ops)) =
+            Sha256Compress::evaluator(&mut cpu, &mut memory, &bare_instruction).unwrap();
+
+        assert_eq!(res, Some(0));
+        assert_eq!(load_ops.len(), 8 + 16);
+        assert_eq!(store_ops.len(), 8);
+
+        for (i, word) in ABC_DIGEST.iter().enumerate() {
+            let LoadOp::Op(_, _, value) = memory
+                .read(STATE_ADDRESS + (i * 4) as u32, MemAccessSize::Word)
+                .unwrap();
+            assert_eq!(value, *word);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! SHA-256 compression function precompile.
+//!
+//! The precompile updates a SHA-256 state of 8 words in place with a single 64-byte block. The block is passed as
+//! 16 message words, already decoded from big-endian bytes. Both addresses must be word-aligned.
+//!
+//! Calls are proven by adding [`Sha256CompressCircuit`] as an extension of the prover, see [`circuit`].
+//!
+//! In the guest, `use_precompiles!` additionally generates a `Sha256` type implementing [`digest::Digest`] on top of
+//! the precompile.
+#![cfg_attr(target_arch = "riscv32", no_std)]
+
+pub use digest;
+
+#[cfg(target_arch = "riscv32")]
+pub mod guest;
+#[cfg(target_arch = "riscv32")]
+pub use guest::*;
+
+#[cfg(not(target_arch = "riscv32"))]
+pub mod circuit;
+#[cfg(not(target_arch = "riscv32"))]
+pub mod host;
+#[cfg(not(target_arch = "riscv32"))]
+pub use circuit::Sha256CompressCircuit;
+#[cfg(not(target_arch = "riscv32"))]
+pub use host::*;
+
+/// Initial SHA-256 state, see FIPS 180-4, section 5.3.3.
+pub const SHA256_INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
@@ -0,0 +1,451 @@
+//! Arithmetic modulo 256-bit primes, for the guest wrappers of field precompiles.
+//!
+//! Field elements are stored as 8 little-endian 32-bit words and are expected to be reduced. A precompile usually
+//! only provides multiplication, so the wrappers implement addition and subtraction here, and inversion and square
+//! roots by exponentiation with the multiplication of the precompile.
+//!
+//! Every function runs in time independent of the values of its arguments, except [`pow`] which branches on the
+//! exponent, a public constant in all uses.
+
+/// Number of 32-bit words in a field element.
+pub const WORDS: usize = 8;
+
+/// A 256-bit integer, stored as little-endian words.
+pub type Words = [u32; WORDS];
+
+/// Computes `a + b` and returns the carry out.
+fn add_carry(a: &Words, b: &Words) -> (Words, u32) {
+    let mut sum = [0u32; WORDS];
+    let mut carry = 0u64;
+    for i in 0..WORDS {
+        let word = a[i] as u64 + b[i] as u64 + carry;
+        sum[i] = word as u32;
+        carry = word >> 32;
+    }
+    (sum, carry as u32)
+}
+
+/// Computes `a - b` and returns the borrow out.
+fn sub_borrow(a: &Words, b: &Words) -> (Words, u32) {
+    let mut diff = [0u32; WORDS];
+    let mut borrow = 0u64;
+    for i in 0..WORDS {
+        let word = (a[i] as u64).wrapping_sub(b[i] as u64 + borrow);
+        diff[i] = word as u32;
+        borrow = word >> 63;
+    }
+    (diff, borrow as u32)
+}
+
+/// Returns `a` if `mask` is all zeros and `b` if it is all ones.
+pub fn select(a: &Words, b: &Words, mask: u32) -> Words {
+    core::array::from_fn(|i| a[i] ^ (mask & (a[i] ^ b[i])))
+}
+
+/// Returns all ones if `a < b`, and all zeros otherwise.
+pub fn lt(a: &Words, b: &Words) -> u32 {
+    sub_borrow(a, b).1.wrapping_neg()
+}
+
+/// Returns all ones if `a == b`, and all zeros otherwise.
+pub fn eq(a: &Words, b: &Words) -> u32 {
+    let diff = a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b));
+    // The top bit of `diff | -diff` is set if and only if `diff` is nonzero.
+    ((diff | diff.wrapping_neg()) >> 31).wrapping_sub(1)
+}
+
+/// Computes `a + b mod p`.
+pub fn add(a: &Words, b: &Words, p: &Words) -> Words {
+    let (sum, carry) = add_carry(a, b);
+    let (reduced, borrow) = sub_borrow(&sum, p);
+    // The sum is at least `p` if it overflowed or subtracting `p` did not.
+    select(&sum, &reduced, (carry | (borrow ^ 1)).wrapping_neg())
+}
+
+/// Computes `a - b mod p`.
+pub fn sub(a: &Words, b: &Words, p: &Words) -> Words {
+    let (diff, borrow) = sub_borrow(a, b);
+    let (wrapped, _) = add_carry(&diff, p);
+    select(&diff, &wrapped, borrow.wrapping_neg())
+}
+
+/// Computes `-a mod p`.
+pub fn neg(a: &Words, p: &Words) -> Words {
+    sub(&[0; WORDS], a, p)
+}
+
+/// Computes `base^exp` with the given multiplication, by square-and-multiply from the most significant bit.
+pub fn pow(base: &Words, exp: &Words, one: &Words, mul: impl Fn(&Words, &Words) -> Words) -> Words {
+    let mut result = *one;
+    for bit in (0..32 * WORDS).rev() {
+        result = mul(&result, &result);
+        if (exp[bit / 32] >> (bit % 32)) & 1 == 1 {
+            result = mul(&result, base);
+        }
+    }
+    result
+}
+
+/// Samples a uniformly random element below `p` from `next_u32`, by rejection.
+pub fn random(p: &Words, mut next_u32: impl FnMut() -> u32) -> Words {
+    let top_mask = u32::MAX >> p[WORDS - 1].leading_zeros();
+    loop {
+        let mut words: Words = core::array::from_fn(|_| next_u32());
+        words[WORDS - 1] &= top_mask;
+        if lt(&words, p) != 0 {
+            return words;
+        }
+    }
+}
+
+/// Implements an operator trait and its assigning variant for `$ty` with right-hand sides `$rhs` and `&$rhs`, from a
+/// function of references.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_binary_ops {
+    ($ty:ty, $rhs:ty, $op:ident::$method:ident, $op_assign:ident::$method_assign:ident, $f:expr) => {
+        impl ::core::ops::$op<$rhs> for $ty {
+            type Output = $ty;
+
+            fn $method(self, rhs: $rhs) -> $ty {
+                ($f)(&self, &rhs)
+            }
+        }
+
+        impl<'a> ::core::ops::$op<&'a $rhs> for $ty {
+            type Output = $ty;
+
+            fn $method(self, rhs: &'a $rhs) -> $ty {
+                ($f)(&self, rhs)
+            }
+        }
+
+        impl ::core::ops::$op_assign<$rhs> for $ty {
+            fn $method_assign(&mut self, rhs: $rhs) {
+                *self = ($f)(&*self, &rhs);
+            }
+        }
+
+        impl<'a> ::core::ops::$op_assign<&'a $rhs> for $ty {
+            fn $method_assign(&mut self, rhs: &'a $rhs) {
+                *self = ($f)(&*self, rhs);
+            }
+        }
+    };
+}
+
+/// Implements [`ff::Field`] and the operators it requires for a newtype over [`Words`].
+///
+/// Additions are computed with this module, products with `mul`, which is usually backed by a precompile, and
+/// inverses by exponentiation to `p - 2`. `sqrt` is a function `fn(&$field) -> (Choice, $field)` returning whether
+/// its argument is a square and, if so, one of its roots, and `non_square` is the non-square of
+/// [`ff::Field::sqrt_ratio`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_field {
+    (
+        $field:ident,
+        modulus: $p:expr,
+        inverse_exponent: $inv:expr,
+        non_square: $gs:expr,
+        mul: $mul:path,
+        sqrt: $sqrt:path $(,)?
+    ) => {
+        $crate::impl_binary_ops!(
+            $field,
+            $field,
+            Add::add,
+            AddAssign::add_assign,
+            |a: &$field, b: &$field| { $field($crate::field::add(&a.0, &b.0, &$p)) }
+        );
+        $crate::impl_binary_ops!(
+            $field,
+            $field,
+            Sub::sub,
+            SubAssign::sub_assign,
+            |a: &$field, b: &$field| { $field($crate::field::sub(&a.0, &b.0, &$p)) }
+        );
+        $crate::impl_binary_ops!(
+            $field,
+            $field,
+            Mul::mul,
+            MulAssign::mul_assign,
+            |a: &$field, b: &$field| { $field($mul(&a.0, &b.0)) }
+        );
+
+        impl ::core::ops::Neg for $field {
+            type Output = $field;
+
+            fn neg(self) -> $field {
+                $field($crate::field::neg(&self.0, &$p))
+            }
+        }
+
+        impl ::core::iter::Sum for $field {
+            fn sum<I: Iterator<Item = $field>>(iter: I) -> $field {
+                iter.fold(<$field as $crate::ff::Field>::ZERO, |acc, x| acc + x)
+            }
+        }
+
+        impl<'a> ::core::iter::Sum<&'a $field> for $field {
+            fn sum<I: Iterator<Item = &'a $field>>(iter: I) -> $field {
+                iter.fold(<$field as $crate::ff::Field>::ZERO, |acc, x| acc + x)
+            }
+        }
+
+        impl ::core::iter::Product for $field {
+            fn product<I: Iterator<Item = $field>>(iter: I) -> $field {
+                iter.fold(<$field as $crate::ff::Field>::ONE, |acc, x| acc * x)
+            }
+        }
+
+        impl<'a> ::core::iter::Product<&'a $field> for $field {
+            fn product<I: Iterator<Item = &'a $field>>(iter: I) -> $field {
+                iter.fold(<$field as $crate::ff::Field>::ONE, |acc, x| acc * x)
+            }
+        }
+
+        impl $crate::subtle::ConstantTimeEq for $field {
+            fn ct_eq(&self, other: &Self) -> $crate::subtle::Choice {
+                (($crate::field::eq(&self.0, &other.0) & 1) as u8).into()
+            }
+        }
+
+        impl $crate::subtle::ConditionallySelectable for $field {
+            fn conditional_select(a: &Self, b: &Self, choice: $crate::subtle::Choice) -> Self {
+                let mask = (choice.unwrap_u8() as u32).wrapping_neg();
+                $field($crate::field::select(&a.0, &b.0, mask))
+            }
+        }
+
+        impl $crate::ff::Field for $field {
+            const ZERO: Self = $field([0; $crate::field::WORDS]);
+            const ONE: Self = $field([1, 0, 0, 0, 0, 0, 0, 0]);
+
+            fn random(mut rng: impl $crate::rand_core::RngCore) -> Self {
+                $field($crate::field::random(&$p, || rng.next_u32()))
+            }
+
+            fn square(&self) -> Self {
+                *self * self
+            }
+
+            fn double(&self) -> Self {
+                *self + self
+            }
+
+            fn invert(&self) -> $crate::subtle::CtOption<Self> {
+                use $crate::ff::Field;
+
+                let inverse = $crate::field::pow(&self.0, &$inv, &Self::ONE.0, $mul);
+                $crate::subtle::CtOption::new($field(inverse), !self.is_zero())
+            }
+
+            fn sqrt_ratio(num: &Self, div: &Self) -> ($crate::subtle::Choice, Self) {
+                use $crate::{ff::Field, subtle::ConditionallySelectable};
+
+                // Division by zero yields zero, which is a square but must be reported as a non-square unless the
+                // numerator is zero as well.
+                let ratio = *num * div.invert().unwrap_or(Self::ZERO);
+                let (is_square, root) = $sqrt(&ratio);
+                let (_, non_square_root) = $sqrt(&(ratio * $gs));
+                let root = Self::conditional_select(&non_square_root, &root, is_square);
+                (is_square & !(div.is_zero() & !num.is_zero()), root)
+            }
+        }
+    };
+}
+
+/// Implements [`group::Group`] and the operators it requires for an affine point type with `x` and `y` coordinates
+/// implementing [`ff::Field`].
+///
+/// `add` is a function `fn(&$point, &$point) -> $point` adding any two points, usually backed by a precompile, and
+/// `scalar_be_bytes` a function `fn(&$scalar) -> [u8; 32]` encoding a scalar as a big-endian integer. The point type
+/// must also implement `Neg` and an inherent `double`. Scalar multiplication always adds, so that its running time
+/// does not depend on the scalar.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_group {
+    (
+        $point:ident,
+        scalar: $scalar:ty,
+        identity: $identity:expr,
+        generator: $generator:expr,
+        add: $add:path,
+        scalar_be_bytes: $scalar_be_bytes:path $(,)?
+    ) => {
+        $crate::impl_binary_ops!($point, $point, Add::add, AddAssign::add_assign, $add);
+        $crate::impl_binary_ops!(
+            $point,
+            $point,
+            Sub::sub,
+            SubAssign::sub_assign,
+            |a: &$point, b: &$point| { $add(a, &-*b) }
+        );
+        $crate::impl_binary_ops!(
+            $point,
+            $scalar,
+            Mul::mul,
+            MulAssign::mul_assign,
+            |point: &$point, scalar: &$scalar| {
+                use $crate::subtle::ConditionallySelectable;
+
+                let mut result = $identity;
+                for byte in $scalar_be_bytes(scalar) {
+                    for bit in (0..8).rev() {
+                        result = result.double();
+                        let sum = $add(&result, point);
+                        result =
+                            $point::conditional_select(&result, &sum, ((byte >> bit) & 1).into());
+                    }
+                }
+                result
+            }
+        );
+
+        impl ::core::iter::Sum for $point {
+            fn sum<I: Iterator<Item = $point>>(iter: I) -> $point {
+                iter.fold($identity, |acc, x| acc + x)
+            }
+        }
+
+        impl<'a> ::core::iter::Sum<&'a $point> for $point {
+            fn sum<I: Iterator<Item = &'a $point>>(iter: I) -> $point {
+                iter.fold($identity, |acc, x| acc + x)
+            }
+        }
+
+        impl $crate::subtle::ConstantTimeEq for $point {
+            fn ct_eq(&self, other: &Self) -> $crate::subtle::Choice {
+                use $crate::subtle::ConstantTimeEq;
+
+                self.x.ct_eq(&other.x) & self.y.ct_eq(&other.y)
+            }
+        }
+
+        impl $crate::subtle::ConditionallySelectable for $point {
+            fn conditional_select(a: &Self, b: &Self, choice: $crate::subtle::Choice) -> Self {
+                Self {
+                    x: $crate::subtle::ConditionallySelectable::conditional_select(
+                        &a.x, &b.x, choice,
+                    ),
+                    y: $crate::subtle::ConditionallySelectable::conditional_select(
+                        &a.y, &b.y, choice,
+                    ),
+                }
+            }
+        }
+
+        impl $crate::group::Group for $point {
+            type Scalar = $scalar;
+
+            fn random(rng: impl $crate::rand_core::RngCore) -> Self {
+                $generator * <$scalar as $crate::ff::Field>::random(rng)
+            }
+
+            fn identity() -> Self {
+                $identity
+            }
+
+            fn generator() -> Self {
+                $generator
+            }
+
+            fn is_identity(&self) -> $crate::subtle::Choice {
+                $crate::subtle::ConstantTimeEq::ct_eq(self, &$identity)
+            }
+
+            fn double(&self) -> Self {
+                $point::double(self)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use num_bigint::BigUint;
+
+    /// The secp256k1 base field modulus, p = 2^256 - 2^32 - 977, which makes sums overflow 256 bits.
+    const P: Words = [
+        0xfffffc2f, 0xfffffffe, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff,
+        0xffffffff,
+    ];
+
+    fn to_biguint(words: &Words) -> BigUint {
+        BigUint::from_slice(words)
+    }
+
+    fn from_biguint(value: &BigUint) -> Words {
+        let mut words = [0u32; WORDS];
+        for (word, digit) in words.iter_mut().zip(value.iter_u32_digits()) {
+            *word = digit;
+        }
+        words
+    }
+
+    fn mul(a: &Words, b: &Words) -> Words {
+        from_biguint(&(to_biguint(a) * to_biguint(b) % to_biguint(&P)))
+    }
+
+    fn samples() -> Vec<Words> {
+        let p = to_biguint(&P);
+        let mut state = 0x1234_5678u32;
+        let mut next_u32 = move || {
+            // xorshift32
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        let mut samples = vec![
+            [0; WORDS],
+            from_biguint(&BigUint::from(1u32)),
+            from_biguint(&(&p - 1u32)),
+            from_biguint(&(&p - 2u32)),
+        ];
+        samples.extend((0..8).map(|_| random(&P, &mut next_u32)));
+        samples
+    }
+
+    #[test]
+    fn test_add_sub_neg() {
+        let p = to_biguint(&P);
+        for a in samples() {
+            for b in samples() {
+                let (x, y) = (to_biguint(&a), to_biguint(&b));
+                assert_eq!(to_biguint(&add(&a, &b, &P)), (&x + &y) % &p);
+                assert_eq!(to_biguint(&sub(&a, &b, &P)), (&x + &p - &y) % &p);
+            }
+            assert_eq!(to_biguint(&neg(&a, &P)), (&p - to_biguint(&a)) % &p);
+        }
+    }
+
+    #[test]
+    fn test_comparisons() {
+        for a in samples() {
+            for b in samples() {
+                let (x, y) = (to_biguint(&a), to_biguint(&b));
+                assert_eq!(lt(&a, &b) != 0, x < y);
+                assert_eq!(eq(&a, &b) != 0, x == y);
+            }
+            assert_eq!(select(&a, &P, 0), a);
+            assert_eq!(select(&a, &P, u32::MAX), P);
+        }
+    }
+
+    #[test]
+    fn test_pow() {
+        let p = to_biguint(&P);
+        let one = from_biguint(&BigUint::from(1u32));
+        let exp = from_biguint(&(&p - 2u32));
+        for a in samples() {
+            assert_eq!(
+                to_biguint(&pow(&a, &exp, &one, mul)),
+                to_biguint(&a).modpow(&(&p - 2u32), &p)
+            );
+        }
+    }
+}
@@ -1,5 +1,12 @@
 #![cfg_attr(target_arch = "riscv32", no_std)]
 
+pub use ff;
+pub use group;
+pub use rand_core;
+pub use subtle;
+
+pub mod field;
+
 #[cfg(not(target_arch = "riscv32"))]
 mod traits;
 #[cfg(not(target_arch = "riscv32"))]
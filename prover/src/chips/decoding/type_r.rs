@@ -6,7 +6,8 @@ use crate::{
 };
 
 use crate::column::Column::{
-    self, ImmC, InstrVal, IsAdd, IsSub, OpA, OpA0, OpA1_4, OpB, OpB0, OpB1_4, OpC, OpC0_3, OpC4,
+    self, Funct3, Funct7, ImmC, InstrVal, IsAdd, IsCustom, IsSub, OpA, OpA0, OpA1_4, OpB, OpB0,
+    OpB1_4, OpC, OpC0_3, OpC4,
 };
 use crate::trace::eval::trace_eval;
 use nexus_vm::riscv::InstructionType::RType;
@@ -50,6 +51,12 @@ impl MachineChip for TypeRChip {
         let op_c4 = (op_c_raw >> 4) & 0x1;
         traces.fill_columns(row_idx, op_c0_3, OpC0_3);
         traces.fill_columns(row_idx, op_c4, OpC4);
+
+        // Only custom instructions need their funct3 and funct7 fields in the trace; builtin ones have them fixed.
+        if !step.instruction.opcode.is_builtin() {
+            traces.fill_columns(row_idx, step.instruction.opcode.fn3().value(), Funct3);
+            traces.fill_columns(row_idx, step.instruction.opcode.fn7().value(), Funct7);
+        }
     }
     fn add_constraints<E: stwo_prover::constraint_framework::EvalAtRow>(
         eval: &mut E,
@@ -81,13 +88,19 @@ impl MachineChip for TypeRChip {
             is_type_r.clone() * (op_b0.clone() + op_b1_4.clone() * BaseField::from(1 << 1) - op_b),
         );
 
-        // (is_type_r) ・ (b0110011 + op_a0・2^7 - instr_val_1) = 0
+        // (is_type_r - is_custom) ・ (b0110011 + op_a0・2^7 - instr_val_1)
+        //   + (is_custom) ・ (b0001011 + op_a0・2^7 - instr_val_1) = 0
         let instr_val = trace_eval!(trace_eval, InstrVal);
+        let [is_custom] = trace_eval!(trace_eval, IsCustom);
         eval.add_constraint(
-            is_type_r.clone()
+            (is_type_r.clone() - is_custom.clone())
                 * (E::F::from(BaseField::from(0b0110011))
                     + op_a0.clone() * BaseField::from(1 << 7)
-                    - instr_val[0].clone()),
+                    - instr_val[0].clone())
+                + is_custom.clone()
+                    * (E::F::from(BaseField::from(0b0001011))
+                        + op_a0.clone() * BaseField::from(1 << 7)
+                        - instr_val[0].clone()),
         );
 
         // (is_add) ・ (1-imm_c)・ (op_a1_4 + b000・2^4 + op_b0・2^7 - instr_val_2) = 0
@@ -198,6 +211,102 @@ impl MachineChip for TypeRChip {
                     - instr_val[1].clone()),
         );
 
+        // (is_mul) ・ (1-imm_c)・ (op_a1_4 + b000・2^4 + op_b0・2^7 - instr_val_2) = 0
+        let [is_mul] = trace_eval!(trace_eval, Column::IsMul);
+        eval.add_constraint(
+            is_mul.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_a1_4.clone() + op_b0.clone() * BaseField::from(1 << 7)
+                    - instr_val[1].clone()),
+        );
+
+        // (is_mulh)  ・ (1-imm_c)・ (op_a1_4 + b001・2^4 + op_b0・2^7 - instr_val_2) = 0
+        let [is_mulh] = trace_eval!(trace_eval, Column::IsMulh);
+        eval.add_constraint(
+            is_mulh.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_a1_4.clone()
+                    + E::F::from(BaseField::from(0b001)) * BaseField::from(1 << 4)
+                    + op_b0.clone() * BaseField::from(1 << 7)
+                    - instr_val[1].clone()),
+        );
+
+        // (is_mulhsu)・ (1-imm_c)・ (op_a1_4 + b010・2^4 + op_b0・2^7 - instr_val_2) = 0
+        let [is_mulhsu] = trace_eval!(trace_eval, Column::IsMulhsu);
+        eval.add_constraint(
+            is_mulhsu.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_a1_4.clone()
+                    + E::F::from(BaseField::from(0b010)) * BaseField::from(1 << 4)
+                    + op_b0.clone() * BaseField::from(1 << 7)
+                    - instr_val[1].clone()),
+        );
+
+        // (is_mulhu) ・ (1-imm_c)・ (op_a1_4 + b011・2^4 + op_b0・2^7 - instr_val_2) = 0
+        let [is_mulhu] = trace_eval!(trace_eval, Column::IsMulhu);
+        eval.add_constraint(
+            is_mulhu.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_a1_4.clone()
+                    + E::F::from(BaseField::from(0b011)) * BaseField::from(1 << 4)
+                    + op_b0.clone() * BaseField::from(1 << 7)
+                    - instr_val[1].clone()),
+        );
+
+        // (is_div)   ・ (1-imm_c)・ (op_a1_4 + b100・2^4 + op_b0・2^7 - instr_val_2) = 0
+        let [is_div] = trace_eval!(trace_eval, Column::IsDiv);
+        eval.add_constraint(
+            is_div.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_a1_4.clone()
+                    + E::F::from(BaseField::from(0b100)) * BaseField::from(1 << 4)
+                    + op_b0.clone() * BaseField::from(1 << 7)
+                    - instr_val[1].clone()),
+        );
+
+        // (is_divu)  ・ (1-imm_c)・ (op_a1_4 + b101・2^4 + op_b0・2^7 - instr_val_2) = 0
+        let [is_divu] = trace_eval!(trace_eval, Column::IsDivu);
+        eval.add_constraint(
+            is_divu.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_a1_4.clone()
+                    + E::F::from(BaseField::from(0b101)) * BaseField::from(1 << 4)
+                    + op_b0.clone() * BaseField::from(1 << 7)
+                    - instr_val[1].clone()),
+        );
+
+        // (is_rem)   ・ (1-imm_c)・ (op_a1_4 + b110・2^4 + op_b0・2^7 - instr_val_2) = 0
+        let [is_rem] = trace_eval!(trace_eval, Column::IsRem);
+        eval.add_constraint(
+            is_rem.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_a1_4.clone()
+                    + E::F::from(BaseField::from(0b110)) * BaseField::from(1 << 4)
+                    + op_b0.clone() * BaseField::from(1 << 7)
+                    - instr_val[1].clone()),
+        );
+
+        // (is_remu)  ・ (1-imm_c)・ (op_a1_4 + b111・2^4 + op_b0・2^7 - instr_val_2) = 0
+        let [is_remu] = trace_eval!(trace_eval, Column::IsRemu);
+        eval.add_constraint(
+            is_remu.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_a1_4.clone()
+                    + E::F::from(BaseField::from(0b111)) * BaseField::from(1 << 4)
+                    + op_b0.clone() * BaseField::from(1 << 7)
+                    - instr_val[1].clone()),
+        );
+
+        // (is_custom) ・ (op_a1_4 + funct3・2^4 + op_b0・2^7 - instr_val_2) = 0
+        let [funct3] = trace_eval!(trace_eval, Funct3);
+        eval.add_constraint(
+            is_custom.clone()
+                * (op_a1_4.clone()
+                    + funct3 * BaseField::from(1 << 4)
+                    + op_b0.clone() * BaseField::from(1 << 7)
+                    - instr_val[1].clone()),
+        );
+
         // (is_type_r) ・ (op_b1_4 + op_c0_3・2^4 - instr_val_3) = 0
         eval.add_constraint(
             is_type_r.clone()
@@ -294,5 +403,99 @@ impl MachineChip for TypeRChip {
                     + E::F::from(BaseField::from(0b0000000)) * BaseField::from(1 << 1)
                     - instr_val[3].clone()),
         );
+
+        // (is_mul)   ・ (1-imm_c)・ (op_c4 + b0000001・2 - instr_val_4) = 0
+        eval.add_constraint(
+            is_mul.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_c4.clone()
+                    + E::F::from(BaseField::from(0b0000001)) * BaseField::from(1 << 1)
+                    - instr_val[3].clone()),
+        );
+
+        // (is_mulh)  ・ (1-imm_c)・ (op_c4 + b0000001・2 - instr_val_4) = 0
+        eval.add_constraint(
+            is_mulh.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_c4.clone()
+                    + E::F::from(BaseField::from(0b0000001)) * BaseField::from(1 << 1)
+                    - instr_val[3].clone()),
+        );
+
+        // (is_mulhsu)・ (1-imm_c)・ (op_c4 + b0000001・2 - instr_val_4) = 0
+        eval.add_constraint(
+            is_mulhsu.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_c4.clone()
+                    + E::F::from(BaseField::from(0b0000001)) * BaseField::from(1 << 1)
+                    - instr_val[3].clone()),
+        );
+
+        // (is_mulhu) ・ (1-imm_c)・ (op_c4 + b0000001・2 - instr_val_4) = 0
+        eval.add_constraint(
+            is_mulhu.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_c4.clone()
+                    + E::F::from(BaseField::from(0b0000001)) * BaseField::from(1 << 1)
+                    - instr_val[3].clone()),
+        );
+
+        // (is_div)   ・ (1-imm_c)・ (op_c4 + b0000001・2 - instr_val_4) = 0
+        eval.add_constraint(
+            is_div.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_c4.clone()
+                    + E::F::from(BaseField::from(0b0000001)) * BaseField::from(1 << 1)
+                    - instr_val[3].clone()),
+        );
+
+        // (is_divu)  ・ (1-imm_c)・ (op_c4 + b0000001・2 - instr_val_4) = 0
+        eval.add_constraint(
+            is_divu.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_c4.clone()
+                    + E::F::from(BaseField::from(0b0000001)) * BaseField::from(1 << 1)
+                    - instr_val[3].clone()),
+        );
+
+        // (is_rem)   ・ (1-imm_c)・ (op_c4 + b0000001・2 - instr_val_4) = 0
+        eval.add_constraint(
+            is_rem.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_c4.clone()
+                    + E::F::from(BaseField::from(0b0000001)) * BaseField::from(1 << 1)
+                    - instr_val[3].clone()),
+        );
+
+        // (is_remu)  ・ (1-imm_c)・ (op_c4 + b0000001・2 - instr_val_4) = 0
+        eval.add_constraint(
+            is_remu.clone()
+                * (one.clone() - imm_c.clone())
+                * (op_c4.clone()
+                    + E::F::from(BaseField::from(0b0000001)) * BaseField::from(1 << 1)
+                    - instr_val[3].clone()),
+        );
+
+        // (is_custom) ・ (op_c4 + funct7・2 - instr_val_4) = 0
+        let [funct7] = trace_eval!(trace_eval, Funct7);
+        eval.add_constraint(
+            is_custom.clone()
+                * (op_c4.clone() + funct7 * BaseField::from(1 << 1) - instr_val[3].clone()),
+        );
+
+        // M-extension and custom instructions have no immediate form, so ImmC must not be used to switch off the decoding above.
+        // (is_mul + is_mulh + is_mulhsu + is_mulhu + is_div + is_divu + is_rem + is_remu + is_custom)・imm_c = 0
+        eval.add_constraint(
+            (is_mul
+                + is_mulh
+                + is_mulhsu
+                + is_mulhu
+                + is_div
+                + is_divu
+                + is_rem
+                + is_remu
+                + is_custom)
+                * imm_c,
+        );
     }
 }
@@ -5,6 +5,7 @@ use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 use crate::{
     column::Column::{self, *},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -132,6 +133,12 @@ impl MachineChip for AddChip {
                         + carry_flag[0].clone())),
         );
     }
+
+    fn declare_support(support: &mut Support) {
+        support
+            .opcodes
+            .extend([BuiltinOpcode::ADD, BuiltinOpcode::ADDI]);
+    }
 }
 
 #[cfg(test)]
@@ -5,6 +5,7 @@ use nexus_vm::riscv::BuiltinOpcode;
 use crate::{
     column::Column::{self, *},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -107,6 +108,10 @@ impl MachineChip for AuipcChip {
                     - value_a[3].clone() * modulus.clone()),
         );
     }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::AUIPC);
+    }
 }
 
 #[cfg(test)]
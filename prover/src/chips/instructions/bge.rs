@@ -6,6 +6,7 @@ use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 use crate::{
     column::Column::{self, *},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -220,6 +221,10 @@ impl MachineChip for BgeChip {
                     - pc_next[3].clone() * modulus.clone()),
         );
     }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::BGE);
+    }
 }
 
 #[cfg(test)]
@@ -6,6 +6,7 @@ use nexus_vm::riscv::BuiltinOpcode;
 use crate::{
     column::Column::{self, *},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -158,6 +159,10 @@ impl MachineChip for BgeuChip {
                     - pc_next[3].clone() * modulus.clone()),
         );
     }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::BGEU);
+    }
 }
 
 #[cfg(test)]
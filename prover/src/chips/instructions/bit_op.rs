@@ -16,6 +16,7 @@ use crate::{
         self, IsAnd, IsOr, IsXor, ValueA, ValueA4_7, ValueB, ValueB4_7, ValueC, ValueC4_7,
     },
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         program_trace::ProgramTraces,
@@ -427,6 +428,17 @@ impl MachineChip for BitOpChip {
             }
         }
     }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.extend([
+            BuiltinOpcode::AND,
+            BuiltinOpcode::ANDI,
+            BuiltinOpcode::OR,
+            BuiltinOpcode::ORI,
+            BuiltinOpcode::XOR,
+            BuiltinOpcode::XORI,
+        ]);
+    }
 }
 
 #[cfg(test)]
@@ -528,7 +540,7 @@ mod test {
         let ext = ExtensionComponent::bit_op_multiplicity();
         let (_, claimed_sum_2) = ext.generate_interaction_trace(&side_note, &lookup_elements);
 
-        let ext = ExtensionComponent::final_reg();
+        let ext = ExtensionComponent::final_reg([0; 32], None);
         let (_, claimed_sum_3) = ext.generate_interaction_trace(&side_note, &lookup_elements);
         assert_eq!(
             claimed_sum_1 + claimed_sum_2 + claimed_sum_3,
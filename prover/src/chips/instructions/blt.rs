@@ -9,6 +9,7 @@ use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 use crate::{
     column::Column::{self, *},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -211,6 +212,10 @@ impl MachineChip for BltChip {
         // sgn_b is 0 or 1
         eval.add_constraint(is_blt * (sgn_b.clone() * (E::F::one() - sgn_b.clone())));
     }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::BLT);
+    }
 }
 
 #[cfg(test)]
@@ -6,6 +6,7 @@ use nexus_vm::riscv::BuiltinOpcode;
 use crate::{
     column::Column::{self, *},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -155,6 +156,10 @@ impl MachineChip for BltuChip {
                     - pc_next[3].clone() * modulus.clone()),
         );
     }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::BLTU);
+    }
 }
 
 #[cfg(test)]
@@ -12,6 +12,7 @@ use nexus_vm::riscv::BuiltinOpcode;
 use crate::{
     column::Column::{self, *},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -259,6 +260,10 @@ impl MachineChip for BneChip {
 
         // carry_{1,2,3,4} ∈ {0,1} is enforced in RangeBoolChip
     }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::BNE);
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,193 @@
+// This file contains the CPU side of custom instructions, i.e. precompile calls.
+//
+// The main trace doesn't know how a precompile computes its result. Instead, every custom instruction row adds
+// (clk, index, value_b, value_c, value_a) to a relation and an extension proving the precompile is expected to remove
+// the same tuple, together with the memory accesses of the call.
+
+use std::collections::BTreeMap;
+
+use nexus_vm::{memory::MemoryRecord, riscv::InstructionType, WORD_SIZE};
+use stwo_prover::{
+    constraint_framework::{logup::LogupTraceGenerator, EvalAtRow, Relation, RelationEntry},
+    core::{
+        backend::simd::{
+            column::BaseColumn,
+            m31::{PackedBaseField, LOG_N_LANES},
+        },
+        fields::m31::BaseField,
+    },
+};
+
+use crate::{
+    column::{
+        Column::{self, Funct3, Funct7, IsCustom, ValueA, ValueB, ValueC},
+        PreprocessedColumn,
+    },
+    components::AllLookupElements,
+    preflight::PRECOMPILE_OPCODE,
+    trace::{
+        eval::{preprocessed_trace_eval, trace_eval, TraceEval},
+        program_trace::ProgramTraces,
+        sidenote::{PrecompileCall, PrecompileMemoryAccess, SideNote},
+        FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder, Word,
+    },
+    traits::MachineChip,
+};
+
+/// The tuple consists of clk, precompile index, value_b, value_c and value_a.
+const LOOKUP_TUPLE_SIZE: usize = 3 * WORD_SIZE + 1 + WORD_SIZE;
+stwo_prover::relation!(PrecompileLookupElements, LOOKUP_TUPLE_SIZE);
+
+pub struct CustomChip;
+
+impl MachineChip for CustomChip {
+    fn draw_lookup_elements(
+        all_elements: &mut AllLookupElements,
+        channel: &mut impl stwo_prover::core::channel::Channel,
+    ) {
+        all_elements.insert(PrecompileLookupElements::draw(channel));
+    }
+
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return,
+        };
+        let instruction = &vm_step.step.instruction;
+        if instruction.opcode.is_builtin()
+            || instruction.opcode.raw() != PRECOMPILE_OPCODE
+            || instruction.ins_type != InstructionType::RType
+        {
+            return;
+        }
+
+        // A precompile that doesn't return a result leaves rd unchanged.
+        let value_a: Word = vm_step.get_result().unwrap_or(vm_step.get_value_a());
+        traces.fill_columns(row_idx, value_a, ValueA);
+
+        let clk = row_idx as u32 + 1;
+        // Loads are applied before stores, so that a byte both read and written by the call ends up with the stored
+        // value.
+        let mut records: Vec<&MemoryRecord> = vm_step.step.memory_records.iter().collect();
+        records.sort_by_key(|record| matches!(record, MemoryRecord::StoreRecord(..)));
+        let mut accesses: BTreeMap<u32, PrecompileMemoryAccess> = BTreeMap::new();
+        for record in records {
+            assert_eq!(record.get_timestamp(), clk, "timestamp mismatch");
+            let is_store = matches!(record, MemoryRecord::StoreRecord(..));
+            let size = record.get_size() as usize;
+            let value = record.get_value().to_le_bytes();
+            for (i, byte) in value.into_iter().enumerate().take(size) {
+                let address = record
+                    .get_address()
+                    .checked_add(i as u32)
+                    .expect("memory access range overflowed back to address zero");
+                let access = accesses.entry(address).or_insert_with(|| {
+                    let (prev_timestamp, prev_value) = side_note
+                        .rw_mem_check
+                        .last_access
+                        .get(&address)
+                        .copied()
+                        .unwrap_or((0, 0));
+                    PrecompileMemoryAccess {
+                        address,
+                        prev_value,
+                        prev_timestamp,
+                        value: prev_value,
+                    }
+                });
+                if is_store {
+                    access.value = byte;
+                } else {
+                    assert_eq!(
+                        access.value, byte,
+                        "memory access value mismatch at address 0x{address:x}"
+                    );
+                }
+            }
+        }
+        for access in accesses.values() {
+            side_note
+                .rw_mem_check
+                .last_access
+                .insert(access.address, (clk, access.value));
+        }
+
+        let index = ((instruction.opcode.fn7().value() as u16) << 3)
+            | instruction.opcode.fn3().value() as u16;
+        side_note.precompile_calls.push(PrecompileCall {
+            clk,
+            pc: vm_step.step.pc,
+            index,
+            value_b: u32::from_le_bytes(vm_step.get_value_b()),
+            value_c: u32::from_le_bytes(vm_step.get_value_c().0),
+            value_a: u32::from_le_bytes(value_a),
+            memory: accesses.into_values().collect(),
+        });
+    }
+
+    fn fill_interaction_trace(
+        logup_trace_gen: &mut LogupTraceGenerator,
+        original_traces: &FinalizedTraces,
+        preprocessed_traces: &PreprocessedTraces,
+        _program_traces: &ProgramTraces,
+        lookup_elements: &AllLookupElements,
+    ) {
+        let lookup_elements: &PrecompileLookupElements = lookup_elements.as_ref();
+        let [is_custom] = original_traces.get_base_column(IsCustom);
+        let [funct3] = original_traces.get_base_column(Funct3);
+        let [funct7] = original_traces.get_base_column(Funct7);
+        let clk =
+            preprocessed_traces.get_preprocessed_base_column::<WORD_SIZE>(PreprocessedColumn::Clk);
+        let values: [[&BaseColumn; WORD_SIZE]; 3] =
+            [ValueB, ValueC, ValueA].map(|col| original_traces.get_base_column(col));
+
+        let mut logup_col_gen = logup_trace_gen.new_col();
+        for vec_row in 0..(1 << (original_traces.log_size() - LOG_N_LANES)) {
+            let mut tuple: Vec<PackedBaseField> = clk.iter().map(|b| b.data[vec_row]).collect();
+            tuple.push(
+                funct7.data[vec_row] * PackedBaseField::broadcast(BaseField::from(1 << 3))
+                    + funct3.data[vec_row],
+            );
+            for value in values.iter() {
+                tuple.extend(value.iter().map(|b| b.data[vec_row]));
+            }
+            assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
+            logup_col_gen.write_frac(
+                vec_row,
+                is_custom.data[vec_row].into(),
+                lookup_elements.combine(&tuple),
+            );
+        }
+        logup_col_gen.finalize_col();
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        lookup_elements: &AllLookupElements,
+    ) {
+        let lookup_elements: &PrecompileLookupElements = lookup_elements.as_ref();
+        let [is_custom] = trace_eval!(trace_eval, IsCustom);
+        let [funct3] = trace_eval!(trace_eval, Funct3);
+        let [funct7] = trace_eval!(trace_eval, Funct7);
+        let clk = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::Clk);
+
+        let mut tuple = clk.to_vec();
+        tuple.push(funct7 * BaseField::from(1 << 3) + funct3);
+        for col in [ValueB, ValueC, ValueA] {
+            tuple.extend(trace_eval.column_eval::<WORD_SIZE>(col));
+        }
+        assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
+
+        eval.add_to_relation(RelationEntry::new(
+            lookup_elements,
+            is_custom.into(),
+            &tuple,
+        ));
+    }
+}
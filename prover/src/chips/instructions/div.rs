@@ -0,0 +1,536 @@
+use num_traits::{One, Zero};
+use stwo_prover::{constraint_framework::EvalAtRow, core::fields::m31::BaseField};
+
+use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+
+use crate::{
+    column::Column::{self, *},
+    components::AllLookupElements,
+    preflight::Support,
+    trace::{
+        eval::{trace_eval, TraceEval},
+        sidenote::SideNote,
+        ProgramStep, TracesBuilder, Word,
+    },
+    traits::{ExecuteChip, MachineChip},
+};
+
+use super::{
+    mul::{fill_mul_carries, mul_add_chunks, mul_add_with_carries, sign_extend},
+    sub::subtract_with_borrow,
+};
+
+// Support DIV opcode.
+pub struct DivChip;
+// Support DIVU opcode.
+pub struct DivuChip;
+
+pub struct ExecutionResult {
+    pub quotient: Word,
+    pub remainder: Word,
+    /// Carries of `quotient * divisor + remainder` at 16-bit boundaries.
+    pub carries: [u16; WORD_SIZE],
+    pub abs_c: Word,
+    /// Borrow bits of `0 - divisor` at 16-bit boundaries, only set for negative divisors.
+    pub abs_c_borrow: [bool; 2],
+    pub abs_rem: Word,
+    /// Borrow bits of `0 - remainder` at 16-bit boundaries, only set for negative remainders.
+    pub abs_rem_borrow: [bool; 2],
+    /// `abs_c - abs_rem - 1`, zero on division by zero.
+    pub rem_diff: Word,
+    /// Carry bit of `abs_rem + rem_diff + 1` at the 16-bit boundary.
+    pub rem_diff_carry: bool,
+    pub div_by_zero: bool,
+    pub overflow: bool,
+}
+
+/// Computes the quotient and the remainder of `b / c` following the RISC-V semantics, along with the auxiliary
+/// values used by the constraints.
+///
+/// For signed divisions the quotient is rounded towards zero and the remainder has the sign of the dividend.
+/// Division by zero results in a quotient with all bits set and the dividend as the remainder, and the
+/// overflowing signed division `-2^31 / -1` results in `-2^31` with a zero remainder.
+pub(crate) fn execute_div(program_step: &ProgramStep, signed: bool) -> ExecutionResult {
+    let value_b = program_step.get_value_b();
+    let (value_c, _) = program_step.get_value_c();
+    let b = u32::from_le_bytes(value_b);
+    let c = u32::from_le_bytes(value_c);
+
+    let div_by_zero = c == 0;
+    let overflow = signed && b == i32::MIN as u32 && c == u32::MAX;
+    let (quotient, remainder) = if div_by_zero {
+        (u32::MAX, b)
+    } else if signed {
+        (
+            (b as i32).wrapping_div(c as i32) as u32,
+            (b as i32).wrapping_rem(c as i32) as u32,
+        )
+    } else {
+        (b / c, b % c)
+    };
+
+    let is_negative = |x: u32| signed && (x >> 31) == 1;
+    let (sgn_b, sgn_c) = (is_negative(b), is_negative(c));
+    let (sgn_q, sgn_r) = (is_negative(quotient), is_negative(remainder));
+
+    let (product, carries) = mul_add_with_carries(
+        quotient.to_le_bytes(),
+        value_c,
+        remainder.to_le_bytes(),
+        sgn_q,
+        sgn_c,
+        sgn_r,
+    );
+    let (low, high) = product.split_at(WORD_SIZE);
+    assert_eq!(low, value_b);
+    if !overflow {
+        assert!(high
+            .iter()
+            .all(|&limb| limb == if sgn_b { 0xFF } else { 0 }));
+    }
+
+    let abs = |x: u32, sgn: bool| -> (Word, [bool; 2]) {
+        if sgn {
+            let (abs, borrow_bits) = subtract_with_borrow([0u8; WORD_SIZE], x.to_le_bytes());
+            (abs, [borrow_bits[1], borrow_bits[3]])
+        } else {
+            (x.to_le_bytes(), [false; 2])
+        }
+    };
+    let (abs_c, abs_c_borrow) = abs(c, sgn_c);
+    let (abs_rem, abs_rem_borrow) = abs(remainder, sgn_r);
+
+    let (rem_diff, rem_diff_carry) = if div_by_zero {
+        (0, false)
+    } else {
+        let abs_c = u32::from_le_bytes(abs_c);
+        let abs_rem = u32::from_le_bytes(abs_rem);
+        let rem_diff = abs_c - abs_rem - 1;
+        let carry = (abs_rem & 0xFFFF) + (rem_diff & 0xFFFF) + 1 > 0xFFFF;
+        (rem_diff, carry)
+    };
+
+    ExecutionResult {
+        quotient: quotient.to_le_bytes(),
+        remainder: remainder.to_le_bytes(),
+        carries,
+        abs_c,
+        abs_c_borrow,
+        abs_rem,
+        abs_rem_borrow,
+        rem_diff: rem_diff.to_le_bytes(),
+        rem_diff_carry,
+        div_by_zero,
+        overflow,
+    }
+}
+
+/// Fills the main trace for division and remainder instructions.
+///
+/// The quotient goes into Qt and the remainder into Rem. For signed divisions the sign bits of ValueB, ValueC,
+/// Qt and Rem are stored in SgnB, SgnC, SgnQt and SgnRem, and the most significant limbs with the sign bit cleared
+/// in Helper2[3], Helper3[3], QtAux and Helper4[3].
+pub(crate) fn fill_div(
+    traces: &mut TracesBuilder,
+    row_idx: usize,
+    vm_step: &ProgramStep,
+    signed: bool,
+) {
+    let ExecutionResult {
+        quotient,
+        remainder,
+        carries,
+        abs_c,
+        abs_c_borrow,
+        abs_rem,
+        abs_rem_borrow,
+        rem_diff,
+        rem_diff_carry,
+        div_by_zero,
+        overflow,
+    } = execute_div(vm_step, signed);
+
+    traces.fill_columns(row_idx, quotient, Qt);
+    traces.fill_columns(row_idx, remainder, Rem);
+    fill_mul_carries(traces, row_idx, carries);
+    traces.fill_columns(row_idx, abs_c, AbsC);
+    traces.fill_columns(row_idx, abs_c_borrow, AbsCBorrow);
+    traces.fill_columns(row_idx, abs_rem, AbsRem);
+    traces.fill_columns(row_idx, abs_rem_borrow, AbsRemBorrow);
+    traces.fill_columns(row_idx, rem_diff, RemDiff);
+    traces.fill_columns(row_idx, [rem_diff_carry, false], CarryFlag);
+    traces.fill_columns(row_idx, div_by_zero, DivByZero);
+
+    if signed {
+        let value_b = vm_step.get_value_b();
+        let (value_c, _) = vm_step.get_value_c();
+        let mut helper_b = value_b;
+        helper_b[WORD_SIZE - 1] &= 0x7f;
+        let mut helper_c = value_c;
+        helper_c[WORD_SIZE - 1] &= 0x7f;
+        let mut helper_rem = remainder;
+        helper_rem[WORD_SIZE - 1] &= 0x7f;
+
+        traces.fill_columns(row_idx, helper_b, Helper2);
+        traces.fill_columns(row_idx, helper_c, Helper3);
+        traces.fill_columns(row_idx, quotient[WORD_SIZE - 1] & 0x7f, QtAux);
+        traces.fill_columns(row_idx, helper_rem, Helper4);
+        traces.fill_columns(row_idx, vm_step.get_sgn_b(), SgnB);
+        traces.fill_columns(row_idx, vm_step.get_sgn_c(), SgnC);
+        traces.fill_columns(row_idx, (quotient[WORD_SIZE - 1] >> 7) == 1, SgnQt);
+        traces.fill_columns(row_idx, (remainder[WORD_SIZE - 1] >> 7) == 1, SgnRem);
+        traces.fill_columns(row_idx, overflow, DivOverflow);
+    }
+}
+
+/// Adds constraints for `ValueB = Qt * ValueC + Rem` with `|Rem| < |ValueC|` on rows where `selector` is set,
+/// and `result` is the column that must equal ValueA.
+///
+/// The equation is checked on 64-bit sign-extended values, except for the signed overflow case where only the
+/// lower 32 bits are checked. On division by zero the remainder bound is not checked and Qt must have all bits set.
+pub(crate) fn constrain_div<E: EvalAtRow>(
+    eval: &mut E,
+    trace_eval: &TraceEval<E>,
+    selector: E::F,
+    signed: bool,
+    result: Column,
+) {
+    let value_a = trace_eval!(trace_eval, ValueA);
+    let value_b = trace_eval!(trace_eval, ValueB);
+    let value_c = trace_eval!(trace_eval, ValueC);
+    let qt = trace_eval!(trace_eval, Qt);
+    let rem = trace_eval!(trace_eval, Rem);
+    let [div_by_zero] = trace_eval!(trace_eval, DivByZero);
+    let one = E::F::one();
+
+    let result = trace_eval.column_eval::<WORD_SIZE>(result);
+    for i in 0..WORD_SIZE {
+        eval.add_constraint(selector.clone() * (value_a[i].clone() - result[i].clone()));
+    }
+
+    // Sign bits, zero for unsigned divisions.
+    // selector・(h_2[3] + sgn_b・2^7 - b_val_4) = 0
+    // selector・(h_3[3] + sgn_c・2^7 - c_val_4) = 0
+    // selector・(qt_aux + sgn_qt・2^7 - qt_4) = 0
+    // selector・(h_4[3] + sgn_rem・2^7 - rem_4) = 0
+    let (sgn_b, sgn_c, sgn_qt, sgn_rem, overflow) = if signed {
+        let [sgn_b] = trace_eval!(trace_eval, SgnB);
+        let [sgn_c] = trace_eval!(trace_eval, SgnC);
+        let [sgn_qt] = trace_eval!(trace_eval, SgnQt);
+        let [sgn_rem] = trace_eval!(trace_eval, SgnRem);
+        let [overflow] = trace_eval!(trace_eval, DivOverflow);
+        let helper2 = trace_eval!(trace_eval, Helper2);
+        let helper3 = trace_eval!(trace_eval, Helper3);
+        let [qt_aux] = trace_eval!(trace_eval, QtAux);
+        let helper4 = trace_eval!(trace_eval, Helper4);
+        for (sign_removed, sgn, value) in [
+            (helper2[3].clone(), sgn_b.clone(), value_b[3].clone()),
+            (helper3[3].clone(), sgn_c.clone(), value_c[3].clone()),
+            (qt_aux, sgn_qt.clone(), qt[3].clone()),
+            (helper4[3].clone(), sgn_rem.clone(), rem[3].clone()),
+        ] {
+            eval.add_constraint(
+                selector.clone() * (sign_removed + sgn * BaseField::from(1 << 7) - value),
+            );
+        }
+        (sgn_b, sgn_c, sgn_qt, sgn_rem, overflow)
+    } else {
+        (
+            E::F::zero(),
+            E::F::zero(),
+            E::F::zero(),
+            E::F::zero(),
+            E::F::zero(),
+        )
+    };
+
+    // qt・c + rem = b (mod 2^64) on sign-extended values. In case of overflow, only the lower 32 bits are checked.
+    let chunks = mul_add_chunks(
+        trace_eval,
+        &sign_extend::<E>(qt.clone(), sgn_qt),
+        &sign_extend::<E>(value_c.clone(), sgn_c.clone()),
+        &sign_extend::<E>(rem.clone(), sgn_rem.clone()),
+        &sign_extend::<E>(value_b.clone(), sgn_b.clone()),
+    );
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        if i < WORD_SIZE / 2 {
+            eval.add_constraint(selector.clone() * chunk);
+        } else {
+            eval.add_constraint(selector.clone() * (one.clone() - overflow.clone()) * chunk);
+        }
+    }
+
+    // Overflow happens only for b = -2^31 and c = -1.
+    // selector・overflow・(b_val_i - [0, 0, 0, 2^7]_i) = 0
+    // selector・overflow・(c_val_i - (2^8-1)) = 0
+    if signed {
+        for i in 0..WORD_SIZE {
+            let min_limb: u32 = if i == WORD_SIZE - 1 { 1 << 7 } else { 0 };
+            eval.add_constraint(
+                selector.clone()
+                    * overflow.clone()
+                    * (value_b[i].clone() - E::F::from(BaseField::from(min_limb))),
+            );
+            eval.add_constraint(
+                selector.clone()
+                    * overflow.clone()
+                    * (value_c[i].clone() - E::F::from(BaseField::from(0xFF))),
+            );
+        }
+    }
+
+    // Division by zero: c = 0 and qt = 2^32 - 1, the remainder equals b by the equation above.
+    // selector・div_by_zero・c_val_i = 0
+    // selector・div_by_zero・(qt_i - (2^8-1)) = 0
+    for i in 0..WORD_SIZE {
+        eval.add_constraint(selector.clone() * div_by_zero.clone() * value_c[i].clone());
+        eval.add_constraint(
+            selector.clone()
+                * div_by_zero.clone()
+                * (qt[i].clone() - E::F::from(BaseField::from(0xFF))),
+        );
+    }
+
+    // Absolute values, computed as 0 - x for negative values.
+    // selector・(abs_k - (1-sgn)・x_k - sgn・(borrow_k・2^16 - x_k - borrow_{k-1})) = 0 for 16-bit chunks
+    let abs_c = trace_eval!(trace_eval, AbsC);
+    let abs_c_borrow = trace_eval!(trace_eval, AbsCBorrow);
+    let abs_rem = trace_eval!(trace_eval, AbsRem);
+    let abs_rem_borrow = trace_eval!(trace_eval, AbsRemBorrow);
+    for (abs, borrow, value, sgn) in [
+        (&abs_c, &abs_c_borrow, &value_c, sgn_c),
+        (&abs_rem, &abs_rem_borrow, &rem, sgn_rem.clone()),
+    ] {
+        let mut borrow_in = E::F::zero();
+        for k in 0..2 {
+            let abs_chunk = abs[2 * k].clone() + abs[2 * k + 1].clone() * BaseField::from(1 << 8);
+            let value_chunk =
+                value[2 * k].clone() + value[2 * k + 1].clone() * BaseField::from(1 << 8);
+            eval.add_constraint(
+                selector.clone()
+                    * (abs_chunk
+                        - (one.clone() - sgn.clone()) * value_chunk.clone()
+                        - sgn.clone()
+                            * (borrow[k].clone() * BaseField::from(1 << 16)
+                                - value_chunk
+                                - borrow_in)),
+            );
+            borrow_in = borrow[k].clone();
+        }
+    }
+
+    // Remainder bound |rem| < |c| unless dividing by zero: abs_rem + rem_diff + 1 = abs_c without overflow.
+    // selector・(1-div_by_zero)・(abs_rem_1 + rem_diff_1 + 1 - abs_c_1 - carry_1・2^16) = 0
+    // selector・(1-div_by_zero)・(abs_rem_2 + rem_diff_2 + carry_1 - abs_c_2) = 0
+    let rem_diff = trace_eval!(trace_eval, RemDiff);
+    let [carry, _] = trace_eval!(trace_eval, CarryFlag);
+    let chunk = |word: &[E::F; WORD_SIZE], k: usize| -> E::F {
+        word[2 * k].clone() + word[2 * k + 1].clone() * BaseField::from(1 << 8)
+    };
+    let not_div_by_zero = selector.clone() * (one.clone() - div_by_zero);
+    eval.add_constraint(
+        not_div_by_zero.clone()
+            * (chunk(&abs_rem, 0) + chunk(&rem_diff, 0) + one
+                - chunk(&abs_c, 0)
+                - carry.clone() * BaseField::from(1 << 16)),
+    );
+    eval.add_constraint(
+        not_div_by_zero * (chunk(&abs_rem, 1) + chunk(&rem_diff, 1) + carry - chunk(&abs_c, 1)),
+    );
+
+    // The remainder is zero or has the sign of the dividend.
+    // selector・(sgn_b - sgn_rem)・(rem_1 + rem_2 + rem_3 + rem_4) = 0
+    if signed {
+        let rem_sum = rem
+            .iter()
+            .cloned()
+            .reduce(|acc, limb| acc + limb)
+            .expect("word is not empty");
+        eval.add_constraint(selector * (sgn_b - sgn_rem) * rem_sum);
+    }
+}
+
+impl ExecuteChip for DivChip {
+    type ExecutionResult = ExecutionResult;
+
+    fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
+        execute_div(program_step, true)
+    }
+}
+
+impl MachineChip for DivChip {
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        if !matches!(
+            vm_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::DIV)
+        ) {
+            return;
+        }
+        let ExecutionResult { quotient, .. } = Self::execute(vm_step);
+        assert_eq!(
+            quotient,
+            vm_step
+                .get_result()
+                .expect("DIV instruction must have a result")
+        );
+        traces.fill_columns(row_idx, quotient, ValueA);
+        fill_div(traces, row_idx, vm_step, true);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+    ) {
+        let [is_div] = trace_eval!(trace_eval, IsDiv);
+        constrain_div(eval, trace_eval, is_div, true, Qt);
+    }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::DIV);
+    }
+}
+
+impl ExecuteChip for DivuChip {
+    type ExecutionResult = ExecutionResult;
+
+    fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
+        execute_div(program_step, false)
+    }
+}
+
+impl MachineChip for DivuChip {
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        if !matches!(
+            vm_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::DIVU)
+        ) {
+            return;
+        }
+        let ExecutionResult { quotient, .. } = Self::execute(vm_step);
+        assert_eq!(
+            quotient,
+            vm_step
+                .get_result()
+                .expect("DIVU instruction must have a result")
+        );
+        traces.fill_columns(row_idx, quotient, ValueA);
+        fill_div(traces, row_idx, vm_step, false);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+    ) {
+        let [is_divu] = trace_eval!(trace_eval, IsDivu);
+        constrain_div(eval, trace_eval, is_divu, false, Qt);
+    }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::DIVU);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use crate::{
+        chips::{
+            AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip,
+            RegisterMemCheckChip, SllChip, SubChip,
+        },
+        test_utils::assert_chip,
+        trace::{
+            program::iter_program_steps, program_trace::ProgramTracesBuilder, PreprocessedTraces,
+        },
+    };
+
+    use super::*;
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    /// Builds a basic block applying `opcodes` to every pair of registers among x0..=x7, which cover
+    /// zero, positive and negative values, the signed overflow pair and extreme values.
+    pub(crate) fn setup_basic_block_ir(opcodes: &[BuiltinOpcode]) -> Vec<BasicBlock> {
+        let mut instructions = vec![
+            // x1 = 20, x2 = 3
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 20),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 3),
+            // x3 = -20, x4 = -3
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 3, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 4, 0, 2),
+            // x5 = -2^31
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 5, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 5, 5, 31),
+            // x6 = -1
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 6, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 6, 0, 6),
+            // x7 = 2^31 - 1
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 7, 5, 6),
+        ];
+        for &opcode in opcodes {
+            for rs1 in 0..=7 {
+                for rs2 in 0..=7 {
+                    instructions.push(Instruction::new_ir(Opcode::from(opcode), 8, rs1, rs2));
+                }
+            }
+        }
+        vec![BasicBlock::new(instructions)]
+    }
+
+    #[test]
+    fn test_k_trace_constrained_div_instructions() {
+        type Chips = (
+            CpuChip,
+            DecodingCheckChip,
+            AddChip,
+            SubChip,
+            SllChip,
+            DivChip,
+            DivuChip,
+            RegisterMemCheckChip,
+            ProgramMemCheckChip,
+            RangeCheckChip,
+        );
+        let basic_block = setup_basic_block_ir(&[BuiltinOpcode::DIV, BuiltinOpcode::DIVU]);
+        let k = 1;
+
+        // Get traces from VM K-Trace interface
+        let (view, vm_traces) = k_trace_direct(&basic_block, k).expect("Failed to create trace");
+        let program_info = view.get_program_memory();
+
+        // Trace circuit
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_steps = iter_program_steps(&vm_traces, traces.num_rows());
+        let program_traces = ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, program_info);
+        let mut side_note = SideNote::new(&program_traces, &view);
+
+        for (row_idx, program_step) in program_steps.enumerate() {
+            Chips::fill_main_trace(&mut traces, row_idx, &program_step, &mut side_note);
+        }
+        assert_chip::<Chips>(traces, Some(program_traces.finalize()));
+    }
+}
@@ -5,6 +5,7 @@ use nexus_vm::riscv::BuiltinOpcode;
 use crate::{
     column::Column::{self, *},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -142,6 +143,10 @@ impl MachineChip for JalChip {
                     - pc_next[3].clone() * modulus.clone()),
         );
     }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::JAL);
+    }
 }
 
 #[cfg(test)]
@@ -5,6 +5,7 @@ use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 use crate::{
     column::Column::{self, *},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -198,6 +199,10 @@ impl MachineChip for JalrChip {
             eval.add_constraint(is_jalr.clone() * (pc_next_aux[i].clone() - pc_next[i].clone()));
         }
     }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::JALR);
+    }
 }
 
 #[cfg(test)]
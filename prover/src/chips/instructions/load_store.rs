@@ -20,6 +20,7 @@ use crate::{
         PreprocessedColumn, ProgramColumn,
     },
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{preprocessed_trace_eval, program_trace_eval, trace_eval},
         program_trace::ProgramTraces,
@@ -186,6 +187,20 @@ impl MachineChip for LoadStoreChip {
         eval.add_constraint(
             public_output_flag.clone() * (ram_final_value.clone() - public_output_value.clone()),
         );
+        // Public bytes must take part in memory checking, otherwise their initial and final values would be ignored.
+        // (initial_memory_flag + public_output_flag) ・(1 - ram_init_final_flag) = 0
+        let [ram_init_final_flag] = trace_eval!(trace_eval, Column::RamInitFinalFlag);
+        eval.add_constraint(
+            (initial_memory_flag.clone() + public_output_flag.clone())
+                * (E::F::one() - ram_init_final_flag.clone()),
+        );
+        // In a segment every accessed byte is public, so that its initial value cannot be made up by the prover.
+        // public_ram_complete ・ram_init_final_flag ・(1 - initial_memory_flag) = 0
+        let [public_ram_complete] =
+            program_trace_eval!(trace_eval, ProgramColumn::PublicRamComplete);
+        eval.add_constraint(
+            public_ram_complete * ram_init_final_flag * (E::F::one() - initial_memory_flag.clone()),
+        );
 
         // Computing ram1_ts_prev_aux = clk - 1 - ram1_ts_prev
         // Helper1 used for borrow handling
@@ -549,6 +564,19 @@ impl MachineChip for LoadStoreChip {
         );
         Self::constrain_final_values(eval, trace_eval, lookup_elements);
     }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.extend([
+            BuiltinOpcode::SB,
+            BuiltinOpcode::SH,
+            BuiltinOpcode::SW,
+            BuiltinOpcode::LB,
+            BuiltinOpcode::LH,
+            BuiltinOpcode::LBU,
+            BuiltinOpcode::LHU,
+            BuiltinOpcode::LW,
+        ]);
+    }
 }
 
 impl LoadStoreChip {
@@ -1073,6 +1101,7 @@ mod test {
                 range32::Range32Chip, range8::Range8Chip,
             },
             AddChip, BeqChip, BitOpChip, CpuChip, DecodingCheckChip, RegisterMemCheckChip, SllChip,
+            SyscallChip,
         },
         machine::Machine,
         test_utils::assert_chip,
@@ -1152,6 +1181,8 @@ mod test {
             AddChip,
             BeqChip,
             SllChip,
+            // Draws lookup elements of the built-in private input extension.
+            SyscallChip,
             LoadStoreChip,
             RegisterMemCheckChip,
             Range8Chip,
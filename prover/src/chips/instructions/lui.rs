@@ -5,6 +5,7 @@ use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 use crate::{
     column::Column::{self, *},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -72,6 +73,10 @@ impl MachineChip for LuiChip {
             eval.add_constraint(is_lui.clone() * (value_c[i].clone() - value_a[i].clone()));
         }
     }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::LUI);
+    }
 }
 
 #[cfg(test)]
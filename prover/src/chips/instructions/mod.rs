@@ -7,10 +7,15 @@ pub(crate) mod bit_op;
 pub(crate) mod blt;
 pub(crate) mod bltu;
 pub(crate) mod bne;
+pub(crate) mod custom;
+pub(crate) mod div;
 pub(crate) mod jal;
 pub(crate) mod jalr;
 pub(crate) mod load_store;
 pub(crate) mod lui;
+pub(crate) mod mul;
+pub(crate) mod mulh;
+pub(crate) mod rem;
 pub(crate) mod sll;
 pub(crate) mod slt;
 pub(crate) mod sltu;
@@ -21,8 +26,9 @@ pub(crate) mod syscall;
 
 pub use self::{
     add::add_with_carries, add::AddChip, auipc::AuipcChip, beq::BeqChip, bge::BgeChip,
-    bgeu::BgeuChip, bit_op::BitOpChip, blt::BltChip, bltu::BltuChip, bne::BneChip, jal::JalChip,
-    jalr::JalrChip, load_store::LoadStoreChip, lui::LuiChip, sll::SllChip, slt::SltChip,
-    sltu::SltuChip, sra::SraChip, srl::SrlChip, sub::subtract_with_borrow, sub::SubChip,
-    syscall::SyscallChip,
+    bgeu::BgeuChip, bit_op::BitOpChip, blt::BltChip, bltu::BltuChip, bne::BneChip,
+    custom::CustomChip, div::DivChip, div::DivuChip, jal::JalChip, jalr::JalrChip,
+    load_store::LoadStoreChip, lui::LuiChip, mul::MulChip, mulh::MulhChip, mulh::MulhsuChip,
+    mulh::MulhuChip, rem::RemChip, rem::RemuChip, sll::SllChip, slt::SltChip, sltu::SltuChip,
+    sra::SraChip, srl::SrlChip, sub::subtract_with_borrow, sub::SubChip, syscall::SyscallChip,
 };
@@ -0,0 +1,353 @@
+use num_traits::Zero;
+use stwo_prover::{constraint_framework::EvalAtRow, core::fields::m31::BaseField};
+
+use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+
+use crate::{
+    column::Column::{self, *},
+    components::AllLookupElements,
+    preflight::Support,
+    trace::{
+        eval::{trace_eval, TraceEval},
+        sidenote::SideNote,
+        ProgramStep, TracesBuilder, Word,
+    },
+    traits::{ExecuteChip, MachineChip},
+};
+
+// Support MUL opcode.
+pub struct MulChip;
+
+pub struct ExecutionResult {
+    /// Lower 64 bits of the product in 8-bit limbs.
+    pub product: [u8; 2 * WORD_SIZE],
+    /// Carries of the product at 16-bit boundaries.
+    pub carries: [u16; WORD_SIZE],
+}
+
+/// Computes the lower 64 bits of `b * c`, where `sgn_b` and `sgn_c` are the sign bits of operands interpreted as
+/// signed values: an operand is extended with ones if its flag is set, and with zeroes otherwise.
+///
+/// Returns the product in 8-bit limbs and the carries at 16-bit boundaries.
+pub fn mul_with_carries(
+    b: Word,
+    c: Word,
+    sgn_b: bool,
+    sgn_c: bool,
+) -> ([u8; 2 * WORD_SIZE], [u16; WORD_SIZE]) {
+    mul_add_with_carries(b, c, [0u8; WORD_SIZE], sgn_b, sgn_c, false)
+}
+
+/// Computes the lower 64 bits of `b * c + d`, where every operand is extended with ones to 64 bits if the
+/// corresponding sign flag is set, and with zeroes otherwise.
+///
+/// Returns the result in 8-bit limbs and the carries at 16-bit boundaries.
+pub fn mul_add_with_carries(
+    b: Word,
+    c: Word,
+    d: Word,
+    sgn_b: bool,
+    sgn_c: bool,
+    sgn_d: bool,
+) -> ([u8; 2 * WORD_SIZE], [u16; WORD_SIZE]) {
+    let extend = |word: Word, sgn: bool| -> [u64; 2 * WORD_SIZE] {
+        std::array::from_fn(|i| match (i < WORD_SIZE, sgn) {
+            (true, _) => word[i] as u64,
+            (false, true) => 0xFF,
+            (false, false) => 0,
+        })
+    };
+    let b = extend(b, sgn_b);
+    let c = extend(c, sgn_c);
+    let d = extend(d, sgn_d);
+    // Sum of limb products contributing to the k-th limb of the result.
+    let partial_product =
+        |k: usize| -> u64 { (0..=k).map(|i| b[i] * c[k - i]).sum::<u64>() + d[k] };
+
+    let mut product = [0u8; 2 * WORD_SIZE];
+    let mut carries = [0u16; WORD_SIZE];
+    let mut carry = 0u64;
+    for (chunk, chunk_carry) in carries.iter_mut().enumerate() {
+        let k = 2 * chunk;
+        let sum = partial_product(k) + (partial_product(k + 1) << 8) + carry;
+        product[k] = sum as u8;
+        product[k + 1] = (sum >> 8) as u8;
+        carry = sum >> 16;
+        *chunk_carry = carry as u16;
+    }
+    (product, carries)
+}
+
+/// Fills [`Column::MulCarry`] and [`Column::MulCarryHigh`] with the carries returned by [`mul_with_carries`].
+pub(crate) fn fill_mul_carries(
+    traces: &mut TracesBuilder,
+    row_idx: usize,
+    carries: [u16; WORD_SIZE],
+) {
+    let carry_low = carries.map(|carry| (carry & 0xFF) as u8);
+    let carry_high = carries.map(|carry| (carry >> 8) as u8);
+    traces.fill_columns(row_idx, carry_low, MulCarry);
+    traces.fill_columns(row_idx, carry_high, MulCarryHigh);
+}
+
+/// Extends a word in 8-bit limbs to 64 bits, `sgn` is the sign bit for signed words and zero otherwise.
+pub(crate) fn sign_extend<E: EvalAtRow>(
+    word: [E::F; WORD_SIZE],
+    sgn: E::F,
+) -> [E::F; 2 * WORD_SIZE] {
+    std::array::from_fn(|i| {
+        if i < WORD_SIZE {
+            word[i].clone()
+        } else {
+            sgn.clone() * BaseField::from(0xFF)
+        }
+    })
+}
+
+/// Returns the expressions of `lhs * rhs + addend - result (mod 2^64)` at 16-bit boundaries, using
+/// [`Column::MulCarry`] and [`Column::MulCarryHigh`] as carries. All arguments are 64-bit values in 8-bit limbs.
+///
+/// The i-th expression is s_{2i} + s_{2i+1}・2^8 + carry_{i-1} - p_{2i} - p_{2i+1}・2^8 - carry_i・2^16,
+/// where s_k = sum_{i + j = k} lhs_i・rhs_j + addend_k and carry_i = mul_carry_i + mul_carry_high_i・2^8.
+///
+/// All limbs of the arguments and [`Column::MulCarry`] are assumed to be range-checked to 8 bits,
+/// and [`Column::MulCarryHigh`] to 3 bits, which keeps both sides of every equation below the modulus of M31.
+pub(crate) fn mul_add_chunks<E: EvalAtRow>(
+    trace_eval: &TraceEval<E>,
+    lhs: &[E::F; 2 * WORD_SIZE],
+    rhs: &[E::F; 2 * WORD_SIZE],
+    addend: &[E::F; 2 * WORD_SIZE],
+    result: &[E::F; 2 * WORD_SIZE],
+) -> [E::F; WORD_SIZE] {
+    let carry = trace_eval!(trace_eval, MulCarry);
+    let carry_high = trace_eval!(trace_eval, MulCarryHigh);
+
+    let partial_product = |k: usize| -> E::F {
+        (0..=k).fold(addend[k].clone(), |acc, i| {
+            acc + lhs[i].clone() * rhs[k - i].clone()
+        })
+    };
+
+    let mut carry_in = E::F::zero();
+    std::array::from_fn(|chunk| {
+        let k = 2 * chunk;
+        let carry_out = carry[chunk].clone() + carry_high[chunk].clone() * BaseField::from(1 << 8);
+        let expr = partial_product(k)
+            + partial_product(k + 1) * BaseField::from(1 << 8)
+            + carry_in.clone()
+            - (result[k].clone()
+                + result[k + 1].clone() * BaseField::from(1 << 8)
+                + carry_out.clone() * BaseField::from(1 << 16));
+        carry_in = carry_out;
+        expr
+    })
+}
+
+/// Adds constraints for `lhs * rhs = product_high・2^32 + product_low (mod 2^64)` on rows where `selector` is set.
+///
+/// The operands are sign-extended to 64 bits with `sgn_lhs` and `sgn_rhs`; zero should be passed for unsigned operands.
+pub(crate) fn constrain_mul<E: EvalAtRow>(
+    eval: &mut E,
+    trace_eval: &TraceEval<E>,
+    selector: E::F,
+    (lhs, sgn_lhs): (Column, E::F),
+    (rhs, sgn_rhs): (Column, E::F),
+    (product_low, product_high): (Column, Column),
+) {
+    let lhs = sign_extend::<E>(trace_eval.column_eval(lhs), sgn_lhs);
+    let rhs = sign_extend::<E>(trace_eval.column_eval(rhs), sgn_rhs);
+    let product_low = trace_eval.column_eval::<WORD_SIZE>(product_low);
+    let product_high = trace_eval.column_eval::<WORD_SIZE>(product_high);
+    let product: [E::F; 2 * WORD_SIZE] = std::array::from_fn(|i| {
+        if i < WORD_SIZE {
+            product_low[i].clone()
+        } else {
+            product_high[i - WORD_SIZE].clone()
+        }
+    });
+    let addend = std::array::from_fn(|_| E::F::zero());
+
+    for chunk in mul_add_chunks(trace_eval, &lhs, &rhs, &addend, &product) {
+        eval.add_constraint(selector.clone() * chunk);
+    }
+}
+
+impl ExecuteChip for MulChip {
+    type ExecutionResult = ExecutionResult;
+
+    fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
+        let value_b = program_step.get_value_b();
+        let (value_c, _) = program_step.get_value_c();
+
+        // The lower 32 bits of the product don't depend on the signedness of operands.
+        let (product, carries) = mul_with_carries(value_b, value_c, false, false);
+        ExecutionResult { product, carries }
+    }
+}
+
+impl MachineChip for MulChip {
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        if !matches!(
+            vm_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::MUL)
+        ) {
+            return;
+        }
+
+        let ExecutionResult { product, carries } = Self::execute(vm_step);
+        let (low, high) = product.split_at(WORD_SIZE);
+
+        assert_eq!(
+            low,
+            vm_step
+                .get_result()
+                .expect("MUL instruction must have a result")
+        );
+        traces.fill_columns_bytes(row_idx, low, ValueA);
+        traces.fill_columns_bytes(row_idx, high, Helper1);
+        fill_mul_carries(traces, row_idx, carries);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+    ) {
+        let [is_mul] = trace_eval!(trace_eval, IsMul);
+
+        // The result is the lower word of the unsigned product, the upper word is kept in Helper1.
+        constrain_mul(
+            eval,
+            trace_eval,
+            is_mul,
+            (ValueB, E::F::zero()),
+            (ValueC, E::F::zero()),
+            (ValueA, Helper1),
+        );
+    }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::MUL);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        chips::{
+            AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip,
+            RegisterMemCheckChip, SllChip, SubChip,
+        },
+        test_utils::assert_chip,
+        trace::{
+            program::iter_program_steps, program_trace::ProgramTracesBuilder, PreprocessedTraces,
+        },
+    };
+
+    use super::*;
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    fn setup_basic_block_ir() -> Vec<BasicBlock> {
+        let basic_block = BasicBlock::new(vec![
+            // Set x1 = 5, x2 = 7
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 5),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 7),
+            // x3 = x1 * x2 = 35
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 3, 1, 2),
+            // x4 = -5
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 4, 0, 1),
+            // x5 = x4 * x2 = -35
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 5, 4, 2),
+            // x6 = x4 * x4 = 25
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 6, 4, 4),
+            // x7 = x6 * x0 = 0
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 7, 6, 0),
+            // x8 = 1 << 31
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 8, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 8, 8, 31),
+            // x9 = x8 * x8 = 0 (overflow is truncated)
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 9, 8, 8),
+            // x10 = 0xFFFFFFFF * 0xFFFFFFFF = 1 (largest carries)
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 11, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 10, 0, 11),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 10, 10, 10),
+            // x0 = x1 * x2, discarded result
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 0, 1, 2),
+        ]);
+        vec![basic_block]
+    }
+
+    #[test]
+    fn test_mul_with_carries() {
+        let cases = [
+            (0u32, 0u32),
+            (5, 7),
+            (u32::MAX, u32::MAX),
+            (0x8000_0000, 0x8000_0000),
+            (0x1234_5678, 0x9ABC_DEF0),
+        ];
+        for (b, c) in cases {
+            for (signed_b, signed_c) in [(false, false), (true, false), (true, true)] {
+                let sgn_b = signed_b && (b >> 31) == 1;
+                let sgn_c = signed_c && (c >> 31) == 1;
+                let (product, _) = mul_with_carries(b.to_le_bytes(), c.to_le_bytes(), sgn_b, sgn_c);
+                let extend = |x: u32, signed: bool| {
+                    if signed {
+                        x as i32 as i64 as u64
+                    } else {
+                        x as u64
+                    }
+                };
+                let expected = extend(b, signed_b).wrapping_mul(extend(c, signed_c));
+                assert_eq!(u64::from_le_bytes(product), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_k_trace_constrained_mul_instructions() {
+        type Chips = (
+            CpuChip,
+            DecodingCheckChip,
+            AddChip,
+            SubChip,
+            SllChip,
+            MulChip,
+            RegisterMemCheckChip,
+            ProgramMemCheckChip,
+            RangeCheckChip,
+        );
+        let basic_block = setup_basic_block_ir();
+        let k = 1;
+
+        // Get traces from VM K-Trace interface
+        let (view, vm_traces) = k_trace_direct(&basic_block, k).expect("Failed to create trace");
+        let program_info = view.get_program_memory();
+
+        // Trace circuit
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_steps = iter_program_steps(&vm_traces, traces.num_rows());
+        let program_traces = ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, program_info);
+        let mut side_note = SideNote::new(&program_traces, &view);
+
+        for (row_idx, program_step) in program_steps.enumerate() {
+            Chips::fill_main_trace(&mut traces, row_idx, &program_step, &mut side_note);
+        }
+        assert_chip::<Chips>(traces, Some(program_traces.finalize()));
+    }
+}
@@ -0,0 +1,361 @@
+use num_traits::Zero;
+use stwo_prover::{constraint_framework::EvalAtRow, core::fields::m31::BaseField};
+
+use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+
+use crate::{
+    column::Column::{self, *},
+    components::AllLookupElements,
+    preflight::Support,
+    trace::{
+        eval::{trace_eval, TraceEval},
+        sidenote::SideNote,
+        ProgramStep, TracesBuilder,
+    },
+    traits::{ExecuteChip, MachineChip},
+};
+
+use super::mul::{constrain_mul, fill_mul_carries, mul_with_carries, ExecutionResult};
+
+// Support MULH opcode.
+pub struct MulhChip;
+// Support MULHSU opcode.
+pub struct MulhsuChip;
+// Support MULHU opcode.
+pub struct MulhuChip;
+
+/// Computes the 64-bit product for the upper-word multiplications, `signed_b` and `signed_c` tell whether
+/// the corresponding operand is interpreted as a signed value.
+fn execute_mulh(program_step: &ProgramStep, signed_b: bool, signed_c: bool) -> ExecutionResult {
+    let value_b = program_step.get_value_b();
+    let (value_c, _) = program_step.get_value_c();
+    let sgn_b = signed_b && program_step.get_sgn_b();
+    let sgn_c = signed_c && program_step.get_sgn_c();
+
+    let (product, carries) = mul_with_carries(value_b, value_c, sgn_b, sgn_c);
+    ExecutionResult { product, carries }
+}
+
+/// Fills the main trace for the upper-word multiplications.
+///
+/// The result (upper word) goes into ValueA and the lower word into Helper1. For signed operands, the sign bit is
+/// stored in SgnB (SgnC), and the operand with its most significant bit cleared in Helper2 (Helper3).
+fn fill_mulh(
+    traces: &mut TracesBuilder,
+    row_idx: usize,
+    vm_step: &ProgramStep,
+    signed_b: bool,
+    signed_c: bool,
+) {
+    let ExecutionResult { product, carries } = execute_mulh(vm_step, signed_b, signed_c);
+    let (low, high) = product.split_at(WORD_SIZE);
+
+    assert_eq!(
+        high,
+        vm_step
+            .get_result()
+            .expect("MULH, MULHSU and MULHU instructions must have a result")
+    );
+    traces.fill_columns_bytes(row_idx, high, ValueA);
+    traces.fill_columns_bytes(row_idx, low, Helper1);
+    fill_mul_carries(traces, row_idx, carries);
+
+    if signed_b {
+        let mut helper_b = vm_step.get_value_b();
+        helper_b[WORD_SIZE - 1] &= 0x7f;
+        traces.fill_columns(row_idx, helper_b, Helper2);
+        traces.fill_columns(row_idx, vm_step.get_sgn_b(), SgnB);
+    }
+    if signed_c {
+        let (mut helper_c, _) = vm_step.get_value_c();
+        helper_c[WORD_SIZE - 1] &= 0x7f;
+        traces.fill_columns(row_idx, helper_c, Helper3);
+        traces.fill_columns(row_idx, vm_step.get_sgn_c(), SgnC);
+    }
+}
+
+/// Constrains `sgn` to be the most significant bit of `value`, given the last limb of `helper` is range-checked to 7 bits.
+///
+/// selector・(h[3] + sgn・2^7 - value[3]) = 0
+fn constrain_sign_bit<E: EvalAtRow>(
+    eval: &mut E,
+    trace_eval: &TraceEval<E>,
+    selector: E::F,
+    value: Column,
+    helper: Column,
+    sgn: E::F,
+) {
+    let value = trace_eval.column_eval::<WORD_SIZE>(value);
+    let helper = trace_eval.column_eval::<WORD_SIZE>(helper);
+    eval.add_constraint(
+        selector
+            * (helper[WORD_SIZE - 1].clone() + sgn * BaseField::from(1 << 7)
+                - value[WORD_SIZE - 1].clone()),
+    );
+}
+
+impl ExecuteChip for MulhChip {
+    type ExecutionResult = ExecutionResult;
+
+    fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
+        execute_mulh(program_step, true, true)
+    }
+}
+
+impl MachineChip for MulhChip {
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        if !matches!(
+            vm_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::MULH)
+        ) {
+            return;
+        }
+        fill_mulh(traces, row_idx, vm_step, true, true);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+    ) {
+        let [is_mulh] = trace_eval!(trace_eval, IsMulh);
+        let [sgn_b] = trace_eval!(trace_eval, SgnB);
+        let [sgn_c] = trace_eval!(trace_eval, SgnC);
+
+        constrain_sign_bit(
+            eval,
+            trace_eval,
+            is_mulh.clone(),
+            ValueB,
+            Helper2,
+            sgn_b.clone(),
+        );
+        constrain_sign_bit(
+            eval,
+            trace_eval,
+            is_mulh.clone(),
+            ValueC,
+            Helper3,
+            sgn_c.clone(),
+        );
+        constrain_mul(
+            eval,
+            trace_eval,
+            is_mulh,
+            (ValueB, sgn_b),
+            (ValueC, sgn_c),
+            (Helper1, ValueA),
+        );
+    }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::MULH);
+    }
+}
+
+impl ExecuteChip for MulhsuChip {
+    type ExecutionResult = ExecutionResult;
+
+    fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
+        execute_mulh(program_step, true, false)
+    }
+}
+
+impl MachineChip for MulhsuChip {
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        if !matches!(
+            vm_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::MULHSU)
+        ) {
+            return;
+        }
+        fill_mulh(traces, row_idx, vm_step, true, false);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+    ) {
+        let [is_mulhsu] = trace_eval!(trace_eval, IsMulhsu);
+        let [sgn_b] = trace_eval!(trace_eval, SgnB);
+
+        constrain_sign_bit(
+            eval,
+            trace_eval,
+            is_mulhsu.clone(),
+            ValueB,
+            Helper2,
+            sgn_b.clone(),
+        );
+        constrain_mul(
+            eval,
+            trace_eval,
+            is_mulhsu,
+            (ValueB, sgn_b),
+            (ValueC, E::F::zero()),
+            (Helper1, ValueA),
+        );
+    }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::MULHSU);
+    }
+}
+
+impl ExecuteChip for MulhuChip {
+    type ExecutionResult = ExecutionResult;
+
+    fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
+        execute_mulh(program_step, false, false)
+    }
+}
+
+impl MachineChip for MulhuChip {
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        if !matches!(
+            vm_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::MULHU)
+        ) {
+            return;
+        }
+        fill_mulh(traces, row_idx, vm_step, false, false);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+    ) {
+        let [is_mulhu] = trace_eval!(trace_eval, IsMulhu);
+
+        constrain_mul(
+            eval,
+            trace_eval,
+            is_mulhu,
+            (ValueB, E::F::zero()),
+            (ValueC, E::F::zero()),
+            (Helper1, ValueA),
+        );
+    }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::MULHU);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        chips::{
+            AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip,
+            RegisterMemCheckChip, SllChip, SubChip,
+        },
+        test_utils::assert_chip,
+        trace::{
+            program::iter_program_steps, program_trace::ProgramTracesBuilder, PreprocessedTraces,
+        },
+    };
+
+    use super::*;
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    fn setup_basic_block_ir() -> Vec<BasicBlock> {
+        let mut instructions = vec![
+            // x1 = 5, x2 = -7
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 5),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 7),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 2, 0, 2),
+            // x3 = 0x7FFFFFFF
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 3, 3, 31),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 4, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 3, 3, 4),
+            // x4 = 0x80000000
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 4, 4, 31),
+            // x5 = -1
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 5, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 5, 5, 4),
+        ];
+        // Every pair of the operands above, including x0.
+        for opcode in [
+            BuiltinOpcode::MULH,
+            BuiltinOpcode::MULHSU,
+            BuiltinOpcode::MULHU,
+        ] {
+            for rs1 in 0..=5 {
+                for rs2 in 0..=5 {
+                    instructions.push(Instruction::new_ir(Opcode::from(opcode), 6, rs1, rs2));
+                }
+            }
+        }
+        vec![BasicBlock::new(instructions)]
+    }
+
+    #[test]
+    fn test_k_trace_constrained_mulh_instructions() {
+        type Chips = (
+            CpuChip,
+            DecodingCheckChip,
+            AddChip,
+            SubChip,
+            SllChip,
+            MulhChip,
+            MulhsuChip,
+            MulhuChip,
+            RegisterMemCheckChip,
+            ProgramMemCheckChip,
+            RangeCheckChip,
+        );
+        let basic_block = setup_basic_block_ir();
+        let k = 1;
+
+        // Get traces from VM K-Trace interface
+        let (view, vm_traces) = k_trace_direct(&basic_block, k).expect("Failed to create trace");
+        let program_info = view.get_program_memory();
+
+        // Trace circuit
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_steps = iter_program_steps(&vm_traces, traces.num_rows());
+        let program_traces = ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, program_info);
+        let mut side_note = SideNote::new(&program_traces, &view);
+
+        for (row_idx, program_step) in program_steps.enumerate() {
+            Chips::fill_main_trace(&mut traces, row_idx, &program_step, &mut side_note);
+        }
+        assert_chip::<Chips>(traces, Some(program_traces.finalize()));
+    }
+}
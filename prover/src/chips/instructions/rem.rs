@@ -0,0 +1,174 @@
+use stwo_prover::constraint_framework::EvalAtRow;
+
+use nexus_vm::riscv::BuiltinOpcode;
+
+use crate::{
+    column::Column::{self, *},
+    components::AllLookupElements,
+    preflight::Support,
+    trace::{
+        eval::{trace_eval, TraceEval},
+        sidenote::SideNote,
+        ProgramStep, TracesBuilder,
+    },
+    traits::{ExecuteChip, MachineChip},
+};
+
+use super::div::{constrain_div, execute_div, fill_div, ExecutionResult};
+
+// Support REM opcode.
+pub struct RemChip;
+// Support REMU opcode.
+pub struct RemuChip;
+
+impl ExecuteChip for RemChip {
+    type ExecutionResult = ExecutionResult;
+
+    fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
+        execute_div(program_step, true)
+    }
+}
+
+impl MachineChip for RemChip {
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        if !matches!(
+            vm_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::REM)
+        ) {
+            return;
+        }
+        let ExecutionResult { remainder, .. } = Self::execute(vm_step);
+        assert_eq!(
+            remainder,
+            vm_step
+                .get_result()
+                .expect("REM instruction must have a result")
+        );
+        traces.fill_columns(row_idx, remainder, ValueA);
+        fill_div(traces, row_idx, vm_step, true);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+    ) {
+        let [is_rem] = trace_eval!(trace_eval, IsRem);
+        constrain_div(eval, trace_eval, is_rem, true, Rem);
+    }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::REM);
+    }
+}
+
+impl ExecuteChip for RemuChip {
+    type ExecutionResult = ExecutionResult;
+
+    fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
+        execute_div(program_step, false)
+    }
+}
+
+impl MachineChip for RemuChip {
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        if !matches!(
+            vm_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::REMU)
+        ) {
+            return;
+        }
+        let ExecutionResult { remainder, .. } = Self::execute(vm_step);
+        assert_eq!(
+            remainder,
+            vm_step
+                .get_result()
+                .expect("REMU instruction must have a result")
+        );
+        traces.fill_columns(row_idx, remainder, ValueA);
+        fill_div(traces, row_idx, vm_step, false);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+    ) {
+        let [is_remu] = trace_eval!(trace_eval, IsRemu);
+        constrain_div(eval, trace_eval, is_remu, false, Rem);
+    }
+
+    fn declare_support(support: &mut Support) {
+        support.opcodes.insert(BuiltinOpcode::REMU);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        chips::{
+            instructions::div::test::setup_basic_block_ir, AddChip, CpuChip, DecodingCheckChip,
+            ProgramMemCheckChip, RangeCheckChip, RegisterMemCheckChip, SllChip, SubChip,
+        },
+        test_utils::assert_chip,
+        trace::{
+            program::iter_program_steps, program_trace::ProgramTracesBuilder, PreprocessedTraces,
+        },
+    };
+
+    use super::*;
+    use nexus_vm::{emulator::InternalView, trace::k_trace_direct};
+
+    const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    #[test]
+    fn test_k_trace_constrained_rem_instructions() {
+        type Chips = (
+            CpuChip,
+            DecodingCheckChip,
+            AddChip,
+            SubChip,
+            SllChip,
+            RemChip,
+            RemuChip,
+            RegisterMemCheckChip,
+            ProgramMemCheckChip,
+            RangeCheckChip,
+        );
+        let basic_block = setup_basic_block_ir(&[BuiltinOpcode::REM, BuiltinOpcode::REMU]);
+        let k = 1;
+
+        // Get traces from VM K-Trace interface
+        let (view, vm_traces) = k_trace_direct(&basic_block, k).expect("Failed to create trace");
+        let program_info = view.get_program_memory();
+
+        // Trace circuit
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_steps = iter_program_steps(&vm_traces, traces.num_rows());
+        let program_traces = ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, program_info);
+        let mut side_note = SideNote::new(&program_traces, &view);
+
+        for (row_idx, program_step) in program_steps.enumerate() {
+            Chips::fill_main_trace(&mut traces, row_idx, &program_step, &mut side_note);
+        }
+        assert_chip::<Chips>(traces, Some(program_traces.finalize()));
+    }
+}
@@ -6,6 +6,7 @@ use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 use crate::{
     column::Column::{self},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -201,6 +202,12 @@ impl MachineChip for SllChip {
                     - rem[0].clone() * sh4.clone() * sh5.clone()),
         );
     }
+
+    fn declare_support(support: &mut Support) {
+        support
+            .opcodes
+            .extend([BuiltinOpcode::SLL, BuiltinOpcode::SLLI]);
+    }
 }
 
 #[cfg(test)]
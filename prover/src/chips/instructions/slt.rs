@@ -7,6 +7,7 @@ use crate::{
     chips::SubChip,
     column::Column::{self, *},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -178,6 +179,12 @@ impl MachineChip for SltChip {
                     - value_c[3].clone()),
         );
     }
+
+    fn declare_support(support: &mut Support) {
+        support
+            .opcodes
+            .extend([BuiltinOpcode::SLT, BuiltinOpcode::SLTI]);
+    }
 }
 
 #[cfg(test)]
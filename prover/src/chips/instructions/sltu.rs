@@ -6,6 +6,7 @@ use crate::{
     chips::SubChip,
     column::Column::{self, *},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -121,6 +122,12 @@ impl MachineChip for SltuChip {
             }
         }
     }
+
+    fn declare_support(support: &mut Support) {
+        support
+            .opcodes
+            .extend([BuiltinOpcode::SLTU, BuiltinOpcode::SLTIU]);
+    }
 }
 
 #[cfg(test)]
@@ -6,6 +6,7 @@ use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
 use crate::{
     column::Column::{self},
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
@@ -315,6 +316,12 @@ impl MachineChip for SraChip {
             );
         }
     }
+
+    fn declare_support(support: &mut Support) {
+        support
+            .opcodes
+            .extend([BuiltinOpcode::SRA, BuiltinOpcode::SRAI]);
+    }
 }
 
 #[cfg(test)]
@@ -1,15 +1,33 @@
+use std::collections::BTreeMap;
+
+use nexus_common::cpu::Registers;
 use num_traits::One;
-use stwo_prover::{constraint_framework::EvalAtRow, core::fields::m31::BaseField};
+use stwo_prover::{
+    constraint_framework::{logup::LogupTraceGenerator, EvalAtRow, Relation, RelationEntry},
+    core::{
+        backend::simd::{column::BaseColumn, m31::LOG_N_LANES},
+        fields::m31::BaseField,
+    },
+};
 
-use nexus_vm::{riscv::BuiltinOpcode, SyscallCode};
+use nexus_vm::{
+    memory::{MemAccessSize, MemoryRecord},
+    riscv::{BuiltinOpcode, Register},
+    SyscallCode, WORD_SIZE,
+};
 
 use crate::{
-    column::Column::{self},
+    column::{
+        Column::{self, IsSysPrivInputBytes, Reg3ValPrev, ValueA},
+        PreprocessedColumn,
+    },
     components::AllLookupElements,
+    preflight::Support,
     trace::{
-        eval::{trace_eval, TraceEval},
-        sidenote::SideNote,
-        ProgramStep, TracesBuilder,
+        eval::{preprocessed_trace_eval, trace_eval, TraceEval},
+        program_trace::ProgramTraces,
+        sidenote::{PrecompileMemoryAccess, PrivateInputRead, SideNote},
+        FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
     virtual_column::IsTypeSys,
@@ -17,14 +35,88 @@ use crate::{
 
 use crate::virtual_column::VirtualColumn;
 
+/// The tuple consists of clk, the timestamp of the second register slot, the buffer address and the number of bytes
+/// read.
+const LOOKUP_TUPLE_SIZE: usize = 4 * WORD_SIZE;
+stwo_prover::relation!(PrivateInputLookupElements, LOOKUP_TUPLE_SIZE);
+
+/// A chip for system calls.
+///
+/// Most system calls only write a register, `ReadBytesFromPrivateInput` also stores a run of bytes into memory. Its
+/// row adds (clk, reg2_ts, buf, count) to [`PrivateInputLookupElements`], and the built-in private input extension
+/// removes the tuple after proving the stores together with the read of the buffer length from the unused second
+/// register slot.
 pub struct SyscallChip;
 
+impl SyscallChip {
+    /// Records the register read and memory stores of `ReadBytesFromPrivateInput` made outside of the main trace.
+    fn record_private_input_read(row_idx: usize, vm_step: &ProgramStep, side_note: &mut SideNote) {
+        let clk = row_idx as u32 + 1;
+        let buf = vm_step.regs.read(Register::X10);
+        let buflen = vm_step.regs.read(Register::X11);
+        let reg2_cur_ts = clk * 3 + 2;
+        let buflen_prev_timestamp = side_note
+            .register_mem_check
+            .access(Register::X11 as u32, reg2_cur_ts, buflen)
+            .prev_timestamp;
+
+        let mut stores: BTreeMap<u32, u8> = BTreeMap::new();
+        for record in vm_step.step.memory_records.iter() {
+            assert!(
+                matches!(record, MemoryRecord::StoreRecord(..))
+                    && record.get_size() == MemAccessSize::Byte,
+                "private input is stored byte by byte"
+            );
+            assert_eq!(record.get_timestamp(), clk, "timestamp mismatch");
+            stores.insert(record.get_address(), record.get_value() as u8);
+        }
+        let memory: Vec<PrecompileMemoryAccess> = stores
+            .into_iter()
+            .enumerate()
+            .map(|(i, (address, value))| {
+                assert_eq!(
+                    address,
+                    buf.wrapping_add(i as u32),
+                    "private input is stored into the buffer"
+                );
+                let (prev_timestamp, prev_value) = side_note
+                    .rw_mem_check
+                    .last_access
+                    .insert(address, (clk, value))
+                    .unwrap_or((0, 0));
+                PrecompileMemoryAccess {
+                    address,
+                    prev_value,
+                    prev_timestamp,
+                    value,
+                }
+            })
+            .collect();
+        assert!(memory.len() <= buflen as usize, "buffer overflow");
+
+        side_note.private_input_reads.push(PrivateInputRead {
+            clk,
+            buf,
+            buflen,
+            buflen_prev_timestamp,
+            memory,
+        });
+    }
+}
+
 impl MachineChip for SyscallChip {
+    fn draw_lookup_elements(
+        all_elements: &mut AllLookupElements,
+        channel: &mut impl stwo_prover::core::channel::Channel,
+    ) {
+        all_elements.insert(PrivateInputLookupElements::draw(channel));
+    }
+
     fn fill_main_trace(
         traces: &mut TracesBuilder,
         row_idx: usize,
         vm_step: &Option<ProgramStep>,
-        _side_note: &mut SideNote,
+        side_note: &mut SideNote,
     ) {
         let vm_step = match vm_step {
             Some(vm_step) => vm_step,
@@ -65,6 +157,19 @@ impl MachineChip for SyscallChip {
                 traces.fill_columns(row_idx, true, Column::IsSysHeapReset);
                 traces.fill_columns(row_idx, result, Column::ValueA);
             }
+            (0x404, Some(result)) => {
+                traces.fill_columns(row_idx, true, Column::IsSysAuxInput);
+                traces.fill_columns(row_idx, result, Column::ValueA);
+            }
+            (0x405, Some(result)) => {
+                traces.fill_columns(row_idx, true, Column::IsSysOracle);
+                traces.fill_columns(row_idx, result, Column::ValueA);
+            }
+            (0x406, Some(result)) => {
+                traces.fill_columns(row_idx, true, Column::IsSysPrivInputBytes);
+                traces.fill_columns(row_idx, result, Column::ValueA);
+                Self::record_private_input_read(row_idx, vm_step, side_note);
+            }
             _ => {
                 panic!(
                     "Unknown syscall number: 0x{:x} and result: {:?}, on row {}",
@@ -74,10 +179,43 @@ impl MachineChip for SyscallChip {
         };
     }
 
+    fn fill_interaction_trace(
+        logup_trace_gen: &mut LogupTraceGenerator,
+        original_traces: &FinalizedTraces,
+        preprocessed_traces: &PreprocessedTraces,
+        _program_traces: &ProgramTraces,
+        lookup_elements: &AllLookupElements,
+    ) {
+        let lookup_elements: &PrivateInputLookupElements = lookup_elements.as_ref();
+        let [is_sys_priv_input_bytes] = original_traces.get_base_column(IsSysPrivInputBytes);
+        let columns: [[&BaseColumn; WORD_SIZE]; 4] = [
+            preprocessed_traces.get_preprocessed_base_column(PreprocessedColumn::Clk),
+            preprocessed_traces.get_preprocessed_base_column(PreprocessedColumn::Reg2TsCur),
+            original_traces.get_base_column(Reg3ValPrev),
+            original_traces.get_base_column(ValueA),
+        ];
+
+        let mut logup_col_gen = logup_trace_gen.new_col();
+        for vec_row in 0..(1 << (original_traces.log_size() - LOG_N_LANES)) {
+            let tuple: Vec<_> = columns
+                .iter()
+                .flatten()
+                .map(|col| col.data[vec_row])
+                .collect();
+            assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
+            logup_col_gen.write_frac(
+                vec_row,
+                is_sys_priv_input_bytes.data[vec_row].into(),
+                lookup_elements.combine(&tuple),
+            );
+        }
+        logup_col_gen.finalize_col();
+    }
+
     fn add_constraints<E: EvalAtRow>(
         eval: &mut E,
         trace_eval: &TraceEval<E>,
-        _lookup_elements: &AllLookupElements,
+        lookup_elements: &AllLookupElements,
     ) {
         let [is_type_sys] = IsTypeSys::eval(trace_eval);
         let [is_sys_debug] = trace_eval!(trace_eval, Column::IsSysDebug);
@@ -86,6 +224,9 @@ impl MachineChip for SyscallChip {
         let [is_sys_cycle_count] = trace_eval!(trace_eval, Column::IsSysCycleCount);
         let [is_sys_stack_reset] = trace_eval!(trace_eval, Column::IsSysStackReset);
         let [is_sys_heap_reset] = trace_eval!(trace_eval, Column::IsSysHeapReset);
+        let [is_sys_aux_input] = trace_eval!(trace_eval, Column::IsSysAuxInput);
+        let [is_sys_oracle] = trace_eval!(trace_eval, Column::IsSysOracle);
+        let [is_sys_priv_input_bytes] = trace_eval!(trace_eval, Column::IsSysPrivInputBytes);
         let value_b = trace_eval!(trace_eval, Column::ValueB);
 
         // is_type_sys・				(b_val_3) = 0
@@ -102,6 +243,12 @@ impl MachineChip for SyscallChip {
         // is_type_sys・is_sys_stack_reset・	(b_val_2 - 0x04) = 0  // b_val=0x402
         // is_type_sys・is_sys_heap_reset・	(b_val_1 - 0x03) = 0  // b_val=0x403
         // is_type_sys・is_sys_heap_reset・	(b_val_2 - 0x04) = 0  // b_val=0x403
+        // is_type_sys・is_sys_aux_input・	(b_val_1 - 0x04) = 0  // b_val=0x404
+        // is_type_sys・is_sys_aux_input・	(b_val_2 - 0x04) = 0  // b_val=0x404
+        // is_type_sys・is_sys_oracle・		(b_val_1 - 0x05) = 0  // b_val=0x405
+        // is_type_sys・is_sys_oracle・		(b_val_2 - 0x04) = 0  // b_val=0x405
+        // is_type_sys・is_sys_priv_input_bytes・	(b_val_1 - 0x06) = 0  // b_val=0x406
+        // is_type_sys・is_sys_priv_input_bytes・	(b_val_2 - 0x04) = 0  // b_val=0x406
 
         let syscall_table = [
             (SyscallCode::Write as u32, &is_sys_debug),
@@ -113,6 +260,15 @@ impl MachineChip for SyscallChip {
                 &is_sys_stack_reset,
             ),
             (SyscallCode::OverwriteHeapPointer as u32, &is_sys_heap_reset),
+            (
+                SyscallCode::ReadFromAuxiliaryInput as u32,
+                &is_sys_aux_input,
+            ),
+            (SyscallCode::QueryOracle as u32, &is_sys_oracle),
+            (
+                SyscallCode::ReadBytesFromPrivateInput as u32,
+                &is_sys_priv_input_bytes,
+            ),
         ];
 
         eval.add_constraint(is_type_sys.clone() * value_b[2].clone());
@@ -132,7 +288,7 @@ impl MachineChip for SyscallChip {
         }
 
         // Enforce that one flag is set
-        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_priv_input + is_sys_cycle_count + is_sys_stack_reset + is_sys_heap_reset - 1) = 0
+        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_priv_input + is_sys_cycle_count + is_sys_stack_reset + is_sys_heap_reset + is_sys_aux_input + is_sys_oracle + is_sys_priv_input_bytes - 1) = 0
         eval.add_constraint(
             is_type_sys.clone()
                 * (is_sys_debug.clone()
@@ -141,12 +297,15 @@ impl MachineChip for SyscallChip {
                     + is_sys_cycle_count.clone()
                     + is_sys_stack_reset.clone()
                     + is_sys_heap_reset.clone()
+                    + is_sys_aux_input.clone()
+                    + is_sys_oracle.clone()
+                    + is_sys_priv_input_bytes.clone()
                     - E::F::one()),
         );
 
         // Enforcing values for op_a
         // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_cycle_count)・(op_a) = 0
-        // is_type_sys・(is_sys_priv_input + is_sys_heap_reset)・(10 - op_a) = 0
+        // is_type_sys・(is_sys_priv_input + is_sys_aux_input + is_sys_oracle + is_sys_heap_reset + is_sys_priv_input_bytes)・(10 - op_a) = 0
         // is_type_sys・(is_sys_stack_reset)・(2 - op_a) = 0
         let [op_a] = trace_eval!(trace_eval, Column::OpA);
 
@@ -157,7 +316,11 @@ impl MachineChip for SyscallChip {
         );
         eval.add_constraint(
             is_type_sys.clone()
-                * (is_sys_priv_input.clone() + is_sys_heap_reset.clone())
+                * (is_sys_priv_input.clone()
+                    + is_sys_aux_input.clone()
+                    + is_sys_oracle.clone()
+                    + is_sys_heap_reset.clone()
+                    + is_sys_priv_input_bytes.clone())
                 * (E::F::from(BaseField::from(10)) - op_a.clone()),
         );
         eval.add_constraint(
@@ -177,6 +340,42 @@ impl MachineChip for SyscallChip {
                     * (a[0].clone() + a[1].clone() * E::F::from(BaseField::from(256))),
             );
         }
+
+        // The bulk read stores into memory, so its flag must not be set outside of system calls.
+        // (1 - is_type_sys)・is_sys_priv_input_bytes = 0
+        eval.add_constraint((E::F::one() - is_type_sys) * is_sys_priv_input_bytes.clone());
+
+        let lookup_elements: &PrivateInputLookupElements = lookup_elements.as_ref();
+        let mut tuple = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::Clk).to_vec();
+        tuple.extend(preprocessed_trace_eval!(
+            trace_eval,
+            PreprocessedColumn::Reg2TsCur
+        ));
+        tuple.extend(trace_eval!(trace_eval, Reg3ValPrev));
+        tuple.extend(value_a);
+        assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
+        eval.add_to_relation(RelationEntry::new(
+            lookup_elements,
+            is_sys_priv_input_bytes.into(),
+            &tuple,
+        ));
+    }
+
+    fn declare_support(support: &mut Support) {
+        support
+            .opcodes
+            .extend([BuiltinOpcode::ECALL, BuiltinOpcode::EBREAK]);
+        support.syscalls.extend([
+            SyscallCode::Write as u32,
+            SyscallCode::Exit as u32,
+            SyscallCode::ReadFromPrivateInput as u32,
+            SyscallCode::CycleCount as u32,
+            SyscallCode::OverwriteStackPointer as u32,
+            SyscallCode::OverwriteHeapPointer as u32,
+            SyscallCode::ReadFromAuxiliaryInput as u32,
+            SyscallCode::QueryOracle as u32,
+            SyscallCode::ReadBytesFromPrivateInput as u32,
+        ]);
     }
 }
 
@@ -212,6 +411,9 @@ mod test {
             // Private input syscall (0x400)
             Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::ReadFromPrivateInput as u32),
             Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            // Auxiliary input syscall (0x404)
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::ReadFromAuxiliaryInput as u32),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
             // Stack reset syscall (0x402)c
             Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::OverwriteStackPointer as u32),
             Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
@@ -14,7 +14,9 @@ use crate::{
     column::{Column, PreprocessedColumn, ProgramColumn},
     components::AllLookupElements,
     trace::{
-        eval::{preprocessed_trace_eval, program_trace_eval, trace_eval, TraceEval},
+        eval::{
+            preprocessed_trace_eval, program_trace_eval, trace_eval, trace_eval_next_row, TraceEval,
+        },
         program_trace::ProgramTraces,
         sidenote::SideNote,
         utils::FromBaseFields,
@@ -164,6 +166,24 @@ impl MachineChip for ProgramMemCheckChip {
             );
         }
 
+        // Constrain the program counter after the last step of a segment
+        let [final_pc_flag] = program_trace_eval!(trace_eval, ProgramColumn::PrgFinalPcFlag);
+        let final_pc = program_trace_eval!(trace_eval, ProgramColumn::PrgFinalPc);
+        let pc_next = trace_eval!(trace_eval, Column::PcNext);
+        for limb_idx in 0..WORD_SIZE {
+            eval.add_constraint(
+                final_pc_flag.clone() * (pc_next[limb_idx].clone() - final_pc[limb_idx].clone()),
+            );
+        }
+        // The flagged row must hold the last step: it is not padding, and it is either the last row or followed by padding.
+        let [is_padding] = trace_eval!(trace_eval, Column::IsPadding);
+        let [next_is_padding] = trace_eval_next_row!(trace_eval, Column::IsPadding);
+        let [is_last] = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::IsLast);
+        eval.add_constraint(final_pc_flag.clone() * is_padding);
+        eval.add_constraint(
+            final_pc_flag * (E::F::one() - is_last) * (E::F::one() - next_is_padding),
+        );
+
         // Constrain PrgCurCtr = PrgPrevCtr + 1
         let [is_padding] = trace_eval.column_eval(Column::IsPadding);
         let prg_prev_ctr = trace_eval.column_eval::<WORD_SIZE>(Column::ProgCtrPrev);
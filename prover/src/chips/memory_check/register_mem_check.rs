@@ -204,6 +204,7 @@ impl MachineChip for RegisterMemCheckChip {
         let value_c = trace_eval!(trace_eval, Column::ValueC);
 
         // is_alu = is_add + is_sub + is_slt + is_sltu + is_xor + is_or + is_and + is_sll + is_srl + is_sra
+        //        + is_mul + is_mulh + is_mulhsu + is_mulhu + is_div + is_divu + is_rem + is_remu
         let [is_alu] = virtual_column::IsAlu::eval(trace_eval);
 
         for i in 0..WORD_SIZE {
@@ -516,7 +517,7 @@ mod test {
         let (lookup_elements, claimed_sum_1) = assert_chip::<RegisterMemCheckChip>(traces, None);
 
         // verify that logup sums match
-        let ext = ExtensionComponent::final_reg();
+        let ext = ExtensionComponent::final_reg([0; 32], None);
         let (_, claimed_sum_2) = ext.generate_interaction_trace(&side_note, &lookup_elements);
         assert_eq!(claimed_sum_1 + claimed_sum_2, SecureField::zero());
     }
@@ -6,7 +6,8 @@ pub(crate) mod range_check;
 
 pub use instructions::{
     add_with_carries, subtract_with_borrow, AddChip, AuipcChip, BeqChip, BgeChip, BgeuChip,
-    BitOpChip, BltChip, BltuChip, BneChip, JalChip, JalrChip, LoadStoreChip, LuiChip, SllChip,
+    BitOpChip, BltChip, BltuChip, BneChip, CustomChip, DivChip, DivuChip, JalChip, JalrChip,
+    LoadStoreChip, LuiChip, MulChip, MulhChip, MulhsuChip, MulhuChip, RemChip, RemuChip, SllChip,
     SltChip, SltuChip, SraChip, SrlChip, SubChip, SyscallChip,
 };
 
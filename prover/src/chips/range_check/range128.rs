@@ -23,7 +23,9 @@ use crate::{
     traits::MachineChip,
 };
 
-use crate::column::Column::{self, Helper2, Helper3, IsBge, IsBlt, IsSlt};
+use crate::column::Column::{
+    self, Helper2, Helper3, Helper4, IsBge, IsBlt, IsDiv, IsMulh, IsMulhsu, IsRem, IsSlt,
+};
 
 /// A Chip for range-checking values for 0..=127
 ///
@@ -51,11 +53,25 @@ impl MachineChip for Range128Chip {
         let [is_slt] = traces.column(row_idx, IsSlt);
         let [is_bge] = traces.column(row_idx, IsBge);
         let [is_blt] = traces.column(row_idx, IsBlt);
-        let last_limb_checked = [Helper2, Helper3];
-        for col in last_limb_checked.into_iter() {
+        let [is_mulh] = traces.column(row_idx, IsMulh);
+        let [is_mulhsu] = traces.column(row_idx, IsMulhsu);
+        let [is_div] = traces.column(row_idx, IsDiv);
+        let [is_rem] = traces.column(row_idx, IsRem);
+        let last_limb_checked = [
+            (
+                Helper2,
+                is_slt + is_bge + is_blt + is_mulh + is_mulhsu + is_div + is_rem,
+            ),
+            (
+                Helper3,
+                is_slt + is_bge + is_blt + is_mulh + is_div + is_rem,
+            ),
+            (Helper4, is_div + is_rem),
+        ];
+        for (col, selector) in last_limb_checked.into_iter() {
             let word: [_; WORD_SIZE] = traces.column(row_idx, col);
             let last_limb = word[3];
-            fill_main_col(last_limb, is_slt + is_bge + is_blt, side_note);
+            fill_main_col(last_limb, selector, side_note);
         }
         let [is_jalr] = traces.column(row_idx, Column::IsJalr);
         let [qt_aux] = traces.column(row_idx, Column::QtAux);
@@ -68,6 +84,12 @@ impl MachineChip for Range128Chip {
         fill_main_col(qt_aux, is_lh, side_note);
         let [is_lb] = traces.column(row_idx, Column::IsLb);
         fill_main_col(qt_aux, is_lb, side_note);
+        // Check QtAux, the most significant limb of Qt with the sign bit cleared, for signed divisions
+        fill_main_col(qt_aux, is_div + is_rem, side_note);
+        // Check Funct7 of custom instructions
+        let [is_custom] = traces.column(row_idx, Column::IsCustom);
+        let [funct7] = traces.column(row_idx, Column::Funct7);
+        fill_main_col(funct7, is_custom, side_note);
     }
     /// Fills the whole interaction trace in one-go using SIMD in the stwo-usual way
     ///
@@ -85,11 +107,23 @@ impl MachineChip for Range128Chip {
         let [is_slt]: [_; 1] = original_traces.get_base_column(IsSlt);
         let [is_bge]: [_; 1] = original_traces.get_base_column(IsBge);
         let [is_blt]: [_; 1] = original_traces.get_base_column(IsBlt);
-        for col in [Helper2, Helper3].into_iter() {
+        let [is_mulh]: [_; 1] = original_traces.get_base_column(IsMulh);
+        let [is_mulhsu]: [_; 1] = original_traces.get_base_column(IsMulhsu);
+        let [is_div]: [_; 1] = original_traces.get_base_column(IsDiv);
+        let [is_rem]: [_; 1] = original_traces.get_base_column(IsRem);
+        let last_limb_checked: [(Column, &[&BaseColumn]); 3] = [
+            (
+                Helper2,
+                &[is_slt, is_bge, is_blt, is_mulh, is_mulhsu, is_div, is_rem],
+            ),
+            (Helper3, &[is_slt, is_bge, is_blt, is_mulh, is_div, is_rem]),
+            (Helper4, &[is_div, is_rem]),
+        ];
+        for (col, selectors) in last_limb_checked.into_iter() {
             let helper: [_; WORD_SIZE] = original_traces.get_base_column(col);
             check_col(
                 helper[3],
-                &[is_slt, is_bge, is_blt],
+                selectors,
                 original_traces.log_size(),
                 logup_trace_gen,
                 lookup_element,
@@ -122,6 +156,22 @@ impl MachineChip for Range128Chip {
             logup_trace_gen,
             lookup_element,
         );
+        check_col(
+            qt_aux,
+            &[is_div, is_rem],
+            original_traces.log_size(),
+            logup_trace_gen,
+            lookup_element,
+        );
+        let [is_custom] = original_traces.get_base_column(Column::IsCustom);
+        let [funct7] = original_traces.get_base_column(Column::Funct7);
+        check_col(
+            funct7,
+            &[is_custom],
+            original_traces.log_size(),
+            logup_trace_gen,
+            lookup_element,
+        );
     }
 
     fn add_constraints<E: stwo_prover::constraint_framework::EvalAtRow>(
@@ -136,14 +186,27 @@ impl MachineChip for Range128Chip {
         let [is_slt] = trace_eval.column_eval(IsSlt);
         let [is_bge] = trace_eval.column_eval(IsBge);
         let [is_blt] = trace_eval.column_eval(IsBlt);
+        let [is_mulh] = trace_eval.column_eval(IsMulh);
+        let [is_mulhsu] = trace_eval.column_eval(IsMulhsu);
+        let [is_div] = trace_eval.column_eval(IsDiv);
+        let [is_rem] = trace_eval.column_eval(IsRem);
 
         let numerator = is_slt.clone() + is_bge.clone() + is_blt.clone();
-        for col in [Helper2, Helper3].into_iter() {
+        let is_signed_div = is_div.clone() + is_rem.clone();
+        let last_limb_checked = [
+            (
+                Helper2,
+                numerator.clone() + is_mulh.clone() + is_mulhsu.clone() + is_signed_div.clone(),
+            ),
+            (Helper3, numerator + is_mulh.clone() + is_signed_div.clone()),
+            (Helper4, is_signed_div.clone()),
+        ];
+        for (col, numerator) in last_limb_checked.into_iter() {
             let value = trace_eval.column_eval::<WORD_SIZE>(col);
 
             eval.add_to_relation(RelationEntry::new(
                 lookup_elements,
-                numerator.clone().into(),
+                numerator.into(),
                 &[value[3].clone()],
             ));
         }
@@ -174,8 +237,22 @@ impl MachineChip for Range128Chip {
         eval.add_to_relation(RelationEntry::new(
             lookup_elements,
             numerator.into(),
+            &[qt_aux.clone()],
+        ));
+
+        eval.add_to_relation(RelationEntry::new(
+            lookup_elements,
+            is_signed_div.into(),
             &[qt_aux],
         ));
+        let [is_custom] = trace_eval.column_eval(Column::IsCustom);
+        let [funct7] = trace_eval.column_eval(Column::Funct7);
+
+        eval.add_to_relation(RelationEntry::new(
+            lookup_elements,
+            is_custom.into(),
+            &[funct7],
+        ));
     }
 }
 
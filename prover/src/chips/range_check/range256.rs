@@ -11,12 +11,12 @@ use stwo_prover::core::{
 
 use crate::{
     column::Column::{
-        self, CReg1TsPrev, CReg2TsPrev, CReg3TsPrev, FinalPrgMemoryCtr, Helper1, InstrVal,
-        OpC16_23, OpC24_31, Pc, PcNextAux, PrevCtr, ProgCtrCur, ProgCtrPrev, Qt, Ram1TsPrev,
-        Ram1TsPrevAux, Ram1ValCur, Ram1ValPrev, Ram2TsPrev, Ram2TsPrevAux, Ram2ValCur, Ram2ValPrev,
-        Ram3TsPrev, Ram3TsPrevAux, Ram3ValCur, Ram3ValPrev, Ram4TsPrev, Ram4TsPrevAux, Ram4ValCur,
-        Ram4ValPrev, RamBaseAddr, RamFinalCounter, RamFinalValue, RamInitFinalAddr, Reg1TsPrev,
-        Reg2TsPrev, Reg3TsPrev, Rem, RemDiff, ValueA, ValueB, ValueC,
+        self, AbsC, AbsRem, CReg1TsPrev, CReg2TsPrev, CReg3TsPrev, FinalPrgMemoryCtr, Helper1,
+        InstrVal, MulCarry, OpC16_23, OpC24_31, Pc, PcNextAux, PrevCtr, ProgCtrCur, ProgCtrPrev,
+        Qt, Ram1TsPrev, Ram1TsPrevAux, Ram1ValCur, Ram1ValPrev, Ram2TsPrev, Ram2TsPrevAux,
+        Ram2ValCur, Ram2ValPrev, Ram3TsPrev, Ram3TsPrevAux, Ram3ValCur, Ram3ValPrev, Ram4TsPrev,
+        Ram4TsPrevAux, Ram4ValCur, Ram4ValPrev, RamBaseAddr, RamFinalCounter, RamFinalValue,
+        RamInitFinalAddr, Reg1TsPrev, Reg2TsPrev, Reg3TsPrev, Rem, RemDiff, ValueA, ValueB, ValueC,
     },
     components::AllLookupElements,
     trace::{
@@ -36,7 +36,7 @@ const LOOKUP_TUPLE_SIZE: usize = 1;
 stwo_prover::relation!(Range256LookupElements, LOOKUP_TUPLE_SIZE);
 
 impl Range256Chip {
-    const CHECKED_WORDS: [Column; 31] = [
+    const CHECKED_WORDS: [Column; 34] = [
         Pc,
         PcNextAux,
         InstrVal,
@@ -68,6 +68,9 @@ impl Range256Chip {
         RemDiff,
         RamInitFinalAddr,
         RamFinalCounter,
+        MulCarry,
+        AbsC,
+        AbsRem,
     ];
 
     const CHECKED_BYTES: [Column; 9] = [
@@ -13,11 +13,13 @@ use stwo_prover::core::{
 };
 
 use crate::{
-    column::Column::{self, OpC1_3, OpC5_7, OpC8_10},
+    column::Column::{self, Funct3, IsCustom, OpC1_3, OpC5_7, OpC8_10},
     components::AllLookupElements,
     trace::{
-        eval::TraceEval, program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces,
-        PreprocessedTraces, ProgramStep, TracesBuilder,
+        eval::{trace_eval, TraceEval},
+        program_trace::ProgramTraces,
+        sidenote::SideNote,
+        FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
     virtual_column::{
@@ -34,6 +36,24 @@ impl VirtualColumnForSum for Helper1MsbChecked {
     }
 }
 
+/// A flag for MulCarryHigh to be checked against 0..=7.
+struct MulCarryHighChecked;
+
+impl VirtualColumnForSum for MulCarryHighChecked {
+    fn columns() -> &'static [Column] {
+        &[
+            Column::IsMul,
+            Column::IsMulh,
+            Column::IsMulhsu,
+            Column::IsMulhu,
+            Column::IsDiv,
+            Column::IsDivu,
+            Column::IsRem,
+            Column::IsRemu,
+        ]
+    }
+}
+
 /// A Chip for range-checking values for 0..=7
 ///
 /// Range8Chip needs to be located at the end of the chip composition together with the other range check chips
@@ -79,6 +99,29 @@ impl MachineChip for Range8Chip {
             let [helper1_0, _, _, _] = traces.column(row_idx, Column::Helper1);
             fill_main_elm(helper1_0, side_note);
         }
+        // Add multiplicities for MulCarryHigh in case of multiplications and divisions
+        if matches!(
+            step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::MUL)
+                | Some(BuiltinOpcode::MULH)
+                | Some(BuiltinOpcode::MULHSU)
+                | Some(BuiltinOpcode::MULHU)
+                | Some(BuiltinOpcode::DIV)
+                | Some(BuiltinOpcode::DIVU)
+                | Some(BuiltinOpcode::REM)
+                | Some(BuiltinOpcode::REMU)
+        ) {
+            let carries: [BaseField; WORD_SIZE] = traces.column(row_idx, Column::MulCarryHigh);
+            for carry in carries {
+                fill_main_elm(carry, side_note);
+            }
+        }
+
+        // Add multiplicities for Funct3 in case of custom instructions
+        if !step.step.instruction.opcode.is_builtin() {
+            let [funct3] = traces.column(row_idx, Funct3);
+            fill_main_elm(funct3, side_note);
+        }
 
         fill_main_for_type::<IsTypeINoShift>(
             traces,
@@ -164,6 +207,33 @@ impl MachineChip for Range8Chip {
             logup_col_gen.write_frac(vec_row, is_type.into(), denom);
         }
         logup_col_gen.finalize_col();
+
+        // Fill the interaction trace for MulCarryHigh in case of multiplications and divisions
+        let carry_basecolumns: [&BaseColumn; WORD_SIZE] =
+            original_traces.get_base_column(Column::MulCarryHigh);
+        for carry_basecolumn in carry_basecolumns {
+            let mut logup_col_gen = logup_trace_gen.new_col();
+            for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+                let checked_tuple = vec![carry_basecolumn.data[vec_row]];
+                let denom = lookup_element.combine(&checked_tuple);
+                let [is_mul] =
+                    MulCarryHighChecked::read_from_finalized_traces(original_traces, vec_row);
+                logup_col_gen.write_frac(vec_row, is_mul.into(), denom);
+            }
+            logup_col_gen.finalize_col();
+        }
+
+        // Fill the interaction trace for Funct3 in case of custom instructions
+        let [funct3_basecolumn]: [&BaseColumn; 1] = original_traces.get_base_column(Funct3);
+        let [is_custom_basecolumn]: [&BaseColumn; 1] = original_traces.get_base_column(IsCustom);
+        let mut logup_col_gen = logup_trace_gen.new_col();
+        for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+            let checked_tuple = vec![funct3_basecolumn.data[vec_row]];
+            let denom = lookup_element.combine(&checked_tuple);
+            let is_custom = is_custom_basecolumn.data[vec_row];
+            logup_col_gen.write_frac(vec_row, is_custom.into(), denom);
+        }
+        logup_col_gen.finalize_col();
     }
 
     fn add_constraints<E: stwo_prover::constraint_framework::EvalAtRow>(
@@ -192,6 +262,26 @@ impl MachineChip for Range8Chip {
             numerator.into(),
             &[value],
         ));
+
+        // Add checked multiplicities for MulCarryHigh in case of multiplications and divisions
+        let [numerator] = MulCarryHighChecked::eval(trace_eval);
+        let carries = trace_eval.column_eval::<WORD_SIZE>(Column::MulCarryHigh);
+        for carry in carries {
+            eval.add_to_relation(RelationEntry::new(
+                lookup_elements,
+                numerator.clone().into(),
+                &[carry],
+            ));
+        }
+
+        // Add checked multiplicities for Funct3 in case of custom instructions
+        let [is_custom] = trace_eval!(trace_eval, IsCustom);
+        let [funct3] = trace_eval!(trace_eval, Funct3);
+        eval.add_to_relation(RelationEntry::new(
+            lookup_elements,
+            is_custom.into(),
+            &[funct3],
+        ));
     }
 }
 
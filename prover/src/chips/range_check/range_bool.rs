@@ -4,13 +4,15 @@ use num_traits::One;
 
 use crate::{
     column::Column::{
-        self, BorrowFlag, CH1Minus, CH2Minus, CH3Minus, CarryFlag, ImmC, IsAdd, IsAnd, IsAuipc,
-        IsBge, IsBgeu, IsBlt, IsBltu, IsEbreak, IsEcall, IsJal, IsJalr, IsLb, IsLbu, IsLh, IsLhu,
-        IsLui, IsLw, IsOr, IsPadding, IsSb, IsSh, IsSll, IsSlt, IsSltu, IsSra, IsSrl, IsSub, IsSw,
-        IsSysCycleCount, IsSysDebug, IsSysHalt, IsSysHeapReset, IsSysPrivInput, IsSysStackReset,
-        IsXor, LtFlag, OpA0, OpB0, OpB4, OpC0, OpC11, OpC12, OpC20, OpC4, PcCarry, ProgCtrCarry,
-        RamInitFinalFlag, RemAux, SgnA, SgnB, SgnC, ShiftBit1, ShiftBit2, ShiftBit3, ShiftBit4,
-        ShiftBit5, ValueAEffectiveFlag,
+        self, AbsCBorrow, AbsRemBorrow, BorrowFlag, CH1Minus, CH2Minus, CH3Minus, CarryFlag,
+        DivByZero, DivOverflow, ImmC, IsAdd, IsAnd, IsAuipc, IsBge, IsBgeu, IsBlt, IsBltu,
+        IsCustom, IsDiv, IsDivu, IsEbreak, IsEcall, IsJal, IsJalr, IsLb, IsLbu, IsLh, IsLhu, IsLui,
+        IsLw, IsMul, IsMulh, IsMulhsu, IsMulhu, IsOr, IsPadding, IsRem, IsRemu, IsSb, IsSh, IsSll,
+        IsSlt, IsSltu, IsSra, IsSrl, IsSub, IsSw, IsSysAuxInput, IsSysCycleCount, IsSysDebug,
+        IsSysHalt, IsSysHeapReset, IsSysOracle, IsSysPrivInput, IsSysPrivInputBytes,
+        IsSysStackReset, IsXor, LtFlag, OpA0, OpB0, OpB4, OpC0, OpC11, OpC12, OpC20, OpC4, PcCarry,
+        ProgCtrCarry, RamInitFinalFlag, RemAux, SgnA, SgnB, SgnC, SgnQt, SgnRem, ShiftBit1,
+        ShiftBit2, ShiftBit3, ShiftBit4, ShiftBit5, ValueAEffectiveFlag,
     },
     components::AllLookupElements,
     trace::{eval::TraceEval, sidenote::SideNote, ProgramStep, TracesBuilder},
@@ -23,7 +25,7 @@ use crate::{
 /// RangeBoolChip can be located anywhere in the chip composition.
 pub struct RangeBoolChip;
 
-const CHECKED_SINGLE: [Column; 48] = [
+const CHECKED_SINGLE: [Column; 64] = [
     ValueAEffectiveFlag,
     ImmC,
     IsAdd,
@@ -52,20 +54,36 @@ const CHECKED_SINGLE: [Column; 48] = [
     IsSll,
     IsSrl,
     IsSra,
+    IsMul,
+    IsMulh,
+    IsMulhsu,
+    IsMulhu,
+    IsDiv,
+    IsDivu,
+    IsRem,
+    IsRemu,
     IsEcall,
     IsEbreak,
+    IsSysAuxInput,
     IsSysCycleCount,
     IsSysDebug,
     IsSysHalt,
     IsSysHeapReset,
+    IsSysOracle,
     IsSysPrivInput,
+    IsSysPrivInputBytes,
     IsSysStackReset,
+    IsCustom,
     IsPadding,
     LtFlag,
     RemAux,
     SgnA,
     SgnB,
     SgnC,
+    SgnQt,
+    SgnRem,
+    DivByZero,
+    DivOverflow,
     ShiftBit1,
     ShiftBit2,
     ShiftBit3,
@@ -73,7 +91,7 @@ const CHECKED_SINGLE: [Column; 48] = [
     ShiftBit5,
     RamInitFinalFlag,
 ];
-const CHECKED_HALF_WORD: [Column; 7] = [
+const CHECKED_HALF_WORD: [Column; 9] = [
     CarryFlag,
     PcCarry,
     CH1Minus,
@@ -81,6 +99,8 @@ const CHECKED_HALF_WORD: [Column; 7] = [
     CH3Minus,
     ProgCtrCarry,
     BorrowFlag,
+    AbsCBorrow,
+    AbsRemBorrow,
 ];
 const TYPE_R_CHECKED_SINGLE: [Column; 3] = [OpC4, OpA0, OpB0];
 const TYPE_I_NO_SHIFT_SINGLE: [Column; 3] = [OpC11, OpA0, OpB0];
@@ -154,6 +154,30 @@ pub enum Column {
     /// Boolean flag on whether the row is a SRA.
     #[size = 1]
     IsSra,
+    /// Boolean flag on whether the row is a MUL.
+    #[size = 1]
+    IsMul,
+    /// Boolean flag on whether the row is a MULH.
+    #[size = 1]
+    IsMulh,
+    /// Boolean flag on whether the row is a MULHSU.
+    #[size = 1]
+    IsMulhsu,
+    /// Boolean flag on whether the row is a MULHU.
+    #[size = 1]
+    IsMulhu,
+    /// Boolean flag on whether the row is a DIV.
+    #[size = 1]
+    IsDiv,
+    /// Boolean flag on whether the row is a DIVU.
+    #[size = 1]
+    IsDivu,
+    /// Boolean flag on whether the row is a REM.
+    #[size = 1]
+    IsRem,
+    /// Boolean flag on whether the row is a REMU.
+    #[size = 1]
+    IsRemu,
     /// Boolean flag on whether the row is an ECALL.
     #[size = 1]
     IsEcall,
@@ -178,6 +202,18 @@ pub enum Column {
     /// Boolean flag on whether the row is an ECALL_HEAP_RESET (OverwriteHeapPointer).
     #[size = 1]
     IsSysHeapReset,
+    /// Boolean flag on whether the row is an ECALL_AUX_INPUT (ReadFromAuxiliaryInput).
+    #[size = 1]
+    IsSysAuxInput,
+    /// Boolean flag on whether the row is an ECALL_ORACLE (QueryOracle).
+    #[size = 1]
+    IsSysOracle,
+    /// Boolean flag on whether the row is an ECALL_PRIVATE_INPUT_BYTES (ReadBytesFromPrivateInput).
+    #[size = 1]
+    IsSysPrivInputBytes,
+    /// Boolean flag on whether the row is a custom instruction, i.e. a precompile call.
+    #[size = 1]
+    IsCustom,
     /// Boolean flag on whether the row is a padding.
     #[size = 1]
     IsPadding,
@@ -439,6 +475,12 @@ pub enum Column {
     /// Auxiliary variable for decoding instruction: bits[4] of OpB argument
     #[size = 1]
     OpB4,
+    /// Auxiliary variable for decoding custom instructions: the funct3 field, bits[12..=14] of the instruction
+    #[size = 1]
+    Funct3,
+    /// Auxiliary variable for decoding custom instructions: the funct7 field, bits[25..=31] of the instruction
+    #[size = 1]
+    Funct7,
 
     /// Auxiliary variable for decoding type_u immediates: bits[12..=15] of the instruction
     #[size = 1]
@@ -479,6 +521,38 @@ pub enum Column {
     /// On bit-op rows, the more-significant four bits of each limb of ValueC. On those rows, ValueC4_7[i] contains ValueC[i] >> 4.
     #[size = 4]
     ValueC4_7,
+
+    /// On multiplication and division rows, the lower eight bits of the carries of the 64-bit product at 16-bit boundaries.
+    #[size = 4]
+    MulCarry,
+    /// On multiplication and division rows, the remaining bits of the carries of the 64-bit product at 16-bit boundaries. Each limb is in 0..=7.
+    #[size = 4]
+    MulCarryHigh,
+
+    /// On signed division rows, the sign bit of Qt.
+    #[size = 1]
+    SgnQt,
+    /// On signed division rows, the sign bit of Rem.
+    #[size = 1]
+    SgnRem,
+    /// On division rows, the absolute value of ValueC. Equals ValueC for unsigned divisions.
+    #[size = 4]
+    AbsC,
+    /// On division rows, the absolute value of Rem. Equals Rem for unsigned divisions.
+    #[size = 4]
+    AbsRem,
+    /// On signed division rows, the borrow flags of 0 - ValueC at 16-bit boundaries, when ValueC is negative.
+    #[size = 2]
+    AbsCBorrow,
+    /// On signed division rows, the borrow flags of 0 - Rem at 16-bit boundaries, when Rem is negative.
+    #[size = 2]
+    AbsRemBorrow,
+    /// On division rows, boolean flag on whether ValueC is zero.
+    #[size = 1]
+    DivByZero,
+    /// On signed division rows, boolean flag on whether the division overflows, i.e. ValueB = -2^31 and ValueC = -1.
+    #[size = 1]
+    DivOverflow,
 }
 
 // proc macro derived:
@@ -505,6 +579,12 @@ pub enum ProgramColumn {
     /// The first program counter for finding the first executed instruction
     #[size = 4]
     PrgInitialPc,
+    /// A flag on the row of the last step of a segment, whose PcNext must equal PrgFinalPc
+    #[size = 1]
+    PrgFinalPcFlag,
+    /// The program counter after the last step of a segment, used if PrgFinalPcFlag is set
+    #[size = 4]
+    PrgFinalPc,
     /// The one-byte content of publicly known initial memory at PublicRamAddr
     #[size = 1]
     PublicInitialMemoryValue,
@@ -520,6 +600,9 @@ pub enum ProgramColumn {
     /// The address of initial memory or public output; actually a selective copy of RamInitFinalAddr that the verifier knows
     #[size = 4]
     PublicRamAddr,
+    /// One on every row of a segment, where every accessed byte of RAM is both in the initial memory and in the public output
+    #[size = 1]
+    PublicRamComplete,
 }
 
 // proc macro derived:
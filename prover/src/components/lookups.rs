@@ -8,11 +8,21 @@
 //! Internally, [`AllLookupElements`] is a hashmap storing a set of generated alphas and z (=lookup elements) for each
 //! type. Since [`stwo_prover::constraint_framework::Relation`] is not object safe and cannot be boxed, the only way
 //! to store it is by using an enum.
+//!
+//! Relations declared outside of this crate, e.g. by precompile extensions, cannot be added to the enum. They are
+//! stored type-erased with [`AllLookupElements::insert_custom`] and retrieved with [`AllLookupElements::get_custom`].
 
-use std::{any::TypeId, collections::HashMap};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::Arc,
+};
 
 use crate::chips::{
-    instructions::{bit_op::BitOpLookupElements, load_store::LoadStoreLookupElements},
+    instructions::{
+        bit_op::BitOpLookupElements, custom::PrecompileLookupElements,
+        load_store::LoadStoreLookupElements, syscall::PrivateInputLookupElements,
+    },
     memory_check::{
         program_mem_check::ProgramCheckLookupElements,
         register_mem_check::RegisterCheckLookupElements,
@@ -32,6 +42,8 @@ register_relation! {
     enum RelationVariant {
         BitOpLookupElements,
         LoadStoreLookupElements,
+        PrecompileLookupElements,
+        PrivateInputLookupElements,
         ProgramCheckLookupElements,
         RegisterCheckLookupElements,
         Range8LookupElements,
@@ -44,28 +56,57 @@ register_relation! {
 }
 
 #[derive(Default, Debug, Clone)]
-pub struct AllLookupElements(HashMap<TypeId, RelationVariant>);
+pub struct AllLookupElements {
+    registered: HashMap<TypeId, RelationVariant>,
+    custom: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
 
 impl AllLookupElements {
     pub fn insert<T: Into<RelationVariant> + 'static>(&mut self, relation: T) {
-        if self.0.insert(TypeId::of::<T>(), relation.into()).is_some() {
+        if self
+            .registered
+            .insert(TypeId::of::<T>(), relation.into())
+            .is_some()
+        {
+            panic!("attempt to insert duplicate relation")
+        }
+    }
+
+    /// Inserts lookup elements of a relation that isn't registered in this module.
+    pub fn insert_custom<T: Any + Send + Sync>(&mut self, relation: T) {
+        if self
+            .custom
+            .insert(TypeId::of::<T>(), Arc::new(relation))
+            .is_some()
+        {
             panic!("attempt to insert duplicate relation")
         }
     }
 
+    /// Returns lookup elements previously stored with [`Self::insert_custom`].
+    pub fn get_custom<T: Any + Send + Sync>(&self) -> &T {
+        self.custom
+            .get(&TypeId::of::<T>())
+            .and_then(|relation| relation.downcast_ref())
+            .expect("lookup elements weren't initialized")
+    }
+
     pub fn dummy() -> Self {
-        Self(HashMap::from_iter(RelationVariant::dummy_array()))
+        Self {
+            registered: HashMap::from_iter(RelationVariant::dummy_array()),
+            custom: HashMap::new(),
+        }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.registered.is_empty() && self.custom.is_empty()
     }
 }
 
 impl<T: RegisteredLookupBound> AsRef<T> for AllLookupElements {
     fn as_ref(&self) -> &T {
         let variant = self
-            .0
+            .registered
             .get(&TypeId::of::<T>())
             .expect("lookup elements weren't initialized");
         T::unwrap_ref(variant)
@@ -0,0 +1,398 @@
+//! Big integer arithmetic for row-based circuits.
+//!
+//! Integers are little-endian vectors of byte limbs. A relation between integers is written as a [`Poly`], a
+//! polynomial in 256 with row expressions as coefficients, and checked with [`assert_zero`]: the gadget allocates
+//! carries between coefficients, so that the polynomial vanishes at 256 over the integers rather than in the base
+//! field. Modular relations additionally allocate a quotient, see [`assert_zero_mod`] and [`assert_zero_mod_var`].
+//!
+//! Columns allocated by a row must not depend on its values, therefore [`Poly`] tracks bounds of its coefficients,
+//! from which the number of carries and quotient limbs is derived. The bounds assume that every limb passed to
+//! [`Poly::from_bytes`] is range checked whenever the relation is enforced, which is the caller's responsibility.
+//!
+//! Every gadget is gated by a `flag` that must be boolean and at most quadratic, the relation is only enforced on
+//! rows where the flag is set.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, Zero};
+use stwo_prover::core::fields::m31::{BaseField, P};
+
+use super::circuit::{Row, RowField};
+use crate::components::AllLookupElements;
+
+/// Carries are kept in `[-CARRY_OFFSET, CARRY_OFFSET)`, and allocated as two bytes and a bit.
+const CARRY_OFFSET: i64 = 1 << 16;
+
+/// A polynomial in 256 with row expressions as coefficients, representing a signed integer.
+#[derive(Debug, Clone)]
+pub struct Poly<F> {
+    coeffs: Vec<F>,
+    /// Inclusive lower and upper bounds of each coefficient.
+    bounds: Vec<(i64, i64)>,
+}
+
+impl<F: RowField> Poly<F> {
+    /// Integer with the given limbs, each of them must be range checked as a byte.
+    pub fn from_bytes(limbs: &[F]) -> Self {
+        Self {
+            coeffs: limbs.to_vec(),
+            bounds: vec![(0, 255); limbs.len()],
+        }
+    }
+
+    /// Constant integer.
+    pub fn constant(value: &BigUint) -> Self {
+        let bytes = value.to_bytes_le();
+        Self {
+            coeffs: bytes
+                .iter()
+                .map(|&byte| BaseField::from(byte as u32).into())
+                .collect(),
+            bounds: bytes
+                .iter()
+                .map(|&byte| (byte as i64, byte as i64))
+                .collect(),
+        }
+    }
+
+    /// Value of the integer when the trace is filled, and zero when constraints are evaluated.
+    pub fn value<R: Row<F = F>>(&self, row: &R) -> BigInt {
+        self.coeffs.iter().rev().fold(BigInt::zero(), |acc, coeff| {
+            (acc << 8) + signed(row.value(coeff))
+        })
+    }
+
+    fn min_value(&self) -> BigInt {
+        self.bounds
+            .iter()
+            .rev()
+            .fold(BigInt::zero(), |acc, &(lo, _)| (acc << 8) + lo)
+    }
+
+    fn max_value(&self) -> BigInt {
+        self.bounds
+            .iter()
+            .rev()
+            .fold(BigInt::zero(), |acc, &(_, hi)| (acc << 8) + hi)
+    }
+
+    fn zero(len: usize) -> Self {
+        Self {
+            coeffs: vec![F::zero(); len],
+            bounds: vec![(0, 0); len],
+        }
+    }
+}
+
+impl<F: RowField> Add for Poly<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let mut result = Self::zero(self.coeffs.len().max(rhs.coeffs.len()));
+        for poly in [self, rhs] {
+            for (k, (coeff, (lo, hi))) in poly.coeffs.into_iter().zip(poly.bounds).enumerate() {
+                result.coeffs[k] += coeff;
+                result.bounds[k].0 += lo;
+                result.bounds[k].1 += hi;
+            }
+        }
+        result
+    }
+}
+
+impl<F: RowField> Neg for Poly<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            coeffs: self.coeffs.into_iter().map(Neg::neg).collect(),
+            bounds: self.bounds.into_iter().map(|(lo, hi)| (-hi, -lo)).collect(),
+        }
+    }
+}
+
+impl<F: RowField> Sub for Poly<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+impl<F: RowField> Mul for Poly<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if self.coeffs.is_empty() || rhs.coeffs.is_empty() {
+            return Self::zero(0);
+        }
+        let mut result = Self::zero(self.coeffs.len() + rhs.coeffs.len() - 1);
+        for (i, (a, &(a_lo, a_hi))) in self.coeffs.iter().zip(&self.bounds).enumerate() {
+            for (j, (b, &(b_lo, b_hi))) in rhs.coeffs.iter().zip(&rhs.bounds).enumerate() {
+                // Skip zero bytes of constants.
+                if (a_lo, a_hi) == (0, 0) || (b_lo, b_hi) == (0, 0) {
+                    continue;
+                }
+                let products = [a_lo * b_lo, a_lo * b_hi, a_hi * b_lo, a_hi * b_hi];
+                result.coeffs[i + j] += a.clone() * b.clone();
+                result.bounds[i + j].0 += products.iter().min().expect("not empty");
+                result.bounds[i + j].1 += products.iter().max().expect("not empty");
+            }
+        }
+        result
+    }
+}
+
+/// Allocates `len` byte limbs of `value`, range checked `multiplicity` times.
+pub fn next_uint<R: Row>(
+    row: &mut R,
+    value: &BigUint,
+    len: usize,
+    multiplicity: &R::F,
+    lookup_elements: &AllLookupElements,
+) -> Vec<R::F> {
+    let mut bytes = value.to_bytes_le();
+    assert!(
+        bytes.len() <= len || value.is_zero(),
+        "value doesn't fit into {len} bytes"
+    );
+    bytes.resize(len, 0);
+    bytes
+        .into_iter()
+        .map(|byte| {
+            let limb = row.next(BaseField::from(byte as u32));
+            row.range_check_u8(multiplicity.clone(), limb.clone(), lookup_elements);
+            limb
+        })
+        .collect()
+}
+
+/// Constrains `poly` to vanish at 256 over the integers when `flag` is set.
+pub fn assert_zero<R: Row>(
+    row: &mut R,
+    poly: &Poly<R::F>,
+    flag: &R::F,
+    lookup_elements: &AllLookupElements,
+) {
+    let enabled = !row.value(flag).is_zero();
+    let mut carry = R::F::zero();
+    let mut carry_value = 0i64;
+    let mut carry_bound = 0i64;
+    for (k, (coeff, &(lo, hi))) in poly.coeffs.iter().zip(&poly.bounds).enumerate() {
+        let sum = coeff.clone() + carry;
+        if k + 1 == poly.coeffs.len() {
+            row.add_constraint(flag.clone() * sum);
+            break;
+        }
+        carry_bound = (lo.abs().max(hi.abs()) + carry_bound) / 256;
+        assert!(
+            carry_bound < CARRY_OFFSET,
+            "coefficients of the polynomial are too large"
+        );
+        carry_value = if enabled {
+            (signed(row.value(coeff)) + carry_value).div_euclid(256)
+        } else {
+            0
+        };
+
+        let shifted = (carry_value + CARRY_OFFSET) as u32;
+        let low = row.next(BaseField::from(shifted & 0xFF));
+        let high = row.next(BaseField::from((shifted >> 8) & 0xFF));
+        let top = row.next(BaseField::from(shifted >> 16));
+        row.range_check_u8(flag.clone(), low.clone(), lookup_elements);
+        row.range_check_u8(flag.clone(), high.clone(), lookup_elements);
+        row.add_constraint(flag.clone() * top.clone() * (top.clone() - R::F::one()));
+
+        carry = low + high * BaseField::from(1 << 8) + top * BaseField::from(1 << 16)
+            - BaseField::from(CARRY_OFFSET as u32).into();
+        row.add_constraint(flag.clone() * (sum - carry.clone() * BaseField::from(256)));
+    }
+}
+
+/// Constrains `poly` to be divisible by a constant nonzero `modulus` when `flag` is set.
+pub fn assert_zero_mod<R: Row>(
+    row: &mut R,
+    poly: Poly<R::F>,
+    modulus: &BigUint,
+    flag: &R::F,
+    lookup_elements: &AllLookupElements,
+) {
+    let modulus_int = BigInt::from(modulus.clone());
+    // Shift the polynomial by a multiple of the modulus to keep the quotient non-negative.
+    let min = poly.min_value();
+    let offset = if min.sign() == Sign::Minus {
+        (-min + &modulus_int - 1u32) / &modulus_int
+    } else {
+        BigInt::zero()
+    };
+    let max_quotient = (poly.max_value() + &offset * &modulus_int) / &modulus_int;
+
+    let quotient = if row.value(flag).is_zero() {
+        BigUint::zero()
+    } else {
+        ((poly.value(row) + &offset * &modulus_int) / &modulus_int)
+            .to_biguint()
+            .unwrap_or_default()
+    };
+    let quotient = next_uint(
+        row,
+        &quotient,
+        byte_len(&max_quotient),
+        flag,
+        lookup_elements,
+    );
+
+    let shift = (offset * modulus_int)
+        .to_biguint()
+        .expect("offset is non-negative");
+    let poly =
+        poly + Poly::constant(&shift) - Poly::from_bytes(&quotient) * Poly::constant(modulus);
+    assert_zero(row, &poly, flag, lookup_elements);
+}
+
+/// Constrains `poly` to be divisible by `modulus` when `flag` is set.
+///
+/// The modulus is given by range checked byte limbs and must be nonzero, `poly` must be non-negative on valid
+/// witnesses.
+pub fn assert_zero_mod_var<R: Row>(
+    row: &mut R,
+    poly: Poly<R::F>,
+    modulus: &[R::F],
+    flag: &R::F,
+    lookup_elements: &AllLookupElements,
+) {
+    let modulus = Poly::from_bytes(modulus);
+    let quotient = if row.value(flag).is_zero() {
+        BigUint::zero()
+    } else {
+        let modulus = modulus.value(row);
+        (poly.value(row) / modulus).to_biguint().unwrap_or_default()
+    };
+    // The modulus is at least one.
+    let len = byte_len(&poly.max_value());
+    let quotient = next_uint(row, &quotient, len, flag, lookup_elements);
+
+    let poly = poly - Poly::from_bytes(&quotient) * modulus;
+    assert_zero(row, &poly, flag, lookup_elements);
+}
+
+/// Constrains the integer with byte limbs `lhs` to be less than `rhs` when `flag` is set.
+pub fn assert_lt<R: Row>(
+    row: &mut R,
+    lhs: &[R::F],
+    rhs: Poly<R::F>,
+    flag: &R::F,
+    lookup_elements: &AllLookupElements,
+) {
+    let lhs = Poly::from_bytes(lhs);
+    let diff = if row.value(flag).is_zero() {
+        BigUint::zero()
+    } else {
+        (rhs.value(row) - lhs.value(row) - 1u32)
+            .to_biguint()
+            .unwrap_or_default()
+    };
+    let len = byte_len(&rhs.max_value());
+    let diff = next_uint(row, &diff, len, flag, lookup_elements);
+
+    // lhs + diff + 1 = rhs with a non-negative diff.
+    let poly = lhs + Poly::from_bytes(&diff) + Poly::constant(&BigUint::one()) - rhs;
+    assert_zero(row, &poly, flag, lookup_elements);
+}
+
+/// Allocates a boolean that is set if and only if all byte limbs are zero.
+///
+/// The limbs must be range checked whenever the result is used.
+pub fn next_is_zero<R: Row>(row: &mut R, limbs: &[R::F]) -> R::F {
+    let sum = limbs
+        .iter()
+        .cloned()
+        .fold(R::F::zero(), |acc, limb| acc + limb);
+    let sum_value = row.value(&sum);
+
+    let inverse = row.next(if sum_value.is_zero() {
+        BaseField::zero()
+    } else {
+        sum_value.inverse()
+    });
+    let is_zero = row.next(BaseField::from(sum_value.is_zero() as u32));
+    row.add_constraint(is_zero.clone() + sum.clone() * inverse - R::F::one());
+    row.add_constraint(is_zero.clone() * sum);
+    is_zero
+}
+
+/// Converts a field element into a signed integer of the smallest absolute value.
+fn signed(value: BaseField) -> i64 {
+    if value.0 > P / 2 {
+        value.0 as i64 - P as i64
+    } else {
+        value.0 as i64
+    }
+}
+
+fn byte_len(max: &BigInt) -> usize {
+    (max.bits() as usize).div_ceil(8).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::extensions::circuit::TraceRow;
+
+    fn biguint(value: &str) -> BigUint {
+        BigUint::parse_bytes(value.as_bytes(), 16).unwrap()
+    }
+
+    #[test]
+    fn test_mul_mod() {
+        let lookup_elements = AllLookupElements::dummy();
+        let modulus = biguint("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F");
+        let a = biguint("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798");
+        let b = biguint("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8");
+        let c = (&a * &b) % &modulus;
+
+        let mut row = TraceRow::default();
+        let one = BaseField::one();
+        let a = next_uint(&mut row, &a, 32, &one, &lookup_elements);
+        let b = next_uint(&mut row, &b, 32, &one, &lookup_elements);
+        let c = next_uint(&mut row, &c, 32, &one, &lookup_elements);
+        let poly = Poly::from_bytes(&a) * Poly::from_bytes(&b) - Poly::from_bytes(&c);
+        assert_zero_mod(&mut row, poly.clone(), &modulus, &one, &lookup_elements);
+        assert_lt(
+            &mut row,
+            &c,
+            Poly::constant(&modulus),
+            &one,
+            &lookup_elements,
+        );
+
+        let m = next_uint(&mut row, &modulus, 32, &one, &lookup_elements);
+        assert_zero_mod_var(&mut row, poly, &m, &one, &lookup_elements);
+        let is_zero = next_is_zero(&mut row, &[BaseField::zero(); 4]);
+        assert!(is_zero.is_one());
+        let is_zero = next_is_zero(&mut row, &c);
+        assert!(is_zero.is_zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "constraint is not satisfied")]
+    fn test_wrong_product() {
+        let lookup_elements = AllLookupElements::dummy();
+        let modulus = BigUint::from(1_000_003u32);
+
+        let mut row = TraceRow::default();
+        let one = BaseField::one();
+        let a = next_uint(
+            &mut row,
+            &BigUint::from(123_456u32),
+            3,
+            &one,
+            &lookup_elements,
+        );
+        let c = next_uint(&mut row, &BigUint::from(2u32), 3, &one, &lookup_elements);
+        let poly = Poly::from_bytes(&a) * Poly::from_bytes(&a) - Poly::from_bytes(&c);
+        assert_zero_mod(&mut row, poly, &modulus, &one, &lookup_elements);
+    }
+}
@@ -0,0 +1,439 @@
+//! Row-based circuits for custom extensions.
+//!
+//! A [`RowCircuit`] describes a single row of an extension trace: it allocates columns, adds constraints and
+//! lookups through the [`Row`] trait. The same description fills the trace with [`TraceRow`] and evaluates
+//! constraints with [`EvalRow`], so that the two cannot diverge. [`CircuitExtension`] turns a circuit into a
+//! [`CustomExtension`] usable with [`ExtensionComponent::custom`](super::ExtensionComponent::custom).
+//!
+//! Rows are independent of each other, a circuit spanning several rows links them with its own relation, see
+//! [`AllLookupElements::insert_custom`].
+
+use std::{
+    fmt::Debug,
+    ops::{Add, AddAssign, Mul, Neg, Sub},
+    sync::Arc,
+};
+
+use num_traits::Zero;
+use stwo_prover::{
+    constraint_framework::{
+        logup::LogupTraceGenerator, EvalAtRow, FrameworkComponent, FrameworkEval, InfoEvaluator,
+        Relation, RelationEFTraitBound, RelationEntry, TraceLocationAllocator,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::{
+            column::BaseColumn,
+            m31::{LOG_N_LANES, N_LANES},
+            qm31::PackedSecureField,
+            SimdBackend,
+        },
+        fields::{m31::BaseField, qm31::SecureField, FieldExpOps},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        ColumnVec,
+    },
+};
+
+use super::{CustomExtension, Range256LookupElements};
+use crate::{
+    components::{AllLookupElements, LOG_CONSTRAINT_DEGREE},
+    hash::MachineChannel,
+    preflight::Support,
+    trace::sidenote::SideNote,
+};
+
+/// Arithmetic available on values of a [`Row`].
+pub trait RowField:
+    FieldExpOps
+    + Clone
+    + Debug
+    + Zero
+    + Neg<Output = Self>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<BaseField, Output = Self>
+    + AddAssign
+    + From<BaseField>
+{
+}
+
+impl<T> RowField for T where
+    T: FieldExpOps
+        + Clone
+        + Debug
+        + Zero
+        + Neg<Output = Self>
+        + Add<Output = Self>
+        + Sub<Output = Self>
+        + Mul<BaseField, Output = Self>
+        + AddAssign
+        + From<BaseField>
+{
+}
+
+/// A row of an extension trace, either being filled or being constrained.
+pub trait Row {
+    type F: RowField;
+    type EF: RelationEFTraitBound<Self::F>;
+
+    /// Allocates the next column. `value` is only used when the trace is filled.
+    fn next(&mut self, value: BaseField) -> Self::F;
+
+    /// Returns the value of an expression when the trace is filled, and zero when constraints are evaluated.
+    ///
+    /// Used for computing the witness from already allocated columns.
+    fn value(&self, expr: &Self::F) -> BaseField;
+
+    /// Adds a constraint that must be zero on every row.
+    fn add_constraint(&mut self, constraint: Self::F);
+
+    /// Adds `multiplicity / combine(values)` to the logup sum.
+    fn add_to_relation<R: Relation<Self::F, Self::EF>>(
+        &mut self,
+        relation: &R,
+        multiplicity: Self::F,
+        values: &[Self::F],
+    );
+
+    /// Checks that `value` is a byte, `multiplicity` times.
+    ///
+    /// Unlike other lookups, range checks must be counted by the base component and therefore may only be added
+    /// with this method.
+    fn range_check_u8(
+        &mut self,
+        multiplicity: Self::F,
+        value: Self::F,
+        lookup_elements: &AllLookupElements,
+    ) {
+        let relation: &Range256LookupElements = lookup_elements.as_ref();
+        self.add_to_relation(relation, multiplicity, &[value]);
+    }
+}
+
+/// Fills a row of the trace.
+#[derive(Debug, Default)]
+pub struct TraceRow {
+    columns: Vec<BaseField>,
+    fractions: Vec<(SecureField, SecureField)>,
+    range_checks: Vec<(BaseField, BaseField)>,
+}
+
+impl Row for TraceRow {
+    type F = BaseField;
+    type EF = SecureField;
+
+    fn next(&mut self, value: BaseField) -> BaseField {
+        self.columns.push(value);
+        value
+    }
+
+    fn value(&self, expr: &BaseField) -> BaseField {
+        *expr
+    }
+
+    fn add_constraint(&mut self, constraint: BaseField) {
+        debug_assert!(
+            constraint.is_zero(),
+            "constraint is not satisfied after column {}",
+            self.columns.len()
+        );
+    }
+
+    fn add_to_relation<R: Relation<BaseField, SecureField>>(
+        &mut self,
+        relation: &R,
+        multiplicity: BaseField,
+        values: &[BaseField],
+    ) {
+        self.fractions
+            .push((multiplicity.into(), relation.combine(values)));
+    }
+
+    fn range_check_u8(
+        &mut self,
+        multiplicity: BaseField,
+        value: BaseField,
+        lookup_elements: &AllLookupElements,
+    ) {
+        self.range_checks.push((multiplicity, value));
+        let relation: &Range256LookupElements = lookup_elements.as_ref();
+        self.add_to_relation(relation, multiplicity, &[value]);
+    }
+}
+
+/// Evaluates constraints of a row.
+pub struct EvalRow<E>(E);
+
+impl<E: EvalAtRow> Row for EvalRow<E> {
+    type F = E::F;
+    type EF = E::EF;
+
+    fn next(&mut self, _value: BaseField) -> E::F {
+        self.0.next_trace_mask()
+    }
+
+    fn value(&self, _expr: &E::F) -> BaseField {
+        BaseField::zero()
+    }
+
+    fn add_constraint(&mut self, constraint: E::F) {
+        self.0.add_constraint(constraint);
+    }
+
+    fn add_to_relation<R: Relation<E::F, E::EF>>(
+        &mut self,
+        relation: &R,
+        multiplicity: E::F,
+        values: &[E::F],
+    ) {
+        self.0
+            .add_to_relation(RelationEntry::new(relation, multiplicity.into(), values));
+    }
+}
+
+/// A circuit proving one row at a time.
+///
+/// The structure of a row, i.e. its columns, constraints and lookups, must not depend on the input. Lookup values
+/// must be linear in the columns and multiplicities at most quadratic, constraints are allowed to have degree up to 4.
+pub trait RowCircuit: Debug + Send + Sync + 'static {
+    /// Witness of a single row, the default value is used for padding rows and must satisfy all constraints.
+    type Input: Default;
+
+    /// Returns inputs of all rows, e.g. one per precompile call from [`SideNote::precompile_calls`].
+    fn rows(&self, side_note: &SideNote) -> Vec<Self::Input>;
+
+    /// Fills or constrains one row. When constraints are evaluated the input is the default value.
+    fn evaluate<R: Row>(
+        &self,
+        row: &mut R,
+        input: &Self::Input,
+        lookup_elements: &AllLookupElements,
+    );
+
+    /// See [`CustomExtension::draw_lookup_elements`].
+    fn draw_lookup_elements(
+        &self,
+        _lookup_elements: &mut AllLookupElements,
+        _channel: &mut MachineChannel,
+    ) {
+    }
+
+    /// See [`CustomExtension::declare_support`].
+    fn declare_support(&self, _support: &mut Support) {}
+}
+
+/// A [`CustomExtension`] with a trace of fixed size built from a [`RowCircuit`].
+#[derive(Debug)]
+pub struct CircuitExtension<C> {
+    circuit: Arc<C>,
+    log_size: u32,
+}
+
+impl<C: RowCircuit> CircuitExtension<C> {
+    /// Creates an extension with `2^log_size` rows, proving fails if the circuit returns more rows.
+    pub fn new(circuit: C, log_size: u32) -> Self {
+        assert!(
+            log_size >= LOG_N_LANES,
+            "log_size should be big enough for SIMD"
+        );
+        Self {
+            circuit: Arc::new(circuit),
+            log_size,
+        }
+    }
+
+    fn eval(&self, lookup_elements: AllLookupElements) -> CircuitEval<C> {
+        CircuitEval {
+            circuit: self.circuit.clone(),
+            log_size: self.log_size,
+            lookup_elements,
+        }
+    }
+
+    fn dummy_lookup_elements(&self) -> AllLookupElements {
+        let mut lookup_elements = AllLookupElements::dummy();
+        self.circuit
+            .draw_lookup_elements(&mut lookup_elements, &mut MachineChannel::default());
+        lookup_elements
+    }
+
+    fn trace_rows(
+        &self,
+        side_note: &SideNote,
+        lookup_elements: &AllLookupElements,
+    ) -> Vec<TraceRow> {
+        let mut inputs = self.circuit.rows(side_note);
+        assert!(
+            inputs.len() <= 1 << self.log_size,
+            "{} rows don't fit into the extension of log size {}",
+            inputs.len(),
+            self.log_size
+        );
+        inputs.resize_with(1 << self.log_size, Default::default);
+        inputs
+            .iter()
+            .map(|input| {
+                let mut row = TraceRow::default();
+                self.circuit.evaluate(&mut row, input, lookup_elements);
+                row
+            })
+            .collect()
+    }
+}
+
+impl<C: RowCircuit> CustomExtension for CircuitExtension<C> {
+    fn generate_preprocessed_trace(
+        &self,
+    ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+        vec![]
+    }
+
+    fn generate_original_trace(
+        &self,
+        side_note: &SideNote,
+    ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+        let rows = self.trace_rows(side_note, &self.dummy_lookup_elements());
+        let domain = CanonicCoset::new(self.log_size).circle_domain();
+        (0..rows[0].columns.len())
+            .map(|col| {
+                let column = BaseColumn::from_iter(rows.iter().map(|row| row.columns[col]));
+                CircleEvaluation::new(domain, column)
+            })
+            .collect()
+    }
+
+    fn generate_interaction_trace(
+        &self,
+        side_note: &SideNote,
+        lookup_elements: &AllLookupElements,
+    ) -> (
+        ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+        SecureField,
+    ) {
+        let rows = self.trace_rows(side_note, lookup_elements);
+        let num_fractions = rows[0].fractions.len();
+        assert!(num_fractions > 0, "circuit must have at least one lookup");
+
+        // Fractions are summed in pairs, matching `finalize_logup_in_pairs`.
+        let mut logup_trace_gen = LogupTraceGenerator::new(self.log_size);
+        for pair_start in (0..num_fractions).step_by(2) {
+            let mut logup_col_gen = logup_trace_gen.new_col();
+            for (vec_row, lanes) in rows.chunks_exact(N_LANES).enumerate() {
+                let (numerators, denominators): (Vec<SecureField>, Vec<SecureField>) = lanes
+                    .iter()
+                    .map(|row| {
+                        let (n0, d0) = row.fractions[pair_start];
+                        match row.fractions.get(pair_start + 1) {
+                            Some(&(n1, d1)) => (n0 * d1 + n1 * d0, d0 * d1),
+                            None => (n0, d0),
+                        }
+                    })
+                    .unzip();
+                logup_col_gen.write_frac(
+                    vec_row,
+                    PackedSecureField::from_array(
+                        numerators.try_into().expect("chunk size is N_LANES"),
+                    ),
+                    PackedSecureField::from_array(
+                        denominators.try_into().expect("chunk size is N_LANES"),
+                    ),
+                );
+            }
+            logup_col_gen.finalize_col();
+        }
+        logup_trace_gen.finalize_last()
+    }
+
+    fn to_component_prover(
+        &self,
+        tree_span_provider: &mut TraceLocationAllocator,
+        lookup_elements: &AllLookupElements,
+        claimed_sum: SecureField,
+    ) -> Box<dyn ComponentProver<SimdBackend>> {
+        Box::new(FrameworkComponent::new(
+            tree_span_provider,
+            self.eval(lookup_elements.clone()),
+            claimed_sum,
+        ))
+    }
+
+    fn to_component(
+        &self,
+        tree_span_provider: &mut TraceLocationAllocator,
+        lookup_elements: &AllLookupElements,
+        claimed_sum: SecureField,
+    ) -> Box<dyn Component> {
+        Box::new(FrameworkComponent::new(
+            tree_span_provider,
+            self.eval(lookup_elements.clone()),
+            claimed_sum,
+        ))
+    }
+
+    fn trace_sizes(&self) -> TreeVec<Vec<u32>> {
+        self.eval(self.dummy_lookup_elements())
+            .evaluate(InfoEvaluator::empty())
+            .mask_offsets
+            .as_cols_ref()
+            .map_cols(|_| self.log_size)
+    }
+
+    fn preprocessed_trace_sizes(&self) -> Vec<u32> {
+        vec![]
+    }
+
+    fn declare_support(&self, support: &mut Support) {
+        self.circuit.declare_support(support);
+    }
+
+    fn update_side_note(&self, side_note: &mut SideNote) {
+        let rows = self.trace_rows(side_note, &self.dummy_lookup_elements());
+        for (multiplicity, value) in rows
+            .iter()
+            .flat_map(|row| &row.range_checks)
+            .filter(|(multiplicity, _)| !multiplicity.is_zero())
+        {
+            let value = u8::try_from(value.0).expect("range checked value must be a byte");
+            for _ in 0..multiplicity.0 {
+                side_note.range_check_u8(value);
+            }
+        }
+    }
+
+    fn draw_lookup_elements(
+        &self,
+        lookup_elements: &mut AllLookupElements,
+        channel: &mut MachineChannel,
+    ) {
+        self.circuit.draw_lookup_elements(lookup_elements, channel);
+    }
+}
+
+struct CircuitEval<C> {
+    circuit: Arc<C>,
+    log_size: u32,
+    lookup_elements: AllLookupElements,
+}
+
+impl<C: RowCircuit> FrameworkEval for CircuitEval<C> {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_DEGREE
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, eval: E) -> E {
+        let mut row = EvalRow(eval);
+        self.circuit
+            .evaluate(&mut row, &C::Input::default(), &self.lookup_elements);
+        let EvalRow(mut eval) = row;
+        eval.finalize_logup_in_pairs();
+        eval
+    }
+}
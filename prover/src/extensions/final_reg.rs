@@ -45,15 +45,26 @@ impl RegisterIdx {
     }
 }
 
-/// A component for the final register memory state
+/// A component for the initial and final register memory state
+///
+/// The initial values are zero for a whole execution. A segment of an execution starts from the registers left by
+/// the previous one, and exposes its final values unless it is the last segment.
 #[derive(Debug, Clone)]
 pub struct FinalReg {
-    _private: (),
+    // Boxed to keep the size of `ExtensionComponent` small.
+    initial_values: Box<[u32; NUM_REGISTERS as usize]>,
+    final_values: Option<Box<[u32; NUM_REGISTERS as usize]>>,
 }
 
 impl FinalReg {
-    pub(super) const fn new() -> Self {
-        Self { _private: () }
+    pub(super) fn new(
+        initial_values: [u32; NUM_REGISTERS as usize],
+        final_values: Option<[u32; NUM_REGISTERS as usize]>,
+    ) -> Self {
+        Self {
+            initial_values: Box::new(initial_values),
+            final_values: final_values.map(Box::new),
+        }
     }
 }
 
@@ -89,12 +100,38 @@ impl FrameworkEval for FinalRegEval {
         // let _reg_idx = eval.next_trace_mask();
         let reg_idx = RegisterIdx::new(FinalRegEval::LOG_SIZE);
         let reg_idx = eval.get_preprocessed_column(reg_idx.id());
+        let initial_value: Vec<_> = (0..WORD_SIZE)
+            .map(|i| {
+                eval.get_preprocessed_column(PreProcessedColumnId {
+                    id: format!("preprocessed_register_initial_value_{i}"),
+                })
+            })
+            .collect();
+        let public_final_flag = eval.get_preprocessed_column(PreProcessedColumnId {
+            id: "preprocessed_register_final_flag".to_owned(),
+        });
+        let public_final_value: Vec<_> = (0..WORD_SIZE)
+            .map(|i| {
+                eval.get_preprocessed_column(PreProcessedColumnId {
+                    id: format!("preprocessed_register_final_value_{i}"),
+                })
+            })
+            .collect();
         let final_timestamp: Vec<_> = (0..4).map(|_| eval.next_trace_mask()).collect();
         let final_value: Vec<_> = (0..4).map(|_| eval.next_trace_mask()).collect();
 
+        // Constrain the final values to the public ones, if any
+        // public_final_flag ・(final_value - public_final_value) = 0
+        for (value, public_value) in final_value.iter().zip(public_final_value) {
+            eval.add_constraint(public_final_flag.clone() * (value.clone() - public_value));
+        }
+
         // Add initial register memory state
         let mut tuple: [E::F; Self::TUPLE_SIZE] = std::array::from_fn(|_| E::F::zero());
         tuple[0] = reg_idx.clone();
+        for (elm, value) in tuple[1 + WORD_SIZE..].iter_mut().zip(initial_value) {
+            *elm = value;
+        }
         let numerator = E::F::one();
 
         eval.add_to_relation(RelationEntry::new(
@@ -137,8 +174,9 @@ impl BuiltInExtension for FinalReg {
     type Eval = FinalRegEval;
 
     fn generate_preprocessed_trace(
+        &self,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
-        let base_cols = Self::preprocessed_base_columns();
+        let base_cols = self.preprocessed_base_columns();
         let domain = CanonicCoset::new(FinalRegEval::LOG_SIZE).circle_domain();
         base_cols
             .into_iter()
@@ -147,7 +185,7 @@ impl BuiltInExtension for FinalReg {
     }
 
     fn preprocessed_trace_sizes() -> Vec<u32> {
-        vec![FinalRegEval::LOG_SIZE]
+        vec![FinalRegEval::LOG_SIZE; 2 + 2 * WORD_SIZE]
     }
 
     /// The first four columns represent the final values, the following four columns represent the final timestamps.
@@ -165,6 +203,7 @@ impl BuiltInExtension for FinalReg {
     }
 
     fn generate_interaction_trace(
+        &self,
         side_note: &SideNote,
         lookup_elements: &AllLookupElements,
     ) -> (
@@ -174,7 +213,9 @@ impl BuiltInExtension for FinalReg {
         let lookup_element: &RegisterCheckLookupElements = lookup_elements.as_ref();
 
         let mut logup_trace_gen = LogupTraceGenerator::new(FinalRegEval::LOG_SIZE);
-        let row_idx = &Self::preprocessed_base_columns()[0];
+        let preprocessed_cols = self.preprocessed_base_columns();
+        let row_idx = &preprocessed_cols[0];
+        let initial_values = &preprocessed_cols[1..=WORD_SIZE];
         let base_cols = Self::base_columns(side_note);
 
         // Adding the initial register memory state
@@ -184,6 +225,9 @@ impl BuiltInExtension for FinalReg {
             let mut tuple: [PackedM31; FinalRegEval::TUPLE_SIZE] =
                 [BaseField::zero().into(); FinalRegEval::TUPLE_SIZE]; // reg_idx, cur_timestamp, cur_value
             tuple[0] = row_idx; // Use row_idx as register index
+            for (elm, col) in tuple[1 + WORD_SIZE..].iter_mut().zip(initial_values) {
+                *elm = col.data[vec_row];
+            }
             let denom = lookup_element.combine(tuple.as_slice());
             let numerator = PackedBaseField::broadcast(BaseField::one());
             logup_col_gen.write_frac(vec_row, numerator.into(), denom);
@@ -210,9 +254,28 @@ impl BuiltInExtension for FinalReg {
 }
 
 impl FinalReg {
-    fn preprocessed_base_columns() -> Vec<BaseColumn> {
+    /// The register index, the initial values, the flag of public final values and the public final values.
+    fn preprocessed_base_columns(&self) -> Vec<BaseColumn> {
         let reg_idx = BaseColumn::from_iter((0..32).map(BaseField::from));
-        vec![reg_idx]
+        let mut base_cols = vec![reg_idx];
+        for i in 0..WORD_SIZE {
+            let col = self
+                .initial_values
+                .iter()
+                .map(|val| val.into_base_fields()[i]);
+            base_cols.push(BaseColumn::from_iter(col));
+        }
+        let final_flag = BaseField::from(u32::from(self.final_values.is_some()));
+        base_cols.push(BaseColumn::from_iter(
+            std::iter::repeat(final_flag).take(NUM_REGISTERS as usize),
+        ));
+        let final_values = self.final_values.as_deref().copied().unwrap_or_default();
+        for i in 0..WORD_SIZE {
+            let col = final_values.iter().map(|val| val.into_base_fields()[i]);
+            base_cols.push(BaseColumn::from_iter(col));
+        }
+        assert_eq!(base_cols.len(), 2 + 2 * WORD_SIZE);
+        base_cols
     }
     fn base_columns(side_note: &SideNote) -> Vec<BaseColumn> {
         let mut base_cols: Vec<BaseColumn> = vec![];
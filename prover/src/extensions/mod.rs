@@ -5,14 +5,18 @@
 //! each component can have a smaller log size or higher constraint degree bound. Each component is expected to emit
 //! a logup sum that matches with the one from the main trace, enforcing the total sum to equal to zero.
 //!
-//! There's no support for external our-of-crate extensions, mainly precompiles, yet. All components are considered
-//! to be built-in.
-//!
 //! To define a new built-in component, a struct implementing [`BuiltInExtension`] must be added to [`ExtensionComponent`]
 //! enum.
 //!
+//! External out-of-crate extensions, mainly precompiles, implement the type-erased [`CustomExtension`] trait instead
+//! and are wrapped with [`ExtensionComponent::custom`]. A precompile extension consumes calls recorded in
+//! [`SideNote::precompile_calls`] from [`PrecompileLookupElements`] and proves their RAM accesses against
+//! [`LoadStoreLookupElements`]. The [`circuit`] module implements [`CustomExtension`] for row-based circuits, and
+//! [`bigint`] provides gadgets for non-native integer arithmetic in such circuits.
+//!
 //! Some components must always be present, for example [`final_reg::FinalReg`]. They should only be accessible within
-//! the crate to avoid misuse.
+//! the crate to avoid misuse. The built-in [`private_input`] extension reuses [`circuit`], as its size depends on the
+//! trace, and [`final_reg::FinalReg`] depends on the initial and final registers of the proven segment.
 
 use stwo_prover::{
     constraint_framework::{
@@ -21,6 +25,7 @@ use stwo_prover::{
     core::{
         air::{Component, ComponentProver},
         backend::simd::SimdBackend,
+        channel::MerkleChannel,
         fields::{m31::BaseField, qm31::SecureField},
         pcs::TreeVec,
         poly::{circle::CircleEvaluation, BitReversedOrder},
@@ -28,7 +33,24 @@ use stwo_prover::{
     },
 };
 
-use crate::{components::AllLookupElements, trace::sidenote::SideNote};
+use std::sync::Arc;
+
+use nexus_common::riscv::register::NUM_REGISTERS;
+
+use crate::{
+    components::AllLookupElements,
+    hash::{MachineChannel, MachineHasher},
+    preflight::Support,
+    trace::sidenote::SideNote,
+};
+
+pub use crate::chips::{
+    instructions::{custom::PrecompileLookupElements, load_store::LoadStoreLookupElements},
+    range_check::range256::Range256LookupElements,
+};
+
+pub mod bigint;
+pub mod circuit;
 
 mod bit_op;
 mod final_reg;
@@ -39,6 +61,9 @@ mod multiplicity;
 use multiplicity::{Multiplicity128, Multiplicity16, Multiplicity256, Multiplicity32};
 mod multiplicity8;
 use multiplicity8::Multiplicity8;
+mod private_input;
+pub(crate) use private_input::num_rows as num_private_input_rows;
+use private_input::PrivateInputCircuit;
 
 trait FrameworkEvalExt: FrameworkEval + Default + Sync + 'static {
     // TODO: make it variable, e.g. derived by the component implementation from
@@ -52,6 +77,7 @@ trait BuiltInExtension {
     type Eval: FrameworkEvalExt;
 
     fn generate_preprocessed_trace(
+        &self,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>;
 
     fn generate_original_trace(
@@ -59,6 +85,7 @@ trait BuiltInExtension {
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>;
 
     fn generate_interaction_trace(
+        &self,
         side_note: &SideNote,
         lookup_elements: &AllLookupElements,
     ) -> (
@@ -104,6 +131,69 @@ trait BuiltInExtension {
     fn preprocessed_trace_sizes() -> Vec<u32>;
 }
 
+/// Type-erased counterpart of [`BuiltInExtension`] for components defined outside of this crate.
+///
+/// The implementor is responsible for keeping all methods consistent with each other, in particular the component
+/// returned by [`CustomExtension::to_component`] must match the traces and sizes it reports.
+pub trait CustomExtension: std::fmt::Debug + Send + Sync {
+    fn generate_preprocessed_trace(
+        &self,
+    ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>;
+
+    fn generate_original_trace(
+        &self,
+        side_note: &SideNote,
+    ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>;
+
+    fn generate_interaction_trace(
+        &self,
+        side_note: &SideNote,
+        lookup_elements: &AllLookupElements,
+    ) -> (
+        ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+        SecureField,
+    );
+
+    fn to_component_prover(
+        &self,
+        tree_span_provider: &mut TraceLocationAllocator,
+        lookup_elements: &AllLookupElements,
+        claimed_sum: SecureField,
+    ) -> Box<dyn ComponentProver<SimdBackend>>;
+
+    fn to_component(
+        &self,
+        tree_span_provider: &mut TraceLocationAllocator,
+        lookup_elements: &AllLookupElements,
+        claimed_sum: SecureField,
+    ) -> Box<dyn Component>;
+
+    fn trace_sizes(&self) -> TreeVec<Vec<u32>>;
+
+    /// Returns the log_sizes of each preprocessed columns
+    fn preprocessed_trace_sizes(&self) -> Vec<u32>;
+
+    /// Declares the instructions proven by the extension, e.g. the index of a precompile, see
+    /// [`Machine::preflight`](crate::machine::Machine::preflight).
+    fn declare_support(&self, _support: &mut Support) {}
+
+    /// Records lookups into base components made by the extension, e.g. with [`SideNote::range_check_u8`].
+    ///
+    /// Called once after the main trace is filled and before the original trace of any extension is generated.
+    fn update_side_note(&self, _side_note: &mut SideNote) {}
+
+    /// Draws lookup elements of relations declared by the extension, see [`AllLookupElements::insert_custom`].
+    ///
+    /// Called by both the prover and the verifier after lookup elements of the base component are drawn, with the
+    /// channel of the proof.
+    fn draw_lookup_elements(
+        &self,
+        _lookup_elements: &mut AllLookupElements,
+        _channel: &mut MachineChannel,
+    ) {
+    }
+}
+
 extension_dispatch! {
     pub enum ExtensionComponent {
         FinalReg,
@@ -117,8 +207,45 @@ extension_dispatch! {
 }
 
 impl ExtensionComponent {
-    pub(super) const fn final_reg() -> Self {
-        Self::FinalReg(FinalReg::new())
+    /// Wraps an out-of-crate extension, e.g. a precompile circuit.
+    pub fn custom(extension: Arc<dyn CustomExtension>) -> Self {
+        Self::Custom(extension)
+    }
+
+    /// Built-in extensions only assist the base component and prove no instructions of their own.
+    pub(crate) fn declare_support(&self, support: &mut Support) {
+        if let Self::Custom(extension) = self {
+            extension.declare_support(support);
+        }
+    }
+
+    /// Built-in extensions only read the side note and use relations of the base component.
+    pub(crate) fn update_side_note(&self, side_note: &mut SideNote) {
+        if let Self::Custom(extension) = self {
+            extension.update_side_note(side_note);
+        }
+    }
+
+    pub(crate) fn draw_lookup_elements<H: MachineHasher>(
+        &self,
+        lookup_elements: &mut AllLookupElements,
+        channel: &mut <H::MerkleChannel as MerkleChannel>::C,
+    ) {
+        if let Self::Custom(extension) = self {
+            // Custom extensions are type-erased and cannot be generic over the channel of the proof.
+            let mut machine_channel = H::into_machine_channel(std::mem::take(channel));
+            extension.draw_lookup_elements(lookup_elements, &mut machine_channel);
+            *channel = H::from_machine_channel(machine_channel);
+        }
+    }
+
+    /// Unlike other built-in extensions the preprocessed trace depends on the statement, it holds the initial
+    /// registers and the public final registers of a segment, if any.
+    pub(super) fn final_reg(
+        initial_registers: [u32; NUM_REGISTERS],
+        final_registers: Option<[u32; NUM_REGISTERS]>,
+    ) -> Self {
+        Self::FinalReg(FinalReg::new(initial_registers, final_registers))
     }
     pub(super) const fn multiplicity8() -> Self {
         Self::Multiplicity8(Multiplicity8::new())
@@ -138,19 +265,27 @@ impl ExtensionComponent {
     pub(super) const fn bit_op_multiplicity() -> Self {
         Self::BitOpMultiplicity(BitOpMultiplicity::new())
     }
+    /// Unlike other built-in extensions the size depends on the trace, it matches the main trace.
+    pub(super) fn private_input(log_size: u32) -> Self {
+        Self::custom(Arc::new(circuit::CircuitExtension::new(
+            PrivateInputCircuit,
+            log_size,
+        )))
+    }
 }
 
 // A macro mimicking enum_dispatch, but with less flexibility and therefore without shared state managing.
 //
 // To avoid repetitive implementations of components, the main trait [`BuiltInExtension`] features associated
-// type with bound which makes it non object safe, or non dyn-compatible. External precompiles, once introduced,
-// will require type-erased version of this trait since the prover crate cannot know details of implementation.
-// Such precompiles can be implemented as a separate `Custom(Box<dyn ...>)` variant.
+// type with bound which makes it non object safe, or non dyn-compatible. External precompiles use the type-erased
+// [`CustomExtension`] instead since the prover crate cannot know details of implementation, they are stored in
+// a separate `Custom` variant.
 macro_rules! extension_dispatch {
     ($vis:vis enum $_enum:ident { $( $name:ident ),* $(,)? }) => {
         #[derive(Debug, Clone)]
         $vis enum $_enum {
             $($name($name),)*
+            Custom(Arc<dyn CustomExtension>),
         }
 
         $(
@@ -168,7 +303,8 @@ macro_rules! extension_dispatch {
                 &self,
             ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
                 match self {
-                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_preprocessed_trace(), )*
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_preprocessed_trace(inner), )*
+                    $_enum::Custom(inner) => inner.generate_preprocessed_trace(),
                 }
             }
 
@@ -178,6 +314,7 @@ macro_rules! extension_dispatch {
             ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
                 match self {
                     $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_original_trace(side_note), )*
+                    $_enum::Custom(inner) => inner.generate_original_trace(side_note),
                 }
             }
 
@@ -190,7 +327,8 @@ macro_rules! extension_dispatch {
                 SecureField,
             ) {
                 match self {
-                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_interaction_trace(side_note, lookup_elements), )*
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_interaction_trace(inner, side_note, lookup_elements), )*
+                    $_enum::Custom(inner) => inner.generate_interaction_trace(side_note, lookup_elements),
                 }
             }
 
@@ -202,6 +340,7 @@ macro_rules! extension_dispatch {
             ) -> Box<dyn ComponentProver<SimdBackend>> {
                 match self {
                     $( $_enum::$name(inner) => <$name as BuiltInExtension>::to_component_prover(inner, tree_span_provider, lookup_elements, claimed_sum), )*
+                    $_enum::Custom(inner) => inner.to_component_prover(tree_span_provider, lookup_elements, claimed_sum),
                 }
             }
 
@@ -213,18 +352,21 @@ macro_rules! extension_dispatch {
             ) -> Box<dyn Component> {
                 match self {
                     $( $_enum::$name(inner) => <$name as BuiltInExtension>::to_component(inner, tree_span_provider, lookup_elements, claimed_sum), )*
+                    $_enum::Custom(inner) => inner.to_component(tree_span_provider, lookup_elements, claimed_sum),
                 }
             }
 
             pub(crate) fn trace_sizes(&self) -> TreeVec<Vec<u32>> {
                 match self {
                     $( $_enum::$name(inner) => <$name as BuiltInExtension>::trace_sizes(inner), )*
+                    $_enum::Custom(inner) => inner.trace_sizes(),
                 }
             }
 
             pub(crate) fn preprocessed_trace_sizes(&self) -> Vec<u32> {
                 match self {
                     $( $_enum::$name(inner) => <$name as BuiltInExtension>::preprocessed_trace_sizes(), )*
+                    $_enum::Custom(inner) => inner.preprocessed_trace_sizes(),
                 }
             }
         }
@@ -0,0 +1,406 @@
+//! Built-in extension proving the stores of `ReadBytesFromPrivateInput`.
+//!
+//! A row of the main trace holds at most one word of memory accesses, the bulk read instead adds the call to
+//! [`PrivateInputLookupElements`] and this extension stores one byte per row. Rows of the same call are linked with
+//! [`PrivateInputChainElements`] through the address of the byte and the number of remaining bytes, the first row
+//! consumes the call and reads the buffer length from the second register slot of the call, which the main trace
+//! leaves unused for system calls.
+//!
+//! Like the single-byte `ReadFromPrivateInput`, the values of the bytes are a witness of the prover: the proof only
+//! shows that the returned number of bytes was stored into the buffer and that it doesn't exceed the buffer length.
+
+use std::array;
+
+use nexus_vm::{riscv::Register, trace::Trace, SyscallCode, WORD_SIZE};
+use stwo_prover::core::fields::m31::BaseField;
+
+use super::circuit::{Row, RowCircuit, RowField};
+use crate::{
+    chips::{
+        instructions::{load_store::LoadStoreLookupElements, syscall::PrivateInputLookupElements},
+        memory_check::register_mem_check::RegisterCheckLookupElements,
+    },
+    components::AllLookupElements,
+    hash::MachineChannel,
+    trace::{program::iter_program_steps, sidenote::SideNote},
+};
+
+/// The tuple consists of clk, the address of the byte in two 16-bit limbs and the number of remaining bytes in two
+/// 16-bit limbs.
+const CHAIN_TUPLE_SIZE: usize = WORD_SIZE + 2 + 2;
+stwo_prover::relation!(PrivateInputChainElements, CHAIN_TUPLE_SIZE);
+
+/// Witness of a row, i.e. of a single stored byte, or of a call that reads no bytes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PrivateInputRow {
+    is_real: bool,
+    is_first: bool,
+    is_last: bool,
+    is_store: bool,
+    clk: u32,
+    address: u32,
+    /// The number of bytes stored by this row and the following ones.
+    remaining: u32,
+    value: u8,
+    prev_value: u8,
+    prev_timestamp: u32,
+    /// Only set on the first row of a call.
+    reg2_timestamp: u32,
+    buflen: u32,
+    buflen_prev_timestamp: u32,
+}
+
+/// Returns the number of rows needed for the bulk reads of a trace, one per stored byte and at least one per call.
+pub(crate) fn num_rows(trace: &impl Trace) -> usize {
+    iter_program_steps(trace, trace.get_num_steps())
+        .flatten()
+        .filter(|step| {
+            step.get_syscall_code() == Some(SyscallCode::ReadBytesFromPrivateInput as u32)
+        })
+        .map(|step| step.step.memory_records.len().max(1))
+        .sum()
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct PrivateInputCircuit;
+
+impl RowCircuit for PrivateInputCircuit {
+    type Input = PrivateInputRow;
+
+    fn rows(&self, side_note: &SideNote) -> Vec<PrivateInputRow> {
+        let mut rows = Vec::new();
+        for read in &side_note.private_input_reads {
+            let count = read.memory.len() as u32;
+            let first = PrivateInputRow {
+                is_real: true,
+                is_first: true,
+                is_last: true,
+                clk: read.clk,
+                address: read.buf,
+                reg2_timestamp: read.clk * 3 + 2,
+                buflen: read.buflen,
+                buflen_prev_timestamp: read.buflen_prev_timestamp,
+                ..Default::default()
+            };
+            if count == 0 {
+                rows.push(first);
+                continue;
+            }
+            for (i, access) in read.memory.iter().enumerate() {
+                let row = PrivateInputRow {
+                    is_real: true,
+                    is_last: i as u32 + 1 == count,
+                    is_store: true,
+                    clk: read.clk,
+                    address: access.address,
+                    remaining: count - i as u32,
+                    value: access.value,
+                    prev_value: access.prev_value,
+                    prev_timestamp: access.prev_timestamp,
+                    ..Default::default()
+                };
+                rows.push(if i == 0 {
+                    PrivateInputRow {
+                        is_first: true,
+                        reg2_timestamp: first.reg2_timestamp,
+                        buflen: first.buflen,
+                        buflen_prev_timestamp: first.buflen_prev_timestamp,
+                        ..row
+                    }
+                } else {
+                    row
+                });
+            }
+        }
+        rows
+    }
+
+    fn evaluate<R: Row>(
+        &self,
+        row: &mut R,
+        input: &PrivateInputRow,
+        lookup_elements: &AllLookupElements,
+    ) {
+        let one = constant::<R::F>(1);
+
+        let is_real = next_bool(row, input.is_real);
+        let is_first = next_bool(row, input.is_first);
+        let is_last = next_bool(row, input.is_last);
+        let is_store = next_bool(row, input.is_store);
+        for flag in [&is_first, &is_last, &is_store] {
+            row.add_constraint(flag.clone() * (one.clone() - is_real.clone()));
+        }
+
+        let clk = next_bytes(row, input.clk);
+        let address = next_bytes(row, input.address);
+        let remaining = next_bytes(row, input.remaining);
+        for byte in address.iter().chain(&remaining) {
+            row.range_check_u8(is_real.clone(), byte.clone(), lookup_elements);
+        }
+
+        // A call reading no bytes takes a single row without a store.
+        let is_empty = is_real.clone() - is_store.clone();
+        for byte in &remaining {
+            row.add_constraint(is_empty.clone() * byte.clone());
+        }
+        row.add_constraint(is_empty.clone() * (one.clone() - is_first.clone()));
+        row.add_constraint(is_empty * (one.clone() - is_last.clone()));
+
+        // The last store of a call is the only remaining byte.
+        row.add_constraint(
+            is_last.clone() * is_store.clone() * (remaining[0].clone() - one.clone()),
+        );
+        for byte in &remaining[1..] {
+            row.add_constraint(is_last.clone() * is_store.clone() * byte.clone());
+        }
+
+        // Store the byte, prev_timestamp < clk, i.e. clk - 1 - prev_timestamp fits into 32 bits.
+        let value = row.next(BaseField::from(input.value as u32));
+        row.range_check_u8(is_store.clone(), value.clone(), lookup_elements);
+        let prev_value = row.next(BaseField::from(input.prev_value as u32));
+        let prev_timestamp = next_bytes(row, input.prev_timestamp);
+        let clk_lt = next_lt(
+            row,
+            &is_store,
+            input.prev_timestamp,
+            input.clk,
+            &prev_timestamp,
+            &clk,
+        );
+        for byte in &clk_lt {
+            row.range_check_u8(is_store.clone(), byte.clone(), lookup_elements);
+        }
+
+        let load_store: &LoadStoreLookupElements = lookup_elements.as_ref();
+        let mut tuple = address.to_vec();
+        tuple.push(prev_value);
+        tuple.extend(prev_timestamp);
+        row.add_to_relation(load_store, -is_store.clone(), &tuple);
+        let mut tuple = address.to_vec();
+        tuple.push(value);
+        tuple.extend(clk.iter().cloned());
+        row.add_to_relation(load_store, is_store, &tuple);
+
+        // Read the buffer length from a1 on the first row, prev_timestamp < reg2_timestamp.
+        let reg2_timestamp = next_bytes(row, input.reg2_timestamp);
+        let buflen = next_bytes(row, input.buflen);
+        let buflen_prev_timestamp = next_bytes(row, input.buflen_prev_timestamp);
+        let reg2_lt = next_lt(
+            row,
+            &is_first,
+            input.buflen_prev_timestamp,
+            input.reg2_timestamp,
+            &buflen_prev_timestamp,
+            &reg2_timestamp,
+        );
+
+        // The number of bytes read doesn't exceed the buffer length, i.e. buflen - count fits into 32 bits.
+        let diff_value = input.buflen.wrapping_sub(input.remaining);
+        let diff = next_bytes(row, diff_value);
+        let diff_borrow = next_bool(
+            row,
+            (input.remaining & 0xFFFF) + (diff_value & 0xFFFF) > 0xFFFF,
+        );
+        let [count_lo, count_hi] = byte_limbs(&remaining);
+        let [diff_lo, diff_hi] = byte_limbs(&diff);
+        let [buflen_lo, buflen_hi] = byte_limbs(&buflen);
+        row.add_constraint(
+            is_first.clone()
+                * (count_lo + diff_lo - buflen_lo - diff_borrow.clone() * BaseField::from(1 << 16)),
+        );
+        row.add_constraint(is_first.clone() * (count_hi + diff_hi + diff_borrow - buflen_hi));
+        for byte in buflen.iter().chain(&reg2_lt).chain(&diff) {
+            row.range_check_u8(is_first.clone(), byte.clone(), lookup_elements);
+        }
+
+        let registers: &RegisterCheckLookupElements = lookup_elements.as_ref();
+        let a1 = constant::<R::F>(Register::X11 as u32);
+        let mut tuple = vec![a1.clone()];
+        tuple.extend(buflen_prev_timestamp);
+        tuple.extend(buflen.iter().cloned());
+        row.add_to_relation(registers, -is_first.clone(), &tuple);
+        let mut tuple = vec![a1];
+        tuple.extend(reg2_timestamp.iter().cloned());
+        tuple.extend(buflen);
+        row.add_to_relation(registers, is_first.clone(), &tuple);
+
+        // The first row consumes the call, the address is the buffer and the remaining bytes are the count.
+        let private_input: &PrivateInputLookupElements = lookup_elements.as_ref();
+        let tuple: Vec<R::F> = clk
+            .iter()
+            .chain(&reg2_timestamp)
+            .chain(&address)
+            .chain(&remaining)
+            .cloned()
+            .collect();
+        row.add_to_relation(private_input, -is_first.clone(), &tuple);
+
+        // Link the stores of a call, the next row stores at address + 1 with one byte less remaining. The limbs
+        // of the next row are bytes, which determines both the carry and the borrow.
+        let carry = next_bool(row, input.address & 0xFFFF == 0xFFFF);
+        let borrow = next_bool(row, input.remaining & 0xFFFF == 0);
+        let [address_lo, address_hi] = byte_limbs(&address);
+        let [remaining_lo, remaining_hi] = byte_limbs(&remaining);
+        let chain: &PrivateInputChainElements = lookup_elements.get_custom();
+        let mut tuple = clk.to_vec();
+        tuple.extend([
+            address_lo.clone(),
+            address_hi.clone(),
+            remaining_lo.clone(),
+            remaining_hi.clone(),
+        ]);
+        row.add_to_relation(chain, is_first - is_real.clone(), &tuple);
+        let mut tuple = clk.to_vec();
+        tuple.extend([
+            address_lo + one.clone() - carry.clone() * BaseField::from(1 << 16),
+            address_hi + carry,
+            remaining_lo - one + borrow.clone() * BaseField::from(1 << 16),
+            remaining_hi - borrow,
+        ]);
+        row.add_to_relation(chain, is_real - is_last, &tuple);
+    }
+
+    fn draw_lookup_elements(
+        &self,
+        lookup_elements: &mut AllLookupElements,
+        channel: &mut MachineChannel,
+    ) {
+        lookup_elements.insert_custom(PrivateInputChainElements::draw(channel));
+    }
+}
+
+fn constant<F: RowField>(value: u32) -> F {
+    F::from(BaseField::from(value))
+}
+
+fn next_bool<R: Row>(row: &mut R, value: bool) -> R::F {
+    let bit = row.next(BaseField::from(value as u32));
+    row.add_constraint(bit.clone() * (constant::<R::F>(1) - bit.clone()));
+    bit
+}
+
+fn next_bytes<R: Row>(row: &mut R, value: u32) -> [R::F; WORD_SIZE] {
+    let bytes = value.to_le_bytes();
+    array::from_fn(|i| row.next(BaseField::from(bytes[i] as u32)))
+}
+
+fn byte_limbs<F: RowField>(bytes: &[F; WORD_SIZE]) -> [F; 2] {
+    [
+        bytes[0].clone() + bytes[1].clone() * BaseField::from(1 << 8),
+        bytes[2].clone() + bytes[3].clone() * BaseField::from(1 << 8),
+    ]
+}
+
+/// Constrains `lhs < rhs` when `flag` is set, returns the bytes of `rhs - 1 - lhs` which the caller must range check.
+fn next_lt<R: Row>(
+    row: &mut R,
+    flag: &R::F,
+    lhs_value: u32,
+    rhs_value: u32,
+    lhs: &[R::F; WORD_SIZE],
+    rhs: &[R::F; WORD_SIZE],
+) -> [R::F; WORD_SIZE] {
+    let aux_value = rhs_value.wrapping_sub(1).wrapping_sub(lhs_value);
+    let aux = next_bytes(row, aux_value);
+    let borrow = next_bool(
+        row,
+        (lhs_value & 0xFFFF) + 1 + (aux_value & 0xFFFF) > 0xFFFF,
+    );
+    let [lhs_lo, lhs_hi] = byte_limbs(lhs);
+    let [aux_lo, aux_hi] = byte_limbs(&aux);
+    let [rhs_lo, rhs_hi] = byte_limbs(rhs);
+    row.add_constraint(
+        flag.clone()
+            * (lhs_lo + constant::<R::F>(1) + aux_lo
+                - rhs_lo
+                - borrow.clone() * BaseField::from(1 << 16)),
+    );
+    row.add_constraint(flag.clone() * (lhs_hi + aux_hi + borrow - rhs_hi));
+    aux
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nexus_vm::{
+        emulator::{Emulator, HarvardEmulator, InternalView},
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_harvard,
+    };
+
+    use stwo_prover::core::{
+        backend::{simd::SimdBackend, BackendForChannel},
+        vcs::blake2_merkle::Blake2sMerkleHasher,
+    };
+
+    use crate::{
+        hash::{MachineHasher, Poseidon2M31MerkleHasher},
+        machine::{BaseComponent, Machine, ProverConfig},
+    };
+
+    /// Mimics `PrivateInput::read_to_end` with a chunk size of `chunk_size`, reading into a buffer at 0x80000.
+    fn read_to_end(chunk_size: u32, max_chunks: usize) -> Vec<BasicBlock> {
+        let mut instructions = vec![
+            // x8 = 0x80000, the end of the buffer.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 8, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 8, 8, 19),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 11, 0, chunk_size),
+        ];
+        for _ in 0..max_chunks {
+            instructions.extend([
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 8, 0),
+                Instruction::new_ir(
+                    Opcode::from(BuiltinOpcode::ADDI),
+                    17,
+                    0,
+                    SyscallCode::ReadBytesFromPrivateInput as u32,
+                ),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 8, 8, 10),
+            ]);
+        }
+        vec![BasicBlock::new(instructions)]
+    }
+
+    fn prove_verify<H: MachineHasher>(input: &[u8], chunk_size: u32)
+    where
+        SimdBackend: BackendForChannel<H::MerkleChannel>,
+    {
+        let max_chunks = input.len() / chunk_size as usize + 1;
+        let mut emulator = HarvardEmulator::from_basic_blocks(&read_to_end(chunk_size, max_chunks));
+        emulator.set_private_input(input);
+        let (view, trace) = k_trace_harvard(emulator, 1).expect("error generating trace");
+
+        let proof: crate::machine::Proof<H> =
+            Machine::<BaseComponent>::prove_with_config(&trace, &view, &ProverConfig::default())
+                .unwrap();
+        Machine::<BaseComponent>::verify(
+            proof,
+            ProverConfig::default().security_bits(),
+            view.get_program_memory(),
+            view.view_memory_layout().as_ref(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_prove_read_to_end() {
+        // The last chunk is cut short, an exhausted tape returns zero.
+        let input: Vec<u8> = (0..=255).chain(0..45).map(|i| i as u8).collect();
+        prove_verify::<Blake2sMerkleHasher>(&input, 64);
+        prove_verify::<Blake2sMerkleHasher>(&input[..128], 64);
+        prove_verify::<Blake2sMerkleHasher>(&[], 16);
+    }
+
+    #[test]
+    fn test_prove_read_to_end_poseidon2() {
+        // The lookup elements of the extension are drawn from the Poseidon2 channel of the proof.
+        let input: Vec<u8> = (0..100).collect();
+        prove_verify::<Poseidon2M31MerkleHasher>(&input, 64);
+    }
+}
@@ -0,0 +1,173 @@
+//! Hash functions used for the Fiat–Shamir channel and Merkle commitments of machine proofs.
+//!
+//! The choice is made through the [`MachineHasher`] type parameter of [`Machine`](crate::machine::Machine) proving
+//! and verification, and recorded in [`Proof::hash`](crate::machine::Proof::hash). [`Blake2sMerkleHasher`] is the
+//! default, while [`Poseidon2M31MerkleHasher`] is an algebraic alternative that is cheaper to verify recursively.
+//!
+//! Type-erased extensions draw from the channel of the proof through [`MachineChannel`].
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::{
+    channel::{Blake2sChannel, Channel, MerkleChannel},
+    fields::qm31::SecureField,
+    vcs::{
+        blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher},
+        ops::MerkleHasher,
+    },
+};
+
+mod poseidon2;
+
+pub use poseidon2::{
+    Poseidon2M31Channel, Poseidon2M31Hash, Poseidon2M31MerkleChannel, Poseidon2M31MerkleHasher,
+};
+
+/// Identifies the hash function a proof was generated with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HashKind {
+    Blake2s,
+    Poseidon2M31,
+}
+
+/// A Merkle hasher, along with the channel it is used with, that machine proofs can be generated with.
+pub trait MachineHasher: MerkleHasher {
+    type MerkleChannel: MerkleChannel<H = Self>;
+
+    const KIND: HashKind;
+
+    /// Wraps the channel of a proof, so that it can be passed to type-erased extensions.
+    fn into_machine_channel(channel: <Self::MerkleChannel as MerkleChannel>::C) -> MachineChannel;
+
+    /// Unwraps a channel wrapped with [`Self::into_machine_channel`].
+    ///
+    /// Panics if the channel was replaced by one of another hash function.
+    fn from_machine_channel(channel: MachineChannel) -> <Self::MerkleChannel as MerkleChannel>::C;
+}
+
+impl MachineHasher for Blake2sMerkleHasher {
+    type MerkleChannel = Blake2sMerkleChannel;
+
+    const KIND: HashKind = HashKind::Blake2s;
+
+    fn into_machine_channel(channel: Blake2sChannel) -> MachineChannel {
+        MachineChannel::Blake2s(channel)
+    }
+
+    fn from_machine_channel(channel: MachineChannel) -> Blake2sChannel {
+        match channel {
+            MachineChannel::Blake2s(channel) => channel,
+            _ => panic!("machine channel does not use Blake2s"),
+        }
+    }
+}
+
+impl MachineHasher for Poseidon2M31MerkleHasher {
+    type MerkleChannel = Poseidon2M31MerkleChannel;
+
+    const KIND: HashKind = HashKind::Poseidon2M31;
+
+    fn into_machine_channel(channel: Poseidon2M31Channel) -> MachineChannel {
+        MachineChannel::Poseidon2M31(channel)
+    }
+
+    fn from_machine_channel(channel: MachineChannel) -> Poseidon2M31Channel {
+        match channel {
+            MachineChannel::Poseidon2M31(channel) => channel,
+            _ => panic!("machine channel does not use Poseidon2"),
+        }
+    }
+}
+
+/// The Fiat–Shamir channel of a proof, whichever hash function it uses.
+///
+/// The default is only meant for drawing lookup elements outside of a proof, e.g. to evaluate constraints.
+#[derive(Clone, Debug)]
+pub enum MachineChannel {
+    Blake2s(Blake2sChannel),
+    Poseidon2M31(Poseidon2M31Channel),
+}
+
+impl Default for MachineChannel {
+    fn default() -> Self {
+        Self::Blake2s(Blake2sChannel::default())
+    }
+}
+
+macro_rules! dispatch_channel {
+    ($self:ident, $channel:ident => $e:expr) => {
+        match $self {
+            MachineChannel::Blake2s($channel) => $e,
+            MachineChannel::Poseidon2M31($channel) => $e,
+        }
+    };
+}
+
+impl Channel for MachineChannel {
+    /// The fewest bytes drawn by either channel, [`Channel::draw_random_bytes`] is truncated to it.
+    const BYTES_PER_HASH: usize =
+        if Blake2sChannel::BYTES_PER_HASH < Poseidon2M31Channel::BYTES_PER_HASH {
+            Blake2sChannel::BYTES_PER_HASH
+        } else {
+            Poseidon2M31Channel::BYTES_PER_HASH
+        };
+
+    fn trailing_zeros(&self) -> u32 {
+        dispatch_channel!(self, channel => channel.trailing_zeros())
+    }
+
+    fn mix_u32s(&mut self, data: &[u32]) {
+        dispatch_channel!(self, channel => channel.mix_u32s(data))
+    }
+
+    fn mix_felts(&mut self, felts: &[SecureField]) {
+        dispatch_channel!(self, channel => channel.mix_felts(felts))
+    }
+
+    fn mix_u64(&mut self, value: u64) {
+        dispatch_channel!(self, channel => channel.mix_u64(value))
+    }
+
+    fn draw_felt(&mut self) -> SecureField {
+        dispatch_channel!(self, channel => channel.draw_felt())
+    }
+
+    fn draw_felts(&mut self, n_felts: usize) -> Vec<SecureField> {
+        dispatch_channel!(self, channel => channel.draw_felts(n_felts))
+    }
+
+    fn draw_random_bytes(&mut self) -> Vec<u8> {
+        let mut bytes = dispatch_channel!(self, channel => channel.draw_random_bytes());
+        bytes.truncate(Self::BYTES_PER_HASH);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_machine_channel_matches_wrapped_channel() {
+        let mut channel = Poseidon2M31Channel::default();
+        channel.mix_u64(7);
+        let mut machine_channel = Poseidon2M31MerkleHasher::into_machine_channel(channel.clone());
+
+        assert_eq!(machine_channel.draw_felt(), channel.draw_felt());
+        machine_channel.mix_u64(1);
+        channel.mix_u64(1);
+        assert_eq!(machine_channel.draw_felts(2), channel.draw_felts(2));
+        assert_eq!(
+            machine_channel.draw_random_bytes().len(),
+            MachineChannel::BYTES_PER_HASH
+        );
+
+        let mut unwrapped = Poseidon2M31MerkleHasher::from_machine_channel(machine_channel);
+        channel.draw_random_bytes();
+        assert_eq!(unwrapped.draw_felt(), channel.draw_felt());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_machine_channel_rejects_other_hash() {
+        Poseidon2M31MerkleHasher::from_machine_channel(MachineChannel::default());
+    }
+}
@@ -0,0 +1,488 @@
+//! Poseidon2 over the Mersenne-31 field.
+//!
+//! The permutation has width 16 with the x^5 S-box, 8 full rounds and 14 partial rounds, which are the round numbers
+//! of the Poseidon2 paper for 128-bit security over a 31-bit field, as used by Plonky3. The external layer is the
+//! `M4`-based matrix of the paper and the internal layer is the diagonal of Plonky3's Mersenne-31 instance. Round
+//! constants are drawn from the Grain LFSR of the Poseidon reference implementation, see [`GrainLfsr`], which is
+//! how the published Poseidon and Poseidon2 instances are generated.
+//!
+//! The sponge absorbs 8 elements per permutation and outputs 8 elements (248 bits). The input length is written to
+//! the capacity before absorbing, so that zero-padding the last chunk keeps inputs of different lengths apart.
+use std::{fmt, sync::OnceLock};
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::{
+    backend::{
+        simd::{column::BaseColumn, SimdBackend},
+        BackendForChannel, Column, ColumnOps,
+    },
+    channel::{Channel, MerkleChannel},
+    fields::{m31::BaseField, qm31::SecureField},
+    proof_of_work::GrindOps,
+    utils::bit_reverse,
+    vcs::{
+        hash::Hash,
+        ops::{MerkleHasher, MerkleOps},
+    },
+};
+
+const WIDTH: usize = 16;
+const RATE: usize = 8;
+const N_HALF_FULL_ROUNDS: usize = 4;
+const N_PARTIAL_ROUNDS: usize = 14;
+
+/// The modulus of the Mersenne-31 field.
+const P: u32 = (1 << 31) - 1;
+/// Bit length of the modulus.
+const FIELD_BITS: u32 = 31;
+
+/// Exponents of the internal matrix diagonal after its first entry, which is -2, see
+/// [`apply_internal_round_matrix`].
+const INTERNAL_DIAG_SHIFTS: [u32; WIDTH - 1] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 12, 13, 14, 15, 16];
+
+/// The self-shrinking Grain LFSR of the Poseidon reference implementation.
+///
+/// Its 80-bit state is initialized with the field type, the S-box type, the field size, the width and the numbers of
+/// full and partial rounds, and every output bit is taken from a pair of LFSR bits whose first bit is set.
+struct GrainLfsr {
+    /// The last 80 bits of the LFSR, oldest first from the least significant bit.
+    state: u128,
+}
+
+impl GrainLfsr {
+    fn new(field_bits: u32, width: usize, n_full_rounds: usize, n_partial_rounds: usize) -> Self {
+        // Prime field with the x^alpha S-box, followed by the parameters and 30 set bits.
+        let fields = [
+            (1, 2),
+            (0, 4),
+            (field_bits as usize, 12),
+            (width, 12),
+            (n_full_rounds, 10),
+            (n_partial_rounds, 10),
+            ((1 << 30) - 1, 30),
+        ];
+        let mut state = 0u128;
+        let mut len = 0;
+        for (value, n_bits) in fields {
+            for bit in (0..n_bits).rev() {
+                state |= (((value >> bit) & 1) as u128) << len;
+                len += 1;
+            }
+        }
+        debug_assert_eq!(len, 80);
+
+        let mut lfsr = Self { state };
+        for _ in 0..160 {
+            lfsr.step();
+        }
+        lfsr
+    }
+
+    fn step(&mut self) -> bool {
+        let s = self.state;
+        let bit = ((s >> 62) ^ (s >> 51) ^ (s >> 38) ^ (s >> 23) ^ (s >> 13) ^ s) & 1;
+        self.state = (s >> 1) | (bit << 79);
+        bit == 1
+    }
+
+    fn next_bit(&mut self) -> bool {
+        loop {
+            if self.step() {
+                return self.step();
+            }
+            self.step();
+        }
+    }
+
+    /// Draws a field element by rejection sampling, reading bits most significant first.
+    fn next_field_element(&mut self) -> BaseField {
+        loop {
+            let value = (0..FIELD_BITS).fold(0, |acc, _| (acc << 1) | u32::from(self.next_bit()));
+            if value < P {
+                return BaseField::from(value);
+            }
+        }
+    }
+}
+
+struct RoundConstants {
+    external: [[BaseField; WIDTH]; 2 * N_HALF_FULL_ROUNDS],
+    internal: [BaseField; N_PARTIAL_ROUNDS],
+}
+
+/// Round constants, drawn from [`GrainLfsr`] in the order the rounds are applied, with a single constant per partial
+/// round.
+fn round_constants() -> &'static RoundConstants {
+    static CONSTANTS: OnceLock<RoundConstants> = OnceLock::new();
+    CONSTANTS.get_or_init(|| {
+        let mut lfsr = GrainLfsr::new(FIELD_BITS, WIDTH, 2 * N_HALF_FULL_ROUNDS, N_PARTIAL_ROUNDS);
+
+        let mut external = [[BaseField::from(0u32); WIDTH]; 2 * N_HALF_FULL_ROUNDS];
+        let mut internal = [BaseField::from(0u32); N_PARTIAL_ROUNDS];
+        let (initial, terminal) = external.split_at_mut(N_HALF_FULL_ROUNDS);
+        for round in initial {
+            round
+                .iter_mut()
+                .for_each(|c| *c = lfsr.next_field_element());
+        }
+        internal
+            .iter_mut()
+            .for_each(|c| *c = lfsr.next_field_element());
+        for round in terminal {
+            round
+                .iter_mut()
+                .for_each(|c| *c = lfsr.next_field_element());
+        }
+
+        RoundConstants { external, internal }
+    })
+}
+
+fn pow5(x: BaseField) -> BaseField {
+    let x2 = x * x;
+    x2 * x2 * x
+}
+
+/// Multiplies 4 elements by the circulant-like matrix M4 from the Poseidon2 paper.
+fn apply_m4(x: [BaseField; 4]) -> [BaseField; 4] {
+    let t0 = x[0] + x[1];
+    let t1 = x[2] + x[3];
+    let t2 = x[1] + x[1] + t1;
+    let t3 = x[3] + x[3] + t0;
+    let t4 = t1 + t1 + t1 + t1 + t3;
+    let t5 = t0 + t0 + t0 + t0 + t2;
+    [t3 + t5, t5, t2 + t4, t4]
+}
+
+fn apply_external_round_matrix(state: &mut [BaseField; WIDTH]) {
+    for chunk in state.chunks_exact_mut(4) {
+        let mixed = apply_m4(chunk.try_into().expect("chunk size is 4"));
+        chunk.copy_from_slice(&mixed);
+    }
+    let sums: [BaseField; 4] = std::array::from_fn(|i| {
+        state
+            .iter()
+            .skip(i)
+            .step_by(4)
+            .fold(BaseField::from(0u32), |acc, x| acc + *x)
+    });
+    for (i, x) in state.iter_mut().enumerate() {
+        *x += sums[i % 4];
+    }
+}
+
+/// Multiplies by 1 + diag(-2, 2^0, 2^1, ..., 2^8, 2^10, 2^12, ..., 2^16), the internal matrix of Plonky3's
+/// Mersenne-31 instance.
+fn apply_internal_round_matrix(state: &mut [BaseField; WIDTH]) {
+    let sum = state.iter().fold(BaseField::from(0u32), |acc, x| acc + *x);
+    state[0] = sum - state[0] - state[0];
+    for (x, shift) in state[1..].iter_mut().zip(INTERNAL_DIAG_SHIFTS) {
+        *x = *x * BaseField::from(1u32 << shift) + sum;
+    }
+}
+
+fn permute(state: &mut [BaseField; WIDTH]) {
+    let constants = round_constants();
+
+    apply_external_round_matrix(state);
+    for round in &constants.external[..N_HALF_FULL_ROUNDS] {
+        for (x, c) in state.iter_mut().zip(round) {
+            *x = pow5(*x + *c);
+        }
+        apply_external_round_matrix(state);
+    }
+    for c in &constants.internal {
+        state[0] = pow5(state[0] + *c);
+        apply_internal_round_matrix(state);
+    }
+    for round in &constants.external[N_HALF_FULL_ROUNDS..] {
+        for (x, c) in state.iter_mut().zip(round) {
+            *x = pow5(*x + *c);
+        }
+        apply_external_round_matrix(state);
+    }
+}
+
+/// Hashes a sequence of field elements with the sponge construction.
+fn hash(inputs: &[BaseField]) -> [BaseField; RATE] {
+    let mut state = [BaseField::from(0u32); WIDTH];
+    state[RATE] = BaseField::from(inputs.len() as u32);
+
+    if inputs.is_empty() {
+        permute(&mut state);
+    }
+    for chunk in inputs.chunks(RATE) {
+        for (x, input) in state.iter_mut().zip(chunk) {
+            *x += *input;
+        }
+        permute(&mut state);
+    }
+
+    state[..RATE]
+        .try_into()
+        .expect("rate is smaller than width")
+}
+
+/// Splits a word into 16-bit limbs, so that every word maps to distinct field elements.
+fn u32_limbs(value: u32) -> [BaseField; 2] {
+    [value & 0xFFFF, value >> 16].map(BaseField::from)
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Poseidon2M31Hash(pub [BaseField; RATE]);
+
+impl fmt::Display for Poseidon2M31Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for x in &self.0 {
+            write!(f, "{:08x}", x.0)?;
+        }
+        Ok(())
+    }
+}
+
+impl Hash for Poseidon2M31Hash {}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Poseidon2M31MerkleHasher;
+
+impl MerkleHasher for Poseidon2M31MerkleHasher {
+    type Hash = Poseidon2M31Hash;
+
+    fn hash_node(
+        children_hashes: Option<(Self::Hash, Self::Hash)>,
+        column_values: &[BaseField],
+    ) -> Self::Hash {
+        let mut inputs = Vec::with_capacity(2 * RATE + column_values.len());
+        if let Some((left, right)) = children_hashes {
+            inputs.extend_from_slice(&left.0);
+            inputs.extend_from_slice(&right.0);
+        }
+        inputs.extend_from_slice(column_values);
+
+        Poseidon2M31Hash(hash(&inputs))
+    }
+}
+
+impl ColumnOps<Poseidon2M31Hash> for SimdBackend {
+    type Column = Vec<Poseidon2M31Hash>;
+
+    fn bit_reverse_column(column: &mut Self::Column) {
+        bit_reverse(column)
+    }
+}
+
+impl MerkleOps<Poseidon2M31MerkleHasher> for SimdBackend {
+    fn commit_on_layer(
+        log_size: u32,
+        prev_layer: Option<&Vec<Poseidon2M31Hash>>,
+        columns: &[&BaseColumn],
+    ) -> Vec<Poseidon2M31Hash> {
+        (0..1 << log_size)
+            .into_par_iter()
+            .map(|i| {
+                let column_values: Vec<BaseField> =
+                    columns.iter().map(|column| column.at(i)).collect();
+                Poseidon2M31MerkleHasher::hash_node(
+                    prev_layer.map(|prev_layer| (prev_layer[2 * i], prev_layer[2 * i + 1])),
+                    &column_values,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Fiat–Shamir channel over the Poseidon2 sponge.
+#[derive(Clone, Debug, Default)]
+pub struct Poseidon2M31Channel {
+    digest: [BaseField; RATE],
+    n_draws: u32,
+}
+
+impl Poseidon2M31Channel {
+    /// Absorbs field elements into the digest.
+    pub fn mix_base_felts(&mut self, felts: &[BaseField]) {
+        let inputs: Vec<BaseField> = self.digest.iter().chain(felts).copied().collect();
+        self.digest = hash(&inputs);
+        self.n_draws = 0;
+    }
+
+    fn draw_base_felts(&mut self) -> [BaseField; RATE] {
+        let inputs: Vec<BaseField> = self
+            .digest
+            .iter()
+            .copied()
+            .chain(u32_limbs(self.n_draws))
+            .collect();
+        self.n_draws += 1;
+        hash(&inputs)
+    }
+}
+
+impl Channel for Poseidon2M31Channel {
+    /// Two bytes per drawn element, see [`Channel::draw_random_bytes`].
+    const BYTES_PER_HASH: usize = RATE * 2;
+
+    fn trailing_zeros(&self) -> u32 {
+        let bits = self.digest[..4]
+            .iter()
+            .enumerate()
+            .fold(0u128, |acc, (i, x)| acc | (u128::from(x.0) << (31 * i)));
+        bits.trailing_zeros()
+    }
+
+    fn mix_u32s(&mut self, data: &[u32]) {
+        let felts: Vec<BaseField> = data.iter().flat_map(|word| u32_limbs(*word)).collect();
+        self.mix_base_felts(&felts);
+    }
+
+    fn mix_felts(&mut self, felts: &[SecureField]) {
+        let felts: Vec<BaseField> = felts.iter().flat_map(|felt| felt.to_m31_array()).collect();
+        self.mix_base_felts(&felts);
+    }
+
+    fn mix_u64(&mut self, value: u64) {
+        self.mix_u32s(&[value as u32, (value >> 32) as u32]);
+    }
+
+    fn draw_felt(&mut self) -> SecureField {
+        let felts = self.draw_base_felts();
+        SecureField::from_m31_array(felts[..4].try_into().expect("rate is at least 4"))
+    }
+
+    fn draw_felts(&mut self, n_felts: usize) -> Vec<SecureField> {
+        (0..n_felts).map(|_| self.draw_felt()).collect()
+    }
+
+    /// Only the low 16 bits of every element are used, since their distribution is within 2^-31 of uniform, while the
+    /// top bits of a 31-bit element are not uniform as bytes.
+    fn draw_random_bytes(&mut self) -> Vec<u8> {
+        self.draw_base_felts()
+            .iter()
+            .flat_map(|x| (x.0 as u16).to_le_bytes())
+            .collect()
+    }
+}
+
+impl GrindOps<Poseidon2M31Channel> for SimdBackend {
+    fn grind(channel: &Poseidon2M31Channel, pow_bits: u32) -> u64 {
+        (0u64..)
+            .find(|&nonce| {
+                let mut channel = channel.clone();
+                channel.mix_u64(nonce);
+                channel.trailing_zeros() >= pow_bits
+            })
+            .expect("grinding exhausted all nonces")
+    }
+}
+
+#[derive(Default)]
+pub struct Poseidon2M31MerkleChannel;
+
+impl MerkleChannel for Poseidon2M31MerkleChannel {
+    type C = Poseidon2M31Channel;
+    type H = Poseidon2M31MerkleHasher;
+
+    fn mix_root(channel: &mut Self::C, root: Poseidon2M31Hash) {
+        channel.mix_base_felts(&root.0);
+    }
+}
+
+impl BackendForChannel<Poseidon2M31MerkleChannel> for SimdBackend {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits_to_hex(bits: impl Iterator<Item = bool>) -> String {
+        let bits: Vec<bool> = bits.collect();
+        let padding = (4 - bits.len() % 4) % 4;
+        let bits: Vec<bool> = std::iter::repeat(false).take(padding).chain(bits).collect();
+        bits.chunks(4)
+            .map(|nibble| {
+                let digit = nibble
+                    .iter()
+                    .fold(0, |acc, &bit| (acc << 1) | u32::from(bit));
+                char::from_digit(digit, 16).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_grain_lfsr_matches_reference() {
+        // The first round constants of the published width-3 BN254 instances of Poseidon (as in circomlib) and of
+        // Poseidon2 (as in the HorizenLabs reference implementation), which differ in their number of partial rounds.
+        let cases = [
+            (
+                57,
+                "0ee9a592ba9a9518d05986d656f40c2114c4993c11bb29938d21d47304cd8e6e",
+            ),
+            (
+                56,
+                "1d066a255517b7fd8bddd3a93f7804ef7f8fcde48bb4c37a59a09a1a97052816",
+            ),
+        ];
+        for (n_partial_rounds, expected) in cases {
+            let mut lfsr = GrainLfsr::new(254, 3, 8, n_partial_rounds);
+            let bits = (0..254).map(|_| lfsr.next_bit());
+            assert_eq!(bits_to_hex(bits), expected);
+        }
+    }
+
+    #[test]
+    fn test_permutation_known_answer() {
+        let mut state: [BaseField; WIDTH] = std::array::from_fn(|i| BaseField::from(i as u32));
+        permute(&mut state);
+        let expected: [u32; WIDTH] = [
+            1781279123, 2138219184, 1648008002, 1451549590, 1941576110, 558940464, 2036091834,
+            891233125, 789070634, 1427257847, 304937794, 605216657, 1263669768, 1370957136,
+            179561150, 672469867,
+        ];
+        assert_eq!(state, expected.map(BaseField::from));
+
+        let constants = round_constants();
+        assert_eq!(
+            constants.external[0][..4],
+            [1988864850, 1893772157, 1025928330, 1839472709].map(BaseField::from)
+        );
+        assert_eq!(
+            constants.external[2 * N_HALF_FULL_ROUNDS - 1][WIDTH - 1],
+            BaseField::from(1234594393)
+        );
+    }
+
+    #[test]
+    fn test_hash_separates_lengths() {
+        let zero = BaseField::from(0u32);
+
+        assert_ne!(hash(&[]), hash(&[zero]));
+        assert_ne!(hash(&[zero; RATE]), hash(&[zero; RATE + 1]));
+        assert_eq!(hash(&[zero; 3]), hash(&[zero; 3]));
+    }
+
+    #[test]
+    fn test_channel_draws_change_with_mixing() {
+        let mut channel = Poseidon2M31Channel::default();
+        let first = channel.draw_felt();
+        let second = channel.draw_felt();
+        assert_ne!(first, second);
+
+        let mut mixed = Poseidon2M31Channel::default();
+        mixed.mix_u64(1);
+        assert_ne!(mixed.draw_felt(), first);
+        assert_eq!(
+            channel.draw_random_bytes().len(),
+            Poseidon2M31Channel::BYTES_PER_HASH
+        );
+    }
+
+    #[test]
+    fn test_grind() {
+        let channel = Poseidon2M31Channel::default();
+        let nonce = SimdBackend::grind(&channel, 4);
+
+        let mut channel = channel.clone();
+        channel.mix_u64(nonce);
+        assert!(channel.trailing_zeros() >= 4);
+    }
+}
@@ -1,6 +1,7 @@
 pub mod chips;
 pub mod components;
 pub mod extensions;
+pub mod hash;
 pub mod trace;
 
 pub mod column;
@@ -8,16 +9,40 @@ pub mod traits;
 pub mod virtual_column;
 
 pub mod machine;
+pub mod preflight;
 
 #[cfg(test)]
 mod test_utils;
 
 use nexus_vm::emulator::InternalView;
 pub(crate) use nexus_vm::WORD_SIZE;
+use stwo_prover::core::backend::{simd::SimdBackend, BackendForChannel};
 
-pub use machine::Proof;
+pub use extensions::ExtensionComponent;
+pub use hash::{HashKind, MachineHasher, Poseidon2M31MerkleHasher};
+pub use machine::{image_id, Proof, ProverConfig, ProvingError};
+pub use preflight::{PreflightReport, Unsupported};
+pub use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
 
-pub use stwo_prover::core::prover::{ProvingError, VerificationError};
+pub use stwo_prover::core::prover::VerificationError;
+
+/// Checks that every instruction of the program can be proven by the base machine, see
+/// [`Machine::preflight`](machine::Machine::preflight).
+pub fn preflight(elf: &nexus_vm::elf::ElfFile) -> PreflightReport {
+    preflight_with_extensions(&[], elf)
+}
+
+/// Checks that every instruction of the program can be proven by the base machine with the given extensions.
+pub fn preflight_with_extensions(
+    extensions: &[ExtensionComponent],
+    elf: &nexus_vm::elf::ElfFile,
+) -> PreflightReport {
+    machine::Machine::<machine::BaseComponent>::preflight(
+        extensions,
+        &nexus_vm::riscv::decode_instructions(&elf.instructions),
+        elf.base,
+    )
+}
 
 pub fn prove(
     trace: &impl nexus_vm::trace::Trace,
@@ -26,13 +51,107 @@ pub fn prove(
     machine::Machine::<machine::BaseComponent>::prove(trace, view)
 }
 
-pub fn verify(proof: Proof, view: &nexus_vm::emulator::View) -> Result<(), VerificationError> {
-    machine::Machine::<machine::BaseComponent>::verify(
+pub fn prove_with_config<H: MachineHasher>(
+    trace: &impl nexus_vm::trace::Trace,
+    view: &nexus_vm::emulator::View,
+    config: &ProverConfig,
+) -> Result<Proof<H>, ProvingError>
+where
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    machine::Machine::<machine::BaseComponent>::prove_with_config(trace, view, config)
+}
+
+/// Proves the execution with the given extensions, which must also be passed to [`verify_with_extensions`].
+pub fn prove_with_extensions<H: MachineHasher>(
+    extensions: &[ExtensionComponent],
+    trace: &impl nexus_vm::trace::Trace,
+    view: &nexus_vm::emulator::View,
+    config: &ProverConfig,
+) -> Result<Proof<H>, ProvingError>
+where
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    machine::Machine::<machine::BaseComponent>::prove_with_extensions(
+        extensions, trace, view, config,
+    )
+}
+
+/// Verifies the proof of the execution described by `view`, rejecting proofs with less than `min_security_bits` of
+/// conjectured security, see [`ProverConfig::security_bits`].
+pub fn verify<H: MachineHasher>(
+    proof: Proof<H>,
+    min_security_bits: u32,
+    view: &nexus_vm::emulator::View,
+) -> Result<(), VerificationError>
+where
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    verify_with_extensions(&[], proof, min_security_bits, view)
+}
+
+/// Verifies the proof of an execution proven with the given extensions, see [`verify`].
+pub fn verify_with_extensions<H: MachineHasher>(
+    extensions: &[ExtensionComponent],
+    proof: Proof<H>,
+    min_security_bits: u32,
+    view: &nexus_vm::emulator::View,
+) -> Result<(), VerificationError>
+where
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    machine::Machine::<machine::BaseComponent>::verify_with_extensions(
+        extensions,
         proof,
+        min_security_bits,
         view.get_program_memory(),
+        view.view_memory_layout().as_ref(),
         view.view_associated_data().as_deref().unwrap_or_default(),
         view.get_initial_memory(),
         view.get_exit_code(),
         view.get_public_output(),
     )
 }
+
+/// Proves a segment of an execution with the given extensions, see
+/// [`Machine::prove_segment_with_extensions`](machine::Machine::prove_segment_with_extensions).
+pub fn prove_segment_with_extensions<H: MachineHasher>(
+    extensions: &[ExtensionComponent],
+    trace: &impl nexus_vm::trace::Trace,
+    view: &nexus_vm::emulator::View,
+    segment: &nexus_vm::trace::segment::Segment,
+    config: &ProverConfig,
+) -> Result<Proof<H>, ProvingError>
+where
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    machine::Machine::<machine::BaseComponent>::prove_segment_with_extensions(
+        extensions, trace, view, segment, config,
+    )
+}
+
+/// Verifies the proof of a segment of the execution described by `view`, see [`prove_segment_with_extensions`].
+///
+/// Only the program, the initial memory and the associated data are taken from the view, the boundary state is the
+/// one of the segment.
+pub fn verify_segment_with_extensions<H: MachineHasher>(
+    extensions: &[ExtensionComponent],
+    proof: Proof<H>,
+    min_security_bits: u32,
+    view: &nexus_vm::emulator::View,
+    segment: &nexus_vm::trace::segment::Segment,
+) -> Result<(), VerificationError>
+where
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    machine::Machine::<machine::BaseComponent>::verify_segment_with_extensions(
+        extensions,
+        proof,
+        min_security_bits,
+        view.get_program_memory(),
+        view.view_memory_layout().as_ref(),
+        view.view_associated_data().as_deref().unwrap_or_default(),
+        view.get_initial_memory(),
+        segment,
+    )
+}
@@ -6,12 +6,14 @@ use stwo_prover::{
     core::{
         air::{Component, ComponentProver},
         backend::simd::SimdBackend,
-        channel::{Blake2sChannel, Channel},
+        backend::BackendForChannel,
+        channel::{Channel, MerkleChannel},
         fields::qm31::SecureField,
+        fri::FriConfig,
         pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig, TreeVec},
         poly::circle::{CanonicCoset, PolyOps},
-        prover::{prove, verify, ProvingError, StarkProof, VerificationError},
-        vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher},
+        prover::{prove, verify, StarkProof, VerificationError},
+        vcs::blake2_merkle::Blake2sMerkleHasher,
     },
 };
 
@@ -20,9 +22,13 @@ use super::trace::{
     program::iter_program_steps, program_trace::ProgramTracesBuilder, sidenote::SideNote,
     PreprocessedTraces, TracesBuilder,
 };
+use nexus_common::riscv::register::NUM_REGISTERS;
 use nexus_vm::{
-    emulator::{InternalView, MemoryInitializationEntry, ProgramInfo, PublicOutputEntry, View},
-    trace::Trace,
+    emulator::{
+        InternalView, LinearMemoryLayout, MemoryInitializationEntry, ProgramImage, ProgramInfo,
+        PublicOutputEntry, View,
+    },
+    trace::{segment::Segment, Trace},
 };
 
 use super::components::{MachineComponent, MachineEval, LOG_CONSTRAINT_DEGREE};
@@ -30,17 +36,19 @@ use super::traits::MachineChip;
 use crate::{
     chips::{
         AddChip, AuipcChip, BeqChip, BgeChip, BgeuChip, BitOpChip, BltChip, BltuChip, BneChip,
-        CpuChip, DecodingCheckChip, JalChip, JalrChip, LoadStoreChip, LuiChip, ProgramMemCheckChip,
-        RangeCheckChip, RegisterMemCheckChip, SllChip, SltChip, SltuChip, SraChip, SrlChip,
-        SubChip, SyscallChip, TimestampChip,
+        CpuChip, CustomChip, DecodingCheckChip, DivChip, DivuChip, JalChip, JalrChip,
+        LoadStoreChip, LuiChip, MulChip, MulhChip, MulhsuChip, MulhuChip, ProgramMemCheckChip,
+        RangeCheckChip, RegisterMemCheckChip, RemChip, RemuChip, SllChip, SltChip, SltuChip,
+        SraChip, SrlChip, SubChip, SyscallChip, TimestampChip,
     },
     column::{PreprocessedColumn, ProgramColumn},
     components::{self, AllLookupElements},
-    extensions::ExtensionComponent,
+    extensions::{self, ExtensionComponent},
+    hash::{HashKind, MachineHasher},
     traits::generate_interaction_trace,
 };
 use serde::{Deserialize, Serialize};
-/// Base component tuple for constraining virtual machine execution based on RV32I ISA.
+/// Base component tuple for constraining virtual machine execution based on RV32IM ISA.
 pub type BaseComponent = (
     CpuChip,
     DecodingCheckChip,
@@ -62,8 +70,18 @@ pub type BaseComponent = (
     SllChip,
     SrlChip,
     SraChip,
-    LoadStoreChip,
+    MulChip,
+    MulhChip,
+    MulhsuChip,
+    MulhuChip,
+    DivChip,
+    DivuChip,
+    RemChip,
+    RemuChip,
+    // Custom instructions and system calls must precede load/store, they update the same RAM access history.
+    CustomChip,
     SyscallChip,
+    LoadStoreChip,
     ProgramMemCheckChip,
     RegisterMemCheckChip,
     TimestampChip,
@@ -73,7 +91,6 @@ pub type BaseComponent = (
 /// Base extensions used in conjunction with [`BaseComponent`]. These components are always enabled and are not accessible
 /// to downstream crates.
 const BASE_EXTENSIONS: &[ExtensionComponent] = &[
-    ExtensionComponent::final_reg(),
     ExtensionComponent::bit_op_multiplicity(),
     ExtensionComponent::multiplicity8(),
     ExtensionComponent::multiplicity16(),
@@ -82,30 +99,226 @@ const BASE_EXTENSIONS: &[ExtensionComponent] = &[
     ExtensionComponent::multiplicity256(),
 ];
 
+/// Base extensions that depend on the statement, see [`BASE_EXTENSIONS`]: the registers are fixed at the start of a
+/// segment and exposed at its end, and the private input matches the log size of the main trace.
+fn statement_base_extensions(log_size: u32, statement: &Statement) -> [ExtensionComponent; 2] {
+    let (initial_registers, final_registers) = match statement {
+        Statement::Execution { .. } => ([0; NUM_REGISTERS], None),
+        Statement::Segment(segment) => (
+            segment.initial_state.registers,
+            segment.final_state.map(|state| state.registers),
+        ),
+    };
+    [
+        ExtensionComponent::final_reg(initial_registers, final_registers),
+        ExtensionComponent::private_input(log_size),
+    ]
+}
+
+/// The public values of a proof besides the program and its initial memory.
+#[derive(Debug, Clone, Copy)]
+enum Statement<'a> {
+    /// A whole execution, starting from zero registers and ending with the exit code and the public output.
+    Execution {
+        exit_code: &'a [PublicOutputEntry],
+        output_memory: &'a [PublicOutputEntry],
+    },
+    /// A segment of an execution with its boundary state, see [`Segment`].
+    Segment(&'a Segment),
+}
+
+impl Statement<'_> {
+    fn program_traces(
+        &self,
+        log_size: u32,
+        program_info: &ProgramInfo,
+        init_memory: &[MemoryInitializationEntry],
+    ) -> ProgramTracesBuilder {
+        match self {
+            Self::Execution {
+                exit_code,
+                output_memory,
+            } => ProgramTracesBuilder::new(
+                log_size,
+                program_info,
+                init_memory,
+                exit_code,
+                output_memory,
+            ),
+            Self::Segment(segment) => {
+                ProgramTracesBuilder::new_segment(log_size, program_info, segment)
+            }
+        }
+    }
+}
+
+/// Parameters of the polynomial commitment scheme, trading proof size against prover time and security.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProverConfig {
+    /// Log2 of the FRI blowup factor, must be positive.
+    pub log_blowup_factor: u32,
+    /// Number of FRI queries, must be positive.
+    pub n_queries: usize,
+    /// Number of proof-of-work bits required before drawing queries, at most 32.
+    pub pow_bits: u32,
+}
+
+impl Default for ProverConfig {
+    fn default() -> Self {
+        let PcsConfig {
+            pow_bits,
+            fri_config,
+        } = PcsConfig::default();
+        Self {
+            log_blowup_factor: fri_config.log_blowup_factor,
+            n_queries: fri_config.n_queries,
+            pow_bits,
+        }
+    }
+}
+
+/// Errors returned by [`Machine::prove`] and its variants.
+#[derive(Debug, thiserror::Error)]
+pub enum ProvingError {
+    /// The commitment scheme doesn't support the requested parameters.
+    #[error("unsupported prover config: {0:?}")]
+    UnsupportedConfig(ProverConfig),
+    #[error(transparent)]
+    Stwo(#[from] stwo_prover::core::prover::ProvingError),
+}
+
+impl ProverConfig {
+    /// Range of log blowup factors supported by the commitment scheme.
+    const LOG_BLOWUP_FACTOR_BOUNDS: std::ops::RangeInclusive<u32> = 1..=16;
+    /// Largest number of proof-of-work bits, beyond which grinding is infeasible.
+    const MAX_POW_BITS: u32 = 32;
+
+    /// Conjectured security level of [`Self::standard`], and the least that the SDK and CLI accept when verifying.
+    pub const MIN_SECURITY_BITS: u32 = 96;
+
+    /// Parameters with [`Self::MIN_SECURITY_BITS`] of conjectured security.
+    ///
+    /// Unlike [`Self::default`], which mirrors the fast parameters of Stwo meant for testing, these are meant for
+    /// proofs that are verified by anyone else.
+    pub const fn standard() -> Self {
+        Self {
+            log_blowup_factor: 1,
+            n_queries: 80,
+            pow_bits: 16,
+        }
+    }
+
+    /// Returns whether the parameters are supported by the commitment scheme.
+    pub fn is_valid(&self) -> bool {
+        Self::LOG_BLOWUP_FACTOR_BOUNDS.contains(&self.log_blowup_factor)
+            && self.n_queries > 0
+            && self.pow_bits <= Self::MAX_POW_BITS
+    }
+
+    /// Conjectured security level in bits, under the usual FRI proximity gap conjecture.
+    ///
+    /// Saturates at [`u32::MAX`] for nonsensical parameters, which [`Self::is_valid`] rejects.
+    pub fn security_bits(&self) -> u32 {
+        let query_bits = u64::from(self.log_blowup_factor).saturating_mul(self.n_queries as u64);
+        u32::try_from(query_bits.saturating_add(self.pow_bits.into())).unwrap_or(u32::MAX)
+    }
+
+    fn pcs_config(&self) -> PcsConfig {
+        let default = PcsConfig::default().fri_config;
+        PcsConfig {
+            pow_bits: self.pow_bits,
+            fri_config: FriConfig::new(
+                default.log_last_layer_degree_bound,
+                self.log_blowup_factor,
+                self.n_queries,
+            ),
+        }
+    }
+
+    /// Binds the proof to the parameters by mixing them into the channel.
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.log_blowup_factor.into());
+        channel.mix_u64(self.n_queries as u64);
+        channel.mix_u64(self.pow_bits.into());
+    }
+}
+
+/// Proof of a machine execution. The type parameter determines the hash function used for the Fiat–Shamir channel
+/// and Merkle commitments, see [`crate::hash`].
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Proof {
-    pub stark_proof: StarkProof<Blake2sMerkleHasher>,
+#[serde(bound(
+    serialize = "StarkProof<H>: Serialize",
+    deserialize = "StarkProof<H>: Deserialize<'de>"
+))]
+pub struct Proof<H: MachineHasher = Blake2sMerkleHasher> {
+    pub stark_proof: StarkProof<H>,
     pub claimed_sum: Vec<SecureField>,
     pub log_size: u32,
+    /// Image ID of the proven program, see [`image_id`].
+    pub image_id: [u8; 32],
+    /// Parameters used for proving, which the verifier must use as well.
+    pub config: ProverConfig,
+    /// Hash function used for proving, which the verifier must use as well.
+    pub hash: HashKind,
 }
 
-impl Proof {
+impl<H: MachineHasher> Proof<H> {
     /// Similarly to [`StarkProof::size_estimate`] returns the proof size estimate in bytes.
     pub fn size_estimate(&self) -> usize {
         let Self {
             stark_proof,
             claimed_sum,
             log_size,
+            image_id,
+            config,
+            hash,
         } = self;
         stark_proof.size_estimate()
             + claimed_sum.iter().map(std::mem::size_of_val).sum::<usize>()
             + std::mem::size_of_val(log_size)
+            + std::mem::size_of_val(image_id)
+            + std::mem::size_of_val(config)
+            + std::mem::size_of_val(hash)
+    }
+}
+
+/// Returns the image ID of the executed program, which equals [`nexus_vm::elf::ElfFile::image_id`] of its ELF, or
+/// all zeroes if the view has no memory layout.
+///
+/// The image ID binds a proof to the program, but is not a commitment the verifier can check the proof against on its
+/// own: the program is also part of the preprocessed trace alongside the public output, and the verifier rebuilds its
+/// commitment from the program. Verification therefore always requires the program, pinned by this image ID.
+pub fn image_id(view: &View) -> [u8; 32] {
+    program_image_id(
+        view.get_program_memory(),
+        view.get_initial_memory(),
+        view.view_memory_layout().as_ref(),
+    )
+}
+
+/// Derives the image ID from the program and initial memory that the program trace is built from.
+fn program_image_id(
+    program_info: &ProgramInfo,
+    init_memory: &[MemoryInitializationEntry],
+    memory_layout: Option<&LinearMemoryLayout>,
+) -> [u8; 32] {
+    memory_layout
+        .map(|layout| ProgramImage::from_initial_memory(program_info, init_memory, layout).digest())
+        .unwrap_or_default()
+}
+
+/// Binds the proof to the program by mixing its image ID into the channel.
+fn mix_image_id(channel: &mut impl Channel, image_id: &[u8; 32]) {
+    for chunk in image_id.chunks_exact(8) {
+        channel.mix_u64(u64::from_le_bytes(
+            chunk.try_into().expect("chunk size is 8"),
+        ));
     }
 }
 
 /// Main (empty) struct implementing proving functionality of zkVM.
 ///
-/// The generic parameter determines which chips are enabled. The default is [`BaseComponent`] for RV32I ISA.
+/// The generic parameter determines which chips are enabled. The default is [`BaseComponent`] for RV32IM ISA.
 /// This functionality mainly exists for testing and removing a component **does not** remove columns it uses in the AIR.
 ///
 /// Note that the order of chips affects correctness, e.g. if columns used by a component require additional lookups,
@@ -116,55 +329,139 @@ pub struct Machine<C = BaseComponent> {
 
 impl<C: MachineChip + Sync> Machine<C> {
     pub fn prove(trace: &impl Trace, view: &View) -> Result<Proof, ProvingError> {
-        Self::prove_with_extensions(&[], trace, view)
+        Self::prove_with_extensions(&[], trace, view, &ProverConfig::default())
+    }
+
+    pub fn prove_with_config<H: MachineHasher>(
+        trace: &impl Trace,
+        view: &View,
+        prover_config: &ProverConfig,
+    ) -> Result<Proof<H>, ProvingError>
+    where
+        SimdBackend: BackendForChannel<H::MerkleChannel>,
+    {
+        Self::prove_with_extensions(&[], trace, view, prover_config)
+    }
+
+    /// Proves the whole execution in a single trace, sized by the number of steps, the program and the tracked RAM.
+    ///
+    /// Long executions can be split into segments and proven with [`Self::prove_segment_with_extensions`] instead.
+    pub fn prove_with_extensions<H: MachineHasher>(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+        prover_config: &ProverConfig,
+    ) -> Result<Proof<H>, ProvingError>
+    where
+        SimdBackend: BackendForChannel<H::MerkleChannel>,
+    {
+        let statement = Statement::Execution {
+            exit_code: view.get_exit_code(),
+            output_memory: view.get_public_output(),
+        };
+        Self::prove_statement(
+            extensions,
+            trace,
+            view,
+            statement,
+            view.view_tracked_ram_size(),
+            prover_config,
+        )
     }
 
-    pub fn prove_with_extensions(
+    /// Proves a segment of an execution, whose trace and boundary state are returned by
+    /// [`split_into_segments`](nexus_vm::trace::segment::split_into_segments).
+    ///
+    /// The view is the one of the whole execution, it provides the program and the associated data. The proof fixes
+    /// the registers and the accessed RAM before the first step of the segment and exposes them after the last step,
+    /// proofs of consecutive segments must be linked with [`link_segments`](nexus_vm::trace::segment::link_segments).
+    pub fn prove_segment_with_extensions<H: MachineHasher>(
         extensions: &[ExtensionComponent],
         trace: &impl Trace,
         view: &View,
-    ) -> Result<Proof, ProvingError> {
+        segment: &Segment,
+        prover_config: &ProverConfig,
+    ) -> Result<Proof<H>, ProvingError>
+    where
+        SimdBackend: BackendForChannel<H::MerkleChannel>,
+    {
+        assert_eq!(trace.get_num_steps(), segment.num_steps as usize);
+        Self::prove_statement(
+            extensions,
+            trace,
+            view,
+            Statement::Segment(segment),
+            segment.memory.len(),
+            prover_config,
+        )
+    }
+
+    fn prove_statement<H: MachineHasher>(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+        statement: Statement,
+        tracked_ram_size: usize,
+        prover_config: &ProverConfig,
+    ) -> Result<Proof<H>, ProvingError>
+    where
+        SimdBackend: BackendForChannel<H::MerkleChannel>,
+    {
+        if !prover_config.is_valid() {
+            return Err(ProvingError::UnsupportedConfig(*prover_config));
+        }
         let num_steps = trace.get_num_steps();
         let program_len = view.get_program_memory().program.len();
-        let tracked_ram_size = view.view_tracked_ram_size();
+        let private_input_rows = extensions::num_private_input_rows(trace);
 
-        let log_size = Self::max_log_size(&[num_steps, program_len, tracked_ram_size])
-            .max(PreprocessedTraces::MIN_LOG_SIZE);
+        let log_size =
+            Self::max_log_size(&[num_steps, program_len, tracked_ram_size, private_input_rows])
+                .max(PreprocessedTraces::MIN_LOG_SIZE);
 
-        let extensions_iter = BASE_EXTENSIONS.iter().chain(extensions);
+        let statement_extensions = statement_base_extensions(log_size, &statement);
+        let extensions_iter = BASE_EXTENSIONS
+            .iter()
+            .chain(&statement_extensions)
+            .chain(extensions);
 
-        let config = PcsConfig::default();
+        let config = prover_config.pcs_config();
         // Precompute twiddles.
         let twiddles = SimdBackend::precompute_twiddles(
             CanonicCoset::new(
-                log_size + LOG_CONSTRAINT_DEGREE + config.fri_config.log_blowup_factor,
+                Self::max_component_log_size(log_size, extensions_iter.clone())
+                    + LOG_CONSTRAINT_DEGREE
+                    + config.fri_config.log_blowup_factor,
             )
             .circle_domain()
             .half_coset,
         );
 
         // Setup protocol.
-        let prover_channel = &mut Blake2sChannel::default();
+        let prover_channel = &mut <H::MerkleChannel as MerkleChannel>::C::default();
         for byte in view.view_associated_data().unwrap_or_default() {
             prover_channel.mix_u64(byte.into());
         }
+        let image_id = image_id(view);
+        mix_image_id(prover_channel, &image_id);
+        prover_config.mix_into(prover_channel);
 
         let mut commitment_scheme =
-            CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(config, &twiddles);
+            CommitmentSchemeProver::<SimdBackend, H::MerkleChannel>::new(config, &twiddles);
 
         // Fill columns of the preprocessed trace.
         let preprocessed_trace = PreprocessedTraces::new(log_size);
 
         // Fill columns of the original trace.
         let mut prover_traces = TracesBuilder::new(log_size);
-        let program_traces = ProgramTracesBuilder::new(
+        let program_traces = statement.program_traces(
             log_size,
             view.get_program_memory(),
             view.get_initial_memory(),
-            view.get_exit_code(),
-            view.get_public_output(),
         );
-        let mut prover_side_note = SideNote::new(&program_traces, view);
+        let mut prover_side_note = match statement {
+            Statement::Execution { .. } => SideNote::new(&program_traces, view),
+            Statement::Segment(segment) => SideNote::new_segment(&program_traces, segment),
+        };
         let program_steps = iter_program_steps(trace, prover_traces.num_rows());
         for (row_idx, program_step) in program_steps.enumerate() {
             C::fill_main_trace(
@@ -174,6 +471,21 @@ impl<C: MachineChip + Sync> Machine<C> {
                 &mut prover_side_note,
             );
         }
+        if let Statement::Segment(Segment {
+            final_state: Some(final_state),
+            ..
+        }) = statement
+        {
+            assert_eq!(
+                prover_side_note.register_mem_check.last_access_value, final_state.registers,
+                "final registers of the segment don't match the trace"
+            );
+        }
+        // Extensions may add lookups to base extensions, e.g. range checks, which must be counted before any
+        // extension trace is generated.
+        for ext in extensions_iter.clone() {
+            ext.update_side_note(&mut prover_side_note);
+        }
 
         let finalized_trace = prover_traces.finalize();
         let finalized_program_trace = program_traces.finalize();
@@ -203,6 +515,9 @@ impl<C: MachineChip + Sync> Machine<C> {
 
         let mut lookup_elements = AllLookupElements::default();
         C::draw_lookup_elements(&mut lookup_elements, prover_channel);
+        for ext in extensions_iter.clone() {
+            ext.draw_lookup_elements::<H>(&mut lookup_elements, prover_channel);
+        }
 
         let (interaction_trace, claimed_sum) = generate_interaction_trace::<C>(
             &finalized_trace,
@@ -238,7 +553,7 @@ impl<C: MachineChip + Sync> Machine<C> {
         let mut components_ref: Vec<&dyn ComponentProver<SimdBackend>> =
             ext_components.iter().map(|c| &**c).collect();
         components_ref.insert(0, &main_component);
-        let proof = prove::<SimdBackend, Blake2sMerkleChannel>(
+        let proof = prove::<SimdBackend, H::MerkleChannel>(
             &components_ref,
             prover_channel,
             commitment_scheme,
@@ -248,21 +563,35 @@ impl<C: MachineChip + Sync> Machine<C> {
             stark_proof: proof,
             claimed_sum: all_claimed_sum,
             log_size,
+            image_id,
+            config: *prover_config,
+            hash: H::KIND,
         })
     }
 
-    pub fn verify(
-        proof: Proof,
+    /// Verifies the proof of an execution with the given public statement.
+    ///
+    /// The commitment scheme parameters are read from the proof, which is rejected if they give less than
+    /// `min_security_bits` of conjectured security, see [`ProverConfig::security_bits`].
+    pub fn verify<H: MachineHasher>(
+        proof: Proof<H>,
+        min_security_bits: u32,
         program_info: &ProgramInfo,
+        memory_layout: Option<&LinearMemoryLayout>,
         ad: &[u8],
         init_memory: &[MemoryInitializationEntry],
         exit_code: &[PublicOutputEntry],
         output_memory: &[PublicOutputEntry],
-    ) -> Result<(), VerificationError> {
+    ) -> Result<(), VerificationError>
+    where
+        SimdBackend: BackendForChannel<H::MerkleChannel>,
+    {
         Self::verify_with_extensions(
             &[],
             proof,
+            min_security_bits,
             program_info,
+            memory_layout,
             ad,
             init_memory,
             exit_code,
@@ -270,22 +599,127 @@ impl<C: MachineChip + Sync> Machine<C> {
         )
     }
 
-    pub fn verify_with_extensions(
+    /// Verifies the proof of an execution by the machine with the given extensions, see [`Self::verify`].
+    pub fn verify_with_extensions<H: MachineHasher>(
         extensions: &[ExtensionComponent],
-        proof: Proof,
+        proof: Proof<H>,
+        min_security_bits: u32,
         program_info: &ProgramInfo,
+        memory_layout: Option<&LinearMemoryLayout>,
         ad: &[u8],
         init_memory: &[MemoryInitializationEntry],
         exit_code: &[PublicOutputEntry],
         output_memory: &[PublicOutputEntry],
-    ) -> Result<(), VerificationError> {
+    ) -> Result<(), VerificationError>
+    where
+        SimdBackend: BackendForChannel<H::MerkleChannel>,
+    {
+        let statement = Statement::Execution {
+            exit_code,
+            output_memory,
+        };
+        Self::verify_statement(
+            extensions,
+            proof,
+            min_security_bits,
+            program_info,
+            memory_layout,
+            ad,
+            init_memory,
+            statement,
+        )
+    }
+
+    /// Verifies the proof of a segment of an execution with the given boundary state, see
+    /// [`Self::prove_segment_with_extensions`].
+    ///
+    /// The initial memory is the one of the whole execution and only pins the image ID, the accessed RAM of the
+    /// segment is part of the segment itself.
+    pub fn verify_segment_with_extensions<H: MachineHasher>(
+        extensions: &[ExtensionComponent],
+        proof: Proof<H>,
+        min_security_bits: u32,
+        program_info: &ProgramInfo,
+        memory_layout: Option<&LinearMemoryLayout>,
+        ad: &[u8],
+        init_memory: &[MemoryInitializationEntry],
+        segment: &Segment,
+    ) -> Result<(), VerificationError>
+    where
+        SimdBackend: BackendForChannel<H::MerkleChannel>,
+    {
+        let num_rows = 1usize.checked_shl(proof.log_size).unwrap_or(0);
+        if segment.num_steps == 0
+            || segment.num_steps as usize > num_rows
+            || segment.memory.len() > num_rows
+        {
+            return Err(VerificationError::InvalidStructure(
+                "segment doesn't fit the trace".to_string(),
+            ));
+        }
+        Self::verify_statement(
+            extensions,
+            proof,
+            min_security_bits,
+            program_info,
+            memory_layout,
+            ad,
+            init_memory,
+            Statement::Segment(segment),
+        )
+    }
+
+    fn verify_statement<H: MachineHasher>(
+        extensions: &[ExtensionComponent],
+        proof: Proof<H>,
+        min_security_bits: u32,
+        program_info: &ProgramInfo,
+        memory_layout: Option<&LinearMemoryLayout>,
+        ad: &[u8],
+        init_memory: &[MemoryInitializationEntry],
+        statement: Statement,
+    ) -> Result<(), VerificationError>
+    where
+        SimdBackend: BackendForChannel<H::MerkleChannel>,
+    {
         let Proof {
             stark_proof: proof,
             claimed_sum,
             log_size,
+            image_id: proof_image_id,
+            config: prover_config,
+            hash,
         } = proof;
 
-        if claimed_sum.len() != extensions.len() + BASE_EXTENSIONS.len() + 1 {
+        if hash != H::KIND {
+            return Err(VerificationError::InvalidStructure(format!(
+                "proof uses {hash:?} hash, expected {:?}",
+                H::KIND
+            )));
+        }
+
+        if !prover_config.is_valid() {
+            return Err(VerificationError::InvalidStructure(
+                "unsupported prover config".to_string(),
+            ));
+        }
+        if prover_config.security_bits() < min_security_bits {
+            return Err(VerificationError::InvalidStructure(format!(
+                "proof has {} bits of conjectured security, expected at least {min_security_bits}",
+                prover_config.security_bits()
+            )));
+        }
+        let image_id = program_image_id(program_info, init_memory, memory_layout);
+        if proof_image_id != image_id {
+            return Err(VerificationError::InvalidStructure(
+                "image id mismatch".to_string(),
+            ));
+        }
+
+        let statement_extensions = statement_base_extensions(log_size, &statement);
+        if claimed_sum.len()
+            != extensions.len() + BASE_EXTENSIONS.len() + statement_extensions.len() + 1
+        {
             return Err(VerificationError::InvalidStructure(
                 "claimed sum len mismatch".to_string(),
             ));
@@ -295,40 +729,42 @@ impl<C: MachineChip + Sync> Machine<C> {
                 "claimed logup sum is not zero".to_string(),
             ));
         }
-        let extensions_iter = BASE_EXTENSIONS.iter().chain(extensions);
+        let extensions_iter = BASE_EXTENSIONS
+            .iter()
+            .chain(&statement_extensions)
+            .chain(extensions);
 
-        let config = PcsConfig::default();
-        let verifier_channel = &mut Blake2sChannel::default();
+        let config = prover_config.pcs_config();
+        let verifier_channel = &mut <H::MerkleChannel as MerkleChannel>::C::default();
         for &byte in ad {
             verifier_channel.mix_u64(byte.into());
         }
+        mix_image_id(verifier_channel, &image_id);
+        prover_config.mix_into(verifier_channel);
 
-        let commitment_scheme = &mut CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+        let commitment_scheme = &mut CommitmentSchemeVerifier::<H::MerkleChannel>::new(config);
 
         // simulate the prover and compute expected commitment to preprocessed trace
         {
-            let config = PcsConfig::default();
+            let config = prover_config.pcs_config();
             let verifier_channel = &mut verifier_channel.clone();
             let twiddles = SimdBackend::precompute_twiddles(
                 CanonicCoset::new(
-                    log_size + LOG_CONSTRAINT_DEGREE + config.fri_config.log_blowup_factor,
+                    Self::max_component_log_size(log_size, extensions_iter.clone())
+                        + LOG_CONSTRAINT_DEGREE
+                        + config.fri_config.log_blowup_factor,
                 )
                 .circle_domain()
                 .half_coset,
             );
             let commitment_scheme =
-                &mut CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+                &mut CommitmentSchemeProver::<SimdBackend, H::MerkleChannel>::new(
                     config, &twiddles,
                 );
             let preprocessed_trace = PreprocessedTraces::new(log_size);
-            let program_trace = ProgramTracesBuilder::new(
-                log_size,
-                program_info,
-                init_memory,
-                exit_code,
-                output_memory,
-            )
-            .finalize();
+            let program_trace = statement
+                .program_traces(log_size, program_info, init_memory)
+                .finalize();
 
             let mut tree_builder = commitment_scheme.tree_builder();
             let _preprocessed_trace_location = tree_builder.extend_evals(
@@ -379,6 +815,9 @@ impl<C: MachineChip + Sync> Machine<C> {
 
         let mut lookup_elements = AllLookupElements::default();
         C::draw_lookup_elements(&mut lookup_elements, verifier_channel);
+        for ext in extensions_iter.clone() {
+            ext.draw_lookup_elements::<H>(&mut lookup_elements, verifier_channel);
+        }
 
         let tree_span_provider = &mut TraceLocationAllocator::default();
         let main_component = MachineComponent::new(
@@ -413,14 +852,36 @@ impl<C: MachineChip + Sync> Machine<C> {
             .max()
             .expect("sizes is empty")
     }
+
+    /// Returns the largest log size among the main trace and all columns of extensions.
+    fn max_component_log_size<'a>(
+        log_size: u32,
+        extensions: impl Iterator<Item = &'a ExtensionComponent>,
+    ) -> u32 {
+        extensions
+            .flat_map(|ext| {
+                let trace_sizes = ext.trace_sizes();
+                trace_sizes
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .chain(ext.preprocessed_trace_sizes())
+                    .collect::<Vec<_>>()
+            })
+            .fold(log_size, u32::max)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hash::Poseidon2M31MerkleHasher;
     use nexus_vm::{
         riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
-        trace::k_trace_direct,
+        trace::{
+            k_trace_direct,
+            segment::{link_segments, split_into_segments},
+        },
     };
 
     #[test]
@@ -437,9 +898,151 @@ mod tests {
             k_trace_direct(&basic_block, 1).expect("error generating trace");
 
         let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        let verify = |proof, min_security_bits| {
+            Machine::<BaseComponent>::verify(
+                proof,
+                min_security_bits,
+                view.get_program_memory(),
+                view.view_memory_layout().as_ref(),
+                &[],
+                view.get_initial_memory(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+        verify(proof.clone(), ProverConfig::default().security_bits()).unwrap();
+
+        // The image ID is derived by the verifier rather than taken from the proof.
+        let mut tampered = proof;
+        tampered.image_id[0] ^= 1;
+        assert!(verify(tampered, 0).is_err());
+    }
+
+    #[test]
+    fn prove_verify_m_extension() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 20),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 3, 0, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 4, 1, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MULH), 5, 4, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MULHSU), 6, 4, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MULHU), 7, 4, 4),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::DIV), 8, 4, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::DIVU), 9, 4, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::REM), 10, 1, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::REMU), 11, 4, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::DIV), 12, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::REMU), 13, 1, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        Machine::<BaseComponent>::verify(
+            proof,
+            ProverConfig::default().security_bits(),
+            view.get_program_memory(),
+            view.view_memory_layout().as_ref(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn prove_verify_with_config() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let config = ProverConfig {
+            log_blowup_factor: 2,
+            n_queries: 10,
+            pow_bits: 2,
+        };
+        assert_eq!(config.security_bits(), 22);
+
+        let proof: Proof =
+            Machine::<BaseComponent>::prove_with_config(&program_trace, &view, &config).unwrap();
+        assert_eq!(proof.config, config);
+
+        let verify = |proof, min_security_bits| {
+            Machine::<BaseComponent>::verify(
+                proof,
+                min_security_bits,
+                view.get_program_memory(),
+                view.view_memory_layout().as_ref(),
+                &[],
+                view.get_initial_memory(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+        verify(proof.clone(), 22).unwrap();
+        // The verifier rejects proofs below the security it requires, whatever their config claims.
+        assert!(verify(proof.clone(), 23).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.config.n_queries -= 1;
+        assert!(verify(tampered, 0).is_err());
+        let mut tampered = proof;
+        tampered.config.pow_bits = u32::MAX;
+        assert!(verify(tampered, 0).is_err());
+
+        assert!(ProverConfig::standard().is_valid());
+        assert_eq!(
+            ProverConfig::standard().security_bits(),
+            ProverConfig::MIN_SECURITY_BITS
+        );
+        let unbounded = ProverConfig {
+            n_queries: usize::MAX,
+            pow_bits: u32::MAX,
+            ..config
+        };
+        assert!(!unbounded.is_valid());
+        assert_eq!(unbounded.security_bits(), u32::MAX);
+
+        let unsupported = ProverConfig {
+            n_queries: 0,
+            ..config
+        };
+        assert!(matches!(
+            Machine::<BaseComponent>::prove_with_config::<Blake2sMerkleHasher>(
+                &program_trace,
+                &view,
+                &unsupported
+            ),
+            Err(ProvingError::UnsupportedConfig(_))
+        ));
+    }
+
+    #[test]
+    fn prove_verify_poseidon2() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let proof: Proof<Poseidon2M31MerkleHasher> = Machine::<BaseComponent>::prove_with_config(
+            &program_trace,
+            &view,
+            &ProverConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(proof.hash, HashKind::Poseidon2M31);
+
         Machine::<BaseComponent>::verify(
             proof,
+            ProverConfig::default().security_bits(),
             view.get_program_memory(),
+            view.view_memory_layout().as_ref(),
             &[],
             view.get_initial_memory(),
             view.get_exit_code(),
@@ -447,4 +1050,98 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[test]
+    fn prove_verify_segments() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 1, 1, 19),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 0x1ab),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 2, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SB), 1, 2, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 3, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LBU), 4, 1, 4),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let segments = split_into_segments(&program_trace, view.get_initial_memory(), 3);
+        assert_eq!(segments.len(), 3);
+
+        let verify = |proof, segment| {
+            Machine::<BaseComponent>::verify_segment_with_extensions::<Blake2sMerkleHasher>(
+                &[],
+                proof,
+                ProverConfig::default().security_bits(),
+                view.get_program_memory(),
+                view.view_memory_layout().as_ref(),
+                &[],
+                view.get_initial_memory(),
+                segment,
+            )
+        };
+        let proofs: Vec<Proof> = segments
+            .iter()
+            .map(|(segment, trace)| {
+                Machine::<BaseComponent>::prove_segment_with_extensions(
+                    &[],
+                    trace,
+                    &view,
+                    segment,
+                    &ProverConfig::default(),
+                )
+                .unwrap()
+            })
+            .collect();
+        for ((segment, _), proof) in segments.iter().zip(&proofs) {
+            verify(proof.clone(), segment).unwrap();
+        }
+        link_segments(
+            segments.iter().map(|(segment, _)| segment),
+            view.get_program_memory().initial_pc,
+            view.get_initial_memory(),
+        )
+        .unwrap();
+
+        // The boundary state is part of the statement.
+        let (segment, _) = &segments[1];
+        let mut tampered = segment.clone();
+        tampered.final_state.as_mut().unwrap().registers[2] += 1;
+        assert!(verify(proofs[1].clone(), &tampered).is_err());
+
+        let mut tampered = segment.clone();
+        tampered.final_state.as_mut().unwrap().pc += 4;
+        assert!(verify(proofs[1].clone(), &tampered).is_err());
+        assert!(
+            Machine::<BaseComponent>::prove_segment_with_extensions::<Blake2sMerkleHasher>(
+                &[],
+                &segments[1].1,
+                &view,
+                &tampered,
+                &ProverConfig::default(),
+            )
+            .is_err()
+        );
+
+        let mut tampered = segment.clone();
+        tampered.initial_state.registers[1] += 1;
+        assert!(verify(proofs[1].clone(), &tampered).is_err());
+
+        let mut tampered = segment.clone();
+        tampered.memory[0].final_value += 1;
+        assert!(verify(proofs[1].clone(), &tampered).is_err());
+
+        // A segment proof is not a proof of the whole execution.
+        assert!(Machine::<BaseComponent>::verify(
+            proofs[2].clone(),
+            0,
+            view.get_program_memory(),
+            view.view_memory_layout().as_ref(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .is_err());
+    }
 }
@@ -0,0 +1,244 @@
+//! Pre-flight Checks of Guest Programs
+//!
+//! A program that uses an instruction without a chip to prove it is otherwise only rejected deep inside proving, after
+//! it was run and traced. [`Machine::preflight`] instead compares the decoded program with what the machine and its
+//! extensions declare to support, see [`MachineChip::declare_support`], and reports every offending instruction with
+//! its address.
+//!
+//! The check is static, so it covers the whole text rather than an execution:
+//!
+//! - Unimplemented instructions are not reported, as the emulator already refuses to execute them and compilers emit
+//!   `unimp` as a trap that is never reached in successful runs.
+//! - The `rin` and `wou` instructions are converted into loads and stores before proving, and are supported.
+//! - Syscall codes are resolved from the `li a7, <code>` preceding an `ecall` in its basic block, as emitted by the
+//!   runtime. Other `ecall`s are listed in [`PreflightReport::unresolved_syscalls`] but do not fail the check.
+//! - Precompile calls are supported if an extension declares their index, see
+//!   [`CustomExtension::declare_support`](crate::extensions::CustomExtension::declare_support).
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+};
+
+use nexus_vm::{
+    riscv::{BasicBlockProgram, BuiltinOpcode, Instruction, InstructionType, Register},
+    WORD_SIZE,
+};
+
+use crate::{extensions::ExtensionComponent, machine::Machine, traits::MachineChip};
+
+/// Opcode of the custom R-type instructions that invoke precompiles.
+pub(crate) const PRECOMPILE_OPCODE: u8 = 0b0001011;
+
+/// Instructions and syscalls proven by a machine and its extensions.
+#[derive(Debug, Default, Clone)]
+pub struct Support {
+    pub opcodes: HashSet<BuiltinOpcode>,
+    pub syscalls: BTreeSet<u32>,
+    /// Indices of precompiles, as encoded in the custom instruction by `fn7` followed by `fn3`.
+    pub precompiles: BTreeSet<u16>,
+}
+
+/// Why an instruction cannot be proven.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Unsupported {
+    Opcode(BuiltinOpcode),
+    Syscall(u32),
+    Precompile(u16),
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Opcode(opcode) => write!(f, "instruction `{opcode}`"),
+            Self::Syscall(code) => write!(f, "syscall {code:#x}"),
+            Self::Precompile(index) => write!(f, "precompile {index}"),
+        }
+    }
+}
+
+/// Result of [`Machine::preflight`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PreflightReport {
+    /// Addresses of the instructions that cannot be proven, in address order.
+    pub unsupported: Vec<(u32, Unsupported)>,
+    /// Addresses of the `ecall`s whose syscall code could not be determined statically.
+    pub unresolved_syscalls: Vec<u32>,
+}
+
+impl PreflightReport {
+    /// Whether every instruction of the program can be proven, as far as can be determined statically.
+    pub fn is_ok(&self) -> bool {
+        self.unsupported.is_empty()
+    }
+}
+
+impl fmt::Display for PreflightReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            write!(f, "all instructions can be proven")?;
+        } else {
+            write!(
+                f,
+                "{} instruction(s) cannot be proven:",
+                self.unsupported.len()
+            )?;
+            for (pc, reason) in &self.unsupported {
+                write!(f, "\n  {pc:08x}: {reason}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PreflightReport {}
+
+impl<C: MachineChip> Machine<C> {
+    /// Checks the program, whose first instruction is at `base`, against the instructions supported by the machine
+    /// with the given extensions.
+    pub fn preflight(
+        extensions: &[ExtensionComponent],
+        program: &BasicBlockProgram,
+        base: u32,
+    ) -> PreflightReport {
+        let mut support = Support::default();
+        C::declare_support(&mut support);
+        for extension in extensions {
+            extension.declare_support(&mut support);
+        }
+
+        let mut report = PreflightReport::default();
+        let mut pc = base;
+        for block in &program.blocks {
+            for (i, instruction) in block.0.iter().enumerate() {
+                match instruction.opcode.builtin() {
+                    Some(BuiltinOpcode::UNIMPL) => {}
+                    Some(opcode) if !support.opcodes.contains(&opcode) => {
+                        report.unsupported.push((pc, Unsupported::Opcode(opcode)));
+                    }
+                    Some(BuiltinOpcode::ECALL) => match syscall_code(&block.0[..i]) {
+                        Some(code) if !support.syscalls.contains(&code) => {
+                            report.unsupported.push((pc, Unsupported::Syscall(code)));
+                        }
+                        Some(_) => {}
+                        None => report.unresolved_syscalls.push(pc),
+                    },
+                    Some(_) => {}
+                    None if instruction.opcode.raw() == PRECOMPILE_OPCODE => {
+                        let index = ((instruction.opcode.fn7().value() as u16) << 3)
+                            | instruction.opcode.fn3().value() as u16;
+                        if !support.precompiles.contains(&index) {
+                            report
+                                .unsupported
+                                .push((pc, Unsupported::Precompile(index)));
+                        }
+                    }
+                    None => {}
+                }
+                pc += WORD_SIZE as u32;
+            }
+        }
+
+        report
+    }
+}
+
+/// Returns the syscall code loaded into `a7` by the last instruction writing it, if it is a `li`.
+fn syscall_code(preceding: &[Instruction]) -> Option<u32> {
+    let last_write = preceding.iter().rev().find(|instruction| {
+        instruction.op_a == Register::X17
+            && !matches!(
+                instruction.ins_type,
+                InstructionType::SType | InstructionType::BType | InstructionType::Unimpl
+            )
+    })?;
+
+    (last_write.opcode.builtin() == Some(BuiltinOpcode::ADDI) && last_write.op_b == Register::X0)
+        .then_some(last_write.op_c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chips::{AddChip, CpuChip},
+        machine::BaseComponent,
+    };
+
+    use nexus_vm::{
+        riscv::{BasicBlock, Opcode},
+        SyscallCode,
+    };
+
+    fn li_a7(code: u32) -> Instruction {
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, code)
+    }
+
+    #[test]
+    fn test_preflight() {
+        let ecall = Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0);
+        let precompile = Instruction::new(
+            Opcode::new(PRECOMPILE_OPCODE, Some(0b001), Some(0b0000000), "dynamic"),
+            Register::X10,
+            Register::X10,
+            Register::X11 as u32,
+            InstructionType::RType,
+        );
+        let program = BasicBlockProgram {
+            blocks: vec![
+                BasicBlock(vec![
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 10, 10, 11),
+                    li_a7(SyscallCode::Write as u32),
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 1),
+                    ecall.clone(),
+                ]),
+                BasicBlock(vec![
+                    precompile,
+                    li_a7(0x4ff),
+                    ecall.clone(),
+                    // The code is not known statically.
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 17, 10, 11),
+                    ecall,
+                    Instruction::unimpl(),
+                ]),
+            ],
+        };
+
+        let report = Machine::<BaseComponent>::preflight(&[], &program, 0x1000);
+        assert!(!report.is_ok());
+        assert_eq!(
+            report.unsupported,
+            vec![
+                (0x1010, Unsupported::Precompile(1)),
+                (0x1018, Unsupported::Syscall(0x4ff)),
+            ]
+        );
+        assert_eq!(report.unresolved_syscalls, vec![0x1020]);
+        assert_eq!(
+            report.to_string(),
+            "2 instruction(s) cannot be proven:\n  00001010: precompile 1\n  00001018: syscall 0x4ff"
+        );
+    }
+
+    #[test]
+    fn test_preflight_unsupported_opcode() {
+        // A machine without the M extension.
+        type AddMachine = Machine<(CpuChip, AddChip)>;
+
+        let program = BasicBlockProgram {
+            blocks: vec![BasicBlock(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 10, 10, 11),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 10, 10, 11),
+            ])],
+        };
+
+        let report = AddMachine::preflight(&[], &program, 0);
+        assert_eq!(
+            report.unsupported,
+            vec![(4, Unsupported::Opcode(BuiltinOpcode::MUL))]
+        );
+        assert_eq!(
+            report.to_string(),
+            "1 instruction(s) cannot be proven:\n  00000004: instruction `mul`"
+        );
+    }
+}
@@ -80,9 +80,11 @@ impl ProgramStep {
         if let Some(syscall_value) = self.get_syscall_code() {
             let syscall_number = SyscallCode::from(syscall_value);
             match syscall_number {
-                SyscallCode::ReadFromPrivateInput | SyscallCode::OverwriteHeapPointer => {
-                    Register::X10
-                }
+                SyscallCode::ReadFromPrivateInput
+                | SyscallCode::ReadBytesFromPrivateInput
+                | SyscallCode::ReadFromAuxiliaryInput
+                | SyscallCode::QueryOracle
+                | SyscallCode::OverwriteHeapPointer => Register::X10,
                 SyscallCode::OverwriteStackPointer => Register::X2,
                 _ => Register::X0,
             }
@@ -17,6 +17,7 @@ use crate::column::ProgramColumn;
 
 use nexus_vm::{
     emulator::{MemoryInitializationEntry, ProgramInfo, ProgramMemoryEntry, PublicOutputEntry},
+    trace::segment::{Segment, SegmentMemoryEntry},
     WORD_SIZE,
 };
 
@@ -37,12 +38,87 @@ impl ProgramTracesBuilder {
         exit_code: &[PublicOutputEntry],
         output_memory: &[PublicOutputEntry],
     ) -> Self {
+        assert!(init_memory.len() + exit_code.len() + output_memory.len() <= 1 << log_size);
+        let mut ret = Self::with_program(log_size, program_memory, program_memory.initial_pc);
+
+        let init_memory_len = init_memory.len();
+        let exit_code_len = exit_code.len();
+
+        for (row_idx, MemoryInitializationEntry { address, value }) in
+            init_memory.iter().enumerate()
+        {
+            ret.fill_program_columns(row_idx, *address, ProgramColumn::PublicRamAddr);
+
+            ret.fill_program_columns(row_idx, true, ProgramColumn::PublicInitialMemoryFlag);
+            ret.fill_program_columns(row_idx, *value, ProgramColumn::PublicInitialMemoryValue);
+        }
+        let offset = init_memory_len;
+
+        for (row_idx, PublicOutputEntry { address, value }) in exit_code.iter().enumerate() {
+            let row_idx = row_idx + offset;
+            ret.fill_program_columns(row_idx, *address, ProgramColumn::PublicRamAddr);
+
+            ret.fill_program_columns(row_idx, true, ProgramColumn::PublicOutputFlag);
+            ret.fill_program_columns(row_idx, *value, ProgramColumn::PublicOutputValue);
+        }
+        let offset = offset + exit_code_len;
+        for (row_idx, PublicOutputEntry { address, value }) in output_memory.iter().enumerate() {
+            let row_idx = row_idx + offset;
+            ret.fill_program_columns(row_idx, *address, ProgramColumn::PublicRamAddr);
+
+            ret.fill_program_columns(row_idx, true, ProgramColumn::PublicOutputFlag);
+            ret.fill_program_columns(row_idx, *value, ProgramColumn::PublicOutputValue);
+        }
+        ret
+    }
+
+    /// Builds the program trace of a segment of an execution, see [`Segment`].
+    ///
+    /// Every byte of RAM accessed by the segment is both publicly known initial memory and public output, on the same
+    /// row, and the program counter after the last step is public unless the execution ends within the segment.
+    pub fn new_segment(log_size: u32, program_memory: &ProgramInfo, segment: &Segment) -> Self {
+        assert!(segment.memory.len() <= 1 << log_size);
+        assert!(segment.num_steps > 0 && segment.num_steps as usize <= 1 << log_size);
+        let mut ret = Self::with_program(log_size, program_memory, segment.initial_state.pc);
+
+        if let Some(final_state) = &segment.final_state {
+            let row_idx = segment.num_steps as usize - 1;
+            ret.fill_program_columns(row_idx, true, ProgramColumn::PrgFinalPcFlag);
+            ret.fill_program_columns(row_idx, final_state.pc, ProgramColumn::PrgFinalPc);
+        }
+        for (
+            row_idx,
+            SegmentMemoryEntry {
+                address,
+                initial_value,
+                final_value,
+            },
+        ) in segment.memory.iter().enumerate()
+        {
+            ret.fill_program_columns(row_idx, *address, ProgramColumn::PublicRamAddr);
+
+            ret.fill_program_columns(row_idx, true, ProgramColumn::PublicInitialMemoryFlag);
+            ret.fill_program_columns(
+                row_idx,
+                *initial_value,
+                ProgramColumn::PublicInitialMemoryValue,
+            );
+            ret.fill_program_columns(row_idx, true, ProgramColumn::PublicOutputFlag);
+            ret.fill_program_columns(row_idx, *final_value, ProgramColumn::PublicOutputValue);
+        }
+        for row_idx in 0..1 << log_size {
+            ret.fill_program_columns(row_idx, true, ProgramColumn::PublicRamComplete);
+        }
+        ret
+    }
+
+    /// Fills the program memory and the first program counter, leaving public memory empty.
+    fn with_program(log_size: u32, program_memory: &ProgramInfo, initial_pc: u32) -> Self {
         assert!(log_size >= LOG_N_LANES);
         assert!(
             program_memory.program.len() <= 1 << log_size,
             "Program is longer than program trace size"
         );
-        assert!(init_memory.len() + exit_code.len() + output_memory.len() <= 1 << log_size);
 
         let cols = vec![vec![BaseField::zero(); 1 << log_size]; ProgramColumn::COLUMNS_NUM];
         let builder = TracesBuilder { cols, log_size };
@@ -52,7 +128,7 @@ impl ProgramTracesBuilder {
             num_instructions: 0usize,
         };
 
-        ret.fill_program_columns(0, program_memory.initial_pc, ProgramColumn::PrgInitialPc);
+        ret.fill_program_columns(0, initial_pc, ProgramColumn::PrgInitialPc);
         for (
             row_idx,
             ProgramMemoryEntry {
@@ -85,35 +161,6 @@ impl ProgramTracesBuilder {
             );
             ret.fill_program_columns(row_idx, true, ProgramColumn::PrgMemoryFlag);
         }
-
-        let init_memory_len = init_memory.len();
-        let exit_code_len = exit_code.len();
-
-        for (row_idx, MemoryInitializationEntry { address, value }) in
-            init_memory.iter().enumerate()
-        {
-            ret.fill_program_columns(row_idx, *address, ProgramColumn::PublicRamAddr);
-
-            ret.fill_program_columns(row_idx, true, ProgramColumn::PublicInitialMemoryFlag);
-            ret.fill_program_columns(row_idx, *value, ProgramColumn::PublicInitialMemoryValue);
-        }
-        let offset = init_memory_len;
-
-        for (row_idx, PublicOutputEntry { address, value }) in exit_code.iter().enumerate() {
-            let row_idx = row_idx + offset;
-            ret.fill_program_columns(row_idx, *address, ProgramColumn::PublicRamAddr);
-
-            ret.fill_program_columns(row_idx, true, ProgramColumn::PublicOutputFlag);
-            ret.fill_program_columns(row_idx, *value, ProgramColumn::PublicOutputValue);
-        }
-        let offset = offset + exit_code_len;
-        for (row_idx, PublicOutputEntry { address, value }) in output_memory.iter().enumerate() {
-            let row_idx = row_idx + offset;
-            ret.fill_program_columns(row_idx, *address, ProgramColumn::PublicRamAddr);
-
-            ret.fill_program_columns(row_idx, true, ProgramColumn::PublicOutputFlag);
-            ret.fill_program_columns(row_idx, *value, ProgramColumn::PublicOutputValue);
-        }
         ret
     }
 
@@ -166,6 +213,7 @@ impl ProgramTracesBuilder {
 ///
 /// These columns contain the whole program and the first program counter. They don't depend on the runtime information.
 /// Moreover, the publicly known initial memory and the public output are included in the program trace. These depend on the runtime information.
+/// When proving a segment, the latter two hold the accessed RAM before and after the segment, see [`ProgramTracesBuilder::new_segment`].
 /// The commitment to the program trace will be checked by the verifier.
 #[derive(Debug, Clone)]
 pub struct ProgramTraces {
@@ -4,6 +4,7 @@ use std::collections::BTreeMap;
 
 use nexus_vm::{
     emulator::{InternalView, MemoryInitializationEntry, PublicOutputEntry, View},
+    trace::segment::Segment,
     WORD_SIZE,
 };
 
@@ -97,6 +98,59 @@ pub struct BitOpSideNote {
     pub(crate) multiplicity_xor: BTreeMap<u8, u32>,
 }
 
+/// A byte of RW memory accessed by a precompile call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrecompileMemoryAccess {
+    pub address: u32,
+    /// The value before the call, zero if the byte was never accessed.
+    pub prev_value: u8,
+    /// The clock of the previous access, zero if the byte was never accessed.
+    pub prev_timestamp: u32,
+    /// The value after the call, equal to `prev_value` unless the precompile stored into the byte.
+    pub value: u8,
+}
+
+/// A call to a precompile, recorded while filling the main trace.
+///
+/// The main trace only proves that the call happened with these register values, extensions proving the precompile
+/// consume it through [`PrecompileLookupElements`](crate::extensions::PrecompileLookupElements) and are responsible
+/// for its memory accesses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrecompileCall {
+    /// The clock of the call, i.e. its row index in the main trace plus one.
+    pub clk: u32,
+    pub pc: u32,
+    /// The precompile index, `fn7` followed by `fn3` of the custom instruction.
+    pub index: u16,
+    /// The value of rs1.
+    pub value_b: u32,
+    /// The value of rs2.
+    pub value_c: u32,
+    /// The value written to rd.
+    pub value_a: u32,
+    /// Accessed bytes sorted by address.
+    pub memory: Vec<PrecompileMemoryAccess>,
+}
+
+/// A read of the private input by `ReadBytesFromPrivateInput`, recorded while filling the main trace.
+///
+/// The main trace only proves the call and its result, the built-in private input extension consumes it through
+/// [`PrivateInputLookupElements`](crate::chips::instructions::syscall::PrivateInputLookupElements) and proves the
+/// stores.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PrivateInputRead {
+    /// The clock of the call, i.e. its row index in the main trace plus one.
+    pub(crate) clk: u32,
+    /// The address of the buffer, the value of a0 before the call.
+    pub(crate) buf: u32,
+    /// The length of the buffer, the value of a1.
+    pub(crate) buflen: u32,
+    /// The timestamp of the previous access to a1 in the register memory check.
+    pub(crate) buflen_prev_timestamp: u32,
+    /// Stored bytes sorted by address, the number of bytes read is returned in a0.
+    pub(crate) memory: Vec<PrecompileMemoryAccess>,
+}
+
 pub struct SideNote {
     pub program_mem_check: ProgramMemCheckSideNote,
     pub(crate) register_mem_check: RegisterMemCheckSideNote,
@@ -107,30 +161,82 @@ pub struct SideNote {
     pub(crate) range32: RangeCheckSideNote<{ 1 << 5 }>,
     pub(crate) range128: RangeCheckSideNote<{ 1 << 7 }>,
     pub(crate) range256: RangeCheckSideNote<{ 1 << 8 }>,
+    pub(crate) precompile_calls: Vec<PrecompileCall>,
+    pub(crate) private_input_reads: Vec<PrivateInputRead>,
 }
 
 impl SideNote {
     pub fn new(program_traces: &ProgramTracesBuilder, view: &View) -> Self {
+        Self::with_initial_state(
+            program_traces,
+            RegisterMemCheckSideNote::default(),
+            ReadWriteMemCheckSideNote::new(
+                view.get_initial_memory(),
+                view.get_public_output(),
+                view.get_exit_code(),
+            ),
+        )
+    }
+
+    /// Creates the side note for a segment, starting from its initial registers and accessed RAM.
+    ///
+    /// The final values of the accessed RAM are checked like the public output of an execution.
+    pub fn new_segment(program_traces: &ProgramTracesBuilder, segment: &Segment) -> Self {
+        let register_mem_check = RegisterMemCheckSideNote {
+            last_access_value: segment.initial_state.registers,
+            ..Default::default()
+        };
+        let rw_mem_check = ReadWriteMemCheckSideNote {
+            last_access: segment
+                .memory
+                .iter()
+                .map(|entry| (entry.address, (0, entry.initial_value)))
+                .collect(),
+            public_output: segment
+                .memory
+                .iter()
+                .map(|entry| (entry.address, entry.final_value))
+                .collect(),
+        };
+        Self::with_initial_state(program_traces, register_mem_check, rw_mem_check)
+    }
+
+    fn with_initial_state(
+        program_traces: &ProgramTracesBuilder,
+        register_mem_check: RegisterMemCheckSideNote,
+        rw_mem_check: ReadWriteMemCheckSideNote,
+    ) -> Self {
         Self {
             program_mem_check: ProgramMemCheckSideNote {
                 last_access_counter: BTreeMap::new(),
                 pc_offset: program_traces.pc_offset,
                 num_instructions: program_traces.num_instructions,
             },
-            register_mem_check: RegisterMemCheckSideNote::default(),
-            rw_mem_check: ReadWriteMemCheckSideNote::new(
-                view.get_initial_memory(),
-                view.get_public_output(),
-                view.get_exit_code(),
-            ),
+            register_mem_check,
+            rw_mem_check,
             bit_op: BitOpSideNote::default(),
             range8: RangeCheckSideNote::<{ 1 << 3 }>::default(),
             range16: RangeCheckSideNote::<{ 1 << 4 }>::default(),
             range32: RangeCheckSideNote::<{ 1 << 5 }>::default(),
             range128: RangeCheckSideNote::<{ 1 << 7 }>::default(),
             range256: RangeCheckSideNote::<{ 1 << 8 }>::default(),
+            precompile_calls: Vec::new(),
+            private_input_reads: Vec::new(),
         }
     }
+
+    /// Precompile calls in the order of execution.
+    pub fn precompile_calls(&self) -> &[PrecompileCall] {
+        &self.precompile_calls
+    }
+
+    /// Counts a byte checked by an extension against [`Range256LookupElements`](crate::extensions::Range256LookupElements).
+    ///
+    /// Must be called once per check before the original traces of extensions are generated, see
+    /// [`CustomExtension::update_side_note`](crate::extensions::CustomExtension::update_side_note).
+    pub fn range_check_u8(&mut self, value: u8) {
+        self.range256.multiplicity[value as usize] += 1;
+    }
 }
 
 pub(crate) trait RangeCheckSideNoteGetter<const LEN: usize> {
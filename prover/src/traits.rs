@@ -14,6 +14,7 @@ use stwo_prover::{
 
 use crate::{
     components::AllLookupElements,
+    preflight::Support,
     trace::{
         eval::TraceEval, preprocessed::PreprocessedTraces, program_trace::ProgramTraces,
         sidenote::SideNote, FinalizedTraces, ProgramStep, TracesBuilder,
@@ -73,9 +74,13 @@ pub trait MachineChip {
     /// }
     /// ```
     fn draw_lookup_elements(_: &mut AllLookupElements, _: &mut impl Channel) {}
+
+    /// Declares the instructions and syscalls proven by the component, which programs are checked against before
+    /// proving, see [`Machine::preflight`](crate::machine::Machine::preflight).
+    fn declare_support(_support: &mut Support) {}
 }
 
-#[impl_for_tuples(1, 26)]
+#[impl_for_tuples(1, 35)]
 impl MachineChip for Tuple {
     fn fill_main_trace(
         traces: &mut TracesBuilder,
@@ -107,6 +112,10 @@ impl MachineChip for Tuple {
     fn draw_lookup_elements(all_elements: &mut AllLookupElements, channel: &mut impl Channel) {
         for_tuples!( #( Tuple::draw_lookup_elements(all_elements, channel); )* );
     }
+
+    fn declare_support(support: &mut Support) {
+        for_tuples!( #( Tuple::declare_support(support); )* );
+    }
 }
 
 pub fn generate_interaction_trace<C: MachineChip>(
@@ -8,9 +8,10 @@ use stwo_prover::{
 
 use crate::{
     column::Column::{
-        self, ImmC, IsAdd, IsAnd, IsAuipc, IsBeq, IsBge, IsBgeu, IsBlt, IsBltu, IsBne, IsEbreak,
-        IsEcall, IsJal, IsJalr, IsLb, IsLbu, IsLh, IsLhu, IsLui, IsLw, IsOr, IsSb, IsSh, IsSll,
-        IsSlt, IsSltu, IsSra, IsSrl, IsSub, IsSw, IsXor,
+        self, ImmC, IsAdd, IsAnd, IsAuipc, IsBeq, IsBge, IsBgeu, IsBlt, IsBltu, IsBne, IsCustom,
+        IsDiv, IsDivu, IsEbreak, IsEcall, IsJal, IsJalr, IsLb, IsLbu, IsLh, IsLhu, IsLui, IsLw,
+        IsMul, IsMulh, IsMulhsu, IsMulhu, IsOr, IsRem, IsRemu, IsSb, IsSh, IsSll, IsSlt, IsSltu,
+        IsSra, IsSrl, IsSub, IsSw, IsXor,
     },
     trace::{eval::trace_eval, eval::TraceEval, FinalizedTraces, TracesBuilder},
 };
@@ -65,8 +66,9 @@ impl<S: VirtualColumnForSum> VirtualColumn<1> for S {
 pub(crate) struct IsTypeR;
 
 impl IsTypeR {
-    const TYPE_R_OPS: [Column; 10] = [
-        IsAdd, IsSub, IsSlt, IsSltu, IsXor, IsOr, IsAnd, IsSll, IsSrl, IsSra,
+    const TYPE_R_OPS: [Column; 19] = [
+        IsAdd, IsSub, IsSlt, IsSltu, IsXor, IsOr, IsAnd, IsSll, IsSrl, IsSra, IsMul, IsMulh,
+        IsMulhsu, IsMulhu, IsDiv, IsDivu, IsRem, IsRemu, IsCustom,
     ];
 }
 
@@ -119,7 +121,8 @@ pub(crate) struct IsAlu;
 impl VirtualColumnForSum for IsAlu {
     fn columns() -> &'static [Column] {
         &[
-            IsAdd, IsSub, IsSlt, IsSltu, IsXor, IsOr, IsAnd, IsSll, IsSrl, IsSra,
+            IsAdd, IsSub, IsSlt, IsSltu, IsXor, IsOr, IsAnd, IsSll, IsSrl, IsSra, IsMul, IsMulh,
+            IsMulhsu, IsMulhu, IsDiv, IsDivu, IsRem, IsRemu,
         ]
     }
 }
@@ -294,7 +297,7 @@ impl VirtualColumnForSum for IsTypeB {
 }
 
 /// Instead of having is_pc_incremented as a separate column and having
-/// `(is_alu + is_load + is_type_s + is_type_sys + is_type_u - is_pc_incremented) = 0`,
+/// `(is_alu + is_load + is_type_s + is_type_sys + is_type_u + is_custom - is_pc_incremented) = 0`,
 /// we can just have a virtual column is_pc_incremented. This change doesn't change the degree of any constraints.
 pub(crate) struct IsPcIncremented;
 
@@ -307,11 +310,13 @@ impl VirtualColumn<1> for IsPcIncremented {
         let [is_type_sys] = IsTypeSys::read_from_traces_builder(traces, row_idx);
 
         let [is_sys_halt] = traces.column(row_idx, Column::IsSysHalt);
+        let [is_custom] = traces.column(row_idx, Column::IsCustom);
         let ret = is_alu
             + is_load
             + is_type_s
             + is_type_sys * (BaseField::one() - is_sys_halt)
-            + is_type_u;
+            + is_type_u
+            + is_custom;
         [ret]
     }
     fn read_from_finalized_traces(
@@ -325,11 +330,13 @@ impl VirtualColumn<1> for IsPcIncremented {
         let is_type_sys = IsTypeSys::read_from_finalized_traces(traces, vec_idx)[0];
 
         let is_sys_halt = traces.get_base_column::<1>(Column::IsSysHalt)[0].data[vec_idx];
+        let is_custom = traces.get_base_column::<1>(Column::IsCustom)[0].data[vec_idx];
         let ret = is_alu
             + is_load
             + is_type_s
             + is_type_sys * (PackedBaseField::one() - is_sys_halt)
-            + is_type_u;
+            + is_type_u
+            + is_custom;
         [ret]
     }
     fn eval<E: EvalAtRow>(trace_eval: &TraceEval<E>) -> [E::F; 1] {
@@ -340,8 +347,13 @@ impl VirtualColumn<1> for IsPcIncremented {
         let [is_type_sys] = IsTypeSys::eval(trace_eval);
 
         let [is_sys_halt] = trace_eval!(trace_eval, Column::IsSysHalt);
-        let ret =
-            is_alu + is_load + is_type_s + is_type_sys * (E::F::one() - is_sys_halt) + is_type_u;
+        let [is_custom] = trace_eval!(trace_eval, Column::IsCustom);
+        let ret = is_alu
+            + is_load
+            + is_type_s
+            + is_type_sys * (E::F::one() - is_sys_halt)
+            + is_type_u
+            + is_custom;
         [ret]
     }
 }
@@ -351,7 +363,7 @@ impl VirtualColumn<1> for IsPcIncremented {
 /// The definition of op-b-flag follows:
 /// (is-sb + is-sh + is-sw + is-lb + is-lh + is-lw + is-lbu + is-lhu + is-jalr + is-add + is-sub + is-slt + is-sltu
 /// + is-xor + is-or + is-and + is-sll + is-srl + is-sra+ is-beq + is-bne + is-blt + is-bge + is-bltu
-/// + is-bgeu + is-ecall + is-ebreak − op-b-flag) = 0
+/// + is-bgeu + is-ecall + is-ebreak + is-custom − op-b-flag) = 0
 ///
 /// op-b-flag controls whether Reg1Address is used.
 pub(crate) struct OpBFlag;
@@ -360,8 +372,9 @@ impl VirtualColumnForSum for OpBFlag {
     fn columns() -> &'static [Column] {
         &[
             IsSb, IsSh, IsSw, IsLb, IsLh, IsLw, IsLbu, IsLhu, IsJalr, IsAdd, IsSub, IsSlt, IsSltu,
-            IsXor, IsOr, IsAnd, IsSll, IsSrl, IsSra, IsBeq, IsBne, IsBlt, IsBge, IsBltu, IsBgeu,
-            IsEcall, IsEbreak,
+            IsXor, IsOr, IsAnd, IsSll, IsSrl, IsSra, IsMul, IsMulh, IsMulhsu, IsMulhu, IsDiv,
+            IsDivu, IsRem, IsRemu, IsBeq, IsBne, IsBlt, IsBge, IsBltu, IsBgeu, IsEcall, IsEbreak,
+            IsCustom,
         ]
     }
 }
@@ -376,7 +389,7 @@ pub(crate) struct Reg3Accessed;
 // reg3_accessed =
 // (is_type_s + is_type_b) +   // When reading from rs1
 // (is_type_r + is_type_i + is_type_u + is_type_j)  + // For instructions with rd
-// (is_type_sys)·(is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset) // For some syscalls
+// (is_type_sys)·(is_sys_priv_input + is_sys_priv_input_bytes + is_sys_aux_input + is_sys_oracle + is_sys_heap_reset + is_sys_stack_reset) // For some syscalls
 impl VirtualColumn<1> for Reg3Accessed {
     fn read_from_traces_builder(traces: &TracesBuilder, row_idx: usize) -> [BaseField; 1] {
         let [is_type_s] = IsTypeS::read_from_traces_builder(traces, row_idx);
@@ -387,6 +400,9 @@ impl VirtualColumn<1> for Reg3Accessed {
         let [is_type_j] = IsTypeJ::read_from_traces_builder(traces, row_idx);
         let [is_type_sys] = IsTypeSys::read_from_traces_builder(traces, row_idx);
         let [is_sys_priv_input] = traces.column(row_idx, Column::IsSysPrivInput);
+        let [is_sys_priv_input_bytes] = traces.column(row_idx, Column::IsSysPrivInputBytes);
+        let [is_sys_aux_input] = traces.column(row_idx, Column::IsSysAuxInput);
+        let [is_sys_oracle] = traces.column(row_idx, Column::IsSysOracle);
         let [is_sys_heap_reset] = traces.column(row_idx, Column::IsSysHeapReset);
         let [is_sys_stack_reset] = traces.column(row_idx, Column::IsSysStackReset);
 
@@ -396,7 +412,13 @@ impl VirtualColumn<1> for Reg3Accessed {
             + is_type_i
             + is_type_u
             + is_type_j
-            + is_type_sys * (is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset);
+            + is_type_sys
+                * (is_sys_priv_input
+                    + is_sys_priv_input_bytes
+                    + is_sys_aux_input
+                    + is_sys_oracle
+                    + is_sys_heap_reset
+                    + is_sys_stack_reset);
         [ret]
     }
     fn read_from_finalized_traces(
@@ -412,6 +434,10 @@ impl VirtualColumn<1> for Reg3Accessed {
         let is_type_sys = IsTypeSys::read_from_finalized_traces(traces, vec_idx)[0];
         let is_sys_priv_input =
             traces.get_base_column::<1>(Column::IsSysPrivInput)[0].data[vec_idx];
+        let is_sys_priv_input_bytes =
+            traces.get_base_column::<1>(Column::IsSysPrivInputBytes)[0].data[vec_idx];
+        let is_sys_aux_input = traces.get_base_column::<1>(Column::IsSysAuxInput)[0].data[vec_idx];
+        let is_sys_oracle = traces.get_base_column::<1>(Column::IsSysOracle)[0].data[vec_idx];
         let is_sys_heap_reset =
             traces.get_base_column::<1>(Column::IsSysHeapReset)[0].data[vec_idx];
         let is_sys_stack_reset =
@@ -422,7 +448,13 @@ impl VirtualColumn<1> for Reg3Accessed {
             + is_type_i
             + is_type_u
             + is_type_j
-            + is_type_sys * (is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset);
+            + is_type_sys
+                * (is_sys_priv_input
+                    + is_sys_priv_input_bytes
+                    + is_sys_aux_input
+                    + is_sys_oracle
+                    + is_sys_heap_reset
+                    + is_sys_stack_reset);
         [ret]
     }
     fn eval<E: EvalAtRow>(trace_eval: &TraceEval<E>) -> [E::F; 1] {
@@ -434,6 +466,9 @@ impl VirtualColumn<1> for Reg3Accessed {
         let [is_type_j] = IsTypeJ::eval(trace_eval);
         let [is_type_sys] = IsTypeSys::eval(trace_eval);
         let [is_sys_priv_input] = trace_eval!(trace_eval, Column::IsSysPrivInput);
+        let [is_sys_priv_input_bytes] = trace_eval!(trace_eval, Column::IsSysPrivInputBytes);
+        let [is_sys_aux_input] = trace_eval!(trace_eval, Column::IsSysAuxInput);
+        let [is_sys_oracle] = trace_eval!(trace_eval, Column::IsSysOracle);
         let [is_sys_heap_reset] = trace_eval!(trace_eval, Column::IsSysHeapReset);
         let [is_sys_stack_reset] = trace_eval!(trace_eval, Column::IsSysStackReset);
         let ret = is_type_s
@@ -442,7 +477,13 @@ impl VirtualColumn<1> for Reg3Accessed {
             + is_type_i
             + is_type_u
             + is_type_j
-            + is_type_sys * (is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset);
+            + is_type_sys
+                * (is_sys_priv_input
+                    + is_sys_priv_input_bytes
+                    + is_sys_aux_input
+                    + is_sys_oracle
+                    + is_sys_heap_reset
+                    + is_sys_stack_reset);
         [ret]
     }
 }
@@ -91,19 +91,8 @@ pub(crate) fn handle_output(
         },
     };
 
-    // Check that the target architecture is riscv32 if doing public output.
-    let target_check = if !matches!(output_type, OutputType::Custom) {
-        quote! {
-            #[cfg(not(target_arch = "riscv32"))]
-            compile_error!("NexusVM public output interfaces are not available for native builds, use a custom handler instead. Ex: #[nexus_rt::custom_output(bar)]");
-        }
-    } else {
-        quote! {}
-    };
-
     // Build the output token stream
     let expanded = quote! {
-        #target_check
         #(#attrs)*
         fn #fn_name(#inputs) {
             let out = (|| {
@@ -260,19 +249,8 @@ pub(crate) fn handle_input(
         },
     };
 
-    // Check that the target architecture is riscv32 if doing public output.
-    let target_check = if !matches!(input_type, InputType::Custom) {
-        quote! {
-            #[cfg(not(target_arch = "riscv32"))]
-            compile_error!("NexusVM public and private input interfaces are not available for native builds, use a custom handler instead. Ex: #[nexus_rt::custom_input(bar)]");
-        }
-    } else {
-        quote! {}
-    };
-
     // Build the output token stream
     let expanded = quote! {
-        #target_check
         #(#attrs)*
         fn #fn_name(#input_sig) #output {
             let (#(#inputs),*):(#(#types),*) = #input_handler().expect("Failed to read public input");
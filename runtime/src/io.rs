@@ -5,7 +5,8 @@ extern crate alloc;
 mod riscv32 {
     extern crate alloc;
     use crate::{
-        ecall, read_input, write_output, SYS_CYCLE_COUNT, SYS_EXIT, SYS_LOG,
+        ecall, read_input, write_output, SYS_ALLOC_ALIGNED, SYS_CYCLE_COUNT, SYS_EXIT, SYS_LOG,
+        SYS_QUERY_ORACLE, SYS_READ_AUXILIARY_INPUT, SYS_READ_BYTES_PRIVATE_INPUT,
         SYS_READ_PRIVATE_INPUT, WORD_SIZE,
     };
     use serde::{de::DeserializeOwned, Serialize};
@@ -54,6 +55,101 @@ mod riscv32 {
         } // u32::MAX is used a sentinel value that there is nothing (left) on the input tape
     }
 
+    /// A streaming reader of the private input tape
+    ///
+    /// Unlike [`read_private_input`], which makes an ecall per byte, each read copies a run of bytes from the tape
+    /// into memory with a single ecall, and the tape can be consumed incrementally. The bytes are those of the tape as
+    /// set by the host, i.e., the encoded input.
+    pub struct PrivateInput;
+
+    impl PrivateInput {
+        /// Read up to `buf.len()` bytes into `buf`, returning the number of bytes read, which is zero once the tape is
+        /// exhausted.
+        pub fn read(&mut self, buf: &mut [u8]) -> usize {
+            ecall!(
+                SYS_READ_BYTES_PRIVATE_INPUT,
+                buf.as_mut_ptr() as u32,
+                ("a1", buf.len())
+            ) as usize
+        }
+
+        /// Read exactly `buf.len()` bytes into `buf`, failing if the tape ends first.
+        pub fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), postcard::Error> {
+            if self.read(buf) < buf.len() {
+                return Err(postcard::Error::DeserializeUnexpectedEnd);
+            }
+            Ok(())
+        }
+
+        /// Read the rest of the tape, appending it to `buf` and returning the number of bytes read.
+        pub fn read_to_end(&mut self, buf: &mut alloc::vec::Vec<u8>) -> usize {
+            const CHUNK_SIZE: usize = 1024;
+
+            let start = buf.len();
+            loop {
+                let len = buf.len();
+                buf.resize(len + CHUNK_SIZE, 0);
+                let read = self.read(&mut buf[len..]);
+                buf.truncate(len + read);
+                if read < CHUNK_SIZE {
+                    return buf.len() - start;
+                }
+            }
+        }
+    }
+
+    /// Read the next hint off the auxiliary input tape
+    ///
+    /// Hints are provided by the host in the order they are read, and are neither committed to nor constrained by the
+    /// proof, so the guest must check any hint before relying on it, e.g., that a hinted square root squares to its
+    /// input.
+    pub fn read_hint<T: DeserializeOwned>() -> Result<T, postcard::Error> {
+        // Each hint is a COBS frame terminated by a zero byte, so stop there to leave the next hint on the tape.
+        let mut bytes = alloc::vec::Vec::new();
+        while let Some(byte) = read_from_auxiliary_input() {
+            bytes.push(byte);
+            if byte == 0 {
+                break;
+            }
+        }
+        postcard::from_bytes_cobs::<T>(bytes.as_mut_slice())
+    }
+
+    /// Query the host oracle registered on `channel`
+    ///
+    /// Unlike inputs, the request can depend on the execution so far, e.g., ask for the Merkle witness of a key the
+    /// guest computed. The request and response are encoded with `postcard`. Like hints, responses are neither
+    /// committed to nor constrained by the proof, so the guest must check any response before relying on it.
+    pub fn query_oracle<Req: Serialize + ?Sized, Resp: DeserializeOwned>(
+        channel: u32,
+        request: &Req,
+    ) -> Result<Resp, postcard::Error> {
+        let request = postcard::to_allocvec(request)?;
+        let len = ecall!(
+            SYS_QUERY_ORACLE,
+            channel,
+            ("a1", request.as_ptr()),
+            ("a2", request.len())
+        );
+
+        // The response was appended to the auxiliary input tape.
+        let response: alloc::vec::Vec<u8> = (0..len)
+            .map(|_| read_from_auxiliary_input().ok_or(postcard::Error::DeserializeUnexpectedEnd))
+            .collect::<Result<_, _>>()?;
+        postcard::from_bytes::<Resp>(&response)
+    }
+
+    /// Read a byte from the auxiliary input tape
+    fn read_from_auxiliary_input() -> Option<u8> {
+        let out = ecall!(SYS_READ_AUXILIARY_INPUT);
+
+        if out == u32::MAX {
+            None
+        } else {
+            Some(out.to_le_bytes()[0])
+        } // u32::MAX is used a sentinel value that there is nothing (left) on the input tape
+    }
+
     /// Read an object from the public input segment.
     pub fn read_public_input<T: DeserializeOwned>() -> Result<T, postcard::Error> {
         // The first word stores the length of the input (in bytes).
@@ -90,6 +186,51 @@ mod riscv32 {
         Ok(())
     }
 
+    /// Number of bytes of the public output segment, after the exit code, taken by the journal so far.
+    static mut JOURNAL_LEN: usize = 0;
+
+    /// Append an object to the public output journal.
+    ///
+    /// Records are framed as described in [`journal`](crate::journal), so that the host can decode any number of them,
+    /// see the `journal` of the SDK views. The journal starts where [`write_public_output`] writes, so a program uses
+    /// one or the other. Fails with [`postcard::Error::SerializeBufferFull`] if the record does not fit in the public
+    /// output segment of the memory layout.
+    pub fn commit<T: Serialize + ?Sized>(val: &T) -> Result<(), postcard::Error> {
+        let record = crate::journal::encode_record(val)?;
+
+        // SAFETY: the guest is single-threaded.
+        let offset = unsafe { JOURNAL_LEN };
+        // Word 0 is reserved for the exit code.
+        let end = offset + WORD_SIZE + record.len();
+
+        // Under a linear memory layout the public output segment ends where the heap starts, otherwise it is sized
+        // to fit whatever is written.
+        let output_end = ecall!(SYS_ALLOC_ALIGNED) as usize;
+        if output_end > 0 {
+            let output_start: usize;
+            unsafe {
+                core::arch::asm!(
+                    "lw {0}, 0x84(x0)", // 0x84 stores the output start address
+                    out(reg) output_start,
+                )
+            }
+            if output_start
+                .checked_add(end)
+                .is_none_or(|end| end > output_end)
+            {
+                return Err(postcard::Error::SerializeBufferFull);
+            }
+        }
+
+        record.chunks(WORD_SIZE).enumerate().for_each(|(i, chunk)| {
+            let word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            write_output!(offset + (i + 1) * WORD_SIZE, word);
+        });
+
+        unsafe { JOURNAL_LEN = end - WORD_SIZE };
+        Ok(())
+    }
+
     /// Bench cycles, where input is the function name
     pub fn cycle_count_ecall(s: &str) {
         let buf = s.as_ptr();
@@ -138,31 +279,367 @@ macro_rules! println {
     }
 }
 
-#[allow(private_bounds)]
 #[cfg(not(target_arch = "riscv32"))]
 mod native {
-    // a trait required in the bounds of each of these methods, but which is inaccessible for library users
-    // causes use of these functions to fail at compilation, but with a more helpful error than 'not found'
-    trait RequiresRV32Target {}
+    //! Host implementations of the guest I/O functions, so that guest programs can be run, debugged and tested natively.
+    //!
+    //! Inputs are read from, and outputs written to, the [`IoContext`] of the current thread, which a test harness sets
+    //! with [`set_io_context`]. A thread that does I/O without a context loads one from the files named by the
+    //! `NEXUS_*` environment variables, which is how the SDK runs binaries built with `Compile::set_native_build`.
+    use std::{
+        cell::RefCell,
+        collections::{HashMap, VecDeque},
+        fmt, fs,
+        rc::Rc,
+    };
 
     use serde::{de::DeserializeOwned, Serialize};
 
-    pub fn write_log<UNUSABLE: RequiresRV32Target>(_s: &str) {
-        unimplemented!()
+    use crate::journal::{encode_record, split_record};
+
+    /// Environment variable naming the file to read the encoded public input from.
+    pub const PUBLIC_INPUT_ENV: &str = "NEXUS_PUBLIC_INPUT";
+    /// Environment variable naming the file to read the encoded private input tape from.
+    pub const PRIVATE_INPUT_ENV: &str = "NEXUS_PRIVATE_INPUT";
+    /// Environment variable naming the file to read the encoded hints from.
+    pub const AUXILIARY_INPUT_ENV: &str = "NEXUS_AUXILIARY_INPUT";
+    /// Environment variable naming the file to write the encoded public output to.
+    pub const PUBLIC_OUTPUT_ENV: &str = "NEXUS_PUBLIC_OUTPUT";
+
+    type Oracle = Rc<dyn Fn(&[u8]) -> Vec<u8>>;
+
+    /// Inputs and outputs of a guest program running natively.
+    ///
+    /// Inputs are held encoded as on the zkVM, i.e., each input by `postcard::to_stdvec_cobs`, and hints as consecutive
+    /// such frames.
+    #[derive(Default, Clone)]
+    pub struct IoContext {
+        pub public_input: Vec<u8>,
+        pub private_input: VecDeque<u8>,
+        pub auxiliary_input: VecDeque<u8>,
+        /// The encoded public output, once written.
+        pub public_output: Option<Vec<u8>>,
+        /// The logs written by the guest, which are also printed.
+        pub logs: Vec<String>,
+        oracles: HashMap<u32, Oracle>,
+    }
+
+    impl fmt::Debug for IoContext {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let mut channels: Vec<&u32> = self.oracles.keys().collect();
+            channels.sort();
+
+            f.debug_struct("IoContext")
+                .field("public_input", &self.public_input)
+                .field("private_input", &self.private_input)
+                .field("auxiliary_input", &self.auxiliary_input)
+                .field("public_output", &self.public_output)
+                .field("logs", &self.logs)
+                .field("oracles", &channels)
+                .finish()
+        }
     }
 
-    pub fn read_private_input<UNUSABLE: RequiresRV32Target, T: DeserializeOwned>(
-    ) -> Result<T, postcard::Error> {
-        unimplemented!()
+    impl IoContext {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Load the encoded inputs from the files named by the environment variables, leaving any input whose variable
+        /// is not set empty.
+        pub fn from_env() -> std::io::Result<Self> {
+            let read = |var: &str| match std::env::var_os(var) {
+                Some(path) => fs::read(path),
+                None => Ok(Vec::new()),
+            };
+
+            Ok(Self {
+                public_input: read(PUBLIC_INPUT_ENV)?,
+                private_input: read(PRIVATE_INPUT_ENV)?.into(),
+                auxiliary_input: read(AUXILIARY_INPUT_ENV)?.into(),
+                ..Default::default()
+            })
+        }
+
+        pub fn with_public_input<T: Serialize + ?Sized>(
+            mut self,
+            input: &T,
+        ) -> Result<Self, postcard::Error> {
+            self.public_input = postcard::to_allocvec_cobs(input)?;
+            Ok(self)
+        }
+
+        pub fn with_private_input<T: Serialize + ?Sized>(
+            mut self,
+            input: &T,
+        ) -> Result<Self, postcard::Error> {
+            self.private_input = postcard::to_allocvec_cobs(input)?.into();
+            Ok(self)
+        }
+
+        /// Append a hint, to be read by [`read_hint`] after the hints before it.
+        pub fn with_hint<T: Serialize + ?Sized>(
+            mut self,
+            hint: &T,
+        ) -> Result<Self, postcard::Error> {
+            self.auxiliary_input
+                .extend(postcard::to_allocvec_cobs(hint)?);
+            Ok(self)
+        }
+
+        /// Register the oracle answering [`query_oracle`] on `channel`, replacing any previous one.
+        pub fn with_oracle<F: Fn(&[u8]) -> Vec<u8> + 'static>(
+            mut self,
+            channel: u32,
+            oracle: F,
+        ) -> Self {
+            self.oracles.insert(channel, Rc::new(oracle));
+            self
+        }
+
+        /// Decode the public output written by the guest.
+        pub fn public_output<T: DeserializeOwned>(&self) -> Result<T, postcard::Error> {
+            let mut bytes = self
+                .public_output
+                .clone()
+                .ok_or(postcard::Error::DeserializeUnexpectedEnd)?;
+            postcard::from_bytes_cobs::<T>(bytes.as_mut_slice())
+        }
+
+        /// Decode the records committed to the public output journal by the guest, in order.
+        pub fn journal<T: DeserializeOwned>(&self) -> Result<Vec<T>, postcard::Error> {
+            let mut journal = self.public_output.as_deref().unwrap_or_default();
+            core::iter::from_fn(|| {
+                let (record, rest) = match split_record(journal)? {
+                    Ok(split) => split,
+                    Err(e) => return Some(Err(e)),
+                };
+                journal = rest;
+                Some(postcard::from_bytes::<T>(record))
+            })
+            .collect()
+        }
     }
 
-    pub fn read_public_input<UNUSABLE: RequiresRV32Target, T: DeserializeOwned>(
-    ) -> Result<T, postcard::Error> {
-        unimplemented!()
+    thread_local! {
+        static IO_CONTEXT: RefCell<Option<IoContext>> = const { RefCell::new(None) };
     }
 
-    pub fn write_public_output<UNUSABLE: RequiresRV32Target, T: Serialize + ?Sized>(_val: &T) {
-        unimplemented!()
+    /// Set the I/O context of the current thread, returning the previous one.
+    pub fn set_io_context(context: IoContext) -> Option<IoContext> {
+        IO_CONTEXT.with_borrow_mut(|current| current.replace(context))
+    }
+
+    /// Take the I/O context of the current thread, e.g., to check the outputs of a run.
+    pub fn take_io_context() -> Option<IoContext> {
+        IO_CONTEXT.with_borrow_mut(Option::take)
+    }
+
+    fn with_io_context<R>(f: impl FnOnce(&mut IoContext) -> R) -> R {
+        IO_CONTEXT.with_borrow_mut(|context| {
+            let context = match context {
+                Some(context) => context,
+                None => context.insert(
+                    IoContext::from_env()
+                        .expect("Failed to load the I/O context from the environment"),
+                ),
+            };
+            f(context)
+        })
+    }
+
+    /// Write a string to the output console, and to the logs of the I/O context.
+    pub fn write_log(s: &str) -> Option<u32> {
+        print!("{s}");
+        with_io_context(|context| context.logs.push(s.to_string()));
+        Some(s.len() as u32)
+    }
+
+    /// Read an object off the private input tape
+    ///
+    /// exhausts the private input tape, so can only be used once
+    pub fn read_private_input<T: DeserializeOwned>() -> Result<T, postcard::Error> {
+        let mut bytes: Vec<u8> =
+            with_io_context(|context| context.private_input.drain(..).collect());
+        postcard::from_bytes_cobs::<T>(bytes.as_mut_slice())
+    }
+
+    /// Read an object from the public input.
+    pub fn read_public_input<T: DeserializeOwned>() -> Result<T, postcard::Error> {
+        let mut bytes = with_io_context(|context| context.public_input.clone());
+        postcard::from_bytes_cobs::<T>(bytes.as_mut_slice())
+    }
+
+    /// Read the next hint off the auxiliary input tape
+    pub fn read_hint<T: DeserializeOwned>() -> Result<T, postcard::Error> {
+        let mut bytes = with_io_context(|context| {
+            let len = context
+                .auxiliary_input
+                .iter()
+                .position(|&byte| byte == 0)
+                .map_or(context.auxiliary_input.len(), |end| end + 1);
+            context.auxiliary_input.drain(..len).collect::<Vec<u8>>()
+        });
+        postcard::from_bytes_cobs::<T>(bytes.as_mut_slice())
+    }
+
+    /// A streaming reader of the private input tape
+    pub struct PrivateInput;
+
+    impl PrivateInput {
+        /// Read up to `buf.len()` bytes into `buf`, returning the number of bytes read, which is zero once the tape is
+        /// exhausted.
+        pub fn read(&mut self, buf: &mut [u8]) -> usize {
+            with_io_context(|context| {
+                let len = context.private_input.len().min(buf.len());
+                for (byte, value) in buf.iter_mut().zip(context.private_input.drain(..len)) {
+                    *byte = value;
+                }
+                len
+            })
+        }
+
+        /// Read exactly `buf.len()` bytes into `buf`, failing if the tape ends first.
+        pub fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), postcard::Error> {
+            if self.read(buf) < buf.len() {
+                return Err(postcard::Error::DeserializeUnexpectedEnd);
+            }
+            Ok(())
+        }
+
+        /// Read the rest of the tape, appending it to `buf` and returning the number of bytes read.
+        pub fn read_to_end(&mut self, buf: &mut Vec<u8>) -> usize {
+            with_io_context(|context| {
+                let len = context.private_input.len();
+                buf.extend(context.private_input.drain(..));
+                len
+            })
+        }
+    }
+
+    /// Query the oracle registered on `channel` in the I/O context
+    ///
+    /// Panics if there is none, as the zkVM fails the execution.
+    pub fn query_oracle<Req: Serialize + ?Sized, Resp: DeserializeOwned>(
+        channel: u32,
+        request: &Req,
+    ) -> Result<Resp, postcard::Error> {
+        let request = postcard::to_allocvec(request)?;
+        // The oracle is called outside of the context, so that it may do I/O itself.
+        let oracle = with_io_context(|context| context.oracles.get(&channel).cloned())
+            .unwrap_or_else(|| panic!("No oracle registered for channel {channel}"));
+
+        postcard::from_bytes::<Resp>(&oracle(&request))
+    }
+
+    /// Write an object to the public output, and to the file named by the environment variable, if set.
+    pub fn write_public_output<T: Serialize + ?Sized>(val: &T) -> Result<(), postcard::Error> {
+        let bytes = postcard::to_allocvec_cobs(val)?;
+
+        if let Some(path) = std::env::var_os(PUBLIC_OUTPUT_ENV) {
+            fs::write(path, &bytes).expect("Failed to write the public output");
+        }
+        with_io_context(|context| context.public_output = Some(bytes));
+
+        Ok(())
+    }
+
+    /// Append an object to the public output journal, and write the journal to the file named by the environment
+    /// variable, if set.
+    ///
+    /// Records are encoded as on the zkVM, and like there the journal starts where [`write_public_output`] writes, so a
+    /// program uses one or the other.
+    pub fn commit<T: Serialize + ?Sized>(val: &T) -> Result<(), postcard::Error> {
+        let record = encode_record(val)?;
+
+        let journal = with_io_context(|context| {
+            let journal = context.public_output.get_or_insert_with(Vec::new);
+            journal.extend(record);
+            journal.clone()
+        });
+        if let Some(path) = std::env::var_os(PUBLIC_OUTPUT_ENV) {
+            fs::write(path, journal).expect("Failed to write the public output");
+        }
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_io_context() {
+            let context = IoContext::new()
+                .with_public_input(&(1u32, 2u32))
+                .unwrap()
+                .with_private_input(&[3u8, 4, 5])
+                .unwrap()
+                .with_hint(&6u64)
+                .unwrap()
+                .with_hint("seven")
+                .unwrap()
+                .with_oracle(8, |request: &[u8]| request.iter().rev().copied().collect());
+            assert!(set_io_context(context).is_none());
+
+            assert_eq!(read_public_input::<(u32, u32)>(), Ok((1, 2)));
+            assert_eq!(read_hint::<u64>(), Ok(6));
+            assert_eq!(read_hint::<String>().as_deref(), Ok("seven"));
+            assert_eq!(query_oracle::<_, (u8, u8)>(8, &(9u8, 10u8)), Ok((10, 9)));
+
+            // The private input can be read incrementally, as the encoded tape.
+            let mut header = [0u8; 2];
+            PrivateInput.read_exact(&mut header).unwrap();
+            let mut rest = header.to_vec();
+            assert_eq!(PrivateInput.read_to_end(&mut rest), 3);
+            assert_eq!(
+                postcard::from_bytes_cobs::<[u8; 3]>(&mut rest),
+                Ok([3, 4, 5])
+            );
+
+            write_log("hello");
+            write_public_output(&11u32).unwrap();
+
+            let context = take_io_context().unwrap();
+            assert_eq!(context.logs, vec!["hello".to_string()]);
+            assert_eq!(context.public_output::<u32>(), Ok(11));
+        }
+
+        #[test]
+        fn test_commit() {
+            set_io_context(IoContext::new());
+            commit(&1u32).unwrap();
+            commit(&300u32).unwrap();
+            assert_eq!(
+                take_io_context().unwrap().journal::<u32>(),
+                Ok(vec![1, 300])
+            );
+
+            // Records of different types, each padded to a word, with the header one more than the encoded length.
+            set_io_context(IoContext::new());
+            commit(&1u8).unwrap();
+            commit("two").unwrap();
+            commit(&()).unwrap();
+
+            let context = take_io_context().unwrap();
+            let journal = context.public_output.clone().unwrap();
+            assert_eq!(
+                journal,
+                [2, 0, 0, 0, 1, 0, 0, 0, 5, 0, 0, 0, 3, b't', b'w', b'o', 1, 0, 0, 0]
+            );
+            assert_eq!(split_record(&journal[16..]), Some(Ok((&[][..], &[][..]))));
+
+            // The zero words following the journal in the public output segment end it.
+            let mut context = IoContext::new();
+            context.public_output = Some([&journal[16..], &[0; 8]].concat());
+            assert_eq!(context.journal::<()>(), Ok(vec![()]));
+
+            context.public_output = Some(journal[..14].to_vec());
+            assert_eq!(
+                context.journal::<u8>(),
+                Err(postcard::Error::DeserializeUnexpectedEnd)
+            );
+        }
     }
 }
 #[cfg(not(target_arch = "riscv32"))]
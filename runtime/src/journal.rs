@@ -0,0 +1,49 @@
+//! Framing of the public output journal written by [`commit`](crate::commit).
+//!
+//! Each record is a header word holding one more than the byte length of the `postcard` encoding, followed by the
+//! encoding zero-padded to a word boundary. The offset keeps a record of zero length, such as that of `commit(&())`,
+//! distinct from a zero word, which ends the journal: the public output segment is zero-initialized, so the journal
+//! read back from it is followed by zero words up to the end of the segment.
+extern crate alloc;
+
+use alloc::vec::Vec;
+use serde::Serialize;
+
+use crate::WORD_SIZE;
+
+/// A record split off a journal, and the rest of the journal.
+type Split<'a> = (&'a [u8], &'a [u8]);
+
+/// Encode an object as a journal record.
+pub fn encode_record<T: Serialize + ?Sized>(val: &T) -> Result<Vec<u8>, postcard::Error> {
+    let encoding = postcard::to_allocvec(val)?;
+    let header =
+        u32::try_from(encoding.len() + 1).map_err(|_| postcard::Error::SerializeBufferFull)?;
+
+    let record_len = WORD_SIZE + encoding.len().next_multiple_of(WORD_SIZE);
+    let mut record = Vec::with_capacity(record_len);
+    record.extend(header.to_le_bytes());
+    record.extend(encoding);
+    record.resize(record_len, 0x00);
+    Ok(record)
+}
+
+/// Split the first record off a journal, returning its `postcard` encoding and the rest of the journal.
+///
+/// Returns `None` at the end of the journal, that is on an empty journal or a zero header word, and an error if the
+/// record is truncated.
+pub fn split_record(journal: &[u8]) -> Option<Result<Split<'_>, postcard::Error>> {
+    if journal.is_empty() {
+        return None;
+    }
+    let Some((header, rest)) = journal.split_first_chunk::<WORD_SIZE>() else {
+        return Some(Err(postcard::Error::DeserializeUnexpectedEnd));
+    };
+
+    let len = (u32::from_le_bytes(*header) as usize).checked_sub(1)?;
+    let padded_len = len.next_multiple_of(WORD_SIZE);
+    if rest.len() < padded_len {
+        return Some(Err(postcard::Error::DeserializeUnexpectedEnd));
+    }
+    Some(Ok((&rest[..len], &rest[padded_len..])))
+}
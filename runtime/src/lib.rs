@@ -16,6 +16,7 @@ pub use nexus_rt_macros::{
 
 mod io;
 pub use io::*;
+pub mod journal;
 pub use postcard;
 
 // Ecall codes. Allow dead code here because these are only used in the RISC-V runtime, not when
@@ -33,13 +34,18 @@ pub(crate) const SYS_CYCLE_COUNT: u32 = 0x401;
 pub(crate) const SYS_OVERWRITE_SP: u32 = 0x402;
 #[cfg(target_arch = "riscv32")]
 pub(crate) const SYS_ALLOC_ALIGNED: u32 = 0x403;
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_READ_AUXILIARY_INPUT: u32 = 0x404;
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_QUERY_ORACLE: u32 = 0x405;
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_READ_BYTES_PRIVATE_INPUT: u32 = 0x406;
 // Error codes.
 #[cfg(target_arch = "riscv32")]
 pub(crate) const EXIT_SUCCESS: u32 = 0;
 #[cfg(target_arch = "riscv32")]
 pub(crate) const EXIT_PANIC: u32 = 1;
 // Constants.
-#[cfg(target_arch = "riscv32")]
 pub(crate) const WORD_SIZE: usize = 4;
 
 /// Macro for making an ecall with variable number of parameters:
@@ -65,27 +65,17 @@ impl Compile for Compiler<CargoPackager> {
     }
 
     /// Compile and build the guest binary.
+    ///
+    /// Native builds are ordinary host binaries, so they use neither the zkVM target nor its linker script.
     fn build(&mut self) -> Result<PathBuf, BuildError> {
-        let linker_path = Compiler::set_linker()?;
-
-        let rust_flags = [
-            "-C",
-            "relocation-model=pic",
-            "-C",
-            &format!("link-arg=-T{}", linker_path.display()),
-            "-C",
-            "panic=abort",
-        ];
-
         let target = if self.native {
-            "native"
+            None
         } else {
-            "riscv32i-unknown-none-elf"
+            Some("riscv32i-unknown-none-elf")
         };
 
         let profile = if self.debug { "debug" } else { "release" };
 
-        let envs = vec![("CARGO_ENCODED_RUSTFLAGS", rust_flags.join("\x1f"))];
         let prog = self.binary.as_str();
 
         let mut dest = match std::env::var_os("OUT_DIR") {
@@ -101,7 +91,7 @@ impl Compile for Compiler<CargoPackager> {
         let cargo_bin = std::env::var("CARGO").unwrap_or_else(|_err| "cargo".into());
         let mut cmd = Command::new(cargo_bin);
 
-        cmd.envs(envs).args([
+        cmd.args([
             "build",
             "--package",
             self.package.as_str(),
@@ -109,12 +99,26 @@ impl Compile for Compiler<CargoPackager> {
             prog,
             "--target-dir",
             &dest,
-            "--target",
-            target,
             "--profile",
             profile,
         ]);
 
+        if let Some(target) = target {
+            let linker_path = Compiler::set_linker()?;
+
+            let rust_flags = [
+                "-C",
+                "relocation-model=pic",
+                "-C",
+                &format!("link-arg=-T{}", linker_path.display()),
+                "-C",
+                "panic=abort",
+            ];
+
+            cmd.env("CARGO_ENCODED_RUSTFLAGS", rust_flags.join("\x1f"))
+                .args(["--target", target]);
+        }
+
         let res = cmd.output()?;
 
         if !res.status.success() {
@@ -122,8 +126,11 @@ impl Compile for Compiler<CargoPackager> {
             return Err(BuildError::CompilerError);
         }
 
-        let elf_path =
-            PathBuf::from_str(&format!("{}/{}/{}/{}", dest, target, profile, prog)).unwrap();
+        let elf_path = match target {
+            Some(target) => format!("{}/{}/{}/{}", dest, target, profile, prog),
+            None => format!("{}/{}/{}", dest, profile, prog),
+        };
+        let elf_path = PathBuf::from_str(&elf_path).unwrap();
 
         Ok(elf_path)
     }
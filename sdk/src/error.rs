@@ -51,3 +51,47 @@ pub enum PathError {
     #[error("provided path has invalid encoding for use with filesystem")]
     EncodingError,
 }
+
+/// Errors that occur while encoding or decoding a proof file, see [`stwo::format`](crate::stwo::format).
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ProofFormatError {
+    /// The bytes do not start with the proof file magic.
+    #[error("not a Nexus proof file")]
+    BadMagic,
+
+    /// The proof file was written in a format version this release cannot read.
+    #[error("unsupported proof format version {found}, expected version {supported}")]
+    UnsupportedVersion { found: u16, supported: u16 },
+
+    /// The proof was generated by an unknown prover backend.
+    #[error("unknown prover backend {0}")]
+    UnknownBackend(u8),
+
+    /// The proof was generated with an unknown hash function.
+    #[error("unknown hash function {0}")]
+    UnknownHash(u8),
+
+    /// An extension name is not valid UTF-8.
+    #[error("invalid extension name")]
+    InvalidExtensionName,
+
+    /// The header disagrees with the proof it frames.
+    #[error("proof header does not match the proof")]
+    HeaderMismatch,
+
+    /// A count or length is too large for its field in the proof file.
+    #[error("{0} is too large for the proof file format")]
+    Oversized(&'static str),
+
+    /// The proof file ends early.
+    #[error("proof file is truncated")]
+    Truncated,
+
+    /// The proof file has bytes left over after the proof.
+    #[error("proof file has {0} trailing bytes")]
+    TrailingBytes(usize),
+
+    /// The proof itself could not be decoded.
+    #[error("invalid proof payload: {0}")]
+    InvalidPayload(postcard::Error),
+}
@@ -21,6 +21,9 @@ pub use traits::*;
 /// Configure the dynamic compilation of guest programs.
 pub mod compile;
 
+/// Run guest programs natively on the host, for development and debugging.
+pub mod native;
+
 /// Error types for SDK-specific interfaces.
 pub mod error;
 
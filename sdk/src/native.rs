@@ -0,0 +1,208 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::compile::Compile;
+use crate::error::{BuildError, IOError};
+use crate::traits::{Journal, Viewable};
+
+// The environment variables the native build of `nexus-rt` loads its I/O context from, see `nexus_rt::IoContext`.
+const PUBLIC_INPUT_ENV: &str = "NEXUS_PUBLIC_INPUT";
+const PRIVATE_INPUT_ENV: &str = "NEXUS_PRIVATE_INPUT";
+const AUXILIARY_INPUT_ENV: &str = "NEXUS_AUXILIARY_INPUT";
+const PUBLIC_OUTPUT_ENV: &str = "NEXUS_PUBLIC_OUTPUT";
+
+/// Errors that occur while running a guest program natively.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// An error occurred building the guest program dynamically.
+    #[error(transparent)]
+    BuildError(#[from] BuildError),
+
+    /// An error occurred reading or writing to the filesystem, or running the guest program.
+    #[error(transparent)]
+    HostIOError(#[from] io::Error),
+
+    /// An error occurred encoding the inputs or decoding the outputs of the guest program.
+    #[error(transparent)]
+    GuestIOError(#[from] IOError),
+
+    /// The guest program was terminated by a signal, rather than exiting.
+    #[error("guest program was terminated without an exit code")]
+    Terminated,
+}
+
+/// A guest program built for the host, see [`Compile::set_native_build`].
+///
+/// Running it natively is much faster than running it on the zkVM, which helps developing and debugging guest
+/// programs, but proves nothing. The inputs and outputs are encoded as for the zkVM, so that the same host code can
+/// run either.
+pub struct Native {
+    /// The path of the native binary.
+    pub binary: PathBuf,
+    /// The encoded hints on the auxiliary input tape, see [`Native::add_hint`].
+    pub hints: Vec<u8>,
+}
+
+/// A view of a native execution.
+///
+/// The exit code is that of the process, so a panicking guest exits with the code of a Rust panic rather than that of
+/// the zkVM runtime. The logs are everything the guest printed.
+#[derive(Debug, Clone)]
+pub struct View {
+    public_input: Vec<u8>,
+    exit_code: u32,
+    public_output: Option<Vec<u8>>,
+    logs: String,
+}
+
+impl Native {
+    /// Run the native binary at `binary`.
+    pub fn new<P: AsRef<Path> + ?Sized>(binary: &P) -> Self {
+        Self {
+            binary: binary.as_ref().to_path_buf(),
+            hints: Vec::new(),
+        }
+    }
+
+    /// Build the guest program for the host through dynamic compilation (see [`compile`](crate::compile)).
+    pub fn compile(compiler: &mut impl Compile) -> Result<Self, Error> {
+        compiler.set_native_build(true);
+        let binary = compiler.build()?;
+
+        Ok(Self::new(&binary))
+    }
+
+    /// Append a hint to the auxiliary input tape, which the guest reads with `nexus_rt::read_hint` in the same order.
+    pub fn add_hint<H: Serialize + ?Sized>(&mut self, hint: &H) -> Result<(), Error> {
+        let encoded = postcard::to_stdvec_cobs(hint).map_err(IOError::from)?;
+        self.hints.extend(encoded);
+        Ok(())
+    }
+
+    /// Run the guest program natively and return a view of the execution output.
+    pub fn run(&self) -> Result<View, Error> {
+        self.run_with_input::<(), ()>(&(), &())
+    }
+
+    /// Run the guest program natively on private input of type `S` and public input of type `T` and return a view of
+    /// the execution output.
+    pub fn run_with_input<S: Serialize + Sized, T: Serialize + Sized>(
+        &self,
+        private_input: &S,
+        public_input: &T,
+    ) -> Result<View, Error> {
+        let private_encoded = encode_input(private_input)?;
+        let public_encoded = encode_input(public_input)?;
+
+        let dir = std::env::temp_dir().join(format!("nexus-native-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir)?;
+        let view = self.run_in(&dir, &private_encoded, &public_encoded);
+        // The inputs and output are only needed for the duration of the run.
+        let _ = fs::remove_dir_all(&dir);
+
+        view
+    }
+
+    fn run_in(
+        &self,
+        dir: &Path,
+        private_encoded: &[u8],
+        public_encoded: &[u8],
+    ) -> Result<View, Error> {
+        let mut cmd = Command::new(&self.binary);
+
+        for (var, name, bytes) in [
+            (PUBLIC_INPUT_ENV, "public_input", public_encoded),
+            (PRIVATE_INPUT_ENV, "private_input", private_encoded),
+            (
+                AUXILIARY_INPUT_ENV,
+                "auxiliary_input",
+                self.hints.as_slice(),
+            ),
+        ] {
+            let path = dir.join(name);
+            fs::write(&path, bytes)?;
+            cmd.env(var, path);
+        }
+
+        let output_path = dir.join("public_output");
+        let output = cmd
+            .env(PUBLIC_OUTPUT_ENV, &output_path)
+            .stderr(Stdio::inherit())
+            .output()?;
+
+        let exit_code = output.status.code().ok_or(Error::Terminated)?;
+        let public_output = match fs::read(&output_path) {
+            Ok(bytes) => Some(bytes),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(View {
+            public_input: public_encoded.to_vec(),
+            exit_code: exit_code as u32,
+            public_output,
+            logs: String::from_utf8(output.stdout).map_err(IOError::from)?,
+        })
+    }
+}
+
+/// Encode an input as for the zkVM, zero-padded to a word boundary, leaving the tape empty for inputs without content, e.g., `()`.
+fn encode_input<T: Serialize>(input: &T) -> Result<Vec<u8>, IOError> {
+    if postcard::to_stdvec(input)?.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut encoded = postcard::to_stdvec_cobs(input)?;
+    let padded_len = (encoded.len() + 3) & !3;
+    encoded.resize(padded_len, 0x00); // cobs ignores 0x00 padding
+
+    Ok(encoded)
+}
+
+impl Viewable for View {
+    /// Deserialize the public input used for the execution.
+    fn public_input<T: Serialize + DeserializeOwned + Sized>(&self) -> Result<T, IOError> {
+        let mut bytes = self.public_input.clone();
+        Ok(postcard::from_bytes_cobs::<T>(&mut bytes)?)
+    }
+
+    /// Deserialize the exit code resulting from the execution.
+    fn exit_code(&self) -> Result<u32, IOError> {
+        Ok(self.exit_code)
+    }
+
+    /// Deserialize the public output resulting from the execution.
+    fn public_output<U: Serialize + DeserializeOwned + Sized>(&self) -> Result<U, IOError> {
+        let mut bytes = self
+            .public_output
+            .clone()
+            .ok_or(IOError::NotYetAvailableError)?;
+        Ok(postcard::from_bytes_cobs::<U>(&mut bytes)?)
+    }
+
+    /// Decode the records committed to the public output journal by the execution.
+    fn journal<U: DeserializeOwned>(&self) -> Result<Journal<U>, IOError> {
+        // A guest that committed nothing wrote no output, which is an empty journal.
+        Ok(Journal::new(self.public_output.clone().unwrap_or_default()))
+    }
+
+    /// Native executions are not bound to associated data, so this is always empty.
+    fn associated_data(&self) -> Result<Vec<u8>, IOError> {
+        Ok(Vec::new())
+    }
+
+    /// Recover everything the guest printed, as a single log.
+    fn logs(&self) -> Result<Vec<String>, IOError> {
+        if self.logs.is_empty() {
+            Ok(Vec::new())
+        } else {
+            Ok(vec![self.logs.clone()])
+        }
+    }
+}
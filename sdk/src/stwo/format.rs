@@ -0,0 +1,267 @@
+//! Portable proof file format.
+//!
+//! Proofs produced by [`Proof::to_bytes`](super::seq::Proof::to_bytes) are framed by a header that records everything
+//! a verifier must agree on before attempting to decode the proof itself, so that a proof from an incompatible release
+//! is rejected with a clear error rather than failing to decode or decoding to garbage.
+//!
+//! # Encoding
+//!
+//! All integers are little-endian:
+//!
+//! ```text
+//! magic:             [u8; 4] = b"NXPF"
+//! version:           u16
+//! backend:           u8  (see ProverBackend)
+//! hash:              u8  (0 = Blake2s, 1 = Poseidon2 over M31)
+//! log_blowup_factor: u32
+//! n_queries:         u32
+//! pow_bits:          u32
+//! extensions:        u32 count, followed by a u32 length and the UTF-8 name of each extension
+//! payload:           u32 length, followed by the postcard-encoded proof
+//! ```
+//!
+//! The payload encoding is tied to the prover backend, so [`FORMAT_VERSION`] must be bumped whenever it changes.
+use nexus_core::stwo::{HashKind, ProverConfig};
+
+use crate::error::ProofFormatError;
+
+/// Magic bytes at the start of every proof file.
+pub const MAGIC: [u8; 4] = *b"NXPF";
+
+/// Version of the proof file format written by this release, the only one it reads.
+pub const FORMAT_VERSION: u16 = 3;
+
+/// The proving backend a proof was generated with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ProverBackend {
+    Stwo = 1,
+}
+
+/// Header of a proof file, checked before the payload is decoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofHeader {
+    pub version: u16,
+    pub backend: ProverBackend,
+    pub hash: HashKind,
+    pub config: ProverConfig,
+    /// Names of the extensions, beyond the base machine, that the verifier must enable.
+    pub extensions: Vec<String>,
+}
+
+impl ProofHeader {
+    /// Encode the header, followed by the length-prefixed payload.
+    ///
+    /// Fails if a count or length does not fit in its `u32` field.
+    pub(crate) fn encode(&self, payload: &[u8]) -> Result<Vec<u8>, ProofFormatError> {
+        let mut bytes = Vec::with_capacity(payload.len() + 64);
+
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+        bytes.push(self.backend as u8);
+        bytes.push(match self.hash {
+            HashKind::Blake2s => 0,
+            HashKind::Poseidon2M31 => 1,
+        });
+        bytes.extend_from_slice(&self.config.log_blowup_factor.to_le_bytes());
+        bytes.extend_from_slice(&encode_len(self.config.n_queries, "n_queries")?);
+        bytes.extend_from_slice(&self.config.pow_bits.to_le_bytes());
+
+        bytes.extend_from_slice(&encode_len(self.extensions.len(), "extension count")?);
+        for name in &self.extensions {
+            bytes.extend_from_slice(&encode_len(name.len(), "extension name")?);
+            bytes.extend_from_slice(name.as_bytes());
+        }
+
+        bytes.extend_from_slice(&encode_len(payload.len(), "payload")?);
+        bytes.extend_from_slice(payload);
+
+        Ok(bytes)
+    }
+
+    /// Decode a header produced by [`Self::encode`] and return it alongside the payload.
+    ///
+    /// Only the framing is checked here, the caller is responsible for checking the header is supported.
+    pub(crate) fn decode(bytes: &[u8]) -> Result<(Self, &[u8]), ProofFormatError> {
+        let mut reader = Reader(bytes);
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(ProofFormatError::BadMagic);
+        }
+        let version = u16::from_le_bytes(reader.take_array()?);
+        if version != FORMAT_VERSION {
+            return Err(ProofFormatError::UnsupportedVersion {
+                found: version,
+                supported: FORMAT_VERSION,
+            });
+        }
+
+        let backend = match reader.take_array::<1>()?[0] {
+            1 => ProverBackend::Stwo,
+            other => return Err(ProofFormatError::UnknownBackend(other)),
+        };
+        let hash = match reader.take_array::<1>()?[0] {
+            0 => HashKind::Blake2s,
+            1 => HashKind::Poseidon2M31,
+            other => return Err(ProofFormatError::UnknownHash(other)),
+        };
+        let config = ProverConfig {
+            log_blowup_factor: reader.take_u32()?,
+            n_queries: reader.take_u32()? as usize,
+            pow_bits: reader.take_u32()?,
+        };
+
+        let n_extensions = reader.take_u32()?;
+        let extensions = (0..n_extensions)
+            .map(|_| {
+                String::from_utf8(reader.take_bytes()?.to_vec())
+                    .map_err(|_| ProofFormatError::InvalidExtensionName)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let payload = reader.take_bytes()?;
+        if !reader.0.is_empty() {
+            return Err(ProofFormatError::TrailingBytes(reader.0.len()));
+        }
+
+        Ok((
+            Self {
+                version,
+                backend,
+                hash,
+                config,
+                extensions,
+            },
+            payload,
+        ))
+    }
+}
+
+/// Encodes a count or length as a `u32` field.
+fn encode_len(len: usize, field: &'static str) -> Result<[u8; 4], ProofFormatError> {
+    u32::try_from(len)
+        .map(u32::to_le_bytes)
+        .map_err(|_| ProofFormatError::Oversized(field))
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ProofFormatError> {
+        if self.0.len() < len {
+            return Err(ProofFormatError::Truncated);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], ProofFormatError> {
+        Ok(self.take(N)?.try_into().expect("length was checked"))
+    }
+
+    fn take_u32(&mut self) -> Result<u32, ProofFormatError> {
+        Ok(u32::from_le_bytes(self.take_array()?))
+    }
+
+    fn take_bytes(&mut self) -> Result<&'a [u8], ProofFormatError> {
+        let len = self.take_u32()? as usize;
+        self.take(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> ProofHeader {
+        ProofHeader {
+            version: FORMAT_VERSION,
+            backend: ProverBackend::Stwo,
+            hash: HashKind::Blake2s,
+            config: ProverConfig {
+                log_blowup_factor: 1,
+                n_queries: 3,
+                pow_bits: 5,
+            },
+            extensions: vec!["ab".to_string()],
+        }
+    }
+
+    #[rustfmt::skip]
+    const FIXTURE: &[u8] = &[
+        b'N', b'X', b'P', b'F', // magic
+        3, 0,                   // version
+        1,                      // backend
+        0,                      // hash
+        1, 0, 0, 0,             // log_blowup_factor
+        3, 0, 0, 0,             // n_queries
+        5, 0, 0, 0,             // pow_bits
+        1, 0, 0, 0,             // extension count
+        2, 0, 0, 0, b'a', b'b', // extension name
+        3, 0, 0, 0, 7, 8, 9,    // payload
+    ];
+
+    #[test]
+    fn test_header_fixture() {
+        assert_eq!(header().encode(&[7, 8, 9]).unwrap(), FIXTURE);
+
+        let (decoded, payload) = ProofHeader::decode(FIXTURE).unwrap();
+        assert_eq!(decoded, header());
+        assert_eq!(payload, &[7, 8, 9]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_header_oversized() {
+        let mut header = header();
+        header.config.n_queries = u32::MAX as usize + 1;
+        assert_eq!(
+            header.encode(&[]),
+            Err(ProofFormatError::Oversized("n_queries"))
+        );
+    }
+
+    #[test]
+    fn test_header_errors() {
+        let mut bad_magic = FIXTURE.to_vec();
+        bad_magic[0] = b'X';
+        assert_eq!(
+            ProofHeader::decode(&bad_magic),
+            Err(ProofFormatError::BadMagic)
+        );
+
+        let mut future_version = FIXTURE.to_vec();
+        future_version[4] = 4;
+        assert_eq!(
+            ProofHeader::decode(&future_version),
+            Err(ProofFormatError::UnsupportedVersion {
+                found: 4,
+                supported: FORMAT_VERSION
+            })
+        );
+
+        let mut unknown_backend = FIXTURE.to_vec();
+        unknown_backend[6] = 0xFF;
+        assert_eq!(
+            ProofHeader::decode(&unknown_backend),
+            Err(ProofFormatError::UnknownBackend(0xFF))
+        );
+
+        let mut unknown_hash = FIXTURE.to_vec();
+        unknown_hash[7] = 0xFF;
+        assert_eq!(
+            ProofHeader::decode(&unknown_hash),
+            Err(ProofFormatError::UnknownHash(0xFF))
+        );
+
+        assert_eq!(
+            ProofHeader::decode(&FIXTURE[..FIXTURE.len() - 1]),
+            Err(ProofFormatError::Truncated)
+        );
+        assert_eq!(
+            ProofHeader::decode(&[FIXTURE, &[0]].concat()),
+            Err(ProofFormatError::TrailingBytes(1))
+        );
+    }
+}
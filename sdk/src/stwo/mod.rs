@@ -1,2 +1,5 @@
 /// Sequential (non-parallelized, non-distributed) proving for [Stwo](https://eprint.iacr.org/2024/278).
 pub mod seq;
+
+/// Versioned file format for Stwo proofs.
+pub mod format;
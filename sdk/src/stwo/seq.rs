@@ -5,7 +5,8 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::marker::PhantomData;
 use thiserror::Error;
 
-use crate::error::{BuildError, ConfigurationError, IOError, PathError};
+use super::format::{ProofHeader, ProverBackend, FORMAT_VERSION};
+use crate::error::{BuildError, ConfigurationError, IOError, PathError, ProofFormatError};
 
 /// Errors that occur while proving using Stwo.
 #[derive(Debug, Error)]
@@ -45,6 +46,70 @@ pub enum Error {
     /// An error occured configuring the prover.
     #[error(transparent)]
     ConfigurationError(#[from] ConfigurationError),
+
+    /// An error occured decoding or checking a public statement.
+    #[error(transparent)]
+    StatementError(#[from] nexus_core::nvm::StatementError),
+
+    /// An error occured encoding or decoding a proof file.
+    #[error(transparent)]
+    ProofFormatError(#[from] ProofFormatError),
+
+    /// The program uses instructions the prover does not support.
+    #[error(transparent)]
+    PreflightError(#[from] nexus_core::stwo::PreflightReport),
+
+    /// The segments of a segmented proof don't chain together into an execution of the program.
+    #[error(transparent)]
+    SegmentLinkError(#[from] nexus_core::nvm::segment::SegmentLinkError),
+
+    /// The memory left by the segments of a segmented proof differs from the public output of the view.
+    #[error("final memory at {address:#x} doesn't match the public output")]
+    OutputMismatch { address: u32 },
+
+    /// The proof was generated with other extensions than the verifier enabled.
+    #[error("proof requires extensions [{}], verifier enabled [{}]", .proof.join(", "), .verifier.join(", "))]
+    ExtensionMismatch {
+        proof: Vec<String>,
+        verifier: Vec<String>,
+    },
+}
+
+/// A precompile called by the guest, with the host executor running it and the extension proving its calls.
+#[derive(Debug, Clone)]
+pub struct Precompile {
+    name: String,
+    index: u16,
+    register: fn(&mut nexus_core::nvm::Precompiles, u16) -> Result<(), nexus_core::nvm::VMError>,
+    component: nexus_core::stwo::ExtensionComponent,
+}
+
+impl Precompile {
+    /// Creates the precompile at `index`, its position in the `use_precompiles!` invocation of the guest.
+    ///
+    /// The component must prove the calls of `P` at that index, e.g. a
+    /// [`CircuitExtension`](nexus_core::stwo::CircuitExtension) wrapping the circuit of the precompile.
+    pub fn new<P: nexus_precompiles::PrecompileInstruction>(
+        index: u16,
+        component: nexus_core::stwo::ExtensionComponent,
+    ) -> Self {
+        Self {
+            name: format!("{}@{index}", P::metadata().name),
+            index,
+            register: |precompiles, index| precompiles.register::<P>(index),
+            component,
+        }
+    }
+
+    /// The name recorded in proofs, made of the precompile name and its index.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The index of the precompile in the `use_precompiles!` invocation of the guest.
+    pub fn index(&self) -> u16 {
+        self.index
+    }
 }
 
 /// Prover for the Nexus zkVM, when using Stwo.
@@ -53,14 +118,305 @@ pub struct Stwo<C: Compute = Local> {
     pub elf: nexus_core::nvm::ElfFile,
     /// The associated data to prove with.
     pub ad: Vec<u8>,
+    /// The encoded hints on the auxiliary input tape, see [`Prover::add_hint`].
+    pub hints: Vec<u8>,
+    /// The host oracles the guest can query, see [`Prover::register_oracle`].
+    pub oracles: nexus_core::nvm::Oracles,
+    /// The precompiles the guest calls, see [`Stwo::add_precompile`].
+    pub precompiles: Vec<Precompile>,
+    /// The commitment scheme parameters to prove with.
+    pub config: nexus_core::stwo::ProverConfig,
+    /// The hash function to prove with.
+    pub hash: nexus_core::stwo::HashKind,
     _compute: PhantomData<C>,
 }
 
 /// The Stwo proof, alongside machine configuration information needed for verification.
+///
+/// The proof is bound to the image ID of the proven program, but does not carry the program itself. Verifying it
+/// always requires the program image, since the verifier rebuilds the program part of the preprocessed trace from it;
+/// the image ID only lets a verifier pin which image it accepts, see [`Proof::verify_statement`].
+///
+/// A proof of a guest calling precompiles records their names, and must be verified with the same precompiles, see
+/// [`Proof::verify_with_precompiles`].
 #[derive(Serialize, Deserialize)]
 pub struct Proof {
-    proof: nexus_core::stwo::Proof,
+    proof: MachineProof,
+    memory_layout: nexus_core::nvm::internals::LinearMemoryLayout,
+    extensions: Vec<String>,
+}
+
+/// A machine proof, generated with one of the hash functions supported by Stwo.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum MachineProof {
+    Blake2s(nexus_core::stwo::Proof<nexus_core::stwo::Blake2sMerkleHasher>),
+    Poseidon2M31(nexus_core::stwo::Proof<nexus_core::stwo::Poseidon2M31MerkleHasher>),
+}
+
+impl MachineProof {
+    /// The hash function the proof was generated with.
+    pub fn hash(&self) -> nexus_core::stwo::HashKind {
+        match self {
+            Self::Blake2s(proof) => proof.hash,
+            Self::Poseidon2M31(proof) => proof.hash,
+        }
+    }
+
+    /// The commitment scheme parameters the proof was generated with.
+    pub fn config(&self) -> &nexus_core::stwo::ProverConfig {
+        match self {
+            Self::Blake2s(proof) => &proof.config,
+            Self::Poseidon2M31(proof) => &proof.config,
+        }
+    }
+
+    /// The image ID of the proven program.
+    pub fn image_id(&self) -> &[u8; 32] {
+        match self {
+            Self::Blake2s(proof) => &proof.image_id,
+            Self::Poseidon2M31(proof) => &proof.image_id,
+        }
+    }
+
+    /// Verify the proof, rejecting it below [`ProverConfig::MIN_SECURITY_BITS`](nexus_core::stwo::ProverConfig::MIN_SECURITY_BITS).
+    fn verify(
+        &self,
+        extensions: &[nexus_core::stwo::ExtensionComponent],
+        view: &nexus_core::nvm::View,
+    ) -> Result<(), Error> {
+        let min_security_bits = nexus_core::stwo::ProverConfig::MIN_SECURITY_BITS;
+        match self {
+            Self::Blake2s(proof) => nexus_core::stwo::verify_with_extensions(
+                extensions,
+                proof.clone(),
+                min_security_bits,
+                view,
+            )?,
+            Self::Poseidon2M31(proof) => nexus_core::stwo::verify_with_extensions(
+                extensions,
+                proof.clone(),
+                min_security_bits,
+                view,
+            )?,
+        }
+        Ok(())
+    }
+
+    /// Verify the proof of a segment, see [`MachineProof::verify`].
+    fn verify_segment(
+        &self,
+        extensions: &[nexus_core::stwo::ExtensionComponent],
+        view: &nexus_core::nvm::View,
+        segment: &nexus_core::nvm::segment::Segment,
+    ) -> Result<(), Error> {
+        let min_security_bits = nexus_core::stwo::ProverConfig::MIN_SECURITY_BITS;
+        match self {
+            Self::Blake2s(proof) => nexus_core::stwo::verify_segment_with_extensions(
+                extensions,
+                proof.clone(),
+                min_security_bits,
+                view,
+                segment,
+            )?,
+            Self::Poseidon2M31(proof) => nexus_core::stwo::verify_segment_with_extensions(
+                extensions,
+                proof.clone(),
+                min_security_bits,
+                view,
+                segment,
+            )?,
+        }
+        Ok(())
+    }
+
+    fn size_estimate(&self) -> usize {
+        match self {
+            Self::Blake2s(proof) => proof.size_estimate(),
+            Self::Poseidon2M31(proof) => proof.size_estimate(),
+        }
+    }
+}
+
+/// Proofs of the consecutive segments of an execution, see [`Stwo::prove_segmented_with_encoded_input`].
+///
+/// Each segment is proven separately, so the prover only commits to the trace of one segment at a time. The proof of a
+/// segment exposes its boundary state, i.e. the registers and the accessed RAM before and after it, and the verifier
+/// checks that the segments chain together from the initial state of the execution, see
+/// [`link_segments`](nexus_core::nvm::segment::link_segments), and end with the public output.
+#[derive(Serialize, Deserialize)]
+pub struct SegmentedProof {
+    segments: Vec<(nexus_core::nvm::segment::Segment, MachineProof)>,
     memory_layout: nexus_core::nvm::internals::LinearMemoryLayout,
+    extensions: Vec<String>,
+}
+
+impl SegmentedProof {
+    /// The boundary states of the proven segments, in order.
+    pub fn segments(&self) -> impl Iterator<Item = &nexus_core::nvm::segment::Segment> {
+        self.segments.iter().map(|(segment, _)| segment)
+    }
+
+    /// The names of the precompiles the proof was generated with, see [`Precompile::name`].
+    pub fn extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    /// Verify the proof of a guest calling precompiles, which must be the ones it was proven with, in order.
+    pub fn verify_with_precompiles(
+        &self,
+        view: &nexus_core::nvm::View,
+        precompiles: &[Precompile],
+    ) -> Result<(), Error> {
+        use nexus_core::nvm::internals::InternalView;
+        use nexus_core::nvm::internals::PublicOutputEntry;
+
+        let verifier: Vec<String> = precompiles.iter().map(|p| p.name.clone()).collect();
+        if verifier != self.extensions {
+            return Err(Error::ExtensionMismatch {
+                proof: self.extensions.clone(),
+                verifier,
+            });
+        }
+
+        let components: Vec<_> = precompiles.iter().map(|p| p.component.clone()).collect();
+        for (segment, proof) in &self.segments {
+            proof.verify_segment(&components, view, segment)?;
+        }
+
+        let memory = nexus_core::nvm::segment::link_segments(
+            self.segments(),
+            view.get_program_memory().initial_pc,
+            view.get_initial_memory(),
+        )?;
+        for &PublicOutputEntry { address, value } in
+            view.get_exit_code().iter().chain(view.get_public_output())
+        {
+            if memory.get(&address).copied().unwrap_or_default() != value {
+                return Err(Error::OutputMismatch { address });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Proof {
+    /// The commitment scheme parameters the proof was generated and is verified with.
+    pub fn config(&self) -> &nexus_core::stwo::ProverConfig {
+        self.proof.config()
+    }
+
+    /// The conjectured security level of the proof in bits.
+    pub fn security_bits(&self) -> u32 {
+        self.proof.config().security_bits()
+    }
+
+    /// The image ID of the proven program, see [`ElfFile::image_id`](nexus_core::nvm::ElfFile::image_id).
+    pub fn image_id(&self) -> &[u8; 32] {
+        self.proof.image_id()
+    }
+
+    /// The names of the precompiles the proof was generated with, see [`Precompile::name`].
+    pub fn extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    /// Verify the proof against a public statement, which pins the program by its image ID.
+    ///
+    /// Unlike [`Verifiable::verify_expected`], this requires neither the ELF nor the typed public input and output,
+    /// but still the image of the program, e.g. as looked up by the image ID of the statement. The image is rejected
+    /// unless its digest is that image ID.
+    pub fn verify_statement(
+        &self,
+        statement: &nexus_core::nvm::PublicStatement,
+        image: &nexus_core::nvm::ProgramImage,
+    ) -> Result<(), Error> {
+        self.verify_statement_with_precompiles(statement, image, &[])
+    }
+
+    /// Verify the proof of a guest calling precompiles, which must be the ones it was proven with, in order.
+    pub fn verify_with_precompiles(
+        &self,
+        view: &nexus_core::nvm::View,
+        precompiles: &[Precompile],
+    ) -> Result<(), Error> {
+        let verifier: Vec<String> = precompiles.iter().map(|p| p.name.clone()).collect();
+        if verifier != self.extensions {
+            return Err(Error::ExtensionMismatch {
+                proof: self.extensions.clone(),
+                verifier,
+            });
+        }
+
+        let components: Vec<_> = precompiles.iter().map(|p| p.component.clone()).collect();
+        self.proof.verify(&components, view)
+    }
+
+    /// Verify the proof of a guest calling precompiles against a public statement, see [`Self::verify_statement`].
+    pub fn verify_statement_with_precompiles(
+        &self,
+        statement: &nexus_core::nvm::PublicStatement,
+        image: &nexus_core::nvm::ProgramImage,
+        precompiles: &[Precompile],
+    ) -> Result<(), Error> {
+        let view = nexus_core::nvm::View::from_statement(statement, image)?;
+        self.verify_with_precompiles(&view, precompiles)
+    }
+
+    /// Encode the proof in the versioned file format, see [`format`](super::format).
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let header = ProofHeader {
+            version: FORMAT_VERSION,
+            backend: ProverBackend::Stwo,
+            hash: self.proof.hash(),
+            config: *self.proof.config(),
+            extensions: self.extensions.clone(),
+        };
+        let payload = postcard::to_stdvec(self).map_err(IOError::from)?;
+
+        Ok(header.encode(&payload)?)
+    }
+
+    /// Decode a proof produced by [`Self::to_bytes`], checking that this release is able to verify it.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        let (header, payload) = ProofHeader::decode(bytes)?;
+
+        let proof: Self =
+            postcard::from_bytes(payload).map_err(ProofFormatError::InvalidPayload)?;
+        if proof.proof.hash() != header.hash
+            || *proof.proof.config() != header.config
+            || proof.extensions != header.extensions
+        {
+            return Err(ProofFormatError::HeaderMismatch);
+        }
+
+        Ok(proof)
+    }
+}
+
+impl Verifiable for SegmentedProof {
+    type View = nexus_core::nvm::View;
+    type Error = Error;
+
+    fn get_memory_layout(&self) -> &nexus_core::nvm::internals::LinearMemoryLayout {
+        &self.memory_layout
+    }
+
+    /// Verify the proofs of all segments, which fails for proofs of guests calling precompiles, see
+    /// [`SegmentedProof::verify_with_precompiles`].
+    fn verify(&self, view: &Self::View) -> Result<(), <Self as Verifiable>::Error> {
+        self.verify_with_precompiles(view, &[])
+    }
+
+    fn size_estimate(&self) -> usize {
+        self.segments
+            .iter()
+            .map(|(segment, proof)| {
+                proof.size_estimate()
+                    + std::mem::size_of_val(segment)
+                    + std::mem::size_of_val(segment.memory.as_slice())
+            })
+            .sum()
+    }
 }
 
 impl<C: Compute> ByGuestCompilation for Stwo<C>
@@ -76,16 +432,220 @@ where
     }
 }
 
+impl Stwo<Local> {
+    /// Set the commitment scheme parameters, trading proof size against prover time and security.
+    ///
+    /// The parameters are recorded in the proof, see [`Proof::security_bits`] for the resulting security level.
+    /// Defaults to [`ProverConfig::standard`](nexus_core::stwo::ProverConfig::standard), and proofs with less than
+    /// [`ProverConfig::MIN_SECURITY_BITS`](nexus_core::stwo::ProverConfig::MIN_SECURITY_BITS) are rejected when
+    /// verified.
+    pub fn set_prover_config(&mut self, config: nexus_core::stwo::ProverConfig) {
+        self.config = config;
+    }
+
+    /// Set the hash function used for commitments and the Fiat–Shamir channel, see [`nexus_core::stwo::HashKind`].
+    ///
+    /// Blake2s is the default, while Poseidon2 over M31 is cheaper to verify recursively.
+    pub fn set_hash(&mut self, hash: nexus_core::stwo::HashKind) {
+        self.hash = hash;
+    }
+
+    /// Register a precompile called by the guest, which is then run by the zkVM and proven by its extension.
+    ///
+    /// Fails if a precompile was already registered at the same index.
+    pub fn add_precompile(&mut self, precompile: Precompile) -> Result<(), Error> {
+        if self.precompiles.iter().any(|p| p.index == precompile.index) {
+            return Err(nexus_core::nvm::VMError::DuplicateInstruction(
+                nexus_core::nvm::Precompiles::opcode(precompile.index),
+            )
+            .into());
+        }
+        self.precompiles.push(precompile);
+        Ok(())
+    }
+
+    /// Check that every instruction of the program can be proven with the registered precompiles, see
+    /// [`nexus_core::stwo::preflight_with_extensions`].
+    pub fn preflight(&self) -> nexus_core::stwo::PreflightReport {
+        nexus_core::stwo::preflight_with_extensions(&self.extensions(), &self.elf)
+    }
+
+    fn extensions(&self) -> Vec<nexus_core::stwo::ExtensionComponent> {
+        self.precompiles
+            .iter()
+            .map(|p| p.component.clone())
+            .collect()
+    }
+
+    fn precompile_executors(&self) -> Result<nexus_core::nvm::Precompiles, Error> {
+        let mut executors = nexus_core::nvm::Precompiles::default();
+        for precompile in &self.precompiles {
+            (precompile.register)(&mut executors, precompile.index)?;
+        }
+        Ok(executors)
+    }
+
+    /// Run the zkVM on already-encoded private and public input tapes and return a view of the execution output.
+    ///
+    /// The tapes are expected to be encoded as the guest reads them, i.e., as produced by `postcard::to_stdvec_cobs`
+    /// and zero-padded to a word boundary.
+    pub fn run_with_encoded_input(
+        &self,
+        private_encoded: &[u8],
+        public_encoded: &[u8],
+    ) -> Result<nexus_core::nvm::View, Error> {
+        let (view, _) = nexus_core::nvm::k_trace_with_precompiles(
+            self.elf.clone(),
+            self.ad.as_slice(),
+            public_encoded,
+            private_encoded,
+            self.hints.as_slice(),
+            self.oracles.clone(),
+            &self.precompile_executors()?,
+            1,
+        )?; // todo: run without tracing?
+
+        Ok(view)
+    }
+
+    /// Run the zkVM on already-encoded private and public input tapes and return a verifiable proof, along with a
+    /// view of the execution output.
+    pub fn prove_with_encoded_input(
+        self,
+        private_encoded: &[u8],
+        public_encoded: &[u8],
+    ) -> Result<(nexus_core::nvm::View, Proof), Error> {
+        let report = self.preflight();
+        if !report.is_ok() {
+            return Err(report.into());
+        }
+
+        let extensions = self.extensions();
+        let executors = self.precompile_executors()?;
+        let (view, trace) = nexus_core::nvm::k_trace_with_precompiles(
+            self.elf.clone(),
+            self.ad.as_slice(),
+            public_encoded,
+            private_encoded,
+            self.hints.as_slice(),
+            self.oracles,
+            &executors,
+            1,
+        )?;
+        let proof = match self.hash {
+            nexus_core::stwo::HashKind::Blake2s => MachineProof::Blake2s(
+                nexus_core::stwo::prove_with_extensions(&extensions, &trace, &view, &self.config)?,
+            ),
+            nexus_core::stwo::HashKind::Poseidon2M31 => MachineProof::Poseidon2M31(
+                nexus_core::stwo::prove_with_extensions(&extensions, &trace, &view, &self.config)?,
+            ),
+        };
+        Ok((
+            view,
+            Proof {
+                proof,
+                memory_layout: trace.memory_layout,
+                extensions: self.precompiles.iter().map(|p| p.name.clone()).collect(),
+            },
+        ))
+    }
+
+    /// Like [`Self::prove_with_encoded_input`], but split the execution into segments of `steps_per_segment` steps
+    /// and prove each of them separately, see [`SegmentedProof`].
+    ///
+    /// The size of each proven trace is bounded by the segment rather than the whole execution, although the execution
+    /// is still traced as a whole before proving.
+    pub fn prove_segmented_with_encoded_input(
+        self,
+        private_encoded: &[u8],
+        public_encoded: &[u8],
+        steps_per_segment: usize,
+    ) -> Result<(nexus_core::nvm::View, SegmentedProof), Error> {
+        use nexus_core::nvm::internals::InternalView;
+
+        let report = self.preflight();
+        if !report.is_ok() {
+            return Err(report.into());
+        }
+
+        let extensions = self.extensions();
+        let executors = self.precompile_executors()?;
+        // One step per block, so that segments can be split at any step.
+        let (view, trace) = nexus_core::nvm::k_trace_with_precompiles(
+            self.elf.clone(),
+            self.ad.as_slice(),
+            public_encoded,
+            private_encoded,
+            self.hints.as_slice(),
+            self.oracles,
+            &executors,
+            1,
+        )?;
+        let mut segments = Vec::new();
+        for (segment, segment_trace) in nexus_core::nvm::segment::split_into_segments(
+            &trace,
+            view.get_initial_memory(),
+            steps_per_segment,
+        ) {
+            let proof = match self.hash {
+                nexus_core::stwo::HashKind::Blake2s => {
+                    MachineProof::Blake2s(nexus_core::stwo::prove_segment_with_extensions(
+                        &extensions,
+                        &segment_trace,
+                        &view,
+                        &segment,
+                        &self.config,
+                    )?)
+                }
+                nexus_core::stwo::HashKind::Poseidon2M31 => {
+                    MachineProof::Poseidon2M31(nexus_core::stwo::prove_segment_with_extensions(
+                        &extensions,
+                        &segment_trace,
+                        &view,
+                        &segment,
+                        &self.config,
+                    )?)
+                }
+            };
+            segments.push((segment, proof));
+        }
+        Ok((
+            view,
+            SegmentedProof {
+                segments,
+                memory_layout: trace.memory_layout,
+                extensions: self.precompiles.iter().map(|p| p.name.clone()).collect(),
+            },
+        ))
+    }
+}
+
 impl Prover for Stwo<Local> {
     type Proof = Proof;
     type View = nexus_core::nvm::View;
     type Error = Error;
 
     /// Construct a new proving instance.
+    ///
+    /// Fails if the program uses instructions that cannot be proven, see [`nexus_core::stwo::preflight`]. Precompile
+    /// calls are checked when proving instead, since their precompiles are registered afterwards.
     fn new(elf: &nexus_core::nvm::ElfFile) -> Result<Self, <Self as Prover>::Error> {
+        let mut report = nexus_core::stwo::preflight(elf);
+        report
+            .unsupported
+            .retain(|(_, reason)| !matches!(reason, nexus_core::stwo::Unsupported::Precompile(_)));
+        if !report.is_ok() {
+            return Err(report.into());
+        }
+
         Ok(Self {
             elf: elf.clone(),
             ad: Vec::new(),
+            hints: Vec::new(),
+            oracles: nexus_core::nvm::Oracles::default(),
+            precompiles: Vec::new(),
+            config: nexus_core::stwo::ProverConfig::standard(),
+            hash: nexus_core::stwo::HashKind::Blake2s,
             _compute: PhantomData,
         })
     }
@@ -96,6 +656,23 @@ impl Prover for Stwo<Local> {
         Ok(())
     }
 
+    /// Append a hint to the auxiliary input tape.
+    fn add_hint<H: Serialize + ?Sized>(&mut self, hint: &H) -> Result<(), <Self as Prover>::Error> {
+        // Hints are read frame by frame, so they are not padded like the input tapes.
+        let encoded = postcard::to_stdvec_cobs(hint).map_err(IOError::from)?;
+        self.hints.extend(encoded);
+        Ok(())
+    }
+
+    /// Register a host oracle on `channel`, replacing any previous one.
+    fn register_oracle<F>(&mut self, channel: u32, oracle: F) -> Result<(), <Self as Prover>::Error>
+    where
+        F: Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    {
+        self.oracles.register(channel, oracle);
+        Ok(())
+    }
+
     /// Run the zkVM on private input of type `S` and public input of type `T` and return a view of the execution output.
     fn run_with_input<S: Serialize + Sized, T: Serialize + DeserializeOwned + Sized>(
         &self,
@@ -124,15 +701,7 @@ impl Prover for Stwo<Local> {
             public_encoded.resize(public_padded_len, 0x00); // cobs ignores 0x00 padding
         }
 
-        let (view, _) = nexus_core::nvm::k_trace(
-            self.elf.clone(),
-            self.ad.as_slice(),
-            public_encoded.as_slice(),
-            private_encoded.as_slice(),
-            1,
-        )?; // todo: run without tracing?
-
-        Ok(view)
+        self.run_with_encoded_input(private_encoded.as_slice(), public_encoded.as_slice())
     }
 
     /// Run the zkVM on private input of type `S` and public input of type `T` and return a verifiable proof, along with a view of the execution output.
@@ -163,22 +732,7 @@ impl Prover for Stwo<Local> {
             public_encoded.resize(public_padded_len, 0x00); // cobs ignores 0x00 padding
         }
 
-        let (view, trace) = nexus_core::nvm::k_trace(
-            self.elf.clone(),
-            self.ad.as_slice(),
-            public_encoded.as_slice(),
-            private_encoded.as_slice(),
-            1,
-        )?;
-        let proof = nexus_core::stwo::prove(&trace, &view)?;
-
-        Ok((
-            view,
-            Proof {
-                proof,
-                memory_layout: trace.memory_layout,
-            },
-        ))
+        self.prove_with_encoded_input(private_encoded.as_slice(), public_encoded.as_slice())
     }
 }
 
@@ -190,12 +744,249 @@ impl Verifiable for Proof {
         &self.memory_layout
     }
 
+    /// Verify the proof, which fails for proofs of guests calling precompiles, see
+    /// [`Proof::verify_with_precompiles`].
     fn verify(&self, view: &Self::View) -> Result<(), <Self as Verifiable>::Error> {
-        nexus_core::stwo::verify(self.proof.clone(), view)?;
-        Ok(())
+        self.verify_with_precompiles(view, &[])
     }
 
     fn size_estimate(&self) -> usize {
         self.proof.size_estimate()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIB_ELF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../vm/test/fib_10.elf");
+
+    #[test]
+    fn test_verify_statement() {
+        let prover: Stwo<Local> = Stwo::new_from_file(FIB_ELF).unwrap();
+        let image = nexus_core::nvm::ProgramImage::from_elf(&prover.elf);
+        let mut wrong_elf = prover.elf.clone();
+        wrong_elf.instructions[0] ^= 1 << 20;
+        let (view, proof) = prover.prove_with_encoded_input(&[], &[]).unwrap();
+        let proof = Proof::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+
+        let statement = view.public_statement().unwrap();
+        assert_eq!(&statement.program_digest, proof.image_id());
+        let statement =
+            nexus_core::nvm::PublicStatement::from_bytes(&statement.to_bytes()).unwrap();
+        proof.verify_statement(&statement, &image).unwrap();
+
+        let mut wrong_exit_code = statement.clone();
+        wrong_exit_code.exit_code = 1;
+        assert!(proof.verify_statement(&wrong_exit_code, &image).is_err());
+
+        let mut wrong_program = statement.clone();
+        wrong_program.program_digest[0] ^= 1;
+        assert!(matches!(
+            proof.verify_statement(&wrong_program, &image),
+            Err(Error::StatementError(_))
+        ));
+
+        // An image that does not match the image ID of the statement is rejected before verification.
+        let wrong_image = nexus_core::nvm::ProgramImage::from_elf(&wrong_elf);
+        assert!(matches!(
+            proof.verify_statement(&statement, &wrong_image),
+            Err(Error::StatementError(_))
+        ));
+    }
+
+    #[test]
+    fn test_prove_verify_poseidon2() {
+        let mut prover: Stwo<Local> = Stwo::new_from_file(FIB_ELF).unwrap();
+        prover.set_hash(nexus_core::stwo::HashKind::Poseidon2M31);
+        let (view, proof) = prover.prove_with_encoded_input(&[], &[]).unwrap();
+
+        let proof = Proof::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+        assert!(matches!(proof.proof, MachineProof::Poseidon2M31(_)));
+        proof.verify(&view).unwrap();
+    }
+
+    #[test]
+    fn test_prove_verify_segments() {
+        let prover: Stwo<Local> = Stwo::new_from_file(FIB_ELF).unwrap();
+        let (view, proof) = prover
+            .prove_segmented_with_encoded_input(&[], &[], 4096)
+            .unwrap();
+        assert!(proof.segments().count() > 1);
+        assert!(proof.segments().all(|segment| segment.num_steps <= 4096));
+
+        let proof: SegmentedProof =
+            postcard::from_bytes(&postcard::to_stdvec(&proof).unwrap()).unwrap();
+        proof.verify(&view).unwrap();
+
+        // Segments must chain together.
+        let mut tampered: SegmentedProof =
+            postcard::from_bytes(&postcard::to_stdvec(&proof).unwrap()).unwrap();
+        tampered.segments.remove(1);
+        assert!(matches!(
+            tampered.verify(&view),
+            Err(Error::SegmentLinkError(_))
+        ));
+
+        // The proof of a segment is bound to its boundary state.
+        let mut tampered: SegmentedProof =
+            postcard::from_bytes(&postcard::to_stdvec(&proof).unwrap()).unwrap();
+        let (first, second) = tampered.segments.split_at_mut(1);
+        std::mem::swap(&mut first[0].1, &mut second[0].1);
+        assert!(matches!(
+            tampered.verify(&view),
+            Err(Error::VerificationError(_))
+        ));
+
+        let mut tampered: SegmentedProof =
+            postcard::from_bytes(&postcard::to_stdvec(&proof).unwrap()).unwrap();
+        let (last, _) = tampered.segments.last_mut().unwrap();
+        last.memory.last_mut().unwrap().final_value ^= 1;
+        assert!(matches!(
+            tampered.verify(&view),
+            Err(Error::VerificationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_reject_insecure_config() {
+        let mut prover: Stwo<Local> = Stwo::new_from_file(FIB_ELF).unwrap();
+        let config = nexus_core::stwo::ProverConfig::default();
+        assert!(config.security_bits() < nexus_core::stwo::ProverConfig::MIN_SECURITY_BITS);
+        prover.set_prover_config(config);
+        let (view, proof) = prover.prove_with_encoded_input(&[], &[]).unwrap();
+
+        assert!(matches!(
+            proof.verify(&view),
+            Err(Error::VerificationError(_))
+        ));
+    }
+
+    const fn addi(rd: u32, rs1: u32, imm: u32) -> u32 {
+        (imm << 20) | (rs1 << 15) | (rd << 7) | 0b0010011
+    }
+
+    /// A program running `body`, then exiting with code 0.
+    fn guest_elf(body: &[u32]) -> nexus_core::nvm::ElfFile {
+        let mut instructions = body.to_vec();
+        instructions.extend([
+            addi(10, 0, 0),
+            // Write the exit code to the output, whose address is stored at 0x84, then exit.
+            (0x84 << 20) | (0b010 << 12) | (5 << 7) | 0b0000011, // lw x5, 0x84(x0)
+            (10 << 20) | (5 << 15) | 0b1011011,                  // wou x10, 0(x5)
+            addi(17, 0, 0x201),
+            0b1110011, // ecall
+        ]);
+        nexus_core::nvm::ElfFile::new(
+            instructions,
+            0x1000,
+            0x1000,
+            Default::default(),
+            Default::default(),
+            Vec::new(),
+        )
+    }
+
+    /// A program calling the precompile at index 0 on an empty buffer.
+    fn precompile_elf() -> nexus_core::nvm::ElfFile {
+        guest_elf(&[
+            addi(10, 0, 0),
+            addi(11, 0, 0),
+            // Custom R-type instruction `x12 = precompile_0(x10, x11)`.
+            (11 << 20) | (10 << 15) | (12 << 7) | 0b0001011,
+        ])
+    }
+
+    /// Header of the proof file of [`guest_elf`] with an empty body, proven with the standard config.
+    #[rustfmt::skip]
+    const FILE_HEADER_FIXTURE: &[u8] = &[
+        b'N', b'X', b'P', b'F', // magic
+        3, 0,                   // version
+        1,                      // backend
+        0,                      // hash
+        1, 0, 0, 0,             // log_blowup_factor
+        80, 0, 0, 0,            // n_queries
+        16, 0, 0, 0,            // pow_bits
+        0, 0, 0, 0,             // extension count
+    ];
+
+    /// Tail of the payload of the same proof, after the Stwo proof and the claimed sums whose encoding is up to Stwo.
+    #[rustfmt::skip]
+    const PAYLOAD_TAIL_FIXTURE: &[u8] = &[
+        9, // log_size
+        21, 138, 82, 108, 139, 157, 4, 155, 31, 199, 219, 106, 8, 35, 197, 54, // image_id
+        196, 170, 39, 36, 179, 128, 113, 4, 30, 208, 103, 129, 195, 225, 65, 82,
+        1, 80, 16, // config
+        0,         // hash
+        180, 32, 184, 32, 184, 32, 188, 32, 188, 32, 188, 34, 188, 66, 188, 68, // memory_layout
+        0, // extensions
+    ];
+
+    #[test]
+    fn test_proof_file_fixture() {
+        let prover: Stwo<Local> = Stwo::new(&guest_elf(&[])).unwrap();
+        let (view, proof) = prover.prove_with_encoded_input(&[], &[]).unwrap();
+        let bytes = proof.to_bytes().unwrap();
+
+        let (header, rest) = bytes.split_at(FILE_HEADER_FIXTURE.len());
+        assert_eq!(header, FILE_HEADER_FIXTURE);
+        let (payload_len, payload) = rest.split_at(4);
+        assert_eq!(
+            u32::from_le_bytes(payload_len.try_into().unwrap()) as usize,
+            payload.len()
+        );
+        // The machine proof is tagged with its hash function, here Blake2s.
+        assert_eq!(payload[0], 0);
+        assert!(payload.ends_with(PAYLOAD_TAIL_FIXTURE));
+
+        let decoded = Proof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes().unwrap(), bytes);
+        decoded.verify(&view).unwrap();
+    }
+
+    fn dummy_hash() -> Precompile {
+        Precompile::new::<dummy_hash::DummyHash>(
+            0,
+            nexus_core::stwo::ExtensionComponent::custom(std::sync::Arc::new(
+                nexus_core::stwo::CircuitExtension::new(dummy_hash::DummyHashCircuit::new(0), 4),
+            )),
+        )
+    }
+
+    #[test]
+    fn test_prove_verify_precompile() {
+        let elf = precompile_elf();
+
+        // Precompiles are registered after construction, so they are only required when proving.
+        let prover: Stwo<Local> = Stwo::new(&elf).unwrap();
+        assert!(matches!(
+            prover.prove_with_encoded_input(&[], &[]),
+            Err(Error::PreflightError(_))
+        ));
+
+        let mut prover: Stwo<Local> = Stwo::new(&elf).unwrap();
+        prover.add_precompile(dummy_hash()).unwrap();
+        assert!(matches!(
+            prover.add_precompile(dummy_hash()),
+            Err(Error::VMError(_))
+        ));
+        assert!(prover.preflight().is_ok());
+        let (view, proof) = prover.prove_with_encoded_input(&[], &[]).unwrap();
+        assert_eq!(proof.extensions(), ["DummyHash@0"]);
+
+        let proof = Proof::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+        proof
+            .verify_with_precompiles(&view, &[dummy_hash()])
+            .unwrap();
+        assert!(matches!(
+            proof.verify(&view),
+            Err(Error::ExtensionMismatch { .. })
+        ));
+
+        let statement = view.public_statement().unwrap();
+        let image = view.program_image().unwrap();
+        proof
+            .verify_statement_with_precompiles(&statement, &image, &[dummy_hash()])
+            .unwrap();
+    }
+}
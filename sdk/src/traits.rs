@@ -1,7 +1,9 @@
 use crypto::digest::{Digest, OutputSizeUser};
 use crypto_common::generic_array::{ArrayLength, GenericArray};
 use nexus_common::constants::WORD_SIZE;
+use nexus_rt::journal::split_record;
 use serde::{de::DeserializeOwned, Serialize};
+use std::marker::PhantomData;
 use std::path::Path;
 
 use nexus_core::nvm::internals::*;
@@ -90,7 +92,7 @@ impl CheckedView for nexus_core::nvm::View {
         );
 
         let static_memory_size =
-            (&expected_elf.rom_image.len() + &expected_elf.ram_image.len()) * WORD_SIZE;
+            (expected_elf.rom_image.len() + expected_elf.ram_image.len()) * WORD_SIZE;
 
         Self::new(
             &Some(*memory_layout),
@@ -105,6 +107,50 @@ impl CheckedView for nexus_core::nvm::View {
     }
 }
 
+/// The records committed to the public output journal by `nexus_rt::commit`, decoded in order.
+///
+/// The journal is framed as described in [`nexus_rt::journal`], and ends at the first zero header word or the end of
+/// the bytes. A truncated record is reported as an error, after which the iterator ends.
+#[derive(Debug, Clone)]
+pub struct Journal<U> {
+    bytes: Vec<u8>,
+    offset: usize,
+    _record: PhantomData<U>,
+}
+
+impl<U> Journal<U> {
+    /// Decode the journal from the bytes of the public output segment.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            _record: PhantomData,
+        }
+    }
+}
+
+impl<U: DeserializeOwned> Iterator for Journal<U> {
+    type Item = Result<U, IOError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let split = split_record(self.bytes.get(self.offset..).unwrap_or_default());
+        let (record, rest) = match split {
+            Some(Ok(split)) => split,
+            Some(Err(e)) => {
+                self.offset = self.bytes.len();
+                return Some(Err(e.into()));
+            }
+            None => {
+                self.offset = self.bytes.len();
+                return None;
+            }
+        };
+
+        self.offset = self.bytes.len() - rest.len();
+        Some(postcard::from_bytes::<U>(record).map_err(IOError::from))
+    }
+}
+
 /// A view of an execution capturing the context needed for proof distribution and verification.
 pub trait Viewable {
     /// Deserialize the public input used for the execution.
@@ -152,6 +198,9 @@ pub trait Viewable {
         ))
     }
 
+    /// Decode the records committed to the public output journal by the execution, see [`Journal`].
+    fn journal<U: DeserializeOwned>(&self) -> Result<Journal<U>, IOError>;
+
     /// Deserialize the associated data bound into the execution.
     fn associated_data(&self) -> Result<Vec<u8>, IOError>;
 
@@ -195,6 +244,15 @@ impl Viewable for nexus_core::nvm::View {
         }
     }
 
+    /// Decode the records committed to the public output journal by the execution.
+    fn journal<U: DeserializeOwned>(&self) -> Result<Journal<U>, IOError> {
+        if let Some(bytes) = self.view_public_output() {
+            Ok(Journal::new(bytes))
+        } else {
+            Err(IOError::NotYetAvailableError)
+        }
+    }
+
     /// Deserialize the associated data bound into the execution.
     fn associated_data(&self) -> Result<Vec<u8>, IOError> {
         if let Some(bytes) = self.view_associated_data() {
@@ -227,7 +285,7 @@ pub trait ByGuestCompilation: Prover {
 pub trait Prover: Sized {
     type Proof: Verifiable;
     type View: CheckedView;
-    type Error: From<nexus_core::nvm::ElfError>;
+    type Error: From<nexus_core::nvm::ElfError> + From<ConfigurationError>;
 
     /// Construct a new proving instance.
     fn new(elf: &nexus_core::nvm::ElfFile) -> Result<Self, <Self as Prover>::Error>;
@@ -245,6 +303,31 @@ pub trait Prover: Sized {
     /// Set the associated data bytes to be bound into the proof.
     fn set_associated_data(&mut self, ad: &[u8]) -> Result<(), <Self as Prover>::Error>;
 
+    /// Append a hint to the auxiliary input tape, which the guest reads with `nexus_rt::read_hint` in the same order.
+    ///
+    /// Hints are neither bound into the proof nor constrained, so the guest must check any hint it relies on.
+    ///
+    /// Fails with [`ConfigurationError::NotApplicableOperation`] unless the prover supports hints.
+    fn add_hint<H: Serialize + ?Sized>(&mut self, hint: &H) -> Result<(), <Self as Prover>::Error> {
+        let _ = hint;
+        Err(ConfigurationError::NotApplicableOperation.into())
+    }
+
+    /// Register a host oracle, which the guest queries on `channel` with `nexus_rt::query_oracle`.
+    ///
+    /// The oracle is called with the `postcard`-encoded request of the guest and returns the `postcard`-encoded
+    /// response. It is only called by the first pass of an execution, whose responses the proving pass replays. Like
+    /// hints, responses are neither bound into the proof nor constrained.
+    ///
+    /// Fails with [`ConfigurationError::NotApplicableOperation`] unless the prover supports oracles.
+    fn register_oracle<F>(&mut self, channel: u32, oracle: F) -> Result<(), <Self as Prover>::Error>
+    where
+        F: Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    {
+        let _ = (channel, oracle);
+        Err(ConfigurationError::NotApplicableOperation.into())
+    }
+
     /// Run the zkVM and return a view of the execution output.
     fn run(&self) -> Result<Self::View, <Self as Prover>::Error> {
         Self::run_with_input::<(), ()>(self, &(), &())
@@ -468,11 +551,29 @@ pub trait Verifiable: Serialize + DeserializeOwned {
             output_encoded.resize(output_padded_len, 0x00); // cobs ignores 0x00 padding
         }
 
+        self.verify_expected_encoded(
+            input_encoded.as_slice(),
+            expected_exit_code,
+            output_encoded.as_slice(),
+            expected_elf,
+            expected_ad,
+        )
+    }
+
+    /// Verify the proof of an execution, with the public input and output provided as already-encoded tapes.
+    fn verify_expected_encoded(
+        &self,
+        expected_public_input_encoded: &[u8],
+        expected_exit_code: u32,
+        expected_public_output_encoded: &[u8],
+        expected_elf: &nexus_core::nvm::ElfFile,
+        expected_ad: &[u8],
+    ) -> Result<(), <Self as Verifiable>::Error> {
         let view = Self::View::new_from_expected(
             self.get_memory_layout(),
-            input_encoded.as_slice(),
+            expected_public_input_encoded,
             &expected_exit_code.to_le_bytes(),
-            output_encoded.as_slice(),
+            expected_public_output_encoded,
             expected_elf,
             expected_ad,
         );
@@ -526,3 +627,35 @@ pub trait Verifiable: Serialize + DeserializeOwned {
     /// Return a size estimate for the proof, in bytes.
     fn size_estimate(&self) -> usize;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal() {
+        let mut bytes = Vec::new();
+        for value in [1u32, 300] {
+            bytes.extend(nexus_rt::journal::encode_record(&value).unwrap());
+        }
+        assert_eq!(bytes, [2, 0, 0, 0, 1, 0, 0, 0, 3, 0, 0, 0, 172, 2, 0, 0]);
+
+        let records: Vec<u32> = Journal::new(bytes.clone())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records, vec![1, 300]);
+        assert!(Journal::<u32>::new(Vec::new()).next().is_none());
+
+        // The zero-initialized remainder of the public output segment is not read as records.
+        let mut padded = nexus_rt::journal::encode_record(&()).unwrap();
+        padded.extend([0; 8]);
+        let records: Vec<()> = Journal::new(padded).collect::<Result<_, _>>().unwrap();
+        assert_eq!(records, vec![()]);
+
+        // A truncated record ends the journal.
+        let mut journal = Journal::<u32>::new(bytes[..13].to_vec());
+        assert_eq!(journal.next().unwrap().unwrap(), 1);
+        assert!(journal.next().unwrap().is_err());
+        assert!(journal.next().is_none());
+    }
+}
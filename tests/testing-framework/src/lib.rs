@@ -434,6 +434,42 @@ mod test {
         verify(proof, &view).unwrap();
     }
 
+    #[test]
+    #[serial]
+    fn test_prove_private_input_stream() {
+        let elfs = compile_multi(
+            "examples/src/bin/private_input_stream",
+            &["-C opt-level=3"],
+            &HOME_PATH,
+        );
+
+        // Longer than a chunk of `PrivateInput::read_to_end`, so that the tape is read with several ecalls.
+        let private_input_bytes: Vec<u8> = (0..3000u32).map(|i| (i * 7) as u8).collect();
+        let sum = private_input_bytes
+            .iter()
+            .map(|&byte| byte as u32)
+            .sum::<u32>();
+        let mut expected_output_bytes =
+            to_allocvec_cobs(&mut (private_input_bytes.len() as u32, sum)).unwrap();
+
+        let padded_len = word_align!(expected_output_bytes.len());
+        expected_output_bytes.resize(padded_len, 0);
+
+        let (view, execution_trace) = k_trace(elfs[0].clone(), &[], &[], &private_input_bytes, K)
+            .expect("error generating trace");
+
+        let output = view.get_public_output();
+        let output_bytes = output.iter().map(|entry| entry.value).collect::<Vec<_>>();
+
+        assert_eq!(
+            output_bytes, expected_output_bytes,
+            "Output bytes don't match expected output"
+        );
+
+        let proof = prove(&execution_trace, &view).unwrap();
+        verify(proof, &view).unwrap();
+    }
+
     #[test]
     #[serial]
     fn test_emulate_fail() {
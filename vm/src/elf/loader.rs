@@ -22,6 +22,7 @@
 //!
 //! - `ElfFile::from_bytes`: Allows creation of `ElfFile` from raw bytes
 //! - `ElfFile::from_path`: Allows creation of `ElfFile` from a file path
+//! - `ElfFile::image_id`: Computes the program identifier that proofs of its execution bind to
 //!
 //! # Usage
 //!
@@ -44,6 +45,7 @@
 //! a Harvard architecture model. Ensure your ELF files are compatible with these specifications.
 
 use crate::elf::parser;
+use crate::emulator::ProgramImage;
 
 use elf::{endian::LittleEndian, ElfBytes};
 use std::collections::BTreeMap;
@@ -127,6 +129,16 @@ impl ElfFile {
             .collect();
         Self::from_bytes(data.as_slice())
     }
+
+    /// Compute the image ID: the digest of the program as loaded for proving, i.e., its instructions placed at the
+    /// program start and its static ROM and RAM.
+    ///
+    /// Proofs bind to this identifier, and it equals the program digest of the execution's
+    /// [`PublicStatement`](crate::emulator::PublicStatement). It pins the program but does not replace it: verifying
+    /// a proof still requires the [`ProgramImage`] with this digest.
+    pub fn image_id(&self) -> [u8; 32] {
+        ProgramImage::from_elf(self).digest()
+    }
 }
 
 #[cfg(test)]
@@ -190,4 +202,19 @@ mod tests {
             assert_eq!(elf.instructions.len(), *number_of_instruction);
         }
     }
+
+    #[test]
+    fn test_image_id() {
+        let elf = ElfFile::from_path("test/fib_10.elf").unwrap();
+        let (view, _) = crate::trace::k_trace(elf.clone(), &[], &[], &[], 1).unwrap();
+
+        assert_eq!(
+            view.public_statement().unwrap().program_digest,
+            elf.image_id()
+        );
+
+        let mut modified = elf.clone();
+        modified.instructions[0] ^= 1 << 7;
+        assert_ne!(modified.image_id(), elf.image_id());
+    }
 }
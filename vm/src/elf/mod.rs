@@ -1,7 +1,10 @@
 mod error;
 mod loader;
 mod parser;
+mod symbols;
 
 pub use error::ParserError as ElfError;
 pub use loader::ElfFile;
 pub use nexus_common::constants::WORD_SIZE;
+pub(crate) use parser::parse_precompile_metadata;
+pub use symbols::{Symbol, SymbolTable};
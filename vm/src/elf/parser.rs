@@ -322,8 +322,7 @@ impl Ord for PrecompileDescription<'_> {
 /// Parses the precompile metadata from the ELF file. This function finds all symbols that indicate
 /// pieces of precompile metadata and then ensures that there is a complete contiguous set of unique
 /// precompiles labeled 0 though N-1 via heapification.
-#[allow(dead_code)]
-fn parse_precompile_metadata(
+pub(crate) fn parse_precompile_metadata(
     elf: &ElfBytes<LittleEndian>,
     data: &[u8],
 ) -> Result<HashMap<u16, String>> {
@@ -0,0 +1,247 @@
+//! Function Symbols of RISC-V 32-bit Executables
+//!
+//! The loader only keeps what is needed to execute a program, see [`ElfFile`](super::ElfFile). Debugging and profiling
+//! tools additionally need to map addresses back to the functions that contain them, which this module provides
+//! through [`SymbolTable`].
+//!
+//! Names are kept as they appear in the ELF, i.e., Rust symbols are mangled. [`Symbol::demangled_name`] recovers the
+//! path of functions mangled with the legacy scheme, which is the default of `rustc`.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use nexus_vm::elf::{ElfFile, SymbolTable};
+//!
+//! let elf_file = ElfFile::from_path("test/fib_10.elf").unwrap();
+//! let symbols = SymbolTable::from_path("test/fib_10.elf").unwrap();
+//!
+//! let entry = symbols.lookup(elf_file.entry).unwrap();
+//! assert_eq!(symbols.find(&entry.name), Some(entry));
+//! ```
+use std::{collections::BTreeMap, fs, path::Path};
+
+use elf::{abi, endian::LittleEndian, ElfBytes};
+
+use super::error::{ParserError, Result};
+
+/// Section flag of executable sections.
+const SHF_EXECINSTR: u64 = 0x4;
+/// First reserved section index, e.g., of absolute and common symbols.
+const SHN_LORESERVE: u16 = 0xff00;
+
+/// A function defined in the program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub address: u32,
+    /// Size of the function in bytes, zero if unknown.
+    pub size: u32,
+}
+
+impl Symbol {
+    /// Returns the name with legacy Rust mangling undone, e.g., `integration::fib` for
+    /// `_ZN11integration3fib17hcc2459a5b42e564eE`. Other names are returned as they are.
+    pub fn demangled_name(&self) -> String {
+        demangle(&self.name).unwrap_or_else(|| self.name.clone())
+    }
+}
+
+/// Demangles a legacy Rust symbol: `_ZN`, length-prefixed path segments, and `E`, with the last segment being a hash.
+fn demangle(name: &str) -> Option<String> {
+    let mut rest = name.strip_prefix("_ZN")?;
+
+    let mut segments = Vec::new();
+    // Anything after the closing `E` is a suffix, such as the `.llvm.<number>` LLVM appends to local symbols.
+    while !rest.starts_with('E') {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let len: usize = rest[..digits].parse().ok()?;
+        let segment = rest.get(digits..digits + len)?;
+        rest = &rest[digits + len..];
+        segments.push(segment);
+    }
+
+    // The hash that disambiguates crate versions is not part of the path.
+    if segments
+        .last()
+        .is_some_and(|last| last.len() == 17 && last.starts_with('h'))
+    {
+        segments.pop();
+    }
+
+    let segments = segments
+        .into_iter()
+        .map(unescape_segment)
+        .collect::<Option<Vec<_>>>()?;
+    Some(segments.join("::"))
+}
+
+fn unescape_segment(segment: &str) -> Option<String> {
+    // Segments that would start with `$` are prefixed with an underscore.
+    let mut rest = segment
+        .strip_prefix("_$")
+        .map_or(segment, |_| &segment[1..]);
+    let mut unescaped = String::with_capacity(rest.len());
+
+    while !rest.is_empty() {
+        if let Some(tail) = rest.strip_prefix("..") {
+            unescaped.push_str("::");
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix('$') {
+            let end = tail.find('$')?;
+            unescaped.push(match &tail[..end] {
+                "SP" => '@',
+                "BP" => '*',
+                "RF" => '&',
+                "LT" => '<',
+                "GT" => '>',
+                "LP" => '(',
+                "RP" => ')',
+                "C" => ',',
+                escape => char::from_u32(u32::from_str_radix(escape.strip_prefix('u')?, 16).ok()?)?,
+            });
+            rest = &tail[end + 1..];
+        } else {
+            let c = rest.chars().next()?;
+            unescaped.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    Some(unescaped)
+}
+
+/// The function symbols of a program, ordered by address.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    symbols: BTreeMap<u32, Symbol>,
+}
+
+impl SymbolTable {
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(data)?;
+        let (section_headers, _) = elf.section_headers_with_strtab()?;
+        let section_headers = section_headers.ok_or(ParserError::NoSectionHeader)?;
+        let (symbol_table, string_table) = elf.symbol_table()?.ok_or(ParserError::NoSymbolTable)?;
+
+        let mut symbols = BTreeMap::new();
+        for symbol in symbol_table {
+            if symbol.st_shndx == abi::SHN_UNDEF || symbol.st_shndx >= SHN_LORESERVE {
+                continue;
+            }
+            // Entry points written in assembly, such as `_start`, are untyped global symbols in the text section.
+            let is_function = match symbol.st_symtype() {
+                abi::STT_FUNC => true,
+                abi::STT_NOTYPE if symbol.st_bind() == abi::STB_GLOBAL => {
+                    section_headers.get(symbol.st_shndx as usize)?.sh_flags & SHF_EXECINSTR != 0
+                }
+                _ => false,
+            };
+            if !is_function {
+                continue;
+            }
+
+            let name = string_table.get(symbol.st_name as usize)?;
+            if name.is_empty() {
+                continue;
+            }
+
+            let address = u32::try_from(symbol.st_value)
+                .map_err(|_| ParserError::InvalidVirtualAddress(symbol.st_value))?;
+            // Aliases share an address, the first one is kept.
+            symbols.entry(address).or_insert_with(|| Symbol {
+                name: name.to_string(),
+                address,
+                size: symbol.st_size as u32,
+            });
+        }
+
+        Ok(Self { symbols })
+    }
+
+    pub fn from_path<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Returns the function containing the address, if any. Functions of unknown size are assumed to extend up to
+    /// the next function.
+    pub fn lookup(&self, address: u32) -> Option<&Symbol> {
+        self.symbols
+            .range(..=address)
+            .next_back()
+            .map(|(_, symbol)| symbol)
+            .filter(|symbol| symbol.size == 0 || address - symbol.address < symbol.size)
+    }
+
+    /// Returns the function with the given name, if any.
+    pub fn find(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.values().find(|symbol| symbol.name == name)
+    }
+
+    /// Iterates over all functions, ordered by address.
+    pub fn iter(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
+
+impl FromIterator<Symbol> for SymbolTable {
+    fn from_iter<I: IntoIterator<Item = Symbol>>(iter: I) -> Self {
+        let mut symbols = BTreeMap::new();
+        for symbol in iter {
+            symbols.entry(symbol.address).or_insert(symbol);
+        }
+        Self { symbols }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elf::ElfFile;
+
+    #[test]
+    fn test_lookup_entry_point() {
+        let elf = ElfFile::from_path("test/fib_10.elf").unwrap();
+        let symbols = SymbolTable::from_path("test/fib_10.elf").unwrap();
+        assert!(!symbols.is_empty());
+
+        let entry = symbols.lookup(elf.entry).unwrap();
+        assert_eq!(entry.name, "_start");
+        assert_eq!(symbols.find(&entry.name), Some(entry));
+        // `_start` has no size, so it extends up to the next function.
+        assert_eq!(symbols.lookup(elf.entry + 4), Some(entry));
+
+        let main = symbols.find("main").unwrap();
+        assert_eq!(symbols.lookup(main.address + main.size - 4), Some(main));
+
+        // Addresses below the text segment belong to no function.
+        assert_eq!(symbols.lookup(0), None);
+    }
+
+    #[test]
+    fn test_demangle() {
+        assert_eq!(
+            demangle("_ZN11integration3fib17hcc2459a5b42e564eE").as_deref(),
+            Some("integration::fib")
+        );
+        assert_eq!(
+            demangle("_ZN42_$LT$$RF$T$u20$as$u20$core..fmt..Debug$GT$3fmt17hcda15ce0feda9b1aE")
+                .as_deref(),
+            Some("<&T as core::fmt::Debug>::fmt")
+        );
+        assert_eq!(
+            demangle("_ZN4core3fmt5Write10write_char17h225570b1c4528b68E.llvm.751708466022850414")
+                .as_deref(),
+            Some("core::fmt::Write::write_char")
+        );
+        assert_eq!(demangle("main"), None);
+        assert_eq!(demangle("_ZN3foo"), None);
+    }
+}
@@ -169,6 +169,12 @@ pub struct Executor {
     // The private input tape as a FIFO queue.
     pub private_input_tape: VecDeque<u8>,
 
+    // The auxiliary input tape of unconstrained hints as a FIFO queue.
+    pub auxiliary_input_tape: VecDeque<u8>,
+
+    // The host oracles the guest can query, whose responses are appended to the auxiliary input tape.
+    pub oracles: Oracles,
+
     // The global clock counter
     pub global_clock: usize,
 
@@ -188,6 +194,9 @@ pub struct Executor {
     // The cycles tracker: (name, (cycle_count, occurrence))
     pub cycle_tracker: HashMap<String, (usize, usize)>,
 
+    // The call-stack profiler, if enabled
+    pub profiler: Option<Profiler>,
+
     // Debug logs written by the guest program
     pub logs: Option<Vec<Vec<u8>>>,
 
@@ -206,6 +215,11 @@ impl Executor {
         self.private_input_tape = VecDeque::<u8>::from(private_input.to_vec());
     }
 
+    /// Set or overwrite auxiliary input into the auxiliary input tape
+    fn set_auxiliary_input(&mut self, auxiliary_input: &[u8]) {
+        self.auxiliary_input_tape = VecDeque::<u8>::from(auxiliary_input.to_vec());
+    }
+
     /// Set whether to capture logs or print out.
     pub(crate) fn capture_logs(&mut self, capture: bool) {
         if capture && self.logs.is_none() {
@@ -330,6 +344,11 @@ pub trait Emulator {
         self.get_executor_mut().set_private_input(private_input)
     }
 
+    /// Set or overwrite auxiliary input into the auxiliary input tape
+    fn set_auxiliary_input(&mut self, auxiliary_input: &[u8]) {
+        self.get_executor_mut().set_auxiliary_input(auxiliary_input)
+    }
+
     /// Update and return previous timestamps, but it currently works word-wise, so not used.
     #[allow(dead_code)]
     fn manage_timestamps(&mut self, size: &MemAccessSize, address: &u32) -> usize {
@@ -392,7 +411,7 @@ pub struct HarvardEmulator {
     pub executor: Executor,
 
     // The instruction memory image
-    instruction_memory: FixedMemory<RO>,
+    pub(crate) instruction_memory: FixedMemory<RO>,
 
     // The input memory image
     input_memory: FixedMemory<RO>,
@@ -569,6 +588,7 @@ impl Emulator for HarvardEmulator {
         bare_instruction: &Instruction,
         force_provable_transcript: bool,
     ) -> Result<(InstructionResult, MemoryRecords)> {
+        let pc = self.executor.cpu.pc.value;
         let ((res, (load_ops, store_ops)), accessed_io_memory) = match (
             self.executor
                 .instruction_executor
@@ -640,6 +660,10 @@ impl Emulator for HarvardEmulator {
             self.executor.cpu.pc.step();
         }
 
+        if let Some(profiler) = &mut self.executor.profiler {
+            profiler.record(pc, bare_instruction, self.executor.cpu.pc.value);
+        }
+
         // The global clock will update according to the currency of ZK (constraint?)
         // instead of pure RISC-V cycle count.
         // Right now we don't have information how an instruction cost in ZK, so we just
@@ -800,6 +824,9 @@ pub struct LinearEmulator {
     /// Initial snapshot of the static ram image
     initial_static_ram_image: BTreeMap<u32, u8>,
 
+    /// The associated data, without the padding of its memory segment to a word boundary
+    associated_data: Vec<u8>,
+
     // The memory layout
     pub memory_layout: LinearMemoryLayout,
 
@@ -854,13 +881,19 @@ impl LinearEmulator {
             )
             .unwrap();
 
-        Ok(Self::from_elf(
+        let mut emulator = Self::from_elf(
             memory_layout,
             ad,
             &elf,
             public_input.as_slice(),
             private_input,
-        ))
+        );
+        // Custom instructions added to the first pass must also run in the second.
+        emulator
+            .executor
+            .instruction_executor
+            .inherit_precompiles(&emulator_harvard.executor.instruction_executor);
+        Ok(emulator)
     }
 
     /// Creates a Linear Emulator from an ELF file.
@@ -1015,6 +1048,7 @@ impl LinearEmulator {
             public_io_location_index,
             static_rom_image_index: elf_rom_image_index,
             initial_static_ram_image,
+            associated_data: ad.to_vec(),
             memory_layout,
             memory,
             ..Default::default()
@@ -1036,6 +1070,7 @@ impl Emulator for LinearEmulator {
         bare_instruction: &Instruction,
         _force_second_pass: bool, // Linear Emulator always does second pass
     ) -> Result<(InstructionResult, MemoryRecords)> {
+        let pc = self.executor.cpu.pc.value;
         let (res, (load_ops, store_ops)) = match (
             self.executor
                 .instruction_executor
@@ -1082,6 +1117,10 @@ impl Emulator for LinearEmulator {
             self.executor.cpu.pc.step();
         }
 
+        if let Some(profiler) = &mut self.executor.profiler {
+            profiler.record(pc, bare_instruction, self.executor.cpu.pc.value);
+        }
+
         // The global clock will update according to the currency of ZK (constraint?)
         // instead of pure RISC-V cycle count.
         // Right now we don't have information how an instruction cost in ZK, so we just
@@ -1225,15 +1264,6 @@ impl Emulator for LinearEmulator {
             Vec::new()
         };
 
-        let associated_data: Vec<u8> = self
-            .memory
-            .segment_bytes(
-                (Modes::NA as usize, 0),
-                self.memory_layout.ad_start(),
-                Some(self.memory_layout.ad_end()),
-            )
-            .unwrap_or_default();
-
         let tracked_ram_size = self
             .memory_layout
             .tracked_ram_size(self.initial_static_ram_image.len() + rom_initialization.len());
@@ -1270,7 +1300,7 @@ impl Emulator for LinearEmulator {
             tracked_ram_size,
             exit_code,
             output_memory,
-            associated_data,
+            associated_data: self.associated_data.clone(),
         }
     }
 }
@@ -1356,6 +1386,20 @@ mod tests {
         assert_eq!(emulator.executor.private_input_tape, private_input_vec);
     }
 
+    #[test]
+    fn test_harvard_set_auxiliary_input() {
+        let auxiliary_input: [u8; 3] = [7, 8, 9];
+
+        let mut emulator = HarvardEmulator::default();
+        emulator.set_auxiliary_input(&auxiliary_input);
+
+        assert_eq!(
+            emulator.executor.auxiliary_input_tape,
+            VecDeque::from(vec![7, 8, 9])
+        );
+        assert!(emulator.executor.private_input_tape.is_empty());
+    }
+
     #[test]
     fn test_harvard_from_basic_block() {
         let basic_blocks = setup_basic_block_ir();
@@ -1374,6 +1418,21 @@ mod tests {
         assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
     }
 
+    #[test]
+    #[serial]
+    fn test_linear_unaligned_associated_data() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let mut harvard = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+        assert_eq!(harvard.execute(false), Err(VMError::VMExited(0)));
+
+        let ad = [1, 2, 3];
+        let mut emulator = LinearEmulator::from_harvard(&harvard, elf_file, &ad, &[]).unwrap();
+        assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+
+        let view = emulator.finalize();
+        assert_eq!(view.view_associated_data(), Some(ad.to_vec()));
+    }
+
     #[test]
     fn test_linear_fibonacci() {
         let basic_blocks = setup_basic_block_ir();
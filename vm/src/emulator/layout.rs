@@ -105,7 +105,7 @@ use nexus_common::word_align;
 use serde::{Deserialize, Serialize};
 
 // nb: all measurements are in terms of virtual memory
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LinearMemoryLayout {
     // start of the public input
     public_input: u32,
@@ -134,6 +134,9 @@ impl Default for LinearMemoryLayout {
 
 #[allow(dead_code)]
 impl LinearMemoryLayout {
+    /// Number of words in the encoding produced by [`Self::to_words`].
+    pub const ENCODED_WORDS: usize = 8;
+
     fn validate(&self) -> Result<()> {
         // gap should be at least MEMORY_GAP (see runtime) and no more than MEMORY_GAP + WORD_SIZE
         if self.gap_end() - self.gap_start() < MEMORY_GAP {
@@ -190,6 +193,39 @@ impl LinearMemoryLayout {
         }
     }
 
+    /// Encode the layout as a fixed sequence of segment boundaries, independent of the serde format.
+    pub fn to_words(&self) -> [u32; Self::ENCODED_WORDS] {
+        [
+            self.public_input,
+            self.ad,
+            self.exit_code,
+            self.public_output,
+            self.heap,
+            self.gap,
+            self.stack_bottom,
+            self.stack_top,
+        ]
+    }
+
+    /// Decode a layout produced by [`Self::to_words`], validating it.
+    pub fn from_words(words: [u32; Self::ENCODED_WORDS]) -> Result<Self> {
+        let [public_input, ad, exit_code, public_output, heap, gap, stack_bottom, stack_top] =
+            words;
+        let ml = Self {
+            public_input,
+            ad,
+            exit_code,
+            public_output,
+            heap,
+            gap,
+            stack_bottom,
+            stack_top,
+        };
+        ml.validate()?;
+
+        Ok(ml)
+    }
+
     pub fn new(
         max_heap_size: u32,
         max_stack_size: u32,
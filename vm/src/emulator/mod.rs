@@ -9,6 +9,9 @@
 //! - `HarvardEmulator`: An implementation of the emulator using Harvard architecture.
 //! - `LinearEmulator`: An implementation of the emulator using Linear architecture.
 //! - `LinearMemoryLayout`: Defines the memory layout for the linear emulator.
+//! - `PublicStatement`: A compact, serializable statement of a proven execution.
+//! - `Profiler`: Attributes the cycles of an execution to call stacks.
+//! - `Oracles`: Host functions answering queries of the guest during execution.
 //!
 //! ## Memory Management
 //!
@@ -55,10 +58,18 @@
 mod executor;
 mod layout;
 mod memory_stats;
+mod oracle;
+mod profiler;
 mod registry;
 
 pub use executor::{Emulator, Executor, HarvardEmulator, LinearEmulator};
 pub use layout::LinearMemoryLayout;
+pub use oracle::{OracleFn, OracleQuery, Oracles};
+pub use profiler::Profiler;
+pub use registry::Precompiles;
+
+mod statement;
+pub use statement::{ProgramImage, PublicStatement};
 
 mod utils;
 pub use utils::*;
@@ -0,0 +1,158 @@
+//! Host Oracles
+//!
+//! Inputs must be known before execution starts, so a guest cannot be given data that depends on its own intermediate
+//! results through them. Instead, a guest can query an oracle: a host function registered under a channel ID, which
+//! is called with the request bytes of the guest during execution.
+//!
+//! The response is appended to the auxiliary input tape, from which the guest then reads it. Like hints, responses are
+//! not constrained by the proof, so the guest must check them.
+//!
+//! Every query answered in the first pass is recorded in the [`Oracles::transcript`], and the second pass replays the
+//! recorded responses instead of calling the host again, see [`Oracles::replay`]. This keeps both passes consistent
+//! even if the host functions are not deterministic.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use nexus_vm::emulator::Oracles;
+//!
+//! let mut oracles = Oracles::default();
+//! oracles.register(1, |request: &[u8]| request.iter().rev().copied().collect());
+//!
+//! assert_eq!(oracles.query(1, &[1, 2, 3]).unwrap(), vec![3, 2, 1]);
+//!
+//! let mut replay = Oracles::replay(oracles.transcript());
+//! assert_eq!(replay.query(1, &[]).unwrap(), vec![3, 2, 1]);
+//! ```
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::Arc,
+};
+
+use crate::error::{Result, VMError};
+
+/// A host function answering the queries of a guest.
+pub type OracleFn = Arc<dyn Fn(&[u8]) -> Vec<u8> + Send + Sync>;
+
+/// A query answered by an oracle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleQuery {
+    pub channel: u32,
+    pub request: Vec<u8>,
+    pub response: Vec<u8>,
+}
+
+/// Oracles registered by channel ID, together with the queries they answered.
+#[derive(Default, Clone)]
+pub struct Oracles {
+    handlers: HashMap<u32, OracleFn>,
+    transcript: Vec<OracleQuery>,
+    /// Recorded queries to answer instead of calling handlers, in order.
+    replay: Option<VecDeque<OracleQuery>>,
+}
+
+impl fmt::Debug for Oracles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut channels: Vec<&u32> = self.handlers.keys().collect();
+        channels.sort();
+
+        f.debug_struct("Oracles")
+            .field("channels", &channels)
+            .field("transcript", &self.transcript)
+            .field("replay", &self.replay)
+            .finish()
+    }
+}
+
+impl Oracles {
+    /// Oracles that answer with the responses of the recorded queries, in order.
+    pub fn replay(transcript: &[OracleQuery]) -> Self {
+        Self {
+            replay: Some(transcript.iter().cloned().collect()),
+            ..Default::default()
+        }
+    }
+
+    /// Registers the host function answering queries on `channel`, replacing any previous one.
+    pub fn register<F>(&mut self, channel: u32, oracle: F)
+    where
+        F: Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    {
+        self.handlers.insert(channel, Arc::new(oracle));
+    }
+
+    /// Whether queries are answered from a recorded transcript rather than by the registered oracles.
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// The queries answered so far, in order.
+    pub fn transcript(&self) -> &[OracleQuery] {
+        &self.transcript
+    }
+
+    /// Answers a query on `channel`, and records it.
+    ///
+    /// When replaying, the request is not used, and the channel must match the next recorded query.
+    pub fn query(&mut self, channel: u32, request: &[u8]) -> Result<Vec<u8>> {
+        let query = match &mut self.replay {
+            Some(replay) => replay
+                .pop_front()
+                .filter(|query| query.channel == channel)
+                .ok_or(VMError::OracleReplayMismatch(channel))?,
+            None => {
+                let oracle = self
+                    .handlers
+                    .get(&channel)
+                    .ok_or(VMError::UnknownOracleChannel(channel))?;
+                OracleQuery {
+                    channel,
+                    request: request.to_vec(),
+                    response: oracle(request),
+                }
+            }
+        };
+
+        let response = query.response.clone();
+        self.transcript.push(query);
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_and_replay() {
+        let mut oracles = Oracles::default();
+        oracles.register(7, |request: &[u8]| vec![request.len() as u8]);
+
+        assert_eq!(oracles.query(7, b"abc"), Ok(vec![3]));
+        assert_eq!(oracles.query(7, b""), Ok(vec![0]));
+        assert_eq!(oracles.query(8, b""), Err(VMError::UnknownOracleChannel(8)));
+        assert_eq!(
+            oracles.transcript(),
+            &[
+                OracleQuery {
+                    channel: 7,
+                    request: b"abc".to_vec(),
+                    response: vec![3],
+                },
+                OracleQuery {
+                    channel: 7,
+                    request: Vec::new(),
+                    response: vec![0],
+                },
+            ]
+        );
+
+        // The replay answers without the handlers, regardless of the request.
+        let mut replay = Oracles::replay(oracles.transcript());
+        assert_eq!(replay.query(7, b""), Ok(vec![3]));
+        assert_eq!(replay.query(7, b"ignored"), Ok(vec![0]));
+        assert_eq!(replay.transcript(), oracles.transcript());
+        assert_eq!(replay.query(7, b""), Err(VMError::OracleReplayMismatch(7)));
+    }
+}
@@ -0,0 +1,315 @@
+//! Call-Stack Profiling of Guest Programs
+//!
+//! The cycle tracker (`nexus_rt::profile`) only times functions that are annotated in the source. The [`Profiler`]
+//! instead attributes every executed instruction to the call stack it ran under, so that the cost of a program can be
+//! broken down without modifying it.
+//!
+//! Calls and returns are recognized from the link register conventions of the RISC-V calling convention, following
+//! the return-address stack hints of the specification, with `ra` and `t0` as link registers:
+//!
+//! | `rd`     | `rs1`    | `jal`/`jalr` is treated as                                   |
+//! |----------|----------|--------------------------------------------------------------|
+//! | link     | any      | call                                                         |
+//! | not link | link     | return                                                       |
+//! | link     | link     | return followed by a call, unless `rd` and `rs1` are equal   |
+//! | not link | not link | jump, or a tail call if it targets the start of a function   |
+//!
+//! Frames are identified by the address that was called, and only resolved to names when the profile is written, see
+//! [`Profiler::write_collapsed`].
+//!
+//! # Usage
+//!
+//! ```rust
+//! use nexus_vm::elf::{ElfFile, SymbolTable};
+//! use nexus_vm::emulator::{Emulator, HarvardEmulator, Profiler};
+//!
+//! let elf_file = ElfFile::from_path("test/fib_10.elf").unwrap();
+//! let symbols = SymbolTable::from_path("test/fib_10.elf").unwrap();
+//!
+//! let mut emulator = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+//! emulator.get_executor_mut().profiler = Some(Profiler::new(symbols));
+//! emulator.execute(false).unwrap_err();
+//!
+//! // One line per call stack, e.g., `_start;_start_rust;main 1024`.
+//! let mut collapsed = Vec::new();
+//! let profiler = emulator.get_executor().profiler.as_ref().unwrap();
+//! profiler.write_collapsed(&mut collapsed).unwrap();
+//! ```
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use crate::{
+    elf::SymbolTable,
+    riscv::{BuiltinOpcode, Instruction, Register},
+};
+
+/// A frame of the call tree.
+#[derive(Debug)]
+struct Node {
+    /// The address that was called.
+    address: u32,
+    parent: Option<usize>,
+    children: HashMap<u32, usize>,
+    /// Cycles spent in this frame, excluding its callees.
+    cycles: u64,
+}
+
+/// Call tree of an execution, with the cycles spent in each frame.
+///
+/// The profiler is driven by either emulator, both of which run the program at the addresses of the ELF.
+#[derive(Debug)]
+pub struct Profiler {
+    symbols: SymbolTable,
+    nodes: Vec<Node>,
+    current: usize,
+}
+
+impl Profiler {
+    /// Creates a profiler for the program with the given functions, which are used to detect tail calls and to name
+    /// frames.
+    pub fn new(symbols: SymbolTable) -> Self {
+        Self {
+            symbols,
+            nodes: Vec::new(),
+            current: 0,
+        }
+    }
+
+    /// Records the execution of the `instruction` at `pc`, which continued at `next_pc`.
+    pub fn record(&mut self, pc: u32, instruction: &Instruction, next_pc: u32) {
+        if self.nodes.is_empty() {
+            // The first instruction executed is the root of the call tree.
+            self.nodes.push(Node {
+                address: pc,
+                parent: None,
+                children: HashMap::new(),
+                cycles: 0,
+            });
+        }
+        self.nodes[self.current].cycles += 1;
+
+        let is_link = |reg: Register| matches!(reg, Register::X1 | Register::X5);
+        let rd = instruction.op_a;
+        match instruction.opcode.builtin() {
+            Some(BuiltinOpcode::JAL) => {
+                if is_link(rd) {
+                    self.call(next_pc);
+                } else if self.is_function_start(next_pc) {
+                    self.ret();
+                    self.call(next_pc);
+                }
+            }
+            Some(BuiltinOpcode::JALR) => {
+                let rs1 = instruction.op_b;
+                match (is_link(rd), is_link(rs1)) {
+                    (true, true) if rd == rs1 => self.call(next_pc),
+                    (true, true) => {
+                        self.ret();
+                        self.call(next_pc);
+                    }
+                    (true, false) => self.call(next_pc),
+                    (false, true) => self.ret(),
+                    (false, false) if self.is_function_start(next_pc) => {
+                        self.ret();
+                        self.call(next_pc);
+                    }
+                    (false, false) => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Total number of cycles recorded.
+    pub fn total_cycles(&self) -> u64 {
+        self.nodes.iter().map(|node| node.cycles).sum()
+    }
+
+    /// Writes the profile as collapsed stacks, the input format of flame graph tools such as `flamegraph.pl`,
+    /// `inferno-flamegraph` and speedscope.
+    ///
+    /// Every call stack that cycles were spent in is written as one line of the frames from the outermost inwards,
+    /// separated by `;`, followed by a space and the number of cycles. Frames are named by the demangled name of the
+    /// function containing the called address, or by the address in hex if there is none.
+    pub fn write_collapsed(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut lines = self.collapse();
+        lines.sort();
+
+        for line in lines {
+            writeln!(out, "{line}")?;
+        }
+        out.flush()
+    }
+
+    /// Walks the call tree depth-first with an explicit stack, since guests may recurse deeper than the host can.
+    fn collapse(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        // The names of the frames on the current path, and the nodes left to visit with the depth of their parent.
+        let mut path: Vec<String> = Vec::new();
+        let mut pending = Vec::new();
+        if !self.nodes.is_empty() {
+            pending.push((0, 0));
+        }
+
+        while let Some((index, depth)) = pending.pop() {
+            let node = &self.nodes[index];
+            path.truncate(depth);
+            path.push(match self.symbols.lookup(node.address) {
+                Some(symbol) => symbol.demangled_name(),
+                None => format!("{:#010x}", node.address),
+            });
+
+            if node.cycles > 0 {
+                lines.push(format!("{} {}", path.join(";"), node.cycles));
+            }
+            pending.extend(node.children.values().map(|&child| (child, depth + 1)));
+        }
+
+        lines
+    }
+
+    fn is_function_start(&self, address: u32) -> bool {
+        self.symbols
+            .lookup(address)
+            .is_some_and(|symbol| symbol.address == address)
+    }
+
+    fn call(&mut self, address: u32) {
+        let current = self.current;
+        let next = self.nodes.len();
+        let child = *self.nodes[current].children.entry(address).or_insert(next);
+        if child == next {
+            self.nodes.push(Node {
+                address,
+                parent: Some(current),
+                children: HashMap::new(),
+                cycles: 0,
+            });
+        }
+        self.current = child;
+    }
+
+    fn ret(&mut self) {
+        // Returning from the root, e.g., after the stack was unwound by other means, keeps attributing to the root.
+        if let Some(parent) = self.nodes[self.current].parent {
+            self.current = parent;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        elf::{ElfFile, Symbol},
+        emulator::{Emulator, HarvardEmulator, LinearEmulator},
+        riscv::Opcode,
+    };
+
+    #[test]
+    fn test_calls_and_returns() {
+        let symbols = [("_start", 0x1000), ("f", 0x1100), ("g", 0x1200)]
+            .into_iter()
+            .map(|(name, address)| Symbol {
+                name: name.to_string(),
+                address,
+                size: 0,
+            })
+            .collect();
+
+        let addi = Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 10, 1);
+        let call = Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 1, 0, 0xfc);
+        let jump = Instruction::new_ir(Opcode::from(BuiltinOpcode::JALR), 0, 6, 0);
+        let ret = Instruction::new_ir(Opcode::from(BuiltinOpcode::JALR), 0, 1, 0);
+
+        let mut profiler = Profiler::new(symbols);
+        profiler.record(0x1000, &addi, 0x1004);
+        profiler.record(0x1004, &call, 0x1100);
+        // A jump within `f`, e.g., through a jump table.
+        profiler.record(0x1100, &jump, 0x1108);
+        // A jump to the start of `g` is a tail call.
+        profiler.record(0x1108, &jump, 0x1200);
+        profiler.record(0x1200, &ret, 0x1008);
+        profiler.record(0x1008, &addi, 0x100c);
+        assert_eq!(profiler.total_cycles(), 6);
+
+        let mut collapsed = Vec::new();
+        profiler.write_collapsed(&mut collapsed).unwrap();
+        assert_eq!(
+            String::from_utf8(collapsed).unwrap(),
+            "_start 3\n\
+             _start;f 2\n\
+             _start;g 1\n"
+        );
+    }
+
+    #[test]
+    fn test_deep_recursion() {
+        // A chain of frames deeper than the host stack could recurse into, with cycles spent only in the innermost.
+        const DEPTH: usize = 1 << 20;
+        let mut profiler = Profiler::new(SymbolTable::default());
+        profiler.nodes = (0..DEPTH)
+            .map(|index| Node {
+                address: 0x1000,
+                parent: index.checked_sub(1),
+                children: HashMap::from_iter((index + 1 < DEPTH).then_some((0x1000, index + 1))),
+                cycles: (index + 1 == DEPTH) as u64,
+            })
+            .collect();
+
+        let mut collapsed = Vec::new();
+        profiler.write_collapsed(&mut collapsed).unwrap();
+        let collapsed = String::from_utf8(collapsed).unwrap();
+        assert_eq!(collapsed.matches(';').count(), DEPTH - 1);
+        assert!(collapsed.ends_with("0x00001000 1\n"));
+    }
+
+    #[test]
+    fn test_profile_fib() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").unwrap();
+        let symbols = SymbolTable::from_path("test/fib_10.elf").unwrap();
+
+        let mut emulator = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+        emulator.get_executor_mut().profiler = Some(Profiler::new(symbols));
+        emulator.execute(false).unwrap_err();
+
+        let executor = emulator.get_executor();
+        let profiler = executor.profiler.as_ref().unwrap();
+        // The clock starts at one.
+        assert_eq!(profiler.total_cycles(), executor.global_clock as u64 - 1);
+
+        let mut collapsed = Vec::new();
+        profiler.write_collapsed(&mut collapsed).unwrap();
+        let collapsed = String::from_utf8(collapsed).unwrap();
+
+        assert!(collapsed.lines().all(|line| line.starts_with("_start")));
+        assert!(collapsed
+            .lines()
+            .any(|line| line.contains(";main;integration::fib ")));
+    }
+
+    #[test]
+    fn test_profile_fib_linear() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").unwrap();
+        let symbols = SymbolTable::from_path("test/fib_10.elf").unwrap();
+
+        let mut harvard = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+        harvard.execute(false).unwrap_err();
+        let mut emulator = LinearEmulator::from_harvard(&harvard, elf_file, &[], &[]).unwrap();
+        emulator.get_executor_mut().profiler = Some(Profiler::new(symbols));
+        emulator.execute(false).unwrap_err();
+
+        let executor = emulator.get_executor();
+        let profiler = executor.profiler.as_ref().unwrap();
+        assert_eq!(profiler.total_cycles(), executor.global_clock as u64 - 1);
+
+        let mut collapsed = Vec::new();
+        profiler.write_collapsed(&mut collapsed).unwrap();
+        let collapsed = String::from_utf8(collapsed).unwrap();
+        assert!(collapsed
+            .lines()
+            .any(|line| line.contains(";main;integration::fib ")));
+    }
+}
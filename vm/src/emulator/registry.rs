@@ -7,6 +7,7 @@
 //!
 //! - `InstructionExecutorFn`: A type alias for the instruction execution function signature.
 //! - `InstructionExecutorRegistry`: A struct containing mappings of opcodes to their execution functions.
+//! - `Precompiles`: The executors of the precompiles called by a guest, by precompile index.
 //!
 //! ## Instruction Categories
 //!
@@ -47,6 +48,7 @@
 //!   - A `HashMap` `precompiles` for custom instructions.
 //!   - Special `Opcode`s for read input and write output operations.
 //! - The `add_opcode` method allows adding custom instructions at runtime.
+//! - The `add_precompiles` method adds the custom instructions of a `Precompiles` set.
 //! - The `get` method retrieves the execution function for a given opcode.
 //! - Special methods `get_for_read_input` and `get_for_write_output` handle the custom I/O instructions.
 //!
@@ -62,7 +64,7 @@ use crate::{
     memory::{LoadOps, StoreOps, UnifiedMemory},
     riscv::{BuiltinOpcode, Instruction, Opcode},
 };
-use std::collections::HashMap;
+use std::collections::{btree_map::Entry, BTreeMap, HashMap};
 
 pub type InstructionExecutorFn<M> =
     fn(&mut Cpu, &mut M, &Instruction) -> Result<(Option<u32>, (LoadOps, StoreOps)), MemoryError>;
@@ -73,6 +75,67 @@ macro_rules! register_instruction_executor {
     };
 }
 
+/// Opcode of the custom R-type instructions that invoke precompiles.
+const PRECOMPILE_OPCODE: u8 = 0b0001011;
+
+/// Number of precompile indices encodable in the `fn7` and `fn3` fields of an instruction.
+const MAX_PRECOMPILES: u16 = 1 << 10;
+
+/// Executors of the precompiles called by a guest, by their index in its `use_precompiles!` invocation.
+///
+/// Unlike [`InstructionExecutorRegistry::add_opcode`], which registers an executor with one emulator, a set of
+/// precompiles can be handed to [`k_trace_with_precompiles`](crate::trace::k_trace_with_precompiles), which
+/// registers it with both passes.
+#[derive(Debug, Default, Clone)]
+pub struct Precompiles {
+    executors: BTreeMap<u16, InstructionExecutorFn<UnifiedMemory>>,
+}
+
+impl Precompiles {
+    /// Returns the opcode of the instruction calling the precompile at `index`, encoded as `fn7` followed by `fn3`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` does not fit in 10 bits.
+    pub fn opcode(index: u16) -> Opcode {
+        assert!(
+            index < MAX_PRECOMPILES,
+            "precompile index {index} does not fit in 10 bits"
+        );
+        Opcode::new(
+            PRECOMPILE_OPCODE,
+            Some((index & 0b111) as u8),
+            Some((index >> 3) as u8),
+            "dynamic",
+        )
+    }
+
+    /// Registers the executor of the precompile at `index`, failing if the index is already taken.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` does not fit in 10 bits.
+    pub fn register<IE: InstructionExecutor>(&mut self, index: u16) -> Result<()> {
+        let op = Self::opcode(index);
+        match self.executors.entry(index) {
+            Entry::Occupied(_) => Err(VMError::DuplicateInstruction(op)),
+            Entry::Vacant(entry) => {
+                entry.insert(register_instruction_executor!(IE::evaluator));
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the registered indices, in increasing order.
+    pub fn indices(&self) -> impl Iterator<Item = u16> + '_ {
+        self.executors.keys().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.executors.is_empty()
+    }
+}
+
 #[derive(Debug)]
 pub struct InstructionExecutorRegistry {
     builtins: [Option<InstructionExecutorFn<UnifiedMemory>>; BuiltinOpcode::VARIANT_COUNT],
@@ -235,10 +298,31 @@ impl Default for InstructionExecutorRegistry {
 
 impl InstructionExecutorRegistry {
     pub fn add_opcode<IE: InstructionExecutor>(&mut self, op: &Opcode) -> Result<(), VMError> {
+        if self.precompiles.contains_key(op) {
+            return Err(VMError::DuplicateInstruction(op.clone()));
+        }
         self.precompiles
-            .insert(op.clone(), register_instruction_executor!(IE::evaluator))
-            .ok_or(VMError::DuplicateInstruction(op.clone()))
-            .map(|_| ())
+            .insert(op.clone(), register_instruction_executor!(IE::evaluator));
+        Ok(())
+    }
+
+    /// Adds the custom instructions calling the given precompiles.
+    pub fn add_precompiles(&mut self, precompiles: &Precompiles) -> Result<()> {
+        for (&index, &func) in &precompiles.executors {
+            let op = Precompiles::opcode(index);
+            if self.precompiles.contains_key(&op) {
+                return Err(VMError::DuplicateInstruction(op));
+            }
+            self.precompiles.insert(op, func);
+        }
+        Ok(())
+    }
+
+    /// Copies the custom instructions of `other` that are missing from this registry.
+    pub(crate) fn inherit_precompiles(&mut self, other: &Self) {
+        for (op, func) in &other.precompiles {
+            self.precompiles.entry(op.clone()).or_insert(*func);
+        }
     }
 
     pub fn get(&self, op: &Opcode) -> Result<InstructionExecutorFn<UnifiedMemory>> {
@@ -294,3 +378,62 @@ impl InstructionExecutorRegistry {
         op.raw() == self.write_output.raw() && op.fn3() == self.write_output.fn3()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_opcode() {
+        let mut registry = InstructionExecutorRegistry::default();
+        let op = Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "dynamic");
+
+        assert!(matches!(
+            registry.get(&op),
+            Err(VMError::UndefinedInstruction(_))
+        ));
+        registry
+            .add_opcode::<instructions::AddInstruction>(&op)
+            .unwrap();
+        assert!(registry.get(&op).is_ok());
+        assert!(matches!(
+            registry.add_opcode::<instructions::AddInstruction>(&op),
+            Err(VMError::DuplicateInstruction(_))
+        ));
+    }
+
+    #[test]
+    fn test_add_precompiles() {
+        let mut precompiles = Precompiles::default();
+        precompiles
+            .register::<instructions::AddInstruction>(83)
+            .unwrap();
+        assert!(matches!(
+            precompiles.register::<instructions::SubInstruction>(83),
+            Err(VMError::DuplicateInstruction(_))
+        ));
+        assert_eq!(precompiles.indices().collect::<Vec<_>>(), vec![83]);
+
+        // The decoder names every custom instruction "dynamic".
+        let op = Opcode::new(0b0001011, Some(0b011), Some(0b1010), "dynamic");
+        assert_eq!(Precompiles::opcode(83), op);
+
+        let mut registry = InstructionExecutorRegistry::default();
+        registry.add_precompiles(&precompiles).unwrap();
+        assert!(registry.get(&op).is_ok());
+        assert!(matches!(
+            registry.add_precompiles(&precompiles),
+            Err(VMError::DuplicateInstruction(_))
+        ));
+
+        let mut inherited = InstructionExecutorRegistry::default();
+        inherited.inherit_precompiles(&registry);
+        assert!(inherited.get(&op).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in 10 bits")]
+    fn test_precompile_index_out_of_range() {
+        Precompiles::opcode(1 << 10);
+    }
+}
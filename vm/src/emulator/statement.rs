@@ -0,0 +1,388 @@
+//! Public Statements of Proven Executions
+//!
+//! A [`View`] captures everything needed to verify an execution, including the whole program and its static
+//! memory images. This module splits it into two parts that can be distributed independently:
+//!
+//! - [`ProgramImage`]: the loaded program and its static ROM and RAM, identified by [`ProgramImage::digest`], which
+//!   is also the image ID returned by [`ElfFile::image_id`].
+//! - [`PublicStatement`]: the program digest alongside the public input and output, the exit code, the associated
+//!   data and the memory layout, with a stable binary encoding.
+//!
+//! A verifier holding a program image, pinned by its digest, can rebuild the [`View`] of any execution of that
+//! program from its public statement, without access to the ELF.
+//!
+//! # Encoding
+//!
+//! [`PublicStatement::to_bytes`] produces, with all integers little-endian:
+//!
+//! ```text
+//! program_digest:  [u8; 32]
+//! memory_layout:   [u32; LinearMemoryLayout::ENCODED_WORDS]
+//! exit_code:       u32
+//! public_input:    u32 length, followed by the bytes
+//! public_output:   u32 length, followed by the bytes
+//! associated_data: u32 length, followed by the bytes
+//! ```
+use nexus_common::constants::WORD_SIZE;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{
+    convert_instruction, elf_into_program_info, io_entries_into_vec, map_into_io_entries,
+    registry::InstructionExecutorRegistry, slice_into_io_entries, LinearMemoryLayout,
+    MemoryInitializationEntry, ProgramInfo, PublicOutputEntry, View,
+};
+use crate::elf::ElfFile;
+use crate::error::StatementError;
+
+/// The loaded program and its static memory, as committed to by the program digest of a [`PublicStatement`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgramImage {
+    pub program_memory: ProgramInfo,
+    /// Static ROM and RAM, one entry per byte.
+    pub static_memory: Vec<MemoryInitializationEntry>,
+}
+
+impl ProgramImage {
+    /// Load the image of a program, as it is laid out in memory for proving.
+    pub fn from_elf(elf: &ElfFile) -> Self {
+        // Custom instructions `rin` and `wou` are replaced with `lw` and `sw` in the proven program.
+        let registry = InstructionExecutorRegistry::default();
+        let converted_elf = ElfFile {
+            instructions: elf
+                .instructions
+                .iter()
+                .map(|instr| convert_instruction(&registry, instr))
+                .collect(),
+            ..elf.clone()
+        };
+
+        Self {
+            // The program start does not depend on the sizes of the other segments.
+            program_memory: elf_into_program_info(&converted_elf, &LinearMemoryLayout::default()),
+            static_memory: map_into_io_entries::<MemoryInitializationEntry>(&elf.rom_image)
+                .into_iter()
+                .chain(map_into_io_entries::<MemoryInitializationEntry>(
+                    &elf.ram_image,
+                ))
+                .collect(),
+        }
+    }
+
+    /// Extract the image of a program from the initial memory of an execution, which also holds the public input and
+    /// the locations of the public input and output.
+    pub fn from_initial_memory(
+        program_memory: &ProgramInfo,
+        initial_memory: &[MemoryInitializationEntry],
+        layout: &LinearMemoryLayout,
+    ) -> Self {
+        let public_io_locations = layout.public_input_start_location()
+            ..layout.public_output_start_location() + WORD_SIZE as u32;
+        let public_input = layout.public_input_start()..layout.public_input_end();
+
+        Self {
+            program_memory: program_memory.clone(),
+            static_memory: initial_memory
+                .iter()
+                .filter(|entry| {
+                    !public_io_locations.contains(&entry.address)
+                        && !public_input.contains(&entry.address)
+                })
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Compute the SHA-256 digest over the program memory and static memory.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+
+        hasher.update(self.program_memory.initial_pc.to_le_bytes());
+        hasher.update((self.program_memory.program.len() as u32).to_le_bytes());
+        for entry in &self.program_memory.program {
+            hasher.update(entry.pc.to_le_bytes());
+            hasher.update(entry.instruction_word.to_le_bytes());
+        }
+
+        hasher.update((self.static_memory.len() as u32).to_le_bytes());
+        for entry in &self.static_memory {
+            hasher.update(entry.address.to_le_bytes());
+            hasher.update([entry.value]);
+        }
+
+        hasher.finalize().into()
+    }
+}
+
+/// A compact, self-contained statement of a proven execution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicStatement {
+    /// The digest of the [`ProgramImage`] that was executed.
+    pub program_digest: [u8; 32],
+    pub memory_layout: LinearMemoryLayout,
+    /// The encoded public input, excluding its length prefix.
+    pub public_input: Vec<u8>,
+    pub exit_code: u32,
+    /// The encoded public output.
+    pub public_output: Vec<u8>,
+    pub associated_data: Vec<u8>,
+}
+
+impl PublicStatement {
+    /// Encode the statement, see the [module documentation](self) for the format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(&self.program_digest);
+        for word in self.memory_layout.to_words() {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes.extend_from_slice(&self.exit_code.to_le_bytes());
+        for field in [
+            &self.public_input,
+            &self.public_output,
+            &self.associated_data,
+        ] {
+            bytes.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(field);
+        }
+
+        bytes
+    }
+
+    /// Decode a statement produced by [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StatementError> {
+        let mut reader = Reader(bytes);
+
+        let program_digest = reader.take_array::<32>()?;
+        let mut layout_words = [0u32; LinearMemoryLayout::ENCODED_WORDS];
+        for word in layout_words.iter_mut() {
+            *word = reader.take_u32()?;
+        }
+        let memory_layout = LinearMemoryLayout::from_words(layout_words)
+            .map_err(|_| StatementError::InvalidMemoryLayout)?;
+        let exit_code = reader.take_u32()?;
+        let public_input = reader.take_bytes()?;
+        let public_output = reader.take_bytes()?;
+        let associated_data = reader.take_bytes()?;
+
+        if !reader.0.is_empty() {
+            return Err(StatementError::TrailingBytes(reader.0.len()));
+        }
+
+        Ok(Self {
+            program_digest,
+            memory_layout,
+            public_input,
+            exit_code,
+            public_output,
+            associated_data,
+        })
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], StatementError> {
+        if self.0.len() < len {
+            return Err(StatementError::Truncated);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], StatementError> {
+        Ok(self.take(N)?.try_into().expect("length was checked"))
+    }
+
+    fn take_u32(&mut self) -> Result<u32, StatementError> {
+        Ok(u32::from_le_bytes(self.take_array::<4>()?))
+    }
+
+    fn take_bytes(&mut self) -> Result<Vec<u8>, StatementError> {
+        let len = self.take_u32()? as usize;
+        Ok(self.take(len)?.to_vec())
+    }
+}
+
+impl View {
+    /// Return the image of the executed program, if the view has a memory layout.
+    pub fn program_image(&self) -> Option<ProgramImage> {
+        Some(ProgramImage::from_initial_memory(
+            &self.program_memory,
+            &self.initial_memory,
+            self.memory_layout.as_ref()?,
+        ))
+    }
+
+    /// Return the public statement of the execution.
+    ///
+    /// Fails if the view has no memory layout, or if its exit code is not a single word.
+    pub fn public_statement(&self) -> Result<PublicStatement, StatementError> {
+        let memory_layout = self
+            .memory_layout
+            .ok_or(StatementError::MissingMemoryLayout)?;
+        let exit_code = io_entries_into_vec(memory_layout.exit_code(), &self.exit_code);
+        let exit_code = <[u8; WORD_SIZE]>::try_from(exit_code.as_slice())
+            .map_err(|_| StatementError::InvalidExitCode(exit_code.len()))?;
+
+        Ok(PublicStatement {
+            program_digest: ProgramImage::from_initial_memory(
+                &self.program_memory,
+                &self.initial_memory,
+                &memory_layout,
+            )
+            .digest(),
+            memory_layout,
+            public_input: self.view_public_input().unwrap_or_default(),
+            exit_code: u32::from_le_bytes(exit_code),
+            public_output: self.view_public_output().unwrap_or_default(),
+            associated_data: self.associated_data.clone(),
+        })
+    }
+
+    /// Rebuild the view of an execution from its public statement and the image of the executed program.
+    pub fn from_statement(
+        statement: &PublicStatement,
+        image: &ProgramImage,
+    ) -> Result<Self, StatementError> {
+        if image.digest() != statement.program_digest {
+            return Err(StatementError::ProgramDigestMismatch);
+        }
+        let layout = &statement.memory_layout;
+
+        let initial_memory: Vec<MemoryInitializationEntry> =
+            slice_into_io_entries::<MemoryInitializationEntry>(
+                layout.public_input_start_location(),
+                &[
+                    layout.public_input_start().to_le_bytes(),
+                    layout.exit_code().to_le_bytes(), // the exit code is the first word of the output
+                ]
+                .concat(),
+            )
+            .into_iter()
+            .chain(image.static_memory.iter().copied())
+            .chain(slice_into_io_entries::<MemoryInitializationEntry>(
+                layout.public_input_start(),
+                &[
+                    &(statement.public_input.len() as u32).to_le_bytes(),
+                    statement.public_input.as_slice(),
+                ]
+                .concat(),
+            ))
+            .collect();
+
+        Ok(Self::new(
+            &Some(*layout),
+            &Vec::new(),
+            &image.program_memory,
+            &initial_memory,
+            layout.tracked_ram_size(image.static_memory.len()),
+            &slice_into_io_entries::<PublicOutputEntry>(
+                layout.exit_code(),
+                &statement.exit_code.to_le_bytes(),
+            ),
+            &slice_into_io_entries::<PublicOutputEntry>(
+                layout.public_output_start(),
+                &statement.public_output,
+            ),
+            &statement.associated_data,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::{Emulator, InternalView, LinearEmulator};
+    use crate::error::VMError;
+
+    fn fib_view() -> View {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let mut emulator =
+            LinearEmulator::from_elf(LinearMemoryLayout::default(), &[], &elf_file, &[], &[]);
+        assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+
+        emulator.finalize()
+    }
+
+    #[test]
+    fn test_statement_encoding_round_trip() {
+        let statement = PublicStatement {
+            program_digest: [7; 32],
+            memory_layout: LinearMemoryLayout::new(0x1000, 0x1000, 0x10, 0x10, 0x1000, 0x10)
+                .unwrap(),
+            public_input: vec![1, 2, 3, 4],
+            exit_code: 1,
+            public_output: vec![5, 6, 7, 8],
+            associated_data: vec![9],
+        };
+        let bytes = statement.to_bytes();
+
+        assert_eq!(bytes.len(), 32 + 8 * 4 + 4 + (4 + 4) + (4 + 4) + (4 + 1));
+        assert_eq!(PublicStatement::from_bytes(&bytes), Ok(statement));
+        assert_eq!(
+            PublicStatement::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(StatementError::Truncated)
+        );
+        assert_eq!(
+            PublicStatement::from_bytes(&[bytes.as_slice(), &[0]].concat()),
+            Err(StatementError::TrailingBytes(1))
+        );
+    }
+
+    #[test]
+    fn test_view_from_statement() {
+        let view = fib_view();
+        let statement = view.public_statement().unwrap();
+        let image = view.program_image().unwrap();
+
+        let decoded = PublicStatement::from_bytes(&statement.to_bytes()).unwrap();
+        let rebuilt = View::from_statement(&decoded, &image).unwrap();
+
+        assert_eq!(rebuilt.get_initial_memory(), view.get_initial_memory());
+        assert_eq!(
+            rebuilt.get_program_memory().program.len(),
+            view.get_program_memory().program.len()
+        );
+        assert_eq!(
+            rebuilt.view_tracked_ram_size(),
+            view.view_tracked_ram_size()
+        );
+        assert_eq!(rebuilt.view_exit_code(), view.view_exit_code());
+        assert_eq!(rebuilt.view_public_output(), view.view_public_output());
+        assert_eq!(rebuilt.view_associated_data(), view.view_associated_data());
+        assert_eq!(rebuilt.public_statement(), Ok(statement));
+    }
+
+    #[test]
+    fn test_public_statement_errors() {
+        let mut view = fib_view();
+        view.exit_code.pop();
+        assert_eq!(
+            view.public_statement(),
+            Err(StatementError::InvalidExitCode(WORD_SIZE - 1))
+        );
+
+        view.memory_layout = None;
+        assert_eq!(
+            view.public_statement(),
+            Err(StatementError::MissingMemoryLayout)
+        );
+    }
+
+    #[test]
+    fn test_view_from_statement_wrong_program() {
+        let view = fib_view();
+        let statement = view.public_statement().unwrap();
+        let mut image = view.program_image().unwrap();
+        image.program_memory.program[0].instruction_word ^= 1;
+
+        assert_eq!(
+            View::from_statement(&statement, &image).unwrap_err(),
+            StatementError::ProgramDigestMismatch
+        );
+    }
+}
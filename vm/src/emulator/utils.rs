@@ -8,6 +8,7 @@ use super::registry;
 use nexus_common::constants::WORD_SIZE;
 use nexus_common::memory::MemoryRecords;
 use nexus_common::riscv::{opcode::BuiltinOpcode, Opcode};
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 pub type MemoryTranscript = Vec<MemoryRecords>;
@@ -110,14 +111,14 @@ pub fn elf_into_program_info(elf: &ElfFile, layout: &LinearMemoryLayout) -> Prog
 }
 
 // One entry per byte because RO memory can be accessed bytewise
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MemoryInitializationEntry {
     pub address: u32,
     pub value: u8,
 }
 
 // One entry per byte because WO memory can be accessed bytewise
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct PublicOutputEntry {
     pub address: u32,
     pub value: u8,
@@ -127,13 +128,13 @@ io!(MemoryInitializationEntry);
 io!(PublicOutputEntry);
 
 // One entry per instruction because program memory is always accessed instruction-wise
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct ProgramMemoryEntry {
     pub pc: u32,
     pub instruction_word: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProgramInfo {
     // The program counter where the execution starts
     pub initial_pc: u32,
@@ -183,7 +184,7 @@ pub trait InternalView {
     fn add_logs(&mut self, emulator: &impl Emulator);
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct View {
     pub(crate) memory_layout: Option<LinearMemoryLayout>,
     pub(crate) debug_logs: Vec<Vec<u8>>,
@@ -252,6 +253,11 @@ impl View {
             .map(|layout| io_entries_into_vec(layout.public_output_start(), &self.output_memory))
     }
 
+    /// Return the memory layout of the execution, if any.
+    pub fn view_memory_layout(&self) -> Option<LinearMemoryLayout> {
+        self.memory_layout
+    }
+
     /// Return the number of all addresses under RAM memory checking.
     pub fn view_tracked_ram_size(&self) -> usize {
         self.tracked_ram_size
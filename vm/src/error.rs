@@ -30,6 +30,14 @@ pub enum VMError {
     #[error("Invalid profile label for cycle counter: \"{0}\"")]
     InvalidProfileLabel(String),
 
+    // No oracle is registered on the queried channel.
+    #[error("No oracle registered for channel {0}")]
+    UnknownOracleChannel(u32),
+
+    // The replayed execution queries oracles differently than the recorded one.
+    #[error("No recorded oracle response to replay for channel {0}")]
+    OracleReplayMismatch(u32),
+
     #[error("Wrapped MemoryError: {0}")]
     MemoryError(#[from] nexus_common::error::MemoryError),
 
@@ -60,5 +68,33 @@ pub enum VMError {
     UnsupportedInstruction(Opcode),
 }
 
+/// Errors related to decoding or checking a public statement.
+#[derive(Debug, Error, PartialEq)]
+pub enum StatementError {
+    // The encoding ends before all fields are read.
+    #[error("Public statement encoding is truncated")]
+    Truncated,
+
+    // The encoding continues after all fields are read.
+    #[error("Public statement encoding has {0} trailing bytes")]
+    TrailingBytes(usize),
+
+    // The encoded memory layout is invalid.
+    #[error("Public statement has an invalid memory layout")]
+    InvalidMemoryLayout,
+
+    // The program image does not hash to the statement's program digest.
+    #[error("Program image does not match the program digest of the statement")]
+    ProgramDigestMismatch,
+
+    // The view has no memory layout, so its public input and output cannot be located.
+    #[error("View has no memory layout")]
+    MissingMemoryLayout,
+
+    // The exit code is not a single word.
+    #[error("Exit code has {0} bytes, expected 4")]
+    InvalidExitCode(usize),
+}
+
 /// Result type for VM functions that can produce errors.
 pub type Result<T, E = VMError> = std::result::Result<T, E>;
@@ -0,0 +1,602 @@
+//! # GDB Remote Serial Protocol Stub
+//!
+//! This module serves a [`HarvardEmulator`](crate::emulator::HarvardEmulator) or
+//! [`LinearEmulator`](crate::emulator::LinearEmulator) to a debugger speaking the GDB remote serial protocol, such as
+//! `riscv64-unknown-elf-gdb`, `gdb-multiarch` or lldb, so that a guest program can be debugged while it runs on the
+//! zkVM.
+//!
+//! ## Supported Features
+//!
+//! - Continue and single-step, one instruction at a time through [`Emulator::fetch_block`] and
+//!   [`Emulator::execute_instruction`], and interrupting a running guest with Ctrl-C.
+//! - Software and hardware breakpoints, which are equivalent since the program is never patched.
+//! - Register reads and writes, with the RV32I register file described to the debugger through `target.xml`.
+//! - Memory reads and writes, following the access modes of the emulator's memory, see [`DebugTarget`].
+//! - Symbol lookup from the ELF through monitor commands: `monitor symbol <name>` prints the address of a function,
+//!   and `monitor whereis <address>` the function containing an address.
+//!
+//! When the guest exits, the exit code is reported to the debugger. Other errors, such as an invalid memory access
+//! or an undefined instruction, stop the guest with `SIGSEGV` or `SIGILL` respectively, at the faulting instruction.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use std::net::TcpListener;
+//!
+//! use nexus_vm::elf::{ElfFile, SymbolTable};
+//! use nexus_vm::emulator::HarvardEmulator;
+//! use nexus_vm::gdb::GdbStub;
+//!
+//! let elf_file = ElfFile::from_path("test/fib_10.elf").unwrap();
+//! let symbols = SymbolTable::from_path("test/fib_10.elf").unwrap();
+//! let mut emulator = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+//!
+//! let listener = TcpListener::bind("127.0.0.1:9000").unwrap();
+//! let (stream, _) = listener.accept().unwrap();
+//! GdbStub::new(&mut emulator, symbols).run(stream).unwrap();
+//! ```
+//!
+//! The debugger then attaches with:
+//!
+//! ```text
+//! $ riscv64-unknown-elf-gdb test/fib_10.elf
+//! (gdb) target remote :9000
+//! ```
+//!
+//! [`Emulator::fetch_block`]: crate::emulator::Emulator::fetch_block
+//! [`Emulator::execute_instruction`]: crate::emulator::Emulator::execute_instruction
+use std::{
+    collections::BTreeSet,
+    fmt::Write as _,
+    io::{self, Read, Write},
+    net::TcpStream,
+};
+
+use nexus_common::{constants::WORD_SIZE, cpu::Registers};
+
+use crate::{elf::SymbolTable, error::VMError, riscv::Register};
+
+mod packet;
+mod target;
+
+pub use target::DebugTarget;
+
+use packet::{decode_hex, encode_hex, read_packet, write_packet, Incoming, INTERRUPT};
+
+const SIGINT: u8 = 2;
+const SIGILL: u8 = 4;
+const SIGTRAP: u8 = 5;
+const SIGSEGV: u8 = 11;
+
+/// The register number of the program counter, following the 32 general purpose registers.
+const PC_REGNUM: usize = 32;
+
+/// Number of instructions executed between checks for an interrupt from the debugger.
+const INTERRUPT_POLL_INTERVAL: usize = 4096;
+
+/// A connection to a debugger.
+pub trait Connection: Read + Write {
+    /// Returns whether the debugger has requested to interrupt the running guest, without blocking.
+    ///
+    /// Fails if the debugger has closed the connection.
+    fn poll_interrupt(&mut self) -> io::Result<bool>;
+}
+
+impl Connection for TcpStream {
+    fn poll_interrupt(&mut self) -> io::Result<bool> {
+        self.set_nonblocking(true)?;
+        let mut byte = [0u8];
+        let result = match self.read(&mut byte) {
+            Ok(0) => Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(_) => Ok(byte[0] == INTERRUPT),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(false),
+            Err(e) => Err(e),
+        };
+        self.set_nonblocking(false)?;
+        result
+    }
+}
+
+/// Why the guest stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stop {
+    Signal(u8),
+    Exited(u32),
+}
+
+impl Stop {
+    fn reply(self) -> String {
+        match self {
+            Stop::Signal(signal) => format!("S{signal:02x}"),
+            // The debugger only receives the low byte of the exit code.
+            Stop::Exited(code) => format!("W{:02x}", code & 0xff),
+        }
+    }
+}
+
+/// What to do after handling a packet.
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    Reply(String),
+    Resume { step: bool },
+    Detach,
+    Kill,
+}
+
+/// A debugging session of a single guest execution.
+pub struct GdbStub<'a, E: DebugTarget> {
+    emulator: &'a mut E,
+    symbols: SymbolTable,
+    breakpoints: BTreeSet<u32>,
+    last_stop: Stop,
+}
+
+impl<'a, E: DebugTarget> GdbStub<'a, E> {
+    /// Creates a session stopped at the current program counter of the emulator.
+    pub fn new(emulator: &'a mut E, symbols: SymbolTable) -> Self {
+        Self {
+            emulator,
+            symbols,
+            breakpoints: BTreeSet::new(),
+            last_stop: Stop::Signal(SIGTRAP),
+        }
+    }
+
+    /// Serves the debugger until it detaches, kills the guest or closes the connection.
+    pub fn run<C: Connection>(&mut self, mut conn: C) -> io::Result<()> {
+        let mut last_reply = String::new();
+
+        while let Some(incoming) = read_packet(&mut conn)? {
+            let packet = match incoming {
+                Incoming::Packet(packet) => packet,
+                Incoming::Nack => {
+                    write_packet(&mut conn, last_reply.as_bytes())?;
+                    continue;
+                }
+                // The guest is already stopped.
+                Incoming::Interrupt => continue,
+            };
+
+            let reply = match self.handle_packet(&packet) {
+                Action::Reply(reply) => reply,
+                Action::Resume { step: true } => self.step().reply(),
+                Action::Resume { step: false } => self.resume(|| conn.poll_interrupt())?.reply(),
+                Action::Detach => {
+                    write_packet(&mut conn, b"OK")?;
+                    return Ok(());
+                }
+                Action::Kill => return Ok(()),
+            };
+
+            write_packet(&mut conn, reply.as_bytes())?;
+            last_reply = reply;
+        }
+
+        Ok(())
+    }
+
+    fn handle_packet(&mut self, packet: &[u8]) -> Action {
+        let Some((&command, args)) = packet.split_first() else {
+            return Action::Reply(String::new());
+        };
+        let args = String::from_utf8_lossy(args);
+
+        let reply = match command {
+            b'?' => self.last_stop.reply(),
+            b'g' => self.read_registers(),
+            b'G' => ok_or_error(self.write_registers(&args)),
+            b'p' => usize::from_str_radix(&args, 16)
+                .ok()
+                .and_then(|regnum| self.read_register(regnum))
+                .map(|value| encode_hex(&value.to_le_bytes()))
+                .unwrap_or_else(|| "E00".to_string()),
+            b'P' => ok_or_error(self.write_register(&args)),
+            b'm' => self.read_memory(&args),
+            b'M' => ok_or_error(self.write_memory(&args)),
+            b'c' | b's' => {
+                if !args.is_empty() {
+                    match u32::from_str_radix(&args, 16) {
+                        Ok(pc) => self.emulator.get_executor_mut().cpu.pc.value = pc,
+                        Err(_) => return Action::Reply("E00".to_string()),
+                    }
+                }
+                return Action::Resume {
+                    step: command == b's',
+                };
+            }
+            b'Z' | b'z' => match self.update_breakpoint(&args, command == b'Z') {
+                Some(true) => "OK".to_string(),
+                Some(false) => "E00".to_string(),
+                // Watchpoints are not supported.
+                None => String::new(),
+            },
+            b'q' => self.handle_query(&args),
+            b'H' | b'T' => "OK".to_string(),
+            b'D' => return Action::Detach,
+            b'k' => return Action::Kill,
+            _ => String::new(),
+        };
+
+        Action::Reply(reply)
+    }
+
+    fn handle_query(&mut self, query: &str) -> String {
+        if query.starts_with("Supported") {
+            return "PacketSize=1000;qXfer:features:read+".to_string();
+        }
+        if let Some(annex) = query.strip_prefix("Xfer:features:read:target.xml:") {
+            return read_chunk(&target_description(), annex).unwrap_or_else(|| "E00".to_string());
+        }
+        if let Some(command) = query.strip_prefix("Rcmd,") {
+            return match decode_hex(command.as_bytes()).map(String::from_utf8) {
+                Some(Ok(command)) => encode_hex(self.monitor(&command).as_bytes()),
+                _ => "E00".to_string(),
+            };
+        }
+
+        match query {
+            "Attached" => "1",
+            "C" => "QC1",
+            "fThreadInfo" => "m1",
+            "sThreadInfo" => "l",
+            "Symbol::" => "OK",
+            _ => "",
+        }
+        .to_string()
+    }
+
+    fn monitor(&self, command: &str) -> String {
+        let mut words = command.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some("symbol"), Some(name)) => match self.symbols.find(name) {
+                Some(symbol) => format!(
+                    "{} is at 0x{:08x}, {} bytes\n",
+                    symbol.name, symbol.address, symbol.size
+                ),
+                None => format!("No function named {name}\n"),
+            },
+            (Some("whereis"), Some(address)) => {
+                match u32::from_str_radix(address.trim_start_matches("0x"), 16) {
+                    Ok(address) => match self.symbols.lookup(address) {
+                        Some(symbol) => format!(
+                            "0x{address:08x} is in {}+0x{:x}\n",
+                            symbol.name,
+                            address - symbol.address
+                        ),
+                        None => format!("0x{address:08x} is in no known function\n"),
+                    },
+                    Err(_) => format!("Invalid address {address}\n"),
+                }
+            }
+            (Some("cycles"), None) => format!(
+                "{} instructions executed\n",
+                self.emulator.get_executor().global_clock - 1
+            ),
+            _ => "Monitor commands:\n  symbol <name>\n  whereis <address>\n  cycles\n".to_string(),
+        }
+    }
+
+    fn read_register(&self, regnum: usize) -> Option<u32> {
+        let cpu = &self.emulator.get_executor().cpu;
+        match regnum {
+            0..PC_REGNUM => Some(cpu.registers.read(Register::from(regnum as u8))),
+            PC_REGNUM => Some(cpu.pc.value),
+            _ => None,
+        }
+    }
+
+    fn set_register(&mut self, regnum: usize, value: u32) -> bool {
+        let cpu = &mut self.emulator.get_executor_mut().cpu;
+        match regnum {
+            0..PC_REGNUM => cpu.registers.write(Register::from(regnum as u8), value),
+            PC_REGNUM => cpu.pc.value = value,
+            _ => return false,
+        }
+        true
+    }
+
+    fn read_registers(&self) -> String {
+        (0..=PC_REGNUM)
+            .filter_map(|regnum| self.read_register(regnum))
+            .map(|value| encode_hex(&value.to_le_bytes()))
+            .collect()
+    }
+
+    fn write_registers(&mut self, args: &str) -> Option<()> {
+        let bytes = decode_hex(args.as_bytes())?;
+        if bytes.len() != (PC_REGNUM + 1) * WORD_SIZE {
+            return None;
+        }
+        for (regnum, chunk) in bytes.chunks_exact(WORD_SIZE).enumerate() {
+            self.set_register(regnum, u32::from_le_bytes(chunk.try_into().ok()?));
+        }
+        Some(())
+    }
+
+    fn write_register(&mut self, args: &str) -> Option<()> {
+        let (regnum, value) = args.split_once('=')?;
+        let regnum = usize::from_str_radix(regnum, 16).ok()?;
+        let value = u32::from_le_bytes(decode_hex(value.as_bytes())?.try_into().ok()?);
+        self.set_register(regnum, value).then_some(())
+    }
+
+    fn read_memory(&self, args: &str) -> String {
+        let Some((address, len)) = parse_address_and_length(args) else {
+            return "E00".to_string();
+        };
+
+        // A partial read is reported as the readable prefix.
+        let bytes: Vec<u8> = (0..len)
+            .map_while(|offset| self.emulator.read_byte(address.wrapping_add(offset)))
+            .collect();
+        if bytes.is_empty() && len > 0 {
+            return "E14".to_string();
+        }
+        encode_hex(&bytes)
+    }
+
+    fn write_memory(&mut self, args: &str) -> Option<()> {
+        let (location, data) = args.split_once(':')?;
+        let (address, len) = parse_address_and_length(location)?;
+        let bytes = decode_hex(data.as_bytes())?;
+        if bytes.len() != len as usize {
+            return None;
+        }
+
+        bytes
+            .iter()
+            .enumerate()
+            .all(|(offset, byte)| {
+                self.emulator
+                    .write_byte(address.wrapping_add(offset as u32), *byte)
+            })
+            .then_some(())
+    }
+
+    /// Inserts or removes a breakpoint, returning `None` for unsupported breakpoint types.
+    fn update_breakpoint(&mut self, args: &str, insert: bool) -> Option<bool> {
+        let mut fields = args.split(',');
+        if !matches!(fields.next(), Some("0") | Some("1")) {
+            return None;
+        }
+        let Some(Ok(address)) = fields
+            .next()
+            .map(|address| u32::from_str_radix(address, 16))
+        else {
+            return Some(false);
+        };
+
+        if insert {
+            self.breakpoints.insert(address);
+        } else {
+            self.breakpoints.remove(&address);
+        }
+        Some(true)
+    }
+
+    /// Executes the instruction at the program counter.
+    fn step(&mut self) -> Stop {
+        if let Stop::Exited(code) = self.last_stop {
+            return Stop::Exited(code);
+        }
+
+        let pc = self.emulator.get_executor().cpu.pc.value;
+        let result = self.emulator.fetch_block(pc).and_then(|entry| {
+            let instruction = &entry.block.0[(pc - entry.start) as usize / WORD_SIZE];
+            self.emulator.execute_instruction(instruction, false)
+        });
+
+        self.last_stop = match result {
+            Ok(_) => Stop::Signal(SIGTRAP),
+            Err(VMError::VMExited(code)) => Stop::Exited(code),
+            Err(VMError::MemoryError(_)) => Stop::Signal(SIGSEGV),
+            Err(_) => Stop::Signal(SIGILL),
+        };
+        self.last_stop
+    }
+
+    /// Executes instructions until a breakpoint is reached, the guest stops, or `interrupted` returns true.
+    fn resume(&mut self, mut interrupted: impl FnMut() -> io::Result<bool>) -> io::Result<Stop> {
+        let mut executed = 0usize;
+        loop {
+            let stop = self.step();
+            if stop != Stop::Signal(SIGTRAP)
+                || self
+                    .breakpoints
+                    .contains(&self.emulator.get_executor().cpu.pc.value)
+            {
+                return Ok(stop);
+            }
+
+            executed += 1;
+            if executed % INTERRUPT_POLL_INTERVAL == 0 && interrupted()? {
+                self.last_stop = Stop::Signal(SIGINT);
+                return Ok(self.last_stop);
+            }
+        }
+    }
+}
+
+fn ok_or_error(result: Option<()>) -> String {
+    match result {
+        Some(()) => "OK".to_string(),
+        None => "E00".to_string(),
+    }
+}
+
+fn parse_address_and_length(args: &str) -> Option<(u32, u32)> {
+    let (address, len) = args.split_once(',')?;
+    Some((
+        u32::from_str_radix(address, 16).ok()?,
+        u32::from_str_radix(len, 16).ok()?,
+    ))
+}
+
+/// Returns the chunk of `data` requested by an `offset,length` annex of a `qXfer` read.
+fn read_chunk(data: &str, annex: &str) -> Option<String> {
+    let (offset, len) = parse_address_and_length(annex)?;
+    let start = (offset as usize).min(data.len());
+    let end = start.saturating_add(len as usize).min(data.len());
+
+    let marker = if end == data.len() { 'l' } else { 'm' };
+    Some(format!("{marker}{}", &data[start..end]))
+}
+
+/// Describes the RV32I register file, so that the debugger does not need to guess the architecture.
+fn target_description() -> String {
+    let mut registers = String::new();
+    for regnum in 0..PC_REGNUM as u8 {
+        let name = Register::from(regnum).abi_name();
+        let kind = match regnum {
+            1 => "code_ptr",
+            2 => "data_ptr",
+            _ => "int",
+        };
+        let _ = write!(
+            registers,
+            r#"<reg name="{name}" bitsize="32" type="{kind}" regnum="{regnum}"/>"#
+        );
+    }
+
+    format!(
+        concat!(
+            r#"<?xml version="1.0"?><!DOCTYPE target SYSTEM "gdb-target.dtd">"#,
+            r#"<target version="1.0"><architecture>riscv:rv32</architecture>"#,
+            r#"<feature name="org.gnu.gdb.riscv.cpu">{}"#,
+            r#"<reg name="pc" bitsize="32" type="code_ptr" regnum="{}"/>"#,
+            r#"</feature></target>"#
+        ),
+        registers, PC_REGNUM
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elf::ElfFile;
+    use crate::emulator::{HarvardEmulator, LinearEmulator, LinearMemoryLayout};
+
+    fn reply<E: DebugTarget>(stub: &mut GdbStub<'_, E>, packet: &str) -> String {
+        match stub.handle_packet(packet.as_bytes()) {
+            Action::Reply(reply) => reply,
+            action => panic!("unexpected action {action:?}"),
+        }
+    }
+
+    fn fib_symbols() -> SymbolTable {
+        SymbolTable::from_path("test/fib_10.elf").unwrap()
+    }
+
+    fn run_to_exit<E: DebugTarget>(emulator: &mut E) {
+        let mut stub = GdbStub::new(emulator, fib_symbols());
+        let main = stub.symbols.find("main").unwrap().address;
+
+        // Break at `main`, and step from there.
+        assert_eq!(reply(&mut stub, &format!("Z0,{main:x},4")), "OK");
+        assert_eq!(stub.handle_packet(b"c"), Action::Resume { step: false });
+        assert_eq!(stub.resume(|| Ok(false)).unwrap(), Stop::Signal(SIGTRAP));
+        assert_eq!(stub.read_register(PC_REGNUM), Some(main));
+
+        assert_eq!(stub.step(), Stop::Signal(SIGTRAP));
+        assert_ne!(stub.read_register(PC_REGNUM), Some(main));
+
+        // Remove the breakpoint and run to completion.
+        assert_eq!(reply(&mut stub, &format!("z0,{main:x},4")), "OK");
+        assert_eq!(stub.resume(|| Ok(false)).unwrap(), Stop::Exited(0));
+        assert_eq!(stub.step(), Stop::Exited(0));
+    }
+
+    #[test]
+    fn test_harvard_breakpoint_and_exit() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").unwrap();
+        let mut emulator = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+        run_to_exit(&mut emulator);
+    }
+
+    #[test]
+    fn test_linear_breakpoint_and_exit() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").unwrap();
+        let mut emulator =
+            LinearEmulator::from_elf(LinearMemoryLayout::default(), &[], &elf_file, &[], &[]);
+        run_to_exit(&mut emulator);
+    }
+
+    #[test]
+    fn test_registers_and_memory() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").unwrap();
+        let mut emulator = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+        let mut stub = GdbStub::new(&mut emulator, fib_symbols());
+
+        // All 33 registers, with the program counter at the entry point last.
+        let registers = reply(&mut stub, "g");
+        assert_eq!(registers.len(), 33 * 8);
+        assert_eq!(&registers[32 * 8..], "00100000");
+
+        assert_eq!(reply(&mut stub, "P5=78563412"), "OK");
+        assert_eq!(reply(&mut stub, "p5"), "78563412");
+        assert_eq!(reply(&mut stub, "p21"), "E00");
+
+        // The first instruction, as loaded from the ELF.
+        let word = elf_file.instructions[0].to_le_bytes();
+        assert_eq!(reply(&mut stub, "m1000,4"), encode_hex(&word));
+        // The program cannot be written.
+        assert_eq!(reply(&mut stub, "M1000,1:00"), "E00");
+
+        let ram = *elf_file.ram_image.keys().next().unwrap();
+        assert_eq!(reply(&mut stub, &format!("M{ram:x},2:abcd")), "OK");
+        assert_eq!(reply(&mut stub, &format!("m{ram:x},2")), "abcd");
+    }
+
+    #[test]
+    fn test_queries() {
+        let mut emulator = HarvardEmulator::default();
+        let mut stub = GdbStub::new(&mut emulator, fib_symbols());
+
+        assert_eq!(reply(&mut stub, "?"), "S05");
+        assert!(reply(&mut stub, "qSupported:multiprocess+").contains("qXfer:features:read+"));
+
+        let description = reply(&mut stub, "qXfer:features:read:target.xml:0,fff");
+        assert!(description.starts_with("l<?xml"));
+        assert!(description.contains(r#"<reg name="sp" bitsize="32" type="data_ptr" regnum="2"/>"#));
+        assert!(reply(&mut stub, "qXfer:features:read:target.xml:0,10").starts_with('m'));
+
+        let monitor = |stub: &mut GdbStub<'_, HarvardEmulator>, command: &str| {
+            let output = reply(stub, &format!("qRcmd,{}", encode_hex(command.as_bytes())));
+            String::from_utf8(decode_hex(output.as_bytes()).unwrap()).unwrap()
+        };
+        assert_eq!(
+            monitor(&mut stub, "whereis 0x1000"),
+            "0x00001000 is in _start+0x0\n"
+        );
+        assert!(monitor(&mut stub, "symbol main").starts_with("main is at 0x"));
+        assert!(monitor(&mut stub, "help").starts_with("Monitor commands"));
+
+        assert_eq!(reply(&mut stub, "Z2,1000,4"), "");
+        assert_eq!(reply(&mut stub, "vCont?"), "");
+        assert_eq!(stub.handle_packet(b"D"), Action::Detach);
+    }
+
+    #[test]
+    fn test_poll_interrupt() {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+
+        assert!(!server.poll_interrupt().unwrap());
+        client.write_all(&[INTERRUPT]).unwrap();
+        client.flush().unwrap();
+        while !server.poll_interrupt().unwrap() {}
+
+        // A closed connection stops the stub instead of running the guest unattended.
+        drop(client);
+        let error = loop {
+            match server.poll_interrupt() {
+                Ok(interrupted) => assert!(!interrupted),
+                Err(error) => break error,
+            }
+        };
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
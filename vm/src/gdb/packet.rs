@@ -0,0 +1,207 @@
+//! Packet framing of the GDB remote serial protocol.
+//!
+//! Packets are sent as `$<data>#<checksum>`, where the checksum is the sum of the data bytes modulo 256 as two hex
+//! digits. The receiver acknowledges each packet with `+`, or requests retransmission with `-`. Within the data,
+//! `$`, `#`, `}` and `*` are escaped as `}` followed by the byte XOR 0x20.
+use std::{
+    fmt::Write as _,
+    io::{self, Read, Write},
+};
+
+/// The byte the debugger sends outside of a packet to interrupt a running target (Ctrl-C).
+pub(crate) const INTERRUPT: u8 = 0x03;
+
+const ESCAPE: u8 = b'}';
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Incoming {
+    Packet(Vec<u8>),
+    Interrupt,
+    /// The last packet sent was not received correctly and must be sent again.
+    Nack,
+}
+
+fn read_byte(reader: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut byte = [0u8];
+    loop {
+        match reader.read(&mut byte) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(byte[0])),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Reads the next packet or interrupt from the connection, acknowledging packets. Returns `None` once the connection
+/// is closed.
+pub(crate) fn read_packet<C: Read + Write>(conn: &mut C) -> io::Result<Option<Incoming>> {
+    loop {
+        match read_byte(conn)? {
+            None => return Ok(None),
+            Some(b'$') => {}
+            Some(INTERRUPT) => return Ok(Some(Incoming::Interrupt)),
+            Some(b'-') => return Ok(Some(Incoming::Nack)),
+            // Acknowledgements and line noise.
+            Some(_) => continue,
+        }
+
+        let mut data = Vec::new();
+        let mut checksum = 0u8;
+        loop {
+            match read_byte(conn)? {
+                None => return Ok(None),
+                Some(b'#') => break,
+                Some(byte) => {
+                    checksum = checksum.wrapping_add(byte);
+                    data.push(byte);
+                }
+            }
+        }
+
+        let mut expected = [0u8; 2];
+        for digit in expected.iter_mut() {
+            match read_byte(conn)? {
+                None => return Ok(None),
+                Some(byte) => *digit = byte,
+            }
+        }
+
+        if decode_hex(&expected) != Some(vec![checksum]) {
+            conn.write_all(b"-")?;
+            conn.flush()?;
+            continue;
+        }
+        conn.write_all(b"+")?;
+        conn.flush()?;
+
+        return Ok(Some(Incoming::Packet(unescape(&data))));
+    }
+}
+
+/// Frames and writes a packet.
+pub(crate) fn write_packet(writer: &mut impl Write, data: &[u8]) -> io::Result<()> {
+    let escaped = escape(data);
+    let checksum = escaped
+        .iter()
+        .fold(0u8, |acc, byte| acc.wrapping_add(*byte));
+
+    writer.write_all(b"$")?;
+    writer.write_all(&escaped)?;
+    write!(writer, "#{checksum:02x}")?;
+    writer.flush()
+}
+
+fn escape(data: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(data.len());
+    for &byte in data {
+        if matches!(byte, b'$' | b'#' | b'}' | b'*') {
+            escaped.extend_from_slice(&[ESCAPE, byte ^ 0x20]);
+        } else {
+            escaped.push(byte);
+        }
+    }
+    escaped
+}
+
+fn unescape(data: &[u8]) -> Vec<u8> {
+    let mut unescaped = Vec::with_capacity(data.len());
+    let mut bytes = data.iter();
+    while let Some(&byte) = bytes.next() {
+        if byte == ESCAPE {
+            if let Some(&next) = bytes.next() {
+                unescaped.push(next ^ 0x20);
+            }
+        } else {
+            unescaped.push(byte);
+        }
+    }
+    unescaped
+}
+
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+pub(crate) fn decode_hex(hex: &[u8]) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    hex.chunks_exact(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An in-memory connection, reading from a fixed input and recording everything written.
+    struct Mock {
+        input: io::Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Read for Mock {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Mock {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn mock(input: &[u8]) -> Mock {
+        Mock {
+            input: io::Cursor::new(input.to_vec()),
+            output: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_read_packet() {
+        let mut conn = mock(b"+$m1000,4#8e$g#00\x03-");
+
+        assert_eq!(
+            read_packet(&mut conn).unwrap(),
+            Some(Incoming::Packet(b"m1000,4".to_vec()))
+        );
+        // The second packet has a bad checksum and is skipped.
+        assert_eq!(read_packet(&mut conn).unwrap(), Some(Incoming::Interrupt));
+        assert_eq!(read_packet(&mut conn).unwrap(), Some(Incoming::Nack));
+        assert_eq!(read_packet(&mut conn).unwrap(), None);
+
+        assert_eq!(conn.output, b"+-");
+    }
+
+    #[test]
+    fn test_write_packet_escapes() {
+        let mut output = Vec::new();
+        write_packet(&mut output, b"a#b").unwrap();
+        assert_eq!(output, b"$a}\x03b#43");
+
+        let mut conn = mock(&output);
+        assert_eq!(
+            read_packet(&mut conn).unwrap(),
+            Some(Incoming::Packet(b"a#b".to_vec()))
+        );
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(encode_hex(&[0x00, 0xab, 0x10]), "00ab10");
+        assert_eq!(decode_hex(b"00ab10"), Some(vec![0x00, 0xab, 0x10]));
+        assert_eq!(decode_hex(b"0"), None);
+        assert_eq!(decode_hex(b"zz"), None);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    emulator::{Emulator, HarvardEmulator, LinearEmulator},
+    memory::{MemAccessSize, MemoryProcessor},
+};
+
+/// An emulator that can be served to a debugger.
+///
+/// Registers and the program counter are accessed through [`Emulator::get_executor`], this trait only adds byte-wise
+/// memory access. Accesses follow the access modes of the emulator's memory, e.g., the public output cannot be read
+/// and the program cannot be written.
+pub trait DebugTarget: Emulator {
+    /// Reads the byte at the address, or returns `None` if it is not readable.
+    fn read_byte(&self, address: u32) -> Option<u8>;
+
+    /// Writes the byte at the address, returning whether it is writable.
+    fn write_byte(&mut self, address: u32, value: u8) -> bool;
+}
+
+impl DebugTarget for HarvardEmulator {
+    fn read_byte(&self, address: u32) -> Option<u8> {
+        // The data memory falls back to variable memory for all addresses, so the program must be checked first.
+        self.instruction_memory
+            .read(address, MemAccessSize::Byte)
+            .or_else(|_| self.data_memory.read(address, MemAccessSize::Byte))
+            .ok()
+            .map(|op| op.get_value() as u8)
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) -> bool {
+        self.instruction_memory
+            .read(address, MemAccessSize::Byte)
+            .is_err()
+            && self
+                .data_memory
+                .write(address, MemAccessSize::Byte, value.into())
+                .is_ok()
+    }
+}
+
+impl DebugTarget for LinearEmulator {
+    fn read_byte(&self, address: u32) -> Option<u8> {
+        self.memory
+            .read(address, MemAccessSize::Byte)
+            .ok()
+            .map(|op| op.get_value() as u8)
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) -> bool {
+        self.memory
+            .write(address, MemAccessSize::Byte, value.into())
+            .is_ok()
+    }
+}
@@ -0,0 +1,332 @@
+//! Inspection of Guest Binaries
+//!
+//! An [`Inspection`] reports what the zkVM will run for an ELF, much like `objdump`: the disassembled text with
+//! function labels, the data segments, and the memory layout the program is proven with. Custom instructions that
+//! invoke precompiles are named after the precompile, and instructions the zkVM does not support are collected in
+//! [`Inspection::unsupported`] with their location.
+//!
+//! The memory layout depends on the heap and stack usage of an execution, so it is only known once the program has
+//! been run, see [`Inspection::compute_memory_layout`].
+//!
+//! # Usage
+//!
+//! ```rust
+//! use nexus_vm::inspect::Inspection;
+//!
+//! let mut inspection = Inspection::from_path("test/fib_10.elf").unwrap();
+//! inspection.compute_memory_layout(&[], &[], &[]).unwrap();
+//!
+//! assert!(inspection.unsupported.is_empty());
+//! println!("{inspection}");
+//! ```
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use elf::{endian::LittleEndian, ElfBytes};
+use nexus_common::constants::WORD_SIZE;
+
+use crate::{
+    elf::{parse_precompile_metadata, ElfError, ElfFile, SymbolTable},
+    emulator::{Emulator, HarvardEmulator, LinearEmulator, LinearMemoryLayout},
+    error::{Result, VMError},
+    riscv::{decode_instruction, BuiltinOpcode, InstructionType, Register},
+};
+
+/// Opcode of the custom R-type instructions that invoke precompiles.
+const PRECOMPILE_OPCODE: u32 = 0b0001011;
+const MISC_MEM_OPCODE: u32 = 0b0001111;
+const SYSTEM_OPCODE: u32 = 0b1110011;
+const EBREAK: u32 = 0x0010_0073;
+/// `csrrw zero, cycle, zero`, which assemblers emit for `unimp` and compilers use to trap.
+const UNIMP: u32 = 0xc000_1073;
+
+/// Why an instruction cannot be run by the zkVM.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UnsupportedKind {
+    Ebreak,
+    Fence,
+    Csr,
+    /// Not a valid RV32I instruction, nor a known custom instruction.
+    Unknown,
+}
+
+impl fmt::Display for UnsupportedKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ebreak => "ebreak",
+            Self::Fence => "fence",
+            Self::Csr => "CSR access",
+            Self::Unknown => "unknown instruction",
+        })
+    }
+}
+
+/// An instruction the zkVM does not support.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UnsupportedInstruction {
+    pub pc: u32,
+    pub word: u32,
+    pub kind: UnsupportedKind,
+}
+
+/// Report on a guest binary.
+#[derive(Clone)]
+pub struct Inspection {
+    pub elf: ElfFile,
+    pub symbols: SymbolTable,
+    /// Paths of the precompiles the program calls, by their index in the custom instruction encoding.
+    pub precompiles: BTreeMap<u16, String>,
+    /// Unsupported instructions in the text, in address order. The `unimp` trap is not reported.
+    pub unsupported: Vec<UnsupportedInstruction>,
+    /// The memory layout of an execution, once computed.
+    pub memory_layout: Option<LinearMemoryLayout>,
+}
+
+impl Inspection {
+    pub fn from_bytes(data: &[u8]) -> std::result::Result<Self, ElfError> {
+        let elf = ElfFile::from_bytes(data)?;
+        let symbols = SymbolTable::from_bytes(data)?;
+        let precompiles =
+            parse_precompile_metadata(&ElfBytes::<LittleEndian>::minimal_parse(data)?, data)?
+                .into_iter()
+                // Paths are stored as token streams in a JSON string, e.g., `":: dummy_div :: DummyDiv"`.
+                .map(|(index, path)| (index, path.trim_matches('"').replace(' ', "")))
+                .collect();
+
+        let unsupported = elf
+            .instructions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &word)| {
+                classify(word).map(|kind| UnsupportedInstruction {
+                    pc: elf.base + (i * WORD_SIZE) as u32,
+                    word,
+                    kind,
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            elf,
+            symbols,
+            precompiles,
+            unsupported,
+            memory_layout: None,
+        })
+    }
+
+    pub fn from_path<P: AsRef<Path> + ?Sized>(path: &P) -> std::result::Result<Self, ElfError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Runs the program on the given inputs to compute the memory layout it is proven with.
+    pub fn compute_memory_layout(
+        &mut self,
+        public_input: &[u8],
+        private_input: &[u8],
+        ad: &[u8],
+    ) -> Result<LinearMemoryLayout> {
+        let mut harvard = HarvardEmulator::from_elf(&self.elf, public_input, private_input);
+        match harvard.execute(false) {
+            Err(VMError::VMExited(_)) => {}
+            Err(e) => return Err(e),
+            Ok(_) => unreachable!(),
+        }
+
+        let linear = LinearEmulator::from_harvard(&harvard, self.elf.clone(), ad, private_input)?;
+        self.memory_layout = Some(linear.memory_layout);
+        Ok(linear.memory_layout)
+    }
+
+    /// Disassembles the instruction, naming precompile calls after the precompile.
+    pub fn disassemble(&self, word: u32) -> String {
+        let instruction = decode_instruction(word);
+
+        if word & 0x7f == PRECOMPILE_OPCODE && instruction.ins_type == InstructionType::RType {
+            // The index is encoded as `fn7` followed by `fn3`.
+            let index = (((word >> 25) << 3) | ((word >> 12) & 0b111)) as u16;
+            if let Some(path) = self.precompiles.get(&index) {
+                return format!(
+                    "{path} {}, {}, {}",
+                    instruction.op_a,
+                    instruction.op_b,
+                    Register::from(instruction.op_c as u8),
+                );
+            }
+        }
+
+        instruction.to_string()
+    }
+}
+
+/// Returns why the instruction is unsupported, if it is.
+fn classify(word: u32) -> Option<UnsupportedKind> {
+    match word & 0x7f {
+        _ if word == UNIMP => None,
+        _ if word == EBREAK => Some(UnsupportedKind::Ebreak),
+        MISC_MEM_OPCODE => Some(UnsupportedKind::Fence),
+        SYSTEM_OPCODE if (word >> 12) & 0b111 != 0 => Some(UnsupportedKind::Csr),
+        _ if decode_instruction(word).opcode.builtin() == Some(BuiltinOpcode::UNIMPL) => {
+            Some(UnsupportedKind::Unknown)
+        }
+        _ => None,
+    }
+}
+
+/// Writes the contiguous address ranges of a memory image.
+fn write_segments(f: &mut fmt::Formatter<'_>, image: &BTreeMap<u32, u32>) -> fmt::Result {
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for &address in image.keys() {
+        match ranges.last_mut() {
+            Some((_, end)) if *end == address => *end += WORD_SIZE as u32,
+            _ => ranges.push((address, address + WORD_SIZE as u32)),
+        }
+    }
+
+    if ranges.is_empty() {
+        writeln!(f, "  (empty)")?;
+    }
+    for (start, end) in ranges {
+        writeln!(f, "  {start:08x} - {end:08x}  ({} bytes)", end - start)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Inspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "entry point: {:08x}", self.elf.entry)?;
+        writeln!(f, "base address: {:08x}", self.elf.base)?;
+
+        if !self.precompiles.is_empty() {
+            writeln!(f, "\nprecompiles:")?;
+            for (index, path) in &self.precompiles {
+                writeln!(f, "  {index:3}  {path}")?;
+            }
+        }
+
+        writeln!(f, "\ntext:")?;
+        for (i, &word) in self.elf.instructions.iter().enumerate() {
+            let pc = self.elf.base + (i * WORD_SIZE) as u32;
+            if let Some(symbol) = self
+                .symbols
+                .lookup(pc)
+                .filter(|symbol| symbol.address == pc)
+            {
+                writeln!(f, "\n{pc:08x} <{}>:", symbol.demangled_name())?;
+            }
+            writeln!(f, "  {pc:08x}:  {word:08x}  {}", self.disassemble(word))?;
+        }
+
+        writeln!(f, "\nread-only data:")?;
+        write_segments(f, &self.elf.rom_image)?;
+        writeln!(f, "\nread-write data:")?;
+        write_segments(f, &self.elf.ram_image)?;
+
+        if let Some(layout) = &self.memory_layout {
+            writeln!(f, "\nmemory layout:")?;
+            for (name, start, end) in [
+                ("program", layout.program_start(), layout.program_end()),
+                (
+                    "public input",
+                    layout.public_input_start(),
+                    layout.public_input_end(),
+                ),
+                ("associated data", layout.ad_start(), layout.ad_end()),
+                (
+                    "exit code",
+                    layout.exit_code(),
+                    layout.public_output_start(),
+                ),
+                (
+                    "public output",
+                    layout.public_output_start(),
+                    layout.public_output_end(),
+                ),
+                ("heap", layout.heap_start(), layout.heap_end()),
+                ("stack", layout.stack_bottom(), layout.stack_top()),
+            ] {
+                writeln!(f, "  {name:<16} {start:08x} - {end:08x}")?;
+            }
+        }
+
+        if self.unsupported.is_empty() {
+            writeln!(f, "\nno unsupported instructions")?;
+        } else {
+            writeln!(f, "\nunsupported instructions:")?;
+            for instruction in &self.unsupported {
+                let function = self
+                    .symbols
+                    .lookup(instruction.pc)
+                    .map(|symbol| format!(" in <{}>", symbol.demangled_name()))
+                    .unwrap_or_default();
+                writeln!(
+                    f,
+                    "  {:08x}:  {:08x}  {}{function}",
+                    instruction.pc, instruction.word, instruction.kind
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(EBREAK), Some(UnsupportedKind::Ebreak));
+        // fence iorw, iorw
+        assert_eq!(classify(0x0ff0_000f), Some(UnsupportedKind::Fence));
+        // csrr a0, cycle
+        assert_eq!(classify(0xc000_2573), Some(UnsupportedKind::Csr));
+        assert_eq!(classify(0x0000_0000), Some(UnsupportedKind::Unknown));
+
+        assert_eq!(classify(UNIMP), None);
+        // ecall
+        assert_eq!(classify(0x0000_0073), None);
+        // auipc gp, 0x2
+        assert_eq!(classify(0x0000_2197), None);
+    }
+
+    #[test]
+    fn test_inspect_fib() {
+        let mut inspection = Inspection::from_path("test/fib_10.elf").unwrap();
+        assert!(inspection.precompiles.is_empty());
+        assert!(inspection.unsupported.is_empty());
+
+        let layout = inspection.compute_memory_layout(&[], &[], &[]).unwrap();
+        assert_eq!(inspection.memory_layout, Some(layout));
+
+        let report = inspection.to_string();
+        assert!(report.contains("\n00001000 <_start>:\n  00001000:  00002197  auipc gp, 0x2\n"));
+        assert!(report.contains(" <main>:\n"));
+        assert!(report.contains("\nmemory layout:\n  program "));
+        assert!(report.contains("\nno unsupported instructions\n"));
+    }
+
+    #[test]
+    fn test_inspect_precompiles() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/test/program_with_two_precompiles.elf"
+        );
+        let inspection = Inspection::from_path(path).unwrap();
+
+        assert_eq!(
+            inspection.precompiles,
+            BTreeMap::from([
+                (0, "::dummy_div::DummyDiv".to_string()),
+                (1, "::dummy_hash::DummyHash".to_string()),
+            ])
+        );
+
+        // `fn7 = 0, fn3 = 1`, i.e., precompile 1, with `rd = a0, rs1 = a0, rs2 = a1`.
+        let word = 0x0b | (10 << 7) | (1 << 12) | (10 << 15) | (11 << 20);
+        assert_eq!(
+            inspection.disassemble(word),
+            "::dummy_hash::DummyHash a0, a0, a1"
+        );
+    }
+}
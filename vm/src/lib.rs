@@ -2,6 +2,8 @@ pub mod cpu;
 pub mod elf;
 pub mod emulator;
 pub mod error;
+pub mod gdb;
+pub mod inspect;
 pub mod memory;
 pub mod riscv;
 pub mod system;
@@ -17,6 +17,9 @@
 //!    - Exit: Terminate the program with a specified error code.
 //!    - CycleCount: Profile function execution time.
 //!    - ReadFromPrivateInput: Read data from a private input tape.
+//!    - ReadBytesFromPrivateInput: Copy a run of bytes from the private input tape into memory.
+//!    - ReadFromAuxiliaryInput: Read unconstrained hints from an auxiliary input tape.
+//!    - QueryOracle: Query a host oracle, whose response is appended to the auxiliary input tape.
 //!    - OverwriteStackPointer: Modify the stack pointer based on memory layout.
 //!    - OverwriteHeapPointer: Modify the heap pointer based on memory layout.
 //! 3. Handling memory interactions for syscalls.
@@ -33,8 +36,8 @@ use nexus_common::cpu::Registers;
 use crate::{
     cpu::Cpu,
     emulator::{Executor, LinearMemoryLayout},
-    error::{Result, VMError},
-    memory::{LoadOp, MemoryProcessor, StoreOp},
+    error::{MemoryError, Result, VMError},
+    memory::{LoadOp, MemAccessSize, MemoryProcessor, StoreOp},
     riscv::{BuiltinOpcode, Instruction, Register},
 };
 
@@ -48,6 +51,8 @@ pub enum SyscallCode {
     OverwriteStackPointer = 0x402,
     OverwriteHeapPointer = 0x403,
     ReadFromAuxiliaryInput = 0x404,
+    QueryOracle = 0x405,
+    ReadBytesFromPrivateInput = 0x406,
 }
 
 impl SyscallCode {
@@ -59,7 +64,9 @@ impl SyscallCode {
             0x401 => SyscallCode::CycleCount,
             0x402 => SyscallCode::OverwriteStackPointer,
             0x403 => SyscallCode::OverwriteHeapPointer,
-            //0x404 => SyscallCode::ReadFromAuxiliaryInput,
+            0x404 => SyscallCode::ReadFromAuxiliaryInput,
+            0x405 => SyscallCode::QueryOracle,
+            0x406 => SyscallCode::ReadBytesFromPrivateInput,
             _ => return Err(VMError::UnimplementedSyscall(value, pc)),
         };
         Ok(code)
@@ -76,6 +83,8 @@ impl From<u32> for SyscallCode {
             0x402 => SyscallCode::OverwriteStackPointer,
             0x403 => SyscallCode::OverwriteHeapPointer,
             0x404 => SyscallCode::ReadFromAuxiliaryInput,
+            0x405 => SyscallCode::QueryOracle,
+            0x406 => SyscallCode::ReadBytesFromPrivateInput,
             _ => panic!("Invalid syscall code"),
         }
     }
@@ -91,6 +100,8 @@ impl From<SyscallCode> for u32 {
             SyscallCode::OverwriteStackPointer => 0x402,
             SyscallCode::OverwriteHeapPointer => 0x403,
             SyscallCode::ReadFromAuxiliaryInput => 0x404,
+            SyscallCode::QueryOracle => 0x405,
+            SyscallCode::ReadBytesFromPrivateInput => 0x406,
         }
     }
 }
@@ -117,6 +128,11 @@ pub struct SyscallInstruction {
     /// These correspond to registers X10 through X16 ("a0" through "a6").
     /// The number and meaning of arguments depend on the specific system call.
     args: Vec<u32>,
+
+    /// Bytes to store to memory at the given address, which is done by `memory_write`.
+    ///
+    /// Unlike the memory accesses of other syscalls, these stores are recorded.
+    store: Option<(u32, Vec<u8>)>,
 }
 
 impl SyscallInstruction {
@@ -139,6 +155,7 @@ impl SyscallInstruction {
                 cpu.registers[Register::X15],
                 cpu.registers[Register::X16],
             ],
+            store: None,
         })
     }
 
@@ -253,6 +270,57 @@ impl SyscallInstruction {
         Ok(())
     }
 
+    /// Copies up to `buflen` bytes from the private input tape into memory at `buf`.
+    ///
+    /// The number of bytes copied is returned in a0, which is less than `buflen` only if the tape is exhausted.
+    fn execute_read_bytes_from_private_input(
+        &mut self,
+        private_input_tape: &mut VecDeque<u8>,
+        buf: u32,
+        buflen: u32,
+    ) -> Result<()> {
+        let count = private_input_tape.len().min(buflen as usize);
+        let bytes: Vec<u8> = private_input_tape.drain(..count).collect();
+
+        self.result = Some((Register::X10, count as u32));
+        self.store = Some((buf, bytes));
+        Ok(())
+    }
+
+    /// Reads the next byte of the auxiliary input tape, which holds hints that the guest checks rather than trusts.
+    ///
+    /// Like the private input tape, the byte is returned in a0, or `u32::MAX` if the tape is exhausted.
+    fn execute_read_from_auxiliary_input(
+        &mut self,
+        auxiliary_input_tape: &mut VecDeque<u8>,
+    ) -> Result<()> {
+        self.execute_read_from_private_input(auxiliary_input_tape)
+    }
+
+    /// Queries the oracle registered on `channel` with the request in memory, see [`crate::emulator::Oracles`].
+    ///
+    /// The response is appended to the auxiliary input tape and its length is returned in a0. When the oracles replay
+    /// a recorded execution, the request is not read.
+    fn execute_query_oracle(
+        &mut self,
+        executor: &mut Executor,
+        memory: &impl MemoryProcessor,
+        channel: u32,
+        buf: u32,
+        buflen: u32,
+    ) -> Result<()> {
+        let request = if executor.oracles.is_replaying() {
+            Vec::new()
+        } else {
+            memory.read_bytes(buf, buflen as _)?
+        };
+
+        let response = executor.oracles.query(channel, &request)?;
+        self.result = Some((Register::X10, response.len() as u32));
+        executor.auxiliary_input_tape.extend(response);
+        Ok(())
+    }
+
     fn execute_overwrite_stack_pointer(
         &mut self,
         memory_layout: Option<LinearMemoryLayout>,
@@ -348,13 +416,43 @@ impl SyscallInstruction {
 
             SyscallCode::OverwriteHeapPointer => self.execute_overwrite_heap_pointer(memory_layout),
 
-            SyscallCode::ReadFromAuxiliaryInput => unreachable!(), // unreachable since parsing of the code will fail
+            SyscallCode::ReadFromAuxiliaryInput => {
+                self.execute_read_from_auxiliary_input(&mut executor.auxiliary_input_tape)
+            }
+
+            SyscallCode::ReadBytesFromPrivateInput => {
+                let buf = self.args[0];
+                let buflen = self.args[1];
+                self.execute_read_bytes_from_private_input(
+                    &mut executor.private_input_tape,
+                    buf,
+                    buflen,
+                )
+            }
+
+            SyscallCode::QueryOracle => {
+                let channel = self.args[0];
+                let buf = self.args[1];
+                let buflen = self.args[2];
+                self.execute_query_oracle(executor, memory, channel, buf, buflen)
+            }
         }
     }
 
     // Writes to memory for syscall instructions.
-    pub fn memory_write(&self, _memory: &mut impl MemoryProcessor) -> Result<HashSet<StoreOp>> {
-        Ok(HashSet::<StoreOp>::new())
+    pub fn memory_write(&self, memory: &mut impl MemoryProcessor) -> Result<HashSet<StoreOp>> {
+        let mut store_ops = HashSet::<StoreOp>::new();
+
+        if let Some((address, bytes)) = &self.store {
+            for (i, &byte) in bytes.iter().enumerate() {
+                let address = address
+                    .checked_add(i as u32)
+                    .ok_or(MemoryError::AddressCalculationOverflow)?;
+                store_ops.insert(memory.write(address, MemAccessSize::Byte, byte as u32)?);
+            }
+        }
+
+        Ok(store_ops)
     }
 
     // All the write back to registers is done in the write_back function
@@ -397,6 +495,7 @@ mod tests {
             code: SyscallCode::Write,
             result: Some((Register::X10, 0)),
             args: vec![fd, buf_addr, buf_len as _, 0, 0, 0, 0],
+            store: None,
         };
 
         emulator
@@ -425,6 +524,7 @@ mod tests {
             code: SyscallCode::Write,
             result: Some((Register::X10, 0)),
             args: vec![fd, buf_addr, buf_len as _, 0, 0, 0, 0],
+            store: None,
         };
 
         emulator
@@ -450,6 +550,7 @@ mod tests {
             code: SyscallCode::Exit,
             result: Some((Register::X10, 0)),
             args: vec![error_code, 0, 0, 0, 0, 0, 0],
+            store: None,
         };
 
         let result = syscall_instruction.execute_exit(error_code);
@@ -466,6 +567,7 @@ mod tests {
             code: SyscallCode::OverwriteStackPointer,
             result: Some((Register::X10, 0)),
             args: vec![0, 0, 0, 0, 0, 0, 0],
+            store: None,
         };
 
         let _ = syscall_instruction.execute_overwrite_stack_pointer(Some(memory_layout));
@@ -485,6 +587,7 @@ mod tests {
             code: SyscallCode::OverwriteStackPointer,
             result: Some((Register::X10, 0)),
             args: vec![0, 0, 0, 0, 0, 0, 0],
+            store: None,
         };
 
         let _ = syscall_instruction.execute_overwrite_heap_pointer(Some(memory_layout));
@@ -506,6 +609,7 @@ mod tests {
             code: SyscallCode::CycleCount,
             result: Some((Register::X10, 0)),
             args: vec![buf_addr, buf_len as _, 0, 0, 0, 0, 0],
+            store: None,
         };
 
         emulator
@@ -573,6 +677,7 @@ mod tests {
             code: SyscallCode::ReadFromPrivateInput,
             result: Some((Register::X10, 0)),
             args: vec![],
+            store: None,
         };
 
         // Test reading values
@@ -593,4 +698,115 @@ mod tests {
             .result
             .is_some_and(|(reg, value)| { reg == Register::X10 && value == u32::MAX }));
     }
+
+    #[test]
+    fn test_execute_read_from_auxiliary_input() {
+        let mut emulator = setup_emulator();
+        emulator.executor.private_input_tape = VecDeque::from(vec![1]);
+        emulator.executor.auxiliary_input_tape = VecDeque::from(vec![42]);
+
+        let mut cpu = Cpu::default();
+        cpu.registers
+            .write(Register::X17, SyscallCode::ReadFromAuxiliaryInput as u32);
+        let instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0);
+
+        for expected_value in [42, u32::MAX] {
+            let mut syscall_instruction = SyscallInstruction::decode(&instruction, &cpu).unwrap();
+            syscall_instruction
+                .execute(&mut emulator.executor, &emulator.data_memory, None, false)
+                .expect("Failed to execute read from auxiliary input");
+            assert_eq!(
+                syscall_instruction.get_result(),
+                Some((Register::X10, expected_value))
+            );
+        }
+
+        // The private input tape is left untouched.
+        assert_eq!(
+            emulator.executor.private_input_tape,
+            VecDeque::from(vec![1])
+        );
+    }
+
+    #[test]
+    fn test_execute_query_oracle() {
+        let mut emulator = setup_emulator();
+        emulator.executor.auxiliary_input_tape = VecDeque::from(vec![1]);
+        emulator
+            .executor
+            .oracles
+            .register(3, |request: &[u8]| request.repeat(2));
+        emulator.data_memory.write_bytes(0x10, b"ab").unwrap();
+
+        let mut cpu = Cpu::default();
+        cpu.registers
+            .write(Register::X17, SyscallCode::QueryOracle as u32);
+        cpu.registers.write(Register::X10, 3);
+        cpu.registers.write(Register::X11, 0x10);
+        cpu.registers.write(Register::X12, 2);
+        let instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0);
+
+        let mut syscall_instruction = SyscallInstruction::decode(&instruction, &cpu).unwrap();
+        syscall_instruction
+            .execute(&mut emulator.executor, &emulator.data_memory, None, false)
+            .expect("Failed to execute oracle query");
+        assert_eq!(syscall_instruction.get_result(), Some((Register::X10, 4)));
+        assert_eq!(
+            emulator.executor.auxiliary_input_tape,
+            VecDeque::from(b"\x01abab".to_vec())
+        );
+
+        // Queries on a channel without an oracle fail.
+        cpu.registers.write(Register::X10, 4);
+        let mut syscall_instruction = SyscallInstruction::decode(&instruction, &cpu).unwrap();
+        assert_eq!(
+            syscall_instruction.execute(&mut emulator.executor, &emulator.data_memory, None, false),
+            Err(VMError::UnknownOracleChannel(4))
+        );
+    }
+
+    #[test]
+    fn test_execute_read_bytes_from_private_input() {
+        let mut emulator = setup_emulator();
+        emulator.executor.private_input_tape = VecDeque::from(vec![1, 2, 3, 4, 5]);
+
+        let mut cpu = Cpu::default();
+        cpu.registers
+            .write(Register::X17, SyscallCode::ReadBytesFromPrivateInput as u32);
+        cpu.registers.write(Register::X10, 0x20);
+        cpu.registers.write(Register::X11, 3);
+        let instruction = Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0);
+
+        // Reads are cut short at the end of the tape.
+        for (address, expected) in [(0x20, vec![1, 2, 3]), (0x23, vec![4, 5]), (0x25, vec![])] {
+            cpu.registers.write(Register::X10, address);
+            let mut syscall_instruction = SyscallInstruction::decode(&instruction, &cpu).unwrap();
+            syscall_instruction
+                .execute(&mut emulator.executor, &emulator.data_memory, None, false)
+                .expect("Failed to execute read bytes from private input");
+            assert_eq!(
+                syscall_instruction.get_result(),
+                Some((Register::X10, expected.len() as u32))
+            );
+
+            // Every byte stored is recorded.
+            let store_ops = syscall_instruction
+                .memory_write(&mut emulator.data_memory)
+                .unwrap();
+            assert_eq!(store_ops.len(), expected.len());
+            for (i, &byte) in expected.iter().enumerate() {
+                assert!(store_ops.contains(&StoreOp::Op(
+                    MemAccessSize::Byte,
+                    address + i as u32,
+                    byte as u32,
+                    0
+                )));
+            }
+        }
+
+        assert_eq!(
+            emulator.data_memory.read_bytes(0x20, 6).unwrap(),
+            vec![1, 2, 3, 4, 5, 0]
+        );
+    }
 }
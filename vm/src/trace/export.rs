@@ -0,0 +1,340 @@
+//! Exporting Traces for External Tooling
+//!
+//! Traces are Rust values, so consuming them otherwise requires linking against this crate. This module writes them
+//! out in two formats instead:
+//!
+//! - [`write_text`] renders every step as a line of text, so that the traces of two guest versions can be compared
+//!   with ordinary `diff`.
+//! - [`write_chrome_trace`] renders the regions marked by the cycle tracker (`nexus_rt::profile`) as Chrome trace
+//!   event JSON, which can be opened in Perfetto or `chrome://tracing`.
+//!
+//! # Text Format
+//!
+//! The first line is the header `nexus-trace v1`. Every following line describes one step, in execution order, as six
+//! tab-separated fields:
+//!
+//! ```text
+//! timestamp  pc        word      disassembly        rd write     memory accesses
+//! 1          00001000  00002197  auipc gp, 0x2      gp=00003000  -
+//! 13         00001030  00112623  sw ra, 12(sp)      -            S4@00003c1c=00001020
+//! ```
+//!
+//! - `timestamp` is the decimal cycle of the step.
+//! - `pc` and `word` are the address and encoding of the instruction, as eight hex digits.
+//! - `disassembly` is the instruction in assembler syntax.
+//! - `rd write` is `<register>=<value>` if the step writes a register other than `zero`, with the register given by
+//!   its ABI name, and `-` otherwise.
+//! - `memory accesses` is a comma-separated list of `L<size>@<address>=<value>` for loads and
+//!   `S<size>@<address>=<value>` for stores, ordered by address, or `-` if the step does not access memory. The size
+//!   is in bytes and the value is the one loaded or stored.
+//!
+//! All hex values are lowercase and without prefix. Traces padded to a uniform block size end with `unimp` steps that
+//! are not executed. To compare executions of programs that differ in length, drop the timestamps with `cut -f2-`.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use nexus_vm::{elf::ElfFile, trace::{export, k_trace}};
+//!
+//! let elf_file = ElfFile::from_path("test/fib_10.elf").unwrap();
+//! let (view, trace) = k_trace(elf_file, &[], &[], &[], 1).unwrap();
+//!
+//! let mut text = Vec::new();
+//! export::write_text(&trace, &mut text).unwrap();
+//!
+//! let mut json = Vec::new();
+//! export::write_chrome_trace(&trace, &view, &mut json).unwrap();
+//! ```
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use nexus_common::cpu::Registers;
+
+use super::{Step, Trace};
+use crate::{
+    cpu::RegisterFile,
+    emulator::{InternalView, View},
+    memory::MemoryRecord,
+    riscv::{BuiltinOpcode, InstructionType, Register},
+    system::SyscallCode,
+};
+
+/// Header line of the text format, to be bumped whenever the format changes.
+pub const TEXT_HEADER: &str = "nexus-trace v1";
+
+/// Writes the trace in the text format described in the [module documentation](self).
+pub fn write_text(trace: &impl Trace, out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "{TEXT_HEADER}")?;
+
+    for block in trace.get_blocks_iter() {
+        let mut regs = block.regs;
+
+        for step in &block.steps {
+            let rd = match write_back(step, &regs) {
+                Some((reg, value)) => {
+                    regs.write(reg, value);
+                    format!("{reg}={value:08x}")
+                }
+                None => "-".to_string(),
+            };
+
+            let mut records: Vec<&MemoryRecord> = step.memory_records.iter().collect();
+            records.sort_by_key(|record| (record.get_address(), record.get_timestamp()));
+            let accesses = if records.is_empty() {
+                "-".to_string()
+            } else {
+                records
+                    .iter()
+                    .map(|record| {
+                        let kind = match record {
+                            MemoryRecord::LoadRecord(..) => 'L',
+                            MemoryRecord::StoreRecord(..) => 'S',
+                        };
+                        format!(
+                            "{kind}{}@{:08x}={:08x}",
+                            record.get_size() as u32,
+                            record.get_address(),
+                            record.get_value(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+
+            writeln!(
+                out,
+                "{}\t{:08x}\t{:08x}\t{}\t{rd}\t{accesses}",
+                step.timestamp, step.pc, step.raw_instruction, step.instruction,
+            )?;
+        }
+    }
+
+    out.flush()
+}
+
+/// Writes the regions marked by the cycle tracker as Chrome trace event JSON.
+///
+/// Every start and end marker becomes a duration event on a single track, timestamped with the cycle of its `ecall`,
+/// which viewers will display as microseconds. The labels are read from the initial memory of the `view`, updated by
+/// the stores in the trace.
+pub fn write_chrome_trace(trace: &impl Trace, view: &View, out: &mut impl Write) -> io::Result<()> {
+    let mut memory: HashMap<u32, u8> = view
+        .get_initial_memory()
+        .iter()
+        .map(|entry| (entry.address, entry.value))
+        .collect();
+
+    write!(out, "{{\"traceEvents\":[")?;
+    let mut first = true;
+
+    for block in trace.get_blocks_iter() {
+        let mut regs = block.regs;
+
+        for step in &block.steps {
+            if is_syscall(step, &regs, SyscallCode::CycleCount) {
+                let label: Vec<u8> = (0..regs.read(Register::X11))
+                    .map(|offset| {
+                        let address = regs.read(Register::X10).wrapping_add(offset);
+                        memory.get(&address).copied().unwrap_or_default()
+                    })
+                    .collect();
+                let label = String::from_utf8_lossy(&label);
+
+                let event = match label.split_once('#') {
+                    Some(("^", name)) => Some(('B', name)),
+                    Some(("$", name)) => Some(('E', name)),
+                    // Malformed labels already failed the first pass, so cannot occur in a finished trace.
+                    _ => None,
+                };
+                if let Some((phase, name)) = event {
+                    if !first {
+                        write!(out, ",")?;
+                    }
+                    first = false;
+                    write!(
+                        out,
+                        "\n{{\"name\":\"{}\",\"cat\":\"cycle-tracker\",\"ph\":\"{phase}\",\"ts\":{},\"pid\":0,\"tid\":0}}",
+                        escape_json(name),
+                        step.timestamp,
+                    )?;
+                }
+            }
+
+            for record in &step.memory_records {
+                if let MemoryRecord::StoreRecord((size, address, value, _), _) = record {
+                    let bytes = value.to_le_bytes();
+                    for offset in 0..*size as u32 {
+                        memory.insert(address + offset, bytes[offset as usize]);
+                    }
+                }
+            }
+            if let Some((reg, value)) = write_back(step, &regs) {
+                regs.write(reg, value);
+            }
+        }
+    }
+
+    writeln!(out, "\n]}}")?;
+    out.flush()
+}
+
+fn is_syscall(step: &Step, regs: &RegisterFile, code: SyscallCode) -> bool {
+    step.instruction.opcode.builtin() == Some(BuiltinOpcode::ECALL)
+        && regs.read(Register::X17) == code as u32
+}
+
+/// Returns the register written by the step, given the registers before it, and the value written.
+///
+/// The result of a step is not always a register write, e.g., branches report the next pc, so this follows the
+/// instruction semantics instead.
+fn write_back(step: &Step, regs: &RegisterFile) -> Option<(Register, u32)> {
+    let value = step.result?;
+
+    let reg = if step.instruction.is_system_instruction() {
+        if is_syscall(step, regs, SyscallCode::OverwriteStackPointer) {
+            Register::X2
+        } else if is_syscall(step, regs, SyscallCode::ReadFromPrivateInput)
+            || is_syscall(step, regs, SyscallCode::ReadBytesFromPrivateInput)
+            || is_syscall(step, regs, SyscallCode::ReadFromAuxiliaryInput)
+            || is_syscall(step, regs, SyscallCode::QueryOracle)
+            || is_syscall(step, regs, SyscallCode::OverwriteHeapPointer)
+        {
+            Register::X10
+        } else {
+            // Exits report the exit code without writing it.
+            return None;
+        }
+    } else {
+        match step.instruction.ins_type {
+            InstructionType::SType | InstructionType::BType | InstructionType::Unimpl => {
+                return None
+            }
+            _ => step.instruction.op_a,
+        }
+    };
+
+    (reg != Register::X0).then_some((reg, value))
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        elf::ElfFile,
+        emulator::{MemoryInitializationEntry, ProgramInfo},
+        memory::MemoryRecords,
+        riscv::{Instruction, Opcode},
+        trace::{k_trace, Block, UniformTrace},
+    };
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_write_text() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").unwrap();
+        let (_, trace) = k_trace(elf_file, &[], &[], &[], 1).unwrap();
+
+        let mut text = Vec::new();
+        write_text(&trace, &mut text).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), trace.get_num_steps() + 1);
+        assert_eq!(lines[0], TEXT_HEADER);
+        assert_eq!(
+            lines[1],
+            "1\t00001000\t00002197\tauipc gp, 0x2\tgp=00003000\t-"
+        );
+        assert_eq!(
+            lines[13],
+            "13\t00001030\t00112623\tsw ra, 12(sp)\t-\tS4@00003c1c=00001020"
+        );
+        // The final exit does not write its exit code to a register.
+        assert!(lines.last().unwrap().ends_with("\tecall\t-\t-"));
+    }
+
+    #[test]
+    fn test_write_chrome_trace() {
+        let label = |address: u32, text: &str| {
+            text.bytes()
+                .enumerate()
+                .map(move |(i, value)| MemoryInitializationEntry {
+                    address: address + i as u32,
+                    value,
+                })
+                .collect::<Vec<_>>()
+        };
+        let initial_memory = [label(0x100, "^#fib"), label(0x200, "$#fib")].concat();
+        let view = View::new(
+            &None,
+            &Vec::new(),
+            &ProgramInfo::dummy(),
+            &initial_memory,
+            0,
+            &Vec::new(),
+            &Vec::new(),
+            &Vec::new(),
+        );
+
+        let ecall = Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0);
+        let addi = Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0x200);
+        let step = |timestamp, instruction: &Instruction, result| Step {
+            timestamp,
+            pc: 0x1000 + 4 * timestamp,
+            next_pc: 0x1004 + 4 * timestamp,
+            raw_instruction: instruction.encode(),
+            instruction: instruction.clone(),
+            result,
+            memory_records: MemoryRecords::default(),
+        };
+
+        let mut regs = RegisterFile::new();
+        regs.write(Register::X10, 0x100);
+        regs.write(Register::X11, 5);
+        regs.write(Register::X17, SyscallCode::CycleCount as u32);
+        let trace = UniformTrace {
+            k: 3,
+            blocks: vec![Block {
+                regs,
+                steps: vec![
+                    step(1, &ecall, None),
+                    step(2, &addi, Some(0x200)),
+                    step(3, &ecall, None),
+                ],
+            }],
+            ..Default::default()
+        };
+
+        let mut json = Vec::new();
+        write_chrome_trace(&trace, &view, &mut json).unwrap();
+
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            "{\"traceEvents\":[\n\
+             {\"name\":\"fib\",\"cat\":\"cycle-tracker\",\"ph\":\"B\",\"ts\":1,\"pid\":0,\"tid\":0},\n\
+             {\"name\":\"fib\",\"cat\":\"cycle-tracker\",\"ph\":\"E\",\"ts\":3,\"pid\":0,\"tid\":0}\n\
+             ]}\n"
+        );
+    }
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(escape_json("src/main.rs:fib"), "src/main.rs:fib");
+        assert_eq!(escape_json("a\"b\\c\n"), "a\\\"b\\\\c\\u000a");
+    }
+}
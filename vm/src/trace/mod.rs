@@ -1,9 +1,15 @@
+pub mod export;
+pub mod segment;
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
     cpu::{instructions::InstructionResult, RegisterFile},
     elf::ElfFile,
-    emulator::{Emulator, HarvardEmulator, InternalView, LinearEmulator, LinearMemoryLayout, View},
+    emulator::{
+        Emulator, HarvardEmulator, InternalView, LinearEmulator, LinearMemoryLayout, Oracles,
+        Precompiles, View,
+    },
     error::{Result, VMError},
     memory::MemoryRecords,
     riscv::{BasicBlock, Instruction},
@@ -292,15 +298,88 @@ pub fn k_trace(
     public_input: &[u8],
     private_input: &[u8],
     k: usize,
+) -> Result<(View, UniformTrace)> {
+    k_trace_with_hints(elf, ad, public_input, private_input, &[], k)
+}
+
+/// Trace a program over an ELF for a given `k`, with `hints` on the auxiliary input tape.
+///
+/// See [`k_trace`]. Hints are read by both passes but, unlike the inputs, are not part of the returned view.
+pub fn k_trace_with_hints(
+    elf: ElfFile,
+    ad: &[u8],
+    public_input: &[u8],
+    private_input: &[u8],
+    hints: &[u8],
+    k: usize,
+) -> Result<(View, UniformTrace)> {
+    k_trace_with_oracles(
+        elf,
+        ad,
+        public_input,
+        private_input,
+        hints,
+        Oracles::default(),
+        k,
+    )
+}
+
+/// Trace a program over an ELF for a given `k`, with `hints` on the auxiliary input tape and host `oracles` to query.
+///
+/// See [`k_trace_with_hints`]. The oracles are only called in the first pass, and the second pass replays their
+/// recorded responses.
+pub fn k_trace_with_oracles(
+    elf: ElfFile,
+    ad: &[u8],
+    public_input: &[u8],
+    private_input: &[u8],
+    hints: &[u8],
+    oracles: Oracles,
+    k: usize,
+) -> Result<(View, UniformTrace)> {
+    k_trace_with_precompiles(
+        elf,
+        ad,
+        public_input,
+        private_input,
+        hints,
+        oracles,
+        &Precompiles::default(),
+        k,
+    )
+}
+
+/// Trace a program over an ELF for a given `k`, running the custom instructions of the guest with `precompiles`.
+///
+/// See [`k_trace_with_oracles`]. Both passes execute the same precompiles.
+#[allow(clippy::too_many_arguments)]
+pub fn k_trace_with_precompiles(
+    elf: ElfFile,
+    ad: &[u8],
+    public_input: &[u8],
+    private_input: &[u8],
+    hints: &[u8],
+    oracles: Oracles,
+    precompiles: &Precompiles,
+    k: usize,
 ) -> Result<(View, UniformTrace)> {
     assert!(k > 0);
     let mut harvard = HarvardEmulator::from_elf(&elf, public_input, private_input);
+    harvard
+        .get_executor_mut()
+        .instruction_executor
+        .add_precompiles(precompiles)?;
+    harvard.set_auxiliary_input(hints);
+    harvard.get_executor_mut().oracles = oracles;
     harvard.get_executor_mut().capture_logs(true);
 
     match harvard.execute(false) {
         Err(VMError::VMExited(_)) => {
             // todo: consistency check i/o between harvard and linear?
             let mut linear = LinearEmulator::from_harvard(&harvard, elf, ad, private_input)?;
+            linear.set_auxiliary_input(hints);
+            linear.get_executor_mut().oracles =
+                Oracles::replay(harvard.get_executor().oracles.transcript());
 
             let mut trace = UniformTrace {
                 memory_layout: linear.memory_layout,
@@ -338,8 +417,11 @@ pub fn k_trace(
 
 /// Similar to `k_trace`, but uses HarvardEmulator and supports Intermediate Representation (IR) as input instead of an ELF file.
 pub fn k_trace_direct(basic_blocks: &Vec<BasicBlock>, k: usize) -> Result<(View, UniformTrace)> {
-    let mut harvard = HarvardEmulator::from_basic_blocks(basic_blocks);
+    k_trace_harvard(HarvardEmulator::from_basic_blocks(basic_blocks), k)
+}
 
+/// Similar to `k_trace_direct`, but runs an already constructed HarvardEmulator, e.g. one with custom instructions registered.
+pub fn k_trace_harvard(mut harvard: HarvardEmulator, k: usize) -> Result<(View, UniformTrace)> {
     let mut trace = UniformTrace {
         memory_layout: LinearMemoryLayout::default(), // dummy
         k,
@@ -0,0 +1,406 @@
+//! Segments of an Execution
+//!
+//! A long execution can be split into segments of consecutive blocks that are proven independently. Every segment is
+//! described by its [`Segment`], the machine state at its boundaries:
+//!
+//! - the program counter and the registers before its first step and, unless the execution ends within the segment,
+//!   after its last step;
+//! - every byte of RAM accessed by the segment, with its value before the first step and after the last step.
+//!
+//! Consecutive segments chain together if the final program counter and registers of one are the initial ones of the
+//! next, and every byte accessed by a segment starts from the value it was left with by the previous segments, or
+//! from the initial memory of the execution if none accessed it, see [`link_segments`].
+//!
+//! The traces returned by [`split_into_segments`] are rebased, so that each one starts at timestamp one like a trace
+//! of a whole execution does.
+use std::collections::BTreeMap;
+
+use nexus_common::riscv::register::NUM_REGISTERS;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{Block, Step, Trace, UniformTrace};
+use crate::{
+    cpu::RegisterFile,
+    emulator::MemoryInitializationEntry,
+    memory::{MemoryRecord, MemoryRecords},
+    riscv::Register,
+};
+
+/// The program counter and the registers of the machine between two steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MachineState {
+    pub pc: u32,
+    /// Values of `x0` to `x31`, where `x0` is always zero.
+    pub registers: [u32; NUM_REGISTERS],
+}
+
+impl MachineState {
+    fn new(pc: u32, registers: &RegisterFile) -> Self {
+        Self {
+            pc,
+            registers: std::array::from_fn(|i| registers[Register::from(i as u8)]),
+        }
+    }
+}
+
+/// A byte of RAM accessed by a segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentMemoryEntry {
+    pub address: u32,
+    /// The value before the first step of the segment.
+    pub initial_value: u8,
+    /// The value after the last step of the segment.
+    pub final_value: u8,
+}
+
+/// The boundary state of a segment of an execution, see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Segment {
+    pub num_steps: u32,
+    pub initial_state: MachineState,
+    /// The state after the last step, `None` if the execution ends within the segment.
+    pub final_state: Option<MachineState>,
+    /// Accessed bytes of RAM, sorted by address.
+    pub memory: Vec<SegmentMemoryEntry>,
+}
+
+/// Errors returned by [`link_segments`].
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SegmentLinkError {
+    #[error("no segments")]
+    Empty,
+    #[error("segment {0} has no steps")]
+    NoSteps(usize),
+    #[error("segment {0} doesn't start from the initial state of the execution")]
+    InitialState(usize),
+    #[error("segment {0} doesn't start from the final state of the previous segment")]
+    StateMismatch(usize),
+    #[error("the execution ends within segment {0}, which is not the last")]
+    EndedEarly(usize),
+    #[error("the last segment doesn't end the execution")]
+    NotEnded,
+    #[error("segment {0} doesn't list its accessed memory in increasing order of addresses")]
+    UnsortedMemory(usize),
+    #[error("segment {segment} starts from a wrong value at address {address:#x}")]
+    MemoryMismatch { segment: usize, address: u32 },
+}
+
+/// Split the trace of an execution into segments of `n` blocks each, see [`UniformTrace::split_by`], alongside their
+/// boundary states.
+///
+/// `initial_memory` is the initial memory of the execution, as returned by
+/// [`InternalView::get_initial_memory`](crate::emulator::InternalView::get_initial_memory).
+pub fn split_into_segments(
+    trace: &UniformTrace,
+    initial_memory: &[MemoryInitializationEntry],
+    n: usize,
+) -> Vec<(Segment, UniformTrace)> {
+    assert!(n > 0);
+    let mut memory: BTreeMap<u32, u8> = initial_memory
+        .iter()
+        .map(|entry| (entry.address, entry.value))
+        .collect();
+
+    let chunks: Vec<UniformTrace> = trace
+        .split_by(n)
+        .filter(|chunk| chunk.get_num_steps() > 0)
+        .collect();
+    let mut segments = Vec::with_capacity(chunks.len());
+    for (idx, chunk) in chunks.iter().enumerate() {
+        let steps = || chunk.blocks.iter().flat_map(|block| &block.steps);
+        let first_block = &chunk.blocks[0];
+        let initial_state = MachineState::new(first_block.steps[0].pc, &first_block.regs);
+        let final_state = chunks.get(idx + 1).map(|next| {
+            let block = &next.blocks[0];
+            MachineState::new(block.steps[0].pc, &block.regs)
+        });
+
+        let mut accessed: BTreeMap<u32, SegmentMemoryEntry> = BTreeMap::new();
+        for step in steps() {
+            // Loads are applied before stores, like the prover does for a byte both read and written by a step.
+            let mut records: Vec<&MemoryRecord> = step.memory_records.iter().collect();
+            records.sort_by_key(|record| matches!(record, MemoryRecord::StoreRecord(..)));
+            for record in records {
+                let (size, address, value) = match *record {
+                    MemoryRecord::LoadRecord((size, address, value), _) => (size, address, value),
+                    MemoryRecord::StoreRecord((size, address, value, _), _) => {
+                        (size, address, value)
+                    }
+                };
+                for (offset, byte) in value.to_le_bytes()[..size as usize].iter().enumerate() {
+                    let address = address + offset as u32;
+                    let current = memory.get(&address).copied().unwrap_or_default();
+                    accessed
+                        .entry(address)
+                        .or_insert(SegmentMemoryEntry {
+                            address,
+                            initial_value: current,
+                            final_value: current,
+                        })
+                        .final_value = *byte;
+                    memory.insert(address, *byte);
+                }
+            }
+        }
+
+        let offset = steps().next().map_or(0, |step| step.timestamp - 1);
+        let rebased = UniformTrace {
+            blocks: chunk
+                .blocks
+                .iter()
+                .map(|block| rebase_block(block, offset))
+                .collect(),
+            ..chunk.clone()
+        };
+        let segment = Segment {
+            num_steps: rebased.get_num_steps() as u32,
+            initial_state,
+            final_state,
+            memory: accessed.into_values().collect(),
+        };
+        segments.push((segment, rebased));
+    }
+    segments
+}
+
+/// Shift timestamps of the block back by `offset`.
+fn rebase_block(block: &Block, offset: u32) -> Block {
+    Block {
+        regs: block.regs,
+        steps: block
+            .steps
+            .iter()
+            .map(|step| Step {
+                timestamp: step.timestamp - offset,
+                memory_records: step
+                    .memory_records
+                    .iter()
+                    .map(|record| match *record {
+                        MemoryRecord::LoadRecord(op, timestamp) => {
+                            MemoryRecord::LoadRecord(op, timestamp - offset)
+                        }
+                        MemoryRecord::StoreRecord(op, timestamp) => {
+                            MemoryRecord::StoreRecord(op, timestamp - offset)
+                        }
+                    })
+                    .collect::<MemoryRecords>(),
+                ..step.clone()
+            })
+            .collect(),
+    }
+}
+
+/// Check that segments chain together into an execution of the program starting at `initial_pc` with zeroed
+/// registers and `initial_memory`, and return the final memory of the execution.
+///
+/// Only bytes that are initialized or accessed by some segment are returned, all others are zero.
+pub fn link_segments<'a>(
+    segments: impl IntoIterator<Item = &'a Segment>,
+    initial_pc: u32,
+    initial_memory: &[MemoryInitializationEntry],
+) -> Result<BTreeMap<u32, u8>, SegmentLinkError> {
+    let mut memory: BTreeMap<u32, u8> = initial_memory
+        .iter()
+        .map(|entry| (entry.address, entry.value))
+        .collect();
+    let mut state = Some(MachineState {
+        pc: initial_pc,
+        registers: [0; NUM_REGISTERS],
+    });
+    let mut num_segments = 0;
+
+    for (idx, segment) in segments.into_iter().enumerate() {
+        num_segments += 1;
+        if segment.num_steps == 0 {
+            return Err(SegmentLinkError::NoSteps(idx));
+        }
+        match state {
+            None => return Err(SegmentLinkError::EndedEarly(idx - 1)),
+            Some(state) if state != segment.initial_state && idx == 0 => {
+                return Err(SegmentLinkError::InitialState(idx))
+            }
+            Some(state) if state != segment.initial_state => {
+                return Err(SegmentLinkError::StateMismatch(idx))
+            }
+            Some(_) => {}
+        }
+        if !segment
+            .memory
+            .windows(2)
+            .all(|pair| pair[0].address < pair[1].address)
+        {
+            return Err(SegmentLinkError::UnsortedMemory(idx));
+        }
+        for entry in &segment.memory {
+            let value = memory.entry(entry.address).or_default();
+            if *value != entry.initial_value {
+                return Err(SegmentLinkError::MemoryMismatch {
+                    segment: idx,
+                    address: entry.address,
+                });
+            }
+            *value = entry.final_value;
+        }
+        state = segment.final_state;
+    }
+
+    if num_segments == 0 {
+        return Err(SegmentLinkError::Empty);
+    }
+    if state.is_some() {
+        return Err(SegmentLinkError::NotEnded);
+    }
+    Ok(memory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::InternalView;
+    use crate::riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode};
+    use crate::trace::k_trace_direct;
+
+    const BASE: u32 = 0x80000;
+
+    fn program() -> Vec<BasicBlock> {
+        vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 1, 1, 19),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 0x1ab),
+            // *x1 = x2
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 2, 1),
+            // *(x1 + 1) = x2 as u8
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SB), 1, 2, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 3, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LBU), 4, 1, 4),
+        ])]
+    }
+
+    fn entry(address: u32, initial_value: u8, final_value: u8) -> SegmentMemoryEntry {
+        SegmentMemoryEntry {
+            address,
+            initial_value,
+            final_value,
+        }
+    }
+
+    #[test]
+    fn test_split_into_segments() {
+        let (view, trace) = k_trace_direct(&program(), 1).unwrap();
+        let segments = split_into_segments(&trace, &[], 3);
+        assert_eq!(segments.len(), 3);
+
+        let (first, _) = &segments[0];
+        assert_eq!(first.num_steps, 3);
+        assert_eq!(first.initial_state.pc, view.get_program_memory().initial_pc);
+        assert_eq!(first.initial_state.registers, [0; NUM_REGISTERS]);
+        assert!(first.memory.is_empty());
+        let state = first.final_state.unwrap();
+        assert_eq!(state.pc, first.initial_state.pc + 12);
+        assert_eq!(state.registers[1], BASE);
+        assert_eq!(state.registers[2], 0x1ab);
+
+        let (second, second_trace) = &segments[1];
+        assert_eq!(second.initial_state, state);
+        assert_eq!(
+            second.memory,
+            [
+                entry(BASE, 0, 0xab),
+                entry(BASE + 1, 0, 0xac),
+                entry(BASE + 2, 0, 0),
+                entry(BASE + 3, 0, 0),
+            ]
+        );
+        // Every segment starts at timestamp one, including the records of its memory accesses.
+        assert_eq!(second_trace.get_num_steps(), 3);
+        let step = &second_trace.blocks[2].steps[0];
+        assert_eq!(step.timestamp, 3);
+        assert!(step
+            .memory_records
+            .iter()
+            .all(|record| record.get_timestamp() == 3));
+
+        let (last, _) = &segments[2];
+        assert_eq!(last.num_steps, 2);
+        assert_eq!(last.final_state, None);
+        assert_eq!(
+            last.memory,
+            [
+                entry(BASE, 0xab, 0xab),
+                entry(BASE + 1, 0xac, 0xac),
+                entry(BASE + 2, 0, 0),
+                entry(BASE + 3, 0, 0),
+                entry(BASE + 4, 0, 0),
+            ]
+        );
+
+        let memory = link_segments(
+            segments.iter().map(|(segment, _)| segment),
+            first.initial_state.pc,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(memory[&BASE], 0xab);
+        assert_eq!(memory[&(BASE + 1)], 0xac);
+    }
+
+    #[test]
+    fn test_link_segments_errors() {
+        let (_, trace) = k_trace_direct(&program(), 1).unwrap();
+        let segments: Vec<Segment> = split_into_segments(&trace, &[], 3)
+            .into_iter()
+            .map(|(segment, _)| segment)
+            .collect();
+        let initial_pc = segments[0].initial_state.pc;
+        let link = |segments: &[Segment]| link_segments(segments, initial_pc, &[]).map(|_| ());
+        assert_eq!(link(&segments), Ok(()));
+
+        assert_eq!(link(&[]), Err(SegmentLinkError::Empty));
+        assert_eq!(link(&segments[1..]), Err(SegmentLinkError::InitialState(0)));
+        assert_eq!(link(&segments[..2]), Err(SegmentLinkError::NotEnded));
+        assert_eq!(
+            link(&[segments[0].clone(), segments[2].clone()]),
+            Err(SegmentLinkError::StateMismatch(1))
+        );
+
+        // The initial memory of the execution is where untouched bytes start from.
+        assert_eq!(
+            link_segments(
+                &segments,
+                initial_pc,
+                &[MemoryInitializationEntry {
+                    address: BASE + 2,
+                    value: 1
+                }]
+            ),
+            Err(SegmentLinkError::MemoryMismatch {
+                segment: 1,
+                address: BASE + 2
+            })
+        );
+
+        let mut tampered = segments.clone();
+        tampered[2].memory[1].initial_value = 0;
+        assert_eq!(
+            link(&tampered),
+            Err(SegmentLinkError::MemoryMismatch {
+                segment: 2,
+                address: BASE + 1
+            })
+        );
+
+        let mut tampered = segments.clone();
+        tampered[1].memory.swap(0, 1);
+        assert_eq!(link(&tampered), Err(SegmentLinkError::UnsortedMemory(1)));
+
+        let mut tampered = segments.clone();
+        tampered[1].final_state = None;
+        assert_eq!(link(&tampered), Err(SegmentLinkError::EndedEarly(1)));
+
+        let mut tampered = segments;
+        tampered[0].num_steps = 0;
+        assert_eq!(link(&tampered), Err(SegmentLinkError::NoSteps(0)));
+    }
+}